@@ -1,21 +1,27 @@
 use crate::checkpoint::CheckPoint;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
-use crate::fork_choice::{Error as ForkChoiceError, ForkChoice};
+use crate::fork_choice::{Error as ForkChoiceError, ForkChoice, SszForkChoice};
 use crate::metrics::Metrics;
-use crate::persisted_beacon_chain::{PersistedBeaconChain, BEACON_CHAIN_DB_KEY};
+use crate::persisted_beacon_chain::{
+    PersistedBeaconChain, BEACON_CHAIN_DB_KEY, FORK_CHOICE_DB_KEY,
+};
 use lmd_ghost::LmdGhost;
-use log::trace;
-use operation_pool::DepositInsertStatus;
-use operation_pool::{OperationPool, PersistedOperationPool};
-use parking_lot::{RwLock, RwLockReadGuard};
+use log::{trace, warn};
+use lru::LruCache;
+use operation_pool::{DepositInsertStatus, InsertError};
+use operation_pool::{OperationPool, OperationPoolConfig, PersistedOperationPool};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+use slasher::{Slasher, SlasherOutput};
 use slot_clock::SlotClock;
+use state_processing::common::get_indexed_attestation;
 use state_processing::per_block_processing::errors::{
     AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
     ExitValidationError, ProposerSlashingValidationError, TransferValidationError,
 };
+use state_processing::per_block_processing::{verify_block_signature, verify_indexed_attestation};
 use state_processing::{
-    per_block_processing, per_block_processing_without_verifying_block_signature,
-    per_slot_processing, BlockProcessingError,
+    per_block_processing_without_verifying_block_signature, per_slot_processing,
+    per_slot_processing_with_tracer, BlockProcessingError, NoTracer, RewardReport,
 };
 use std::sync::Arc;
 use store::iter::{BestBlockRootsIterator, BlockIterator, BlockRootsIterator, StateRootsIterator};
@@ -29,6 +35,12 @@ use types::*;
 //                          |-------must be this long------|
 pub const GRAFFITI: &str = "sigp/lighthouse-0.0.0-prerelease";
 
+/// The maximum number of invalid block roots remembered by a `BeaconChain`.
+pub const INVALID_BLOCK_ROOTS_CAPACITY: usize = 1_024;
+
+/// The maximum number of reward reports cached by a `BeaconChain`.
+pub const REWARD_REPORTS_CAPACITY: usize = 16;
+
 #[derive(Debug, PartialEq)]
 pub enum BlockProcessingOutcome {
     /// Block was valid and imported into the block graph.
@@ -50,6 +62,10 @@ pub enum BlockProcessingOutcome {
     BlockIsAlreadyKnown,
     /// The block could not be applied to the state, it is invalid.
     PerBlockProcessingError(BlockProcessingError),
+    /// The block has previously been found to be invalid, no need to re-process.
+    BlockIsKnownInvalid,
+    /// The blocks parent is known to be invalid, therefore this block is also invalid.
+    ParentIsInvalid { parent: Hash256 },
 }
 
 pub trait BeaconChainTypes {
@@ -76,11 +92,27 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     /// skip slot if no block is recieved. This is effectively a cache that avoids repeating calls
     /// to `per_slot_processing`.
     state: RwLock<BeaconState<T::EthSpec>>,
+    /// A copy of `self.state` that has been advanced into the next slot ahead of time, along with
+    /// the root of the canonical head block it was built upon. Swapped into `self.state` by
+    /// `catchup_state` once the slot clock reaches that slot.
+    prepared_state: RwLock<Option<(Hash256, BeaconState<T::EthSpec>)>>,
     /// The root of the genesis block.
     genesis_block_root: Hash256,
+    /// The roots of the most recent blocks that carried a valid proposer signature but were
+    /// otherwise found to be invalid. Used to reject these blocks (and their children) without
+    /// re-processing.
+    invalid_block_roots: RwLock<LruCache<Hash256, ()>>,
+    /// The most recently requested reward reports, keyed by epoch and the root of the canonical
+    /// block at the last slot of that epoch (so that a re-org invalidates them). The lock is held
+    /// whilst a report is computed, so that at most one epoch is replayed at a time.
+    reward_reports: Mutex<LruCache<(Epoch, Hash256), Arc<RewardReport>>>,
+    /// Recently computed shufflings, shared by all of the states of this chain.
+    shuffling_cache: Arc<ShufflingCache>,
     /// A state-machine that is updated with information from the network and chooses a canonical
     /// head block.
     pub fork_choice: ForkChoice<T>,
+    /// If enabled, detects slashable offences in the blocks and attestations seen by `self`.
+    pub slasher: Option<Arc<Slasher<T::Store, T::EthSpec>>>,
     /// Stores metrics about this `BeaconChain`.
     pub metrics: Metrics,
 }
@@ -91,9 +123,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         store: Arc<T::Store>,
         slot_clock: T::SlotClock,
         mut genesis_state: BeaconState<T::EthSpec>,
-        genesis_block: BeaconBlock,
+        genesis_block: BeaconBlock<T::EthSpec>,
         spec: ChainSpec,
     ) -> Result<Self, Error> {
+        let shuffling_cache = Arc::new(ShufflingCache::new(SHUFFLING_CACHE_SIZE));
+        genesis_state.shuffling_cache = ShufflingCacheHandle::new(shuffling_cache.clone());
         genesis_state.build_all_caches(&spec)?;
 
         let state_root = genesis_state.canonical_root();
@@ -113,55 +147,135 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             state_root,
         ));
 
+        let fork_choice = ForkChoice::new(store.clone(), &genesis_block, genesis_block_root);
+        fork_choice.update_justified_checkpoint(&*store, &genesis_state)?;
+
         Ok(Self {
             spec,
             slot_clock,
             op_pool: OperationPool::new(),
             state: RwLock::new(genesis_state),
+            prepared_state: RwLock::new(None),
             canonical_head,
             genesis_block_root,
-            fork_choice: ForkChoice::new(store.clone(), &genesis_block, genesis_block_root),
+            invalid_block_roots: RwLock::new(LruCache::new(INVALID_BLOCK_ROOTS_CAPACITY)),
+            reward_reports: Mutex::new(LruCache::new(REWARD_REPORTS_CAPACITY)),
+            shuffling_cache,
+            fork_choice,
+            slasher: None,
             metrics: Metrics::new()?,
             store,
         })
     }
 
-    /// Attempt to load an existing instance from the given `store`.
+    /// Attempt to load an existing instance from the given `store`, with the limits in
+    /// `op_pool_config` applied to the restored operation pool.
     pub fn from_store(
         store: Arc<T::Store>,
         spec: ChainSpec,
+        op_pool_config: OperationPoolConfig,
     ) -> Result<Option<BeaconChain<T>>, Error> {
         let key = Hash256::from_slice(&BEACON_CHAIN_DB_KEY.as_bytes());
-        let p: PersistedBeaconChain<T> = match store.get(&key) {
+        let mut p: PersistedBeaconChain<T> = match store.get(&key) {
             Err(e) => return Err(e.into()),
             Ok(None) => return Ok(None),
             Ok(Some(p)) => p,
         };
 
+        let shuffling_cache = Arc::new(ShufflingCache::new(SHUFFLING_CACHE_SIZE));
+        p.state.shuffling_cache = ShufflingCacheHandle::new(shuffling_cache.clone());
+        p.canonical_head.beacon_state.shuffling_cache =
+            ShufflingCacheHandle::new(shuffling_cache.clone());
+
         let slot_clock = T::SlotClock::new(
             spec.genesis_slot,
             p.state.genesis_time,
             spec.seconds_per_slot,
         );
 
-        let last_finalized_root = p.canonical_head.beacon_state.finalized_root;
-        let last_finalized_block = &p.canonical_head.beacon_block;
+        // Restore the persisted fork choice, rebuilding it from the store if it is missing, cannot
+        // be decoded or is outdated.
+        //
+        // The chain and the fork choice are not persisted atomically, so a restored fork choice
+        // is outdated if it does not contain the persisted head or its finalized block.
+        let head_root = p.canonical_head.beacon_block_root;
+        let finalized_root = match p.canonical_head.beacon_state.finalized_checkpoint.root {
+            // Resolve the `0x00.. 00` alias back to genesis
+            root if root == Hash256::zero() => p.genesis_block_root,
+            root => root,
+        };
+        let rebuild_fork_choice = || {
+            ForkChoice::rebuild_from_store(
+                store.clone(),
+                &p.canonical_head,
+                p.genesis_block_root,
+                &shuffling_cache,
+                &spec,
+            )
+        };
+        let fork_choice = match Self::restore_fork_choice(&store) {
+            Ok(Some(fork_choice))
+                if fork_choice.contains_block(head_root)
+                    && fork_choice.contains_block(finalized_root) =>
+            {
+                fork_choice
+            }
+            Ok(Some(_)) => {
+                warn!("Persisted fork choice is outdated, rebuilding it from the store");
+                rebuild_fork_choice()?
+            }
+            Ok(None) => {
+                warn!("No persisted fork choice found, rebuilding it from the store");
+                rebuild_fork_choice()?
+            }
+            Err(e) => {
+                warn!(
+                    "Unable to restore persisted fork choice, rebuilding it from the store: {:?}",
+                    e
+                );
+                rebuild_fork_choice()?
+            }
+        };
+        fork_choice.update_justified_checkpoint(&*store, &p.state)?;
 
-        let op_pool = p.op_pool.into_operation_pool(&p.state, &spec);
+        let op_pool = p
+            .op_pool
+            .into_operation_pool(&p.state, &spec, op_pool_config);
 
         Ok(Some(BeaconChain {
             spec,
             slot_clock,
-            fork_choice: ForkChoice::new(store.clone(), last_finalized_block, last_finalized_root),
+            fork_choice,
             op_pool,
             canonical_head: RwLock::new(p.canonical_head),
             state: RwLock::new(p.state),
+            prepared_state: RwLock::new(None),
             genesis_block_root: p.genesis_block_root,
+            invalid_block_roots: RwLock::new(LruCache::new(INVALID_BLOCK_ROOTS_CAPACITY)),
+            reward_reports: Mutex::new(LruCache::new(REWARD_REPORTS_CAPACITY)),
+            shuffling_cache,
+            slasher: None,
             metrics: Metrics::new()?,
             store,
         }))
     }
 
+    /// Attempt to load the `ForkChoice` persisted by `persist` from the given `store`.
+    ///
+    /// Returns `Ok(None)` if no fork choice has been persisted.
+    fn restore_fork_choice(
+        store: &Arc<T::Store>,
+    ) -> Result<Option<ForkChoice<T>>, ForkChoiceError> {
+        let key = Hash256::from_slice(&FORK_CHOICE_DB_KEY.as_bytes());
+
+        match store.get::<SszForkChoice>(&key)? {
+            Some(ssz_container) => {
+                ForkChoice::from_ssz_container(ssz_container, store.clone()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Attempt to save this instance to `self.store`.
     pub fn persist(&self) -> Result<(), Error> {
         let p: PersistedBeaconChain<T> = PersistedBeaconChain {
@@ -174,14 +288,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let key = Hash256::from_slice(&BEACON_CHAIN_DB_KEY.as_bytes());
         self.store.put(&key, &p)?;
 
+        let key = Hash256::from_slice(&FORK_CHOICE_DB_KEY.as_bytes());
+        self.store.put(&key, &self.fork_choice.as_ssz_container())?;
+
         Ok(())
     }
 
     /// Returns the beacon block body for each beacon block root in `roots`.
     ///
     /// Fails if any root in `roots` does not have a corresponding block.
-    pub fn get_block_bodies(&self, roots: &[Hash256]) -> Result<Vec<BeaconBlockBody>, Error> {
-        let bodies: Result<Vec<BeaconBlockBody>, _> = roots
+    pub fn get_block_bodies(
+        &self,
+        roots: &[Hash256],
+    ) -> Result<Vec<BeaconBlockBody<T::EthSpec>>, Error> {
+        let bodies: Result<Vec<BeaconBlockBody<T::EthSpec>>, _> = roots
             .iter()
             .map(|root| match self.get_block(root)? {
                 Some(block) => Ok(block.body),
@@ -252,10 +372,31 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// ## Errors
     ///
     /// May return a database error.
-    pub fn get_block(&self, block_root: &Hash256) -> Result<Option<BeaconBlock>, Error> {
+    pub fn get_block(
+        &self,
+        block_root: &Hash256,
+    ) -> Result<Option<BeaconBlock<T::EthSpec>>, Error> {
         Ok(self.store.get(block_root)?)
     }
 
+    /// Returns the state at the given root, if any, with the chain's `ShufflingCache` attached.
+    ///
+    /// ## Errors
+    ///
+    /// May return a database error.
+    pub fn get_state(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<Option<BeaconState<T::EthSpec>>, Error> {
+        Ok(self
+            .store
+            .get::<BeaconState<T::EthSpec>>(state_root)?
+            .map(|mut state| {
+                state.shuffling_cache = ShufflingCacheHandle::new(self.shuffling_cache.clone());
+                state
+            }))
+    }
+
     /// Returns a read-lock guarded `BeaconState` which is the `canonical_head` that has been
     /// updated to match the current slot clock.
     pub fn current_state(&self) -> RwLockReadGuard<BeaconState<T::EthSpec>> {
@@ -289,6 +430,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         if self.state.read().slot < present_slot {
             let mut state = self.state.write();
 
+            // Use the state prepared by `prepare_next_slot_state`, if it was built upon the present
+            // head and state.
+            if let Some((head_block_root, prepared_state)) = self.prepared_state.write().take() {
+                if head_block_root == self.head().beacon_block_root
+                    && prepared_state.slot == state.slot + 1
+                {
+                    *state = prepared_state;
+                }
+            }
+
             // If required, transition the new state to the present slot.
             for _ in state.slot.as_u64()..present_slot.as_u64() {
                 // Ensure the next epoch state caches are built in case of an epoch transition.
@@ -303,6 +454,94 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Advances a copy of the current state into the next slot (including any epoch transition)
+    /// and builds its caches, storing it so `catchup_state` can swap it in once the slot clock
+    /// reaches that slot.
+    ///
+    /// Intended to be called shortly before a slot boundary, so that expensive per-slot processing
+    /// is not performed on demand by whichever caller first notices the new slot.
+    pub fn prepare_next_slot_state(&self) -> Result<(), Error> {
+        let (head_block_root, mut state) = {
+            // Note: `update_canonical_head` holds the `state` lock whilst updating the head, so
+            // the two are always read consistently whilst it is held.
+            let state = self.state.read();
+            (self.head().beacon_block_root, state.clone())
+        };
+
+        // Ensure the next epoch state caches are built in case of an epoch transition.
+        state.build_committee_cache(RelativeEpoch::Next, &self.spec)?;
+
+        per_slot_processing(&mut state, &self.spec)?;
+
+        state.build_all_caches(&self.spec)?;
+
+        *self.prepared_state.write() = Some((head_block_root, state));
+
+        Ok(())
+    }
+
+    /// Returns the rewards and penalties applied to each validator by the per-epoch processing at
+    /// the end of `epoch` (i.e., the transition into `epoch + 1`) on the canonical chain.
+    ///
+    /// The report is computed by replaying the epoch transition upon the canonical state at the
+    /// last slot of `epoch`, and cached so that repeated requests for the same epoch do not
+    /// replay it again.
+    pub fn reward_report(&self, epoch: Epoch) -> Result<Arc<RewardReport>, Error> {
+        let end_slot = epoch.end_slot(T::EthSpec::slots_per_epoch());
+
+        if end_slot >= self.state.read().slot {
+            return Err(Error::EpochNotYetProcessed(epoch));
+        }
+
+        // The latest block at or prior to `end_slot`.
+        let (block_root, _) = self.rev_iter_block_roots(end_slot).next().ok_or_else(|| {
+            Error::DBInconsistent(format!("Missing block root for slot {}", end_slot))
+        })?;
+
+        let mut reward_reports = self.reward_reports.lock();
+        if let Some(report) = reward_reports.get(&(epoch, block_root)) {
+            return Ok(report.clone());
+        }
+
+        let report = Arc::new(self.replay_epoch_transition(epoch, block_root)?);
+        reward_reports.put((epoch, block_root), report.clone());
+
+        Ok(report)
+    }
+
+    /// Computes the reward report for `epoch` by loading the post-state of `block_root` (the
+    /// latest block at or prior to the last slot of `epoch`) and processing it through the epoch
+    /// transition.
+    fn replay_epoch_transition(
+        &self,
+        epoch: Epoch,
+        block_root: Hash256,
+    ) -> Result<RewardReport, Error> {
+        let end_slot = epoch.end_slot(T::EthSpec::slots_per_epoch());
+
+        let block = self
+            .get_block(&block_root)?
+            .ok_or_else(|| Error::MissingBeaconBlock(block_root))?;
+        let mut state = self
+            .get_state(&block.state_root)?
+            .ok_or_else(|| Error::MissingBeaconState(block.state_root))?;
+
+        // Process any skip slots up to `end_slot`, then the epoch transition itself.
+        let mut reports = vec![];
+        while state.slot <= end_slot {
+            // Ensure the next epoch state caches are built in case of an epoch transition.
+            state.build_committee_cache(RelativeEpoch::Next, &self.spec)?;
+
+            per_slot_processing_with_tracer(&mut state, &self.spec, &mut NoTracer, &mut reports)?;
+        }
+
+        // Skip slots may cross earlier epoch boundaries. The last transition is that of `epoch`.
+        reports
+            .pop()
+            .filter(|report| report.epoch == epoch)
+            .ok_or_else(|| Error::EpochNotYetProcessed(epoch))
+    }
+
     /// Build all of the caches on the current state.
     ///
     /// Ideally this shouldn't be required, however we leave it here for testing.
@@ -314,15 +553,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Returns the validator index (if any) for the given public key.
     ///
-    /// Information is retrieved from the present `beacon_state.validator_registry`.
+    /// Information is retrieved from the present `beacon_state.validators`.
     pub fn validator_index(&self, pubkey: &PublicKey) -> Option<usize> {
-        for (i, validator) in self
-            .head()
-            .beacon_state
-            .validator_registry
-            .iter()
-            .enumerate()
-        {
+        for (i, validator) in self.head().beacon_state.validators.iter().enumerate() {
             if validator.pubkey == *pubkey {
                 return Some(i);
             }
@@ -452,7 +685,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // - Otherwise, assume the current slot has been skipped and use the block root from the
         // prior slot.
         //
-        // For all other cases, we simply read the `target_root` from `state.latest_block_roots`.
+        // For all other cases, we simply read the `target_root` from `state.block_roots`.
         let target_root = if state.slot == current_epoch_start_slot {
             if head_block_slot == current_epoch_start_slot {
                 head_block_root
@@ -463,8 +696,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             *state.get_block_root(current_epoch_start_slot)?
         };
 
-        let previous_crosslink_root =
-            Hash256::from_slice(&state.get_current_crosslink(shard)?.tree_hash_root());
+        let target = Checkpoint {
+            epoch: state.current_epoch(),
+            root: target_root,
+        };
+
+        let parent_crosslink = state.get_current_crosslink(shard)?;
+        let crosslink = Crosslink {
+            shard,
+            parent_root: Hash256::from_slice(&parent_crosslink.tree_hash_root()),
+            start_epoch: parent_crosslink.end_epoch,
+            end_epoch: std::cmp::min(
+                target.epoch,
+                parent_crosslink.end_epoch + self.spec.max_epochs_per_crosslink,
+            ),
+            data_root: Hash256::zero(),
+        };
 
         // Collect some metrics.
         self.metrics.attestation_production_successes.inc();
@@ -472,13 +719,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         Ok(AttestationData {
             beacon_block_root: head_block_root,
-            source_epoch: state.current_justified_epoch,
-            source_root: state.current_justified_root,
-            target_epoch: state.current_epoch(),
-            target_root,
-            shard,
-            previous_crosslink_root,
-            crosslink_data_root: Hash256::zero(),
+            source: state.current_justified_checkpoint.clone(),
+            target,
+            crosslink,
         })
     }
 
@@ -488,14 +731,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// if possible.
     pub fn process_attestation(
         &self,
-        attestation: Attestation,
-    ) -> Result<(), AttestationValidationError> {
+        attestation: Attestation<T::EthSpec>,
+    ) -> Result<(), InsertError<AttestationValidationError>> {
         self.metrics.attestation_processing_requests.inc();
         let timer = self.metrics.attestation_processing_times.start_timer();
 
+        let state = &*self.state.read();
+
+        // The slasher must see the attestation even if the `op_pool` rejects it (e.g., because it
+        // conflicts with an attestation that the pool already holds).
+        self.slasher_accept_attestations(std::slice::from_ref(&attestation), state, true);
+
         let result = self
             .op_pool
-            .insert_attestation(attestation, &*self.state.read(), &self.spec);
+            .insert_attestation(attestation, state, &self.spec);
 
         timer.observe_duration();
 
@@ -517,22 +766,101 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         result
     }
 
-    /// Accept some deposit and queue it for inclusion in an appropriate block.
+    /// Converts each of the `attestations` to an `IndexedAttestation` using `state` and queues it
+    /// for processing by the slasher (if enabled).
+    ///
+    /// Attestations that cannot be converted (e.g., they are too old for the committee caches of
+    /// `state`) are ignored. If `verify_signatures` is `true`, attestations with invalid
+    /// signatures are also ignored, so that forged attestations cannot occupy the slasher's
+    /// records. Attestations from blocks have already been verified by block processing.
+    fn slasher_accept_attestations(
+        &self,
+        attestations: &[Attestation<T::EthSpec>],
+        state: &BeaconState<T::EthSpec>,
+        verify_signatures: bool,
+    ) {
+        if let Some(slasher) = &self.slasher {
+            for attestation in attestations {
+                let indexed_attestation = match get_indexed_attestation(state, attestation) {
+                    Ok(indexed_attestation) => indexed_attestation,
+                    Err(e) => {
+                        trace!("Slasher unable to index attestation: {:?}", e);
+                        continue;
+                    }
+                };
+
+                if verify_signatures {
+                    if let Err(e) =
+                        verify_indexed_attestation(state, &indexed_attestation, &self.spec)
+                    {
+                        trace!("Slasher ignoring invalid attestation: {:?}", e);
+                        continue;
+                    }
+                }
+
+                slasher.accept_attestation(indexed_attestation);
+            }
+        }
+    }
+
+    /// Runs the slasher (if enabled) over all the block headers and attestations seen since it was
+    /// last run, submitting any resulting slashings to the `op_pool`, then prunes the slasher's
+    /// records that have fallen out of its history.
+    ///
+    /// Returns all slashings detected, including those rejected by the `op_pool` (e.g., because
+    /// the validator has already been slashed).
+    pub fn process_slasher_queue(&self) -> Result<SlasherOutput<T::EthSpec>, Error> {
+        let output = match &self.slasher {
+            Some(slasher) => {
+                let output = slasher.process_queued()?;
+                slasher.prune(self.state.read().current_epoch())?;
+                output
+            }
+            None => return Ok(SlasherOutput::default()),
+        };
+
+        for proposer_slashing in &output.proposer_slashings {
+            if let Err(e) = self.process_proposer_slashing(proposer_slashing.clone()) {
+                trace!("Proposer slashing rejected by op pool: {:?}", e);
+            }
+        }
+
+        for attester_slashing in &output.attester_slashings {
+            if let Err(e) = self.process_attester_slashing(attester_slashing.clone()) {
+                trace!("Attester slashing rejected by op pool: {:?}", e);
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Accept some deposit (with the given `index` in the deposit contract) and queue it for
+    /// inclusion in an appropriate block.
+    ///
+    /// A `DepositInsertStatus::Replaced` result indicates that the eth1 chain re-organised,
+    /// replacing the deposit at `index` (and removing all subsequent deposits from the pool).
     pub fn process_deposit(
         &self,
-        deposit: Deposit,
+        index: u64,
+        deposit_data: DepositData,
     ) -> Result<DepositInsertStatus, DepositValidationError> {
-        self.op_pool.insert_deposit(deposit)
+        self.op_pool.insert_deposit(index, deposit_data)
     }
 
     /// Accept some exit and queue it for inclusion in an appropriate block.
-    pub fn process_voluntary_exit(&self, exit: VoluntaryExit) -> Result<(), ExitValidationError> {
+    pub fn process_voluntary_exit(
+        &self,
+        exit: VoluntaryExit,
+    ) -> Result<(), InsertError<ExitValidationError>> {
         self.op_pool
             .insert_voluntary_exit(exit, &*self.state.read(), &self.spec)
     }
 
     /// Accept some transfer and queue it for inclusion in an appropriate block.
-    pub fn process_transfer(&self, transfer: Transfer) -> Result<(), TransferValidationError> {
+    pub fn process_transfer(
+        &self,
+        transfer: Transfer,
+    ) -> Result<(), InsertError<TransferValidationError>> {
         self.op_pool
             .insert_transfer(transfer, &*self.state.read(), &self.spec)
     }
@@ -541,7 +869,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn process_proposer_slashing(
         &self,
         proposer_slashing: ProposerSlashing,
-    ) -> Result<(), ProposerSlashingValidationError> {
+    ) -> Result<(), InsertError<ProposerSlashingValidationError>> {
         self.op_pool
             .insert_proposer_slashing(proposer_slashing, &*self.state.read(), &self.spec)
     }
@@ -549,8 +877,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// Accept some attester slashing and queue it for inclusion in an appropriate block.
     pub fn process_attester_slashing(
         &self,
-        attester_slashing: AttesterSlashing,
-    ) -> Result<(), AttesterSlashingValidationError> {
+        attester_slashing: AttesterSlashing<T::EthSpec>,
+    ) -> Result<(), InsertError<AttesterSlashingValidationError>> {
         self.op_pool
             .insert_attester_slashing(attester_slashing, &*self.state.read(), &self.spec)
     }
@@ -558,14 +886,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// Accept some block and attempt to add it to block DAG.
     ///
     /// Will accept blocks from prior slots, however it will reject any block from a future slot.
-    pub fn process_block(&self, block: BeaconBlock) -> Result<BlockProcessingOutcome, Error> {
+    pub fn process_block(
+        &self,
+        block: BeaconBlock<T::EthSpec>,
+    ) -> Result<BlockProcessingOutcome, Error> {
         self.metrics.block_processing_requests.inc();
         let timer = self.metrics.block_processing_times.start_timer();
 
         let finalized_slot = self
             .state
             .read()
-            .finalized_epoch
+            .finalized_checkpoint
+            .epoch
             .start_slot(T::EthSpec::slots_per_epoch());
 
         if block.slot <= finalized_slot {
@@ -593,14 +925,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
-        if self.store.exists::<BeaconBlock>(&block_root)? {
+        if self.store.exists::<BeaconBlock<T::EthSpec>>(&block_root)? {
             return Ok(BlockProcessingOutcome::BlockIsAlreadyKnown);
         }
 
+        if self.is_known_invalid_block_root(&block_root) {
+            return Ok(BlockProcessingOutcome::BlockIsKnownInvalid);
+        }
+
+        let parent_block_root = block.parent_root;
+
+        // Reject the block if it descends from an invalid block.
+        //
+        // The block itself is not marked as invalid; its signature cannot be verified without the
+        // parent state, so any peer could otherwise fill the cache with fabricated roots.
+        if self.is_known_invalid_block_root(&parent_block_root) {
+            return Ok(BlockProcessingOutcome::ParentIsInvalid {
+                parent: parent_block_root,
+            });
+        }
+
         // Load the blocks parent block from the database, returning invalid if that block is not
         // found.
-        let parent_block_root = block.previous_block_root;
-        let parent_block: BeaconBlock = match self.store.get(&parent_block_root)? {
+        let parent_block: BeaconBlock<T::EthSpec> = match self.store.get(&parent_block_root)? {
             Some(previous_block_root) => previous_block_root,
             None => {
                 return Ok(BlockProcessingOutcome::ParentUnknown {
@@ -613,8 +960,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // It is an error because if know the parent block we should also know the parent state.
         let parent_state_root = parent_block.state_root;
         let parent_state = self
-            .store
-            .get(&parent_state_root)?
+            .get_state(&parent_state_root)?
             .ok_or_else(|| Error::DBInconsistent(format!("Missing state {}", parent_state_root)))?;
 
         // Transition the parent state to the block slot.
@@ -625,22 +971,48 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
 
+        // Verify the proposer signature before anything else, so that only blocks signed by their
+        // proposer are ever recorded as invalid.
+        match verify_block_signature(&state, &block, &self.spec) {
+            Err(BlockProcessingError::BeaconStateError(e)) => {
+                return Err(Error::BeaconStateError(e))
+            }
+            Err(e) => return Ok(BlockProcessingOutcome::PerBlockProcessingError(e)),
+            _ => {}
+        }
+
         // Apply the received block to its parent state (which has been transitioned into this
         // slot).
-        match per_block_processing(&mut state, &block, &self.spec) {
+        match per_block_processing_without_verifying_block_signature(&mut state, &block, &self.spec)
+        {
             Err(BlockProcessingError::BeaconStateError(e)) => {
                 return Err(Error::BeaconStateError(e))
             }
-            Err(e) => return Ok(BlockProcessingOutcome::PerBlockProcessingError(e)),
+            Err(e) => {
+                self.invalid_block_roots.write().put(block_root, ());
+
+                return Ok(BlockProcessingOutcome::PerBlockProcessingError(e));
+            }
             _ => {}
         }
 
         let state_root = state.canonical_root();
 
         if block.state_root != state_root {
+            self.invalid_block_roots.write().put(block_root, ());
+
             return Ok(BlockProcessingOutcome::StateRootMismatch);
         }
 
+        // Provide the block header and attestations to the slasher.
+        if let Some(slasher) = &self.slasher {
+            let proposer_index =
+                state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, &self.spec)?;
+            slasher.accept_block_header(proposer_index as u64, block.block_header());
+
+            self.slasher_accept_attestations(&block.body.attestations, &state, false);
+        }
+
         // Store the block and state.
         self.store.put(&block_root, &block)?;
         self.store.put(&state_root, &state)?;
@@ -670,7 +1042,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn produce_block(
         &self,
         randao_reveal: Signature,
-    ) -> Result<(BeaconBlock, BeaconState<T::EthSpec>), BlockProductionError> {
+    ) -> Result<(BeaconBlock<T::EthSpec>, BeaconState<T::EthSpec>), BlockProductionError> {
         let state = self.state.read().clone();
         let slot = self
             .read_slot_clock()
@@ -692,7 +1064,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         mut state: BeaconState<T::EthSpec>,
         produce_at_slot: Slot,
         randao_reveal: Signature,
-    ) -> Result<(BeaconBlock, BeaconState<T::EthSpec>), BlockProductionError> {
+    ) -> Result<(BeaconBlock<T::EthSpec>, BeaconState<T::EthSpec>), BlockProductionError> {
         self.metrics.block_production_requests.inc();
         let timer = self.metrics.block_production_times.start_timer();
 
@@ -703,7 +1075,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
 
-        let previous_block_root = if state.slot > 0 {
+        let parent_root = if state.slot > 0 {
             *state
                 .get_block_root(state.slot - 1)
                 .map_err(|_| BlockProductionError::UnableToGetBlockRootFromState)?
@@ -714,12 +1086,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let mut graffiti: [u8; 32] = [0; 32];
         graffiti.copy_from_slice(GRAFFITI.as_bytes());
 
-        let (proposer_slashings, attester_slashings) =
-            self.op_pool.get_slashings(&state, &self.spec);
+        // Select operations using the constants of the block's epoch, as modified by any
+        // scheduled forks.
+        let spec = self.spec.at_epoch(state.current_epoch());
+
+        let (proposer_slashings, attester_slashings) = self.op_pool.get_slashings(&state, &spec);
 
         let mut block = BeaconBlock {
             slot: state.slot,
-            previous_block_root,
+            parent_root,
             state_root: Hash256::zero(), // Updated after the state is calculated.
             signature: Signature::empty_signature(), // To be completed by a validator.
             body: BeaconBlockBody {
@@ -731,12 +1106,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     block_hash: Hash256::zero(),
                 },
                 graffiti,
-                proposer_slashings,
-                attester_slashings,
-                attestations: self.op_pool.get_attestations(&state, &self.spec),
-                deposits: self.op_pool.get_deposits(&state, &self.spec),
-                voluntary_exits: self.op_pool.get_voluntary_exits(&state, &self.spec),
-                transfers: self.op_pool.get_transfers(&state, &self.spec),
+                proposer_slashings: proposer_slashings.into(),
+                attester_slashings: attester_slashings.into(),
+                attestations: self.op_pool.get_attestations(&state, &spec).into(),
+                deposits: self.op_pool.get_deposits(&state, &spec)?.into(),
+                voluntary_exits: self.op_pool.get_voluntary_exits(&state, &spec).into(),
+                transfers: self.op_pool.get_transfers(&state, &spec).into(),
             },
         };
 
@@ -760,7 +1135,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let timer = self.metrics.fork_choice_times.start_timer();
 
         // Determine the root of the block that is the head of the chain.
-        let beacon_block_root = self.fork_choice.find_head(&self)?;
+        // Refresh the cached justified balances, if the justified checkpoint has changed.
+        self.fork_choice
+            .update_justified_checkpoint(&self.store, &self.current_state())?;
+
+        let beacon_block_root = self.fork_choice.find_head()?;
 
         // End fork choice metrics timer.
         timer.observe_duration();
@@ -769,25 +1148,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         if beacon_block_root != self.head().beacon_block_root {
             self.metrics.fork_choice_changed_head.inc();
 
-            let beacon_block: BeaconBlock = self
+            let beacon_block: BeaconBlock<T::EthSpec> = self
                 .store
                 .get(&beacon_block_root)?
                 .ok_or_else(|| Error::MissingBeaconBlock(beacon_block_root))?;
 
             let beacon_state_root = beacon_block.state_root;
-            let beacon_state: BeaconState<T::EthSpec> = self
-                .store
-                .get(&beacon_state_root)?
+            let mut beacon_state = self
+                .get_state(&beacon_state_root)?
                 .ok_or_else(|| Error::MissingBeaconState(beacon_state_root))?;
 
+            // Committee caches are not stored, but the head state should always have them.
+            beacon_state.build_committee_cache(RelativeEpoch::Previous, &self.spec)?;
+            beacon_state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
+            beacon_state.build_committee_cache(RelativeEpoch::Next, &self.spec)?;
+
             // If we switched to a new chain (instead of building atop the present chain).
-            if self.head().beacon_block_root != beacon_block.previous_block_root {
+            if self.head().beacon_block_root != beacon_block.parent_root {
                 self.metrics.fork_choice_reorg_count.inc();
             };
 
-            let old_finalized_epoch = self.head().beacon_state.finalized_epoch;
-            let new_finalized_epoch = beacon_state.finalized_epoch;
-            let finalized_root = beacon_state.finalized_root;
+            let old_finalized_epoch = self.head().beacon_state.finalized_checkpoint.epoch;
+            let new_finalized_epoch = beacon_state.finalized_checkpoint.epoch;
+            let finalized_root = beacon_state.finalized_checkpoint.root;
 
             // Never revert back past a finalized epoch.
             if new_finalized_epoch < old_finalized_epoch {
@@ -816,13 +1199,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Update the canonical head to `new_head`.
     fn update_canonical_head(&self, new_head: CheckPoint<T::EthSpec>) -> Result<(), Error> {
-        // Update the checkpoint that stores the head of the chain at the time it received the
-        // block.
-        *self.canonical_head.write() = new_head;
-
-        // Update the always-at-the-present-slot state we keep around for performance gains.
-        *self.state.write() = {
-            let mut state = self.canonical_head.read().beacon_state.clone();
+        // Produce the always-at-the-present-slot state we keep around for performance gains.
+        let new_state = {
+            let mut state = new_head.beacon_state.clone();
 
             let present_slot = match self.slot_clock.present_slot() {
                 Ok(Some(slot)) => slot,
@@ -839,6 +1218,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             state
         };
 
+        {
+            // Hold the `state` lock whilst updating the head so the two are never observed out of
+            // sync.
+            let mut state = self.state.write();
+
+            // Update the checkpoint that stores the head of the chain at the time it received the
+            // block.
+            *self.canonical_head.write() = new_head;
+            *state = new_state;
+
+            // Any prepared state was built upon the previous head.
+            *self.prepared_state.write() = None;
+        }
+
         // Save `self` to `self.store`.
         self.persist()?;
 
@@ -855,7 +1248,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ) -> Result<(), Error> {
         let finalized_block = self
             .store
-            .get::<BeaconBlock>(&finalized_block_root)?
+            .get::<BeaconBlock<T::EthSpec>>(&finalized_block_root)?
             .ok_or_else(|| Error::MissingBeaconBlock(finalized_block_root))?;
 
         let new_finalized_epoch = finalized_block.slot.epoch(T::EthSpec::slots_per_epoch());
@@ -873,9 +1266,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Returns `true` if the given block root is amongst the most recent blocks that were found to
+    /// be invalid.
+    pub fn is_known_invalid_block_root(&self, beacon_block_root: &Hash256) -> bool {
+        self.invalid_block_roots.read().contains(beacon_block_root)
+    }
+
     /// Returns `true` if the given block root has not been processed.
     pub fn is_new_block_root(&self, beacon_block_root: &Hash256) -> Result<bool, Error> {
-        Ok(!self.store.exists::<BeaconBlock>(beacon_block_root)?)
+        Ok(!self
+            .store
+            .exists::<BeaconBlock<T::EthSpec>>(beacon_block_root)?)
     }
 
     /// Dumps the entire canonical chain, from the head to genesis to a vector for analysis.
@@ -895,13 +1296,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         dump.push(last_slot.clone());
 
         loop {
-            let beacon_block_root = last_slot.beacon_block.previous_block_root;
+            let beacon_block_root = last_slot.beacon_block.parent_root;
 
             if beacon_block_root == self.spec.zero_hash {
                 break; // Genesis has been reached.
             }
 
-            let beacon_block: BeaconBlock =
+            let beacon_block: BeaconBlock<T::EthSpec> =
                 self.store.get(&beacon_block_root)?.ok_or_else(|| {
                     Error::DBInconsistent(format!("Missing block {}", beacon_block_root))
                 })?;
@@ -6,7 +6,7 @@ use types::{BeaconBlock, BeaconState, EthSpec, Hash256};
 /// head, justified head and finalized head.
 #[derive(Clone, Serialize, PartialEq, Debug, Encode, Decode)]
 pub struct CheckPoint<E: EthSpec> {
-    pub beacon_block: BeaconBlock,
+    pub beacon_block: BeaconBlock<E>,
     pub beacon_block_root: Hash256,
     pub beacon_state: BeaconState<E>,
     pub beacon_state_root: Hash256,
@@ -15,7 +15,7 @@ pub struct CheckPoint<E: EthSpec> {
 impl<E: EthSpec> CheckPoint<E> {
     /// Create a new checkpoint.
     pub fn new(
-        beacon_block: BeaconBlock,
+        beacon_block: BeaconBlock<E>,
         beacon_block_root: Hash256,
         beacon_state: BeaconState<E>,
         beacon_state_root: Hash256,
@@ -31,7 +31,7 @@ impl<E: EthSpec> CheckPoint<E> {
     /// Update all fields of the checkpoint.
     pub fn update(
         &mut self,
-        beacon_block: BeaconBlock,
+        beacon_block: BeaconBlock<E>,
         beacon_block_root: Hash256,
         beacon_state: BeaconState<E>,
         beacon_state_root: Hash256,
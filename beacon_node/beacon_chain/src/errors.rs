@@ -1,5 +1,7 @@
 use crate::fork_choice::Error as ForkChoiceError;
 use crate::metrics::Error as MetricsError;
+use operation_pool::DepositError;
+use slasher::Error as SlasherError;
 use state_processing::BlockProcessingError;
 use state_processing::SlotProcessingError;
 use types::*;
@@ -31,9 +33,13 @@ pub enum BeaconChainError {
     MissingBeaconState(Hash256),
     SlotProcessingError(SlotProcessingError),
     MetricsError(String),
+    SlasherError(SlasherError),
+    /// The canonical chain has not yet transitioned out of the given epoch.
+    EpochNotYetProcessed(Epoch),
 }
 
 easy_from_to!(SlotProcessingError, BeaconChainError);
+easy_from_to!(SlasherError, BeaconChainError);
 
 impl From<MetricsError> for BeaconChainError {
     fn from(e: MetricsError) -> BeaconChainError {
@@ -48,8 +54,10 @@ pub enum BlockProductionError {
     SlotProcessingError(SlotProcessingError),
     BlockProcessingError(BlockProcessingError),
     BeaconStateError(BeaconStateError),
+    DepositError(DepositError),
 }
 
 easy_from_to!(BlockProcessingError, BlockProductionError);
 easy_from_to!(BeaconStateError, BlockProductionError);
 easy_from_to!(SlotProcessingError, BlockProductionError);
+easy_from_to!(DepositError, BlockProductionError);
@@ -1,9 +1,15 @@
-use crate::{BeaconChain, BeaconChainTypes};
-use lmd_ghost::LmdGhost;
+use crate::{BeaconChainTypes, CheckPoint};
+use lmd_ghost::{LmdGhost, LmdGhostSnapshot};
+use parking_lot::RwLock;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
 use state_processing::common::get_attesting_indices_unsorted;
 use std::sync::Arc;
-use store::{Error as StoreError, Store};
-use types::{Attestation, BeaconBlock, BeaconState, BeaconStateError, Epoch, EthSpec, Hash256};
+use store::{DBColumn, Error as StoreError, Store, StoreItem};
+use types::{
+    Attestation, BeaconBlock, BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec, Hash256,
+    RelativeEpoch, ShufflingCache, ShufflingCacheHandle, Slot,
+};
 
 type Result<T> = std::result::Result<T, Error>;
 
@@ -14,6 +20,7 @@ pub enum Error {
     BackendError(String),
     BeaconStateError(BeaconStateError),
     StoreError(StoreError),
+    UnknownJustifiedCheckpoint,
 }
 
 pub struct ForkChoice<T: BeaconChainTypes> {
@@ -23,6 +30,16 @@ pub struct ForkChoice<T: BeaconChainTypes> {
     /// Does not necessarily need to be the _actual_ genesis, it suffices to be the finalized root
     /// whenever the struct was instantiated.
     genesis_block_root: Hash256,
+    /// The justified checkpoint from which `find_head` starts, along with the effective balances
+    /// of its state. Cached so that `find_head` need not read a `BeaconState` from the store.
+    justified_checkpoint: RwLock<Option<JustifiedCheckpoint>>,
+}
+
+/// A justified block, along with the effective balances of its state (indexed by validator index).
+struct JustifiedCheckpoint {
+    root: Hash256,
+    slot: Slot,
+    balances: Vec<u64>,
 }
 
 impl<T: BeaconChainTypes> ForkChoice<T> {
@@ -32,16 +49,81 @@ impl<T: BeaconChainTypes> ForkChoice<T> {
     /// block.
     pub fn new(
         store: Arc<T::Store>,
-        genesis_block: &BeaconBlock,
+        genesis_block: &BeaconBlock<T::EthSpec>,
         genesis_block_root: Hash256,
     ) -> Self {
         Self {
             backend: T::LmdGhost::new(store, genesis_block, genesis_block_root),
             genesis_block_root,
+            justified_checkpoint: RwLock::new(None),
+        }
+    }
+
+    /// Instantiate a fork chooser rooted at the finalized block of `head`, then replay each block
+    /// (and the attestations therein) from the finalized block up to `head`, as read from the
+    /// `store`.
+    ///
+    /// Only the canonical chain is replayed, so any knowledge of non-canonical blocks (and their
+    /// attestations) is lost. The replayed states take their shufflings from `shuffling_cache`.
+    pub fn rebuild_from_store(
+        store: Arc<T::Store>,
+        head: &CheckPoint<T::EthSpec>,
+        genesis_block_root: Hash256,
+        shuffling_cache: &Arc<ShufflingCache>,
+        spec: &ChainSpec,
+    ) -> Result<Self> {
+        // Resolve the `0x00.. 00` alias back to genesis
+        let finalized_root = if head.beacon_state.finalized_checkpoint.root == Hash256::zero() {
+            genesis_block_root
+        } else {
+            head.beacon_state.finalized_checkpoint.root
+        };
+
+        let finalized_block = store
+            .get::<BeaconBlock<T::EthSpec>>(&finalized_root)?
+            .ok_or_else(|| Error::MissingBlock(finalized_root))?;
+
+        let fork_choice = Self::new(store.clone(), &finalized_block, finalized_root);
+
+        // Collect all blocks from the head back to (but excluding) the finalized block.
+        let mut blocks = vec![];
+        let mut block_root = head.beacon_block_root;
+        let mut block = head.beacon_block.clone();
+
+        while block.slot > finalized_block.slot {
+            let parent_root = block.parent_root;
+
+            blocks.push((block_root, block));
+
+            block_root = parent_root;
+            block = store
+                .get::<BeaconBlock<T::EthSpec>>(&block_root)?
+                .ok_or_else(|| Error::MissingBlock(block_root))?;
         }
+
+        for (block_root, block) in blocks.into_iter().rev() {
+            let mut state = store
+                .get::<BeaconState<T::EthSpec>>(&block.state_root)?
+                .ok_or_else(|| Error::MissingState(block.state_root))?;
+            state.shuffling_cache = ShufflingCacheHandle::new(shuffling_cache.clone());
+
+            state.build_committee_cache(RelativeEpoch::Previous, spec)?;
+            state.build_committee_cache(RelativeEpoch::Current, spec)?;
+
+            fork_choice.process_block(&state, &block, block_root)?;
+        }
+
+        Ok(fork_choice)
     }
 
-    pub fn find_head(&self, chain: &BeaconChain<T>) -> Result<Hash256> {
+    /// Ensures the cached justified checkpoint (and the effective balances of its state) match the
+    /// justified checkpoint of `state`, reading the justified block and state from `store` only if
+    /// the checkpoint has changed.
+    pub fn update_justified_checkpoint(
+        &self,
+        store: &T::Store,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<()> {
         let start_slot = |epoch: Epoch| epoch.start_slot(T::EthSpec::slots_per_epoch());
 
         // From the specification:
@@ -49,49 +131,71 @@ impl<T: BeaconChainTypes> ForkChoice<T> {
         // Let justified_head be the descendant of finalized_head with the highest epoch that has
         // been justified for at least 1 epoch ... If no such descendant exists,
         // set justified_head to finalized_head.
-        let (start_state, start_block_root, start_block_slot) = {
-            let state = chain.current_state();
-
-            let (block_root, block_slot) =
-                if state.current_epoch() + 1 > state.current_justified_epoch {
-                    (
-                        state.current_justified_root,
-                        start_slot(state.current_justified_epoch),
-                    )
-                } else {
-                    (state.finalized_root, start_slot(state.finalized_epoch))
-                };
-
-            let block = chain
-                .store
-                .get::<BeaconBlock>(&block_root)?
-                .ok_or_else(|| Error::MissingBlock(block_root))?;
-
-            // Resolve the `0x00.. 00` alias back to genesis
-            let block_root = if block_root == Hash256::zero() {
-                self.genesis_block_root
+        let (block_root, block_slot) =
+            if state.current_epoch() + 1 > state.current_justified_checkpoint.epoch {
+                (
+                    state.current_justified_checkpoint.root,
+                    start_slot(state.current_justified_checkpoint.epoch),
+                )
             } else {
-                block_root
+                (
+                    state.finalized_checkpoint.root,
+                    start_slot(state.finalized_checkpoint.epoch),
+                )
             };
 
-            let state = chain
-                .store
+        // Resolve the `0x00.. 00` alias back to genesis
+        let block_root = if block_root == Hash256::zero() {
+            self.genesis_block_root
+        } else {
+            block_root
+        };
+
+        let is_cached = match &*self.justified_checkpoint.read() {
+            Some(checkpoint) => checkpoint.root == block_root && checkpoint.slot == block_slot,
+            None => false,
+        };
+
+        if !is_cached {
+            let block = store
+                .get::<BeaconBlock<T::EthSpec>>(&block_root)?
+                .ok_or_else(|| Error::MissingBlock(block_root))?;
+
+            let justified_state = store
                 .get::<BeaconState<T::EthSpec>>(&block.state_root)?
                 .ok_or_else(|| Error::MissingState(block.state_root))?;
 
-            (state, block_root, block_slot)
-        };
+            *self.justified_checkpoint.write() = Some(JustifiedCheckpoint {
+                root: block_root,
+                slot: block_slot,
+                balances: justified_state
+                    .validators
+                    .iter()
+                    .map(|v| v.effective_balance)
+                    .collect(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Run the fork choice rule from the cached justified checkpoint, returning the root of the
+    /// head block.
+    ///
+    /// Does not read from the store; `update_justified_checkpoint` must have been called first.
+    pub fn find_head(&self) -> Result<Hash256> {
+        let justified_checkpoint = self.justified_checkpoint.read();
+        let checkpoint = justified_checkpoint
+            .as_ref()
+            .ok_or_else(|| Error::UnknownJustifiedCheckpoint)?;
 
         // A function that returns the weight for some validator index.
         let weight = |validator_index: usize| -> Option<u64> {
-            start_state
-                .validator_registry
-                .get(validator_index)
-                .map(|v| v.effective_balance)
+            checkpoint.balances.get(validator_index).cloned()
         };
 
         self.backend
-            .find_head(start_block_slot, start_block_root, weight)
+            .find_head(checkpoint.slot, checkpoint.root, weight)
             .map_err(Into::into)
     }
 
@@ -102,7 +206,7 @@ impl<T: BeaconChainTypes> ForkChoice<T> {
     pub fn process_block(
         &self,
         state: &BeaconState<T::EthSpec>,
-        block: &BeaconBlock,
+        block: &BeaconBlock<T::EthSpec>,
         block_root: Hash256,
     ) -> Result<()> {
         // Note: we never count the block as a latest message, only attestations.
@@ -123,16 +227,16 @@ impl<T: BeaconChainTypes> ForkChoice<T> {
     fn process_attestation_from_block(
         &self,
         state: &BeaconState<T::EthSpec>,
-        attestation: &Attestation,
+        attestation: &Attestation<T::EthSpec>,
     ) -> Result<()> {
         // Note: `get_attesting_indices_unsorted` requires that the beacon state caches be built.
         let validator_indices = get_attesting_indices_unsorted(
             state,
             &attestation.data,
-            &attestation.aggregation_bitfield,
+            &attestation.aggregation_bits,
         )?;
 
-        let block_hash = attestation.data.target_root;
+        let block_hash = attestation.data.target.root;
 
         // Ignore any attestations to the zero hash.
         //
@@ -150,7 +254,8 @@ impl<T: BeaconChainTypes> ForkChoice<T> {
         if block_hash != Hash256::zero() {
             let block_slot = attestation
                 .data
-                .target_epoch
+                .target
+                .epoch
                 .start_slot(T::EthSpec::slots_per_epoch());
 
             for validator_index in validator_indices {
@@ -162,13 +267,24 @@ impl<T: BeaconChainTypes> ForkChoice<T> {
         Ok(())
     }
 
+    /// Returns `true` if `block_root` is a node of the fork choice backend.
+    pub fn contains_block(&self, block_root: Hash256) -> bool {
+        self.backend.contains_block(block_root)
+    }
+
+    /// Returns a read-only copy of all blocks and latest messages known to the fork choice
+    /// backend, for debugging.
+    pub fn introspect(&self) -> Result<LmdGhostSnapshot> {
+        self.backend.introspect().map_err(Into::into)
+    }
+
     /// Inform the fork choice that the given block (and corresponding root) have been finalized so
     /// it may prune it's storage.
     ///
     /// `finalized_block_root` must be the root of `finalized_block`.
     pub fn process_finalization(
         &self,
-        finalized_block: &BeaconBlock,
+        finalized_block: &BeaconBlock<T::EthSpec>,
         finalized_block_root: Hash256,
     ) -> Result<()> {
         self.backend
@@ -177,6 +293,51 @@ impl<T: BeaconChainTypes> ForkChoice<T> {
     }
 }
 
+impl<T: BeaconChainTypes> ForkChoice<T> {
+    /// Returns a representation of `self` which can be persisted to the store.
+    pub fn as_ssz_container(&self) -> SszForkChoice {
+        SszForkChoice {
+            genesis_block_root: self.genesis_block_root,
+            backend_bytes: self.backend.as_bytes(),
+        }
+    }
+
+    /// Instantiate `Self` from a representation produced by `as_ssz_container`.
+    ///
+    /// Returns an error if the backend bytes are missing or cannot be decoded (e.g., they were
+    /// produced by a different backend or an older version).
+    pub fn from_ssz_container(ssz_container: SszForkChoice, store: Arc<T::Store>) -> Result<Self> {
+        Ok(Self {
+            backend: T::LmdGhost::from_bytes(&ssz_container.backend_bytes, store)?,
+            genesis_block_root: ssz_container.genesis_block_root,
+            justified_checkpoint: RwLock::new(None),
+        })
+    }
+}
+
+/// Helper struct that is used to encode/decode the state of the `ForkChoice` as SSZ bytes.
+///
+/// This is used when persisting the state of the `BeaconChain` to disk.
+#[derive(Encode, Decode, Clone)]
+pub struct SszForkChoice {
+    genesis_block_root: Hash256,
+    pub backend_bytes: Vec<u8>,
+}
+
+impl StoreItem for SszForkChoice {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconChain
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &mut [u8]) -> std::result::Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
+
 impl From<BeaconStateError> for Error {
     fn from(e: BeaconStateError) -> Error {
         Error::BeaconStateError(e)
@@ -10,11 +10,13 @@ pub use self::beacon_chain::{BeaconChain, BeaconChainTypes, BlockProcessingOutco
 pub use self::checkpoint::CheckPoint;
 pub use self::errors::{BeaconChainError, BlockProductionError};
 pub use lmd_ghost;
+pub use operation_pool::{InsertError, OperationPoolConfig};
 pub use parking_lot;
 pub use slot_clock;
 pub use state_processing::per_block_processing::errors::{
     AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
     ExitValidationError, ProposerSlashingValidationError, TransferValidationError,
 };
+pub use state_processing::{RewardReport, ValidatorRewards};
 pub use store;
 pub use types;
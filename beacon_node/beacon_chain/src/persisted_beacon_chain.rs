@@ -8,10 +8,16 @@ use types::{BeaconState, Hash256};
 /// 32-byte key for accessing the `PersistedBeaconChain`.
 pub const BEACON_CHAIN_DB_KEY: &str = "PERSISTEDBEACONCHAINPERSISTEDBEA";
 
+/// 32-byte key for accessing the `SszForkChoice` persisted alongside the `PersistedBeaconChain`.
+///
+/// The fork choice is stored separately so that the layout of `PersistedBeaconChain` does not
+/// depend upon it.
+pub const FORK_CHOICE_DB_KEY: &str = "FORKCHOICEFORKCHOICEFORKCHOICEFO";
+
 #[derive(Encode, Decode)]
 pub struct PersistedBeaconChain<T: BeaconChainTypes> {
     pub canonical_head: CheckPoint<T::EthSpec>,
-    pub op_pool: PersistedOperationPool,
+    pub op_pool: PersistedOperationPool<T::EthSpec>,
     pub genesis_block_root: Hash256,
     pub state: BeaconState<T::EthSpec>,
 }
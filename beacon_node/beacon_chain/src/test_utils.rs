@@ -10,11 +10,14 @@ use store::Store;
 use tree_hash::{SignedRoot, TreeHash};
 use types::{
     test_utils::TestingBeaconStateBuilder, AggregateSignature, Attestation,
-    AttestationDataAndCustodyBit, BeaconBlock, BeaconState, Bitfield, ChainSpec, Domain, EthSpec,
+    AttestationDataAndCustodyBit, BeaconBlock, BeaconState, BitList, ChainSpec, Domain, EthSpec,
     Hash256, Keypair, RelativeEpoch, SecretKey, Signature, Slot,
 };
 
-pub use crate::persisted_beacon_chain::{PersistedBeaconChain, BEACON_CHAIN_DB_KEY};
+pub use crate::fork_choice::SszForkChoice;
+pub use crate::persisted_beacon_chain::{
+    PersistedBeaconChain, BEACON_CHAIN_DB_KEY, FORK_CHOICE_DB_KEY,
+};
 
 /// Indicates how the `BeaconChainHarness` should produce blocks.
 #[derive(Clone, Copy, Debug)]
@@ -209,7 +212,7 @@ where
         mut state: BeaconState<E>,
         slot: Slot,
         block_strategy: BlockStrategy,
-    ) -> (BeaconBlock, BeaconState<E>) {
+    ) -> (BeaconBlock<E>, BeaconState<E>) {
         if slot < state.slot {
             panic!("produce slot cannot be prior to the state slot");
         }
@@ -295,12 +298,14 @@ where
                             )
                             .expect("should produce attestation data");
 
-                        let mut aggregation_bitfield = Bitfield::new();
-                        aggregation_bitfield.set(i, true);
-                        aggregation_bitfield.set(committee_size, false);
+                        let mut aggregation_bits = BitList::with_capacity(committee_size)
+                            .expect("should make aggregation bits");
+                        aggregation_bits
+                            .set(i, true)
+                            .expect("should be able to set aggregation bits");
 
-                        let mut custody_bitfield = Bitfield::new();
-                        custody_bitfield.set(committee_size, false);
+                        let custody_bits = BitList::with_capacity(committee_size)
+                            .expect("should make custody bits");
 
                         let signature = {
                             let message = AttestationDataAndCustodyBit {
@@ -310,7 +315,7 @@ where
                             .tree_hash_root();
 
                             let domain =
-                                spec.get_domain(data.target_epoch, Domain::Attestation, fork);
+                                spec.get_domain(data.target.epoch, Domain::Attestation, fork);
 
                             let mut agg_sig = AggregateSignature::new();
                             agg_sig.add(&Signature::new(
@@ -323,15 +328,18 @@ where
                         };
 
                         let attestation = Attestation {
-                            aggregation_bitfield,
+                            aggregation_bits,
                             data,
-                            custody_bitfield,
+                            custody_bits,
                             signature,
                         };
 
+                        // Insert against `state`, rather than the chain's head state, so that
+                        // attestations for blocks on a fork can be included in its later blocks.
                         self.chain
-                            .process_attestation(attestation)
-                            .expect("should process attestation");
+                            .op_pool
+                            .insert_attestation(attestation, state, spec)
+                            .expect("should insert attestation");
                     }
                 }
             });
@@ -2,20 +2,31 @@
 
 use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, CommonTypes, PersistedBeaconChain,
-    BEACON_CHAIN_DB_KEY,
+    SszForkChoice, BEACON_CHAIN_DB_KEY, FORK_CHOICE_DB_KEY,
+};
+use beacon_chain::{
+    BeaconChain, BeaconChainError, BlockProcessingOutcome, InsertError, OperationPoolConfig,
 };
 use lmd_ghost::ThreadSafeReducedTree;
 use rand::Rng;
+use slasher::{Slasher, SlasherConfig};
+use state_processing::per_slot_processing;
+use std::sync::Arc;
 use store::{MemoryStore, Store};
+use tree_hash::{SignedRoot, TreeHash};
 use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
-use types::{Deposit, EthSpec, Hash256, MinimalEthSpec, Slot};
+use types::{
+    AggregateSignature, Attestation, AttestationDataAndCustodyBit, BeaconBlock, BeaconState,
+    BitList, DepositData, Domain, Epoch, EthSpec, Hash256, MinimalEthSpec, Signature, Slot,
+};
 
 // Should ideally be divisible by 3.
 pub const VALIDATOR_COUNT: usize = 24;
 
 type TestForkChoice = ThreadSafeReducedTree<MemoryStore, MinimalEthSpec>;
+type TestHarness = BeaconChainHarness<TestForkChoice, MinimalEthSpec>;
 
-fn get_harness(validator_count: usize) -> BeaconChainHarness<TestForkChoice, MinimalEthSpec> {
+fn get_harness(validator_count: usize) -> TestHarness {
     let harness = BeaconChainHarness::new(validator_count);
 
     // Move past the zero slot.
@@ -107,12 +118,12 @@ fn finalizes_with_full_participation() {
         "head should be at the expected epoch"
     );
     assert_eq!(
-        state.current_justified_epoch,
+        state.current_justified_checkpoint.epoch,
         state.current_epoch() - 1,
         "the head should be justified one behind the current epoch"
     );
     assert_eq!(
-        state.finalized_epoch,
+        state.finalized_checkpoint.epoch,
         state.current_epoch() - 2,
         "the head should be finalized two behind the current epoch"
     );
@@ -150,12 +161,12 @@ fn finalizes_with_two_thirds_participation() {
     // included in blocks during that epoch.
 
     assert_eq!(
-        state.current_justified_epoch,
+        state.current_justified_checkpoint.epoch,
         state.current_epoch() - 2,
         "the head should be justified two behind the current epoch"
     );
     assert_eq!(
-        state.finalized_epoch,
+        state.finalized_checkpoint.epoch,
         state.current_epoch() - 4,
         "the head should be finalized three behind the current epoch"
     );
@@ -189,11 +200,11 @@ fn does_not_finalize_with_less_than_two_thirds_participation() {
         "head should be at the expected epoch"
     );
     assert_eq!(
-        state.current_justified_epoch, 0,
+        state.current_justified_checkpoint.epoch, 0,
         "no epoch should have been justified"
     );
     assert_eq!(
-        state.finalized_epoch, 0,
+        state.finalized_checkpoint.epoch, 0,
         "no epoch should have been finalized"
     );
 }
@@ -222,15 +233,267 @@ fn does_not_finalize_without_attestation() {
         "head should be at the expected epoch"
     );
     assert_eq!(
-        state.current_justified_epoch, 0,
+        state.current_justified_checkpoint.epoch, 0,
         "no epoch should have been justified"
     );
     assert_eq!(
-        state.finalized_epoch, 0,
+        state.finalized_checkpoint.epoch, 0,
         "no epoch should have been finalized"
     );
 }
 
+/// Signs `block` as the proposer for its slot on the canonical chain.
+fn sign_block(harness: &TestHarness, block: &mut BeaconBlock<MinimalEthSpec>) {
+    let proposer_index = harness
+        .chain
+        .block_proposer(block.slot)
+        .expect("should get block proposer");
+    let fork = harness.chain.head().beacon_state.fork.clone();
+
+    let message = block.signed_root();
+    let epoch = block.slot.epoch(MinimalEthSpec::slots_per_epoch());
+    let domain = harness
+        .spec
+        .get_domain(epoch, Domain::BeaconProposer, &fork);
+    block.signature = Signature::new(&message, domain, &harness.keypairs[proposer_index].sk);
+}
+
+#[test]
+fn rejects_known_invalid_blocks_and_descendants() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness.advance_slot();
+
+    // An empty block atop the head is invalid, even when signed by the correct proposer.
+    let mut invalid_block = BeaconBlock::empty(&harness.spec);
+    invalid_block.slot = harness.chain.read_slot_clock().unwrap();
+    invalid_block.parent_root = harness.chain.head().beacon_block_root;
+    sign_block(&harness, &mut invalid_block);
+    let invalid_root = invalid_block.block_header().canonical_root();
+
+    match harness.chain.process_block(invalid_block.clone()) {
+        Ok(BlockProcessingOutcome::PerBlockProcessingError(_))
+        | Ok(BlockProcessingOutcome::StateRootMismatch) => {}
+        other => panic!("invalid block should be rejected: {:?}", other),
+    }
+    assert!(harness.chain.is_known_invalid_block_root(&invalid_root));
+
+    assert_eq!(
+        harness.chain.process_block(invalid_block),
+        Ok(BlockProcessingOutcome::BlockIsKnownInvalid),
+        "invalid block should not be re-processed"
+    );
+
+    let mut child = BeaconBlock::empty(&harness.spec);
+    child.slot = harness.chain.read_slot_clock().unwrap();
+    child.parent_root = invalid_root;
+    let child_root = child.block_header().canonical_root();
+
+    assert_eq!(
+        harness.chain.process_block(child),
+        Ok(BlockProcessingOutcome::ParentIsInvalid {
+            parent: invalid_root
+        }),
+        "child of invalid block should be rejected"
+    );
+    assert!(
+        !harness.chain.is_known_invalid_block_root(&child_root),
+        "child without a verified signature should not be remembered"
+    );
+}
+
+#[test]
+fn does_not_remember_invalid_blocks_with_bad_signatures() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness.advance_slot();
+
+    // An unsigned, empty block atop the head.
+    let mut unsigned_block = BeaconBlock::empty(&harness.spec);
+    unsigned_block.slot = harness.chain.read_slot_clock().unwrap();
+    unsigned_block.parent_root = harness.chain.head().beacon_block_root;
+    let unsigned_root = unsigned_block.block_header().canonical_root();
+
+    match harness.chain.process_block(unsigned_block) {
+        Ok(BlockProcessingOutcome::PerBlockProcessingError(_)) => {}
+        other => panic!("unsigned block should be rejected: {:?}", other),
+    }
+    assert!(!harness.chain.is_known_invalid_block_root(&unsigned_root));
+}
+
+#[test]
+fn catchup_uses_prepared_state_across_epoch_boundary() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    // Build blocks up until the last slot of the first epoch.
+    harness.extend_chain(
+        MinimalEthSpec::slots_per_epoch() as usize - 1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let expected_state = {
+        let mut state = harness.chain.current_state().clone();
+        per_slot_processing(&mut state, &harness.spec).unwrap();
+        state
+    };
+
+    harness
+        .chain
+        .prepare_next_slot_state()
+        .expect("should prepare next slot state");
+
+    assert_eq!(
+        harness.chain.current_state().slot,
+        expected_state.slot - 1,
+        "preparation should not modify the current state"
+    );
+
+    harness.advance_slot();
+
+    let state = harness.chain.current_state();
+
+    assert_eq!(state.slot, expected_state.slot);
+    assert_eq!(
+        state.current_epoch(),
+        1,
+        "state should have transitioned epoch"
+    );
+    assert_eq!(
+        state.canonical_root(),
+        expected_state.canonical_root(),
+        "prepared state should match state produced by catchup"
+    );
+}
+
+#[test]
+fn slasher_finds_no_offences_on_honest_chain() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 3;
+
+    let mut harness = get_harness(VALIDATOR_COUNT);
+    let slasher = Arc::new(Slasher::new(
+        harness.chain.store.clone(),
+        SlasherConfig::default(),
+    ));
+    harness.chain.slasher = Some(slasher.clone());
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    assert!(
+        slasher.queue_len() > num_blocks_produced as usize,
+        "slasher should receive each block header and its attestations"
+    );
+
+    let output = harness
+        .chain
+        .process_slasher_queue()
+        .expect("should process slasher queue");
+
+    assert!(output.is_empty(), "honest chain should not be slashable");
+    assert_eq!(slasher.queue_len(), 0, "slasher queue should be drained");
+}
+
+#[test]
+fn slasher_receives_gossip_attestations_rejected_by_op_pool() {
+    let mut harness = get_harness(VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let slasher = Arc::new(Slasher::new(
+        harness.chain.store.clone(),
+        SlasherConfig::default(),
+    ));
+    harness.chain.slasher = Some(slasher.clone());
+    harness.chain.op_pool.set_config(OperationPoolConfig {
+        max_attestations: 0,
+        ..OperationPoolConfig::default()
+    });
+
+    let state = harness.chain.head().beacon_state.clone();
+    let committee = state
+        .get_crosslink_committees_at_slot(state.slot)
+        .expect("should get committees")[0]
+        .clone();
+    let data = harness
+        .chain
+        .produce_attestation_data(committee.shard)
+        .expect("should produce attestation data");
+
+    let sign = |validator_index: usize| {
+        let message = AttestationDataAndCustodyBit {
+            data: data.clone(),
+            custody_bit: false,
+        }
+        .tree_hash_root();
+        let domain = harness
+            .spec
+            .get_domain(data.target.epoch, Domain::Attestation, &state.fork);
+
+        let mut signature = AggregateSignature::new();
+        signature.add(&Signature::new(
+            &message,
+            domain,
+            &harness.keypairs[validator_index].sk,
+        ));
+        signature
+    };
+
+    let mut aggregation_bits =
+        BitList::with_capacity(committee.committee.len()).expect("should make aggregation bits");
+    aggregation_bits
+        .set(0, true)
+        .expect("should set aggregation bit");
+
+    let attestation = Attestation {
+        aggregation_bits,
+        data: data.clone(),
+        custody_bits: BitList::with_capacity(committee.committee.len())
+            .expect("should make custody bits"),
+        signature: sign(committee.committee[0]),
+    };
+
+    assert_eq!(
+        harness.chain.process_attestation(attestation.clone()),
+        Err(InsertError::PoolFull { limit: 0 })
+    );
+    assert_eq!(
+        slasher.queue_len(),
+        1,
+        "slasher should receive the attestation rejected by the op pool"
+    );
+
+    let forged = Attestation {
+        signature: sign(committee.committee[1]),
+        ..attestation
+    };
+    assert!(harness.chain.process_attestation(forged).is_err());
+    assert_eq!(
+        slasher.queue_len(),
+        1,
+        "slasher should ignore an attestation with an invalid signature"
+    );
+}
+
 #[test]
 fn roundtrip_operation_pool() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
@@ -247,10 +510,10 @@ fn roundtrip_operation_pool() {
 
     // Add some deposits
     let rng = &mut XorShiftRng::from_seed([66; 16]);
-    for _ in 0..rng.gen_range(1, VALIDATOR_COUNT) {
+    for index in 0..rng.gen_range(1, VALIDATOR_COUNT as u64) {
         harness
             .chain
-            .process_deposit(Deposit::random_for_test(rng))
+            .process_deposit(index, DepositData::random_for_test(rng))
             .unwrap();
     }
 
@@ -261,7 +524,224 @@ fn roundtrip_operation_pool() {
     let p: PersistedBeaconChain<CommonTypes<TestForkChoice, MinimalEthSpec>> =
         harness.chain.store.get(&key).unwrap().unwrap();
 
-    let restored_op_pool = p.op_pool.into_operation_pool(&p.state, &harness.spec);
+    let restored_op_pool = p.op_pool.into_operation_pool(
+        &p.state,
+        &harness.spec,
+        harness.chain.op_pool.config().clone(),
+    );
 
     assert_eq!(harness.chain.op_pool, restored_op_pool);
 }
+
+/// Builds a chain with a fork, persists it and returns the harness.
+fn get_forked_and_persisted_harness() -> BeaconChainHarness<TestForkChoice, MinimalEthSpec> {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    let two_thirds = (VALIDATOR_COUNT / 3) * 2;
+    let delay = MinimalEthSpec::default_spec().min_attestation_inclusion_delay as usize;
+    let initial_blocks = delay + 1;
+
+    harness.extend_chain(
+        initial_blocks,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    harness.advance_slot();
+    harness.extend_chain(
+        delay + 1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators((0..two_thirds).collect()),
+    );
+    harness.extend_chain(
+        delay + 2,
+        BlockStrategy::ForkCanonicalChainAt {
+            previous_slot: Slot::from(initial_blocks),
+            first_slot: Slot::from(initial_blocks + 2),
+        },
+        AttestationStrategy::SomeValidators((two_thirds..VALIDATOR_COUNT).collect()),
+    );
+
+    harness.chain.persist().unwrap();
+
+    harness
+}
+
+#[test]
+fn roundtrip_fork_choice() {
+    let harness = get_forked_and_persisted_harness();
+
+    let restored: BeaconChain<CommonTypes<TestForkChoice, MinimalEthSpec>> =
+        BeaconChain::from_store(
+            harness.chain.store.clone(),
+            harness.spec.clone(),
+            OperationPoolConfig::default(),
+        )
+        .unwrap()
+        .expect("should restore beacon chain");
+
+    assert_eq!(
+        restored.fork_choice.find_head(),
+        harness.chain.fork_choice.find_head(),
+        "restored fork choice should find the same head"
+    );
+    assert_eq!(
+        restored.fork_choice.introspect(),
+        harness.chain.fork_choice.introspect(),
+        "restored fork choice should have the same contents"
+    );
+}
+
+#[test]
+fn rebuilds_fork_choice_when_persisted_fork_choice_is_invalid() {
+    let harness = get_forked_and_persisted_harness();
+
+    let key = Hash256::from_slice(&FORK_CHOICE_DB_KEY.as_bytes());
+    let mut ssz_container: SszForkChoice = harness.chain.store.get(&key).unwrap().unwrap();
+
+    ssz_container.backend_bytes = vec![];
+    harness.chain.store.put(&key, &ssz_container).unwrap();
+
+    let restored: BeaconChain<CommonTypes<TestForkChoice, MinimalEthSpec>> =
+        BeaconChain::from_store(
+            harness.chain.store.clone(),
+            harness.spec.clone(),
+            OperationPoolConfig::default(),
+        )
+        .unwrap()
+        .expect("should restore beacon chain");
+
+    assert_eq!(
+        restored.fork_choice.find_head(),
+        Ok(harness.chain.head().beacon_block_root),
+        "rebuilt fork choice should find the canonical head"
+    );
+}
+
+#[test]
+fn rebuilds_fork_choice_when_persisted_fork_choice_is_missing() {
+    let harness = get_forked_and_persisted_harness();
+
+    // Databases written before the fork choice was persisted do not contain it.
+    let key = Hash256::from_slice(&FORK_CHOICE_DB_KEY.as_bytes());
+    harness.chain.store.delete::<SszForkChoice>(&key).unwrap();
+
+    let restored: BeaconChain<CommonTypes<TestForkChoice, MinimalEthSpec>> =
+        BeaconChain::from_store(
+            harness.chain.store.clone(),
+            harness.spec.clone(),
+            OperationPoolConfig::default(),
+        )
+        .unwrap()
+        .expect("should restore beacon chain");
+
+    assert_eq!(
+        restored.fork_choice.find_head(),
+        Ok(harness.chain.head().beacon_block_root),
+        "rebuilt fork choice should find the canonical head"
+    );
+}
+
+#[test]
+fn rebuilds_fork_choice_when_persisted_fork_choice_is_outdated() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let num_blocks = MinimalEthSpec::slots_per_epoch() as usize;
+
+    harness.extend_chain(
+        num_blocks,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    harness.chain.persist().unwrap();
+
+    let key = Hash256::from_slice(&FORK_CHOICE_DB_KEY.as_bytes());
+    let stale_fork_choice: SszForkChoice = harness.chain.store.get(&key).unwrap().unwrap();
+
+    harness.advance_slot();
+    harness.extend_chain(
+        num_blocks,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    harness.chain.persist().unwrap();
+
+    // As if the node crashed after persisting the chain, but before persisting the fork choice.
+    harness.chain.store.put(&key, &stale_fork_choice).unwrap();
+
+    let restored: BeaconChain<CommonTypes<TestForkChoice, MinimalEthSpec>> =
+        BeaconChain::from_store(
+            harness.chain.store.clone(),
+            harness.spec.clone(),
+            OperationPoolConfig::default(),
+        )
+        .unwrap()
+        .expect("should restore beacon chain");
+
+    assert!(
+        restored
+            .fork_choice
+            .contains_block(harness.chain.head().beacon_block_root),
+        "rebuilt fork choice should contain the canonical head"
+    );
+    assert_eq!(
+        restored.fork_choice.find_head(),
+        Ok(harness.chain.head().beacon_block_root),
+        "rebuilt fork choice should find the canonical head"
+    );
+}
+
+#[test]
+fn reward_report_matches_balance_changes_and_is_cached() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let slots_per_epoch = MinimalEthSpec::slots_per_epoch();
+
+    harness.extend_chain(
+        3 * slots_per_epoch as usize + 1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // Every validator attested throughout epoch 1, so all are rewarded by the transition out of
+    // epoch 2.
+    let epoch = Epoch::new(2);
+    let report = harness
+        .chain
+        .reward_report(epoch)
+        .expect("should replay epoch");
+    assert_eq!(report.epoch, epoch);
+    assert_eq!(report.validators.len(), VALIDATOR_COUNT);
+
+    let state_at_slot = |slot: Slot| -> BeaconState<MinimalEthSpec> {
+        let (block_root, _) = harness.chain.rev_iter_block_roots(slot).next().unwrap();
+        let block = harness.chain.get_block(&block_root).unwrap().unwrap();
+        assert_eq!(block.slot, slot, "every slot has a block");
+        harness.chain.store.get(&block.state_root).unwrap().unwrap()
+    };
+    // The block at the first slot of the next epoch doesn't change any balances, so the
+    // difference between these states is due to the epoch transition alone.
+    let before = state_at_slot(epoch.end_slot(slots_per_epoch));
+    let after = state_at_slot(epoch.end_slot(slots_per_epoch) + 1);
+
+    for (i, rewards) in report.validators.iter().enumerate() {
+        assert!(rewards.source.rewards > 0, "validator {} attested", i);
+        assert_eq!(rewards.source.penalties, 0, "validator {} attested", i);
+
+        let total = rewards.total().unwrap();
+        assert_eq!(
+            after.balances[i],
+            (before.balances[i] + total.rewards).saturating_sub(total.penalties),
+            "balance of validator {}",
+            i
+        );
+    }
+
+    // A repeated request is served from the cache rather than replaying the epoch.
+    let cached = harness.chain.reward_report(epoch).unwrap();
+    assert!(Arc::ptr_eq(&report, &cached));
+
+    // The current epoch has not been processed yet.
+    let current_epoch = harness.chain.current_state().current_epoch();
+    assert_eq!(
+        harness.chain.reward_report(current_epoch),
+        Err(BeaconChainError::EpochNotYetProcessed(current_epoch))
+    );
+}
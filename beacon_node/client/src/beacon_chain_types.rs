@@ -2,7 +2,7 @@ use beacon_chain::{
     lmd_ghost::{LmdGhost, ThreadSafeReducedTree},
     slot_clock::SystemTimeSlotClock,
     store::Store,
-    BeaconChain, BeaconChainTypes,
+    BeaconChain, BeaconChainTypes, OperationPoolConfig,
 };
 use slog::{info, Logger};
 use slot_clock::SlotClock;
@@ -19,9 +19,15 @@ pub trait InitialiseBeaconChain<T: BeaconChainTypes> {
     fn initialise_beacon_chain(
         store: Arc<T::Store>,
         spec: ChainSpec,
+        op_pool_config: OperationPoolConfig,
         log: Logger,
     ) -> BeaconChain<T> {
-        maybe_load_from_store_for_testnet::<_, T::Store, T::EthSpec>(store, spec, log)
+        maybe_load_from_store_for_testnet::<_, T::Store, T::EthSpec>(
+            store,
+            spec,
+            op_pool_config,
+            log,
+        )
     }
 }
 
@@ -43,13 +49,16 @@ impl<T: Store, E: EthSpec, X: BeaconChainTypes> InitialiseBeaconChain<X> for Cli
 fn maybe_load_from_store_for_testnet<T, U: Store, V: EthSpec>(
     store: Arc<U>,
     spec: ChainSpec,
+    op_pool_config: OperationPoolConfig,
     log: Logger,
 ) -> BeaconChain<T>
 where
     T: BeaconChainTypes<Store = U, EthSpec = V>,
     T::LmdGhost: LmdGhost<U, V>,
 {
-    if let Ok(Some(beacon_chain)) = BeaconChain::from_store(store.clone(), spec.clone()) {
+    if let Ok(Some(beacon_chain)) =
+        BeaconChain::from_store(store.clone(), spec.clone(), op_pool_config.clone())
+    {
         info!(
             log,
             "Loaded BeaconChain from store";
@@ -66,7 +75,7 @@ where
         );
         let (genesis_state, _keypairs) = state_builder.build();
 
-        let mut genesis_block = BeaconBlock::empty(&spec);
+        let mut genesis_block = BeaconBlock::<V>::empty(&spec);
         genesis_block.state_root = Hash256::from_slice(&genesis_state.tree_hash_root());
 
         // Slot clock
@@ -78,7 +87,11 @@ where
 
         // Genesis chain
         //TODO: Handle error correctly
-        BeaconChain::from_genesis(store, slot_clock, genesis_state, genesis_block, spec)
-            .expect("Terminate if beacon chain generation fails")
+        let mut beacon_chain =
+            BeaconChain::from_genesis(store, slot_clock, genesis_state, genesis_block, spec)
+                .expect("Terminate if beacon chain generation fails");
+        beacon_chain.op_pool.set_config(op_pool_config);
+
+        beacon_chain
     }
 }
@@ -1,7 +1,9 @@
+use beacon_chain::OperationPoolConfig;
 use clap::ArgMatches;
 use http_server::HttpServerConfig;
 use network::NetworkConfig;
 use serde_derive::{Deserialize, Serialize};
+use slasher::SlasherConfig;
 use slog::{info, o, Drain};
 use std::fs::{self, OpenOptions};
 use std::path::PathBuf;
@@ -17,6 +19,8 @@ pub struct Config {
     pub network: network::NetworkConfig,
     pub rpc: rpc::RPCConfig,
     pub http: HttpServerConfig,
+    pub slasher: SlasherConfig,
+    pub op_pool: OperationPoolConfig,
 }
 
 impl Default for Config {
@@ -31,6 +35,8 @@ impl Default for Config {
             network: NetworkConfig::new(),
             rpc: rpc::RPCConfig::default(),
             http: HttpServerConfig::default(),
+            slasher: SlasherConfig::default(),
+            op_pool: OperationPoolConfig::default(),
         }
     }
 }
@@ -101,6 +107,8 @@ impl Config {
         self.network.apply_cli_args(args)?;
         self.rpc.apply_cli_args(args)?;
         self.http.apply_cli_args(args)?;
+        self.slasher.apply_cli_args(args)?;
+        apply_op_pool_cli_args(&mut self.op_pool, args)?;
 
         if let Some(log_file) = args.value_of("logfile") {
             self.log_file = PathBuf::from(log_file);
@@ -110,3 +118,36 @@ impl Config {
         Ok(())
     }
 }
+
+/// Apply the operation pool limits specified in `args` to `config`.
+fn apply_op_pool_cli_args(
+    config: &mut OperationPoolConfig,
+    args: &ArgMatches,
+) -> Result<(), String> {
+    let mut limits = [
+        ("op-pool-max-attestations", &mut config.max_attestations),
+        (
+            "op-pool-max-attester-slashings",
+            &mut config.max_attester_slashings,
+        ),
+        (
+            "op-pool-max-proposer-slashings",
+            &mut config.max_proposer_slashings,
+        ),
+        (
+            "op-pool-max-voluntary-exits",
+            &mut config.max_voluntary_exits,
+        ),
+        ("op-pool-max-transfers", &mut config.max_transfers),
+    ];
+
+    for (name, limit) in limits.iter_mut() {
+        if let Some(value) = args.value_of(name) {
+            **limit = value
+                .parse()
+                .map_err(|_| format!("Invalid value for --{}: {}", name, value))?;
+        }
+    }
+
+    Ok(())
+}
@@ -6,17 +6,21 @@ pub mod error;
 pub mod notifier;
 
 use beacon_chain::BeaconChain;
+use eth2_libp2p::{fork_topic, Topic, TopicBuilder, BEACON_ATTESTATION_TOPIC, BEACON_PUBSUB_TOPIC};
 use exit_future::Signal;
 use futures::{future::Future, Stream};
-use network::Service as NetworkService;
+use network::{NetworkMessage, Service as NetworkService};
 use prometheus::Registry;
-use slog::{error, info, o};
+use slasher::Slasher;
+use slog::{debug, error, info, o, warn};
 use slot_clock::SlotClock;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::runtime::TaskExecutor;
+use tokio::sync::mpsc;
 use tokio::timer::Interval;
+use types::EthSpec;
 
 pub use beacon_chain::BeaconChainTypes;
 pub use beacon_chain_types::ClientType;
@@ -24,6 +28,10 @@ pub use beacon_chain_types::InitialiseBeaconChain;
 pub use config::Config as ClientConfig;
 pub use eth2_config::Eth2Config;
 
+/// The maximum duration prior to a slot boundary that the head state is advanced into the next
+/// slot. Capped to half the slot duration.
+pub const STATE_PREPARATION_LEAD_MILLIS: u64 = 1_500;
+
 /// Main beacon node client service. This provides the connection and initialisation of the clients
 /// sub-services in multiple threads.
 pub struct Client<T: BeaconChainTypes> {
@@ -62,11 +70,22 @@ where
         let seconds_per_slot = eth2_config.spec.seconds_per_slot;
 
         // Load a `BeaconChain` from the store, or create a new one if it does not exist.
-        let beacon_chain = Arc::new(T::initialise_beacon_chain(
-            store,
+        let mut beacon_chain = T::initialise_beacon_chain(
+            store.clone(),
             eth2_config.spec.clone(),
+            client_config.op_pool.clone(),
             log.clone(),
-        ));
+        );
+        if client_config.slasher.enabled {
+            info!(
+                log,
+                "Slasher enabled";
+                "history_length" => client_config.slasher.history_length,
+            );
+            beacon_chain.slasher =
+                Some(Arc::new(Slasher::new(store, client_config.slasher.clone())));
+        }
+        let beacon_chain = Arc::new(beacon_chain);
         // Registry all beacon chain metrics with the global registry.
         beacon_chain
             .metrics
@@ -133,7 +152,7 @@ where
             Some(http_server::start_service(
                 &client_config.http,
                 executor,
-                network_send,
+                network_send.clone(),
                 beacon_chain.clone(),
                 client_config.db_path().expect("unable to read datadir"),
                 metrics_registry,
@@ -155,11 +174,74 @@ where
 
             let chain = beacon_chain.clone();
             let log = log.new(o!("Service" => "SlotTimer"));
+            let mut network_send = network_send.clone();
+            executor.spawn(
+                exit.clone()
+                    .until(
+                        interval
+                            .for_each(move |_| {
+                                do_state_catchup(&chain, &log);
+                                do_fork_subscription(&chain, &mut network_send, &log);
+
+                                Ok(())
+                            })
+                            .map_err(|_| ()),
+                    )
+                    .map(|_| ()),
+            );
+
+            // Set up the state preparation interval - fire shortly before each slot boundary so
+            // the head state is already advanced into the next slot when that slot starts.
+            let interval = {
+                let slot_duration = Duration::from_secs(seconds_per_slot);
+                let lead = std::cmp::min(
+                    Duration::from_millis(STATE_PREPARATION_LEAD_MILLIS),
+                    slot_duration / 2,
+                );
+                let first_preparation = if duration_to_next_slot > lead {
+                    duration_to_next_slot - lead
+                } else {
+                    duration_to_next_slot + slot_duration - lead
+                };
+
+                Interval::new(Instant::now() + first_preparation, slot_duration)
+            };
+
+            // Set up the slasher interval - process the blocks and attestations seen during each
+            // slot, halfway through the following slot.
+            if beacon_chain.slasher.is_some() {
+                let interval = {
+                    let slot_duration = Duration::from_secs(seconds_per_slot);
+                    Interval::new(
+                        Instant::now() + duration_to_next_slot + slot_duration / 2,
+                        slot_duration,
+                    )
+                };
+
+                let chain = beacon_chain.clone();
+                let log = log.new(o!("Service" => "Slasher"));
+                executor.spawn(
+                    exit.clone()
+                        .until(
+                            interval
+                                .for_each(move |_| {
+                                    do_slasher_processing(&chain, &log);
+
+                                    Ok(())
+                                })
+                                .map_err(|_| ()),
+                        )
+                        .map(|_| ()),
+                );
+            }
+
+            let chain = beacon_chain.clone();
+            let log = log.new(o!("Service" => "StatePreparation"));
             executor.spawn(
                 exit.until(
                     interval
                         .for_each(move |_| {
-                            do_state_catchup(&chain, &log);
+                            do_state_preparation(&chain, &log);
 
                             Ok(())
                         })
@@ -216,3 +298,94 @@ fn do_state_catchup<T: BeaconChainTypes>(chain: &Arc<BeaconChain<T>>, log: &slog
         };
     }
 }
+
+/// If a scheduled fork activates at the next epoch, subscribe to its gossipsub topics so that
+/// messages are received from the first slot of the fork.
+///
+/// Once a fork has been active for a full epoch, unsubscribe from the topics of the fork it
+/// replaced, allowing for messages which were produced just prior to the fork.
+fn do_fork_subscription<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+    network_send: &mut mpsc::UnboundedSender<NetworkMessage>,
+    log: &slog::Logger,
+) {
+    let current_epoch = chain.current_state().current_epoch();
+
+    if let Some(fork) = chain.spec.fork_starting_at(current_epoch + 1) {
+        let topics = fork_topics(fork.version);
+
+        network_send
+            .try_send(NetworkMessage::Subscribe { topics })
+            .unwrap_or_else(|e| {
+                error!(
+                    log,
+                    "ForkSubscriptionFailed";
+                    "fork" => &fork.name,
+                    "error" => format!("{:?}", e),
+                )
+            });
+    }
+
+    if current_epoch == T::EthSpec::genesis_epoch() {
+        return;
+    }
+
+    if let Some(fork) = chain.spec.fork_starting_at(current_epoch - 1) {
+        let topics = fork_topics(chain.spec.fork_version_at(fork.epoch - 1));
+
+        network_send
+            .try_send(NetworkMessage::Unsubscribe { topics })
+            .unwrap_or_else(|e| {
+                error!(
+                    log,
+                    "ForkUnsubscriptionFailed";
+                    "fork" => &fork.name,
+                    "error" => format!("{:?}", e),
+                )
+            });
+    }
+}
+
+/// The gossipsub topics for blocks and attestations on the fork with the given `version`.
+fn fork_topics(version: [u8; 4]) -> Vec<Topic> {
+    vec![
+        TopicBuilder::new(fork_topic(BEACON_PUBSUB_TOPIC, version)).build(),
+        TopicBuilder::new(fork_topic(BEACON_ATTESTATION_TOPIC, version)).build(),
+    ]
+}
+
+fn do_state_preparation<T: BeaconChainTypes>(chain: &Arc<BeaconChain<T>>, log: &slog::Logger) {
+    match chain.prepare_next_slot_state() {
+        Ok(()) => debug!(
+            log,
+            "PreparedNextSlotState";
+            "state_slot" => chain.current_state().slot,
+            "wall_clock_slot" => chain.read_slot_clock(),
+        ),
+        Err(e) => error!(
+            log,
+            "StatePreparationFailed";
+            "error" => format!("{:?}", e),
+        ),
+    };
+}
+
+fn do_slasher_processing<T: BeaconChainTypes>(chain: &Arc<BeaconChain<T>>, log: &slog::Logger) {
+    match chain.process_slasher_queue() {
+        Ok(output) => {
+            if !output.is_empty() {
+                warn!(
+                    log,
+                    "SlashableOffencesDetected";
+                    "proposer_slashings" => output.proposer_slashings.len(),
+                    "attester_slashings" => output.attester_slashings.len(),
+                );
+            }
+        }
+        Err(e) => error!(
+            log,
+            "SlasherProcessingFailed";
+            "error" => format!("{:?}", e),
+        ),
+    };
+}
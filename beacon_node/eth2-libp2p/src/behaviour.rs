@@ -18,7 +18,6 @@ use slog::{o, trace, warn};
 use ssz::{ssz_encode, Decode, DecodeError, Encode};
 use std::num::NonZeroU32;
 use std::time::Duration;
-use types::{Attestation, BeaconBlock};
 
 /// Builds the network behaviour that manages the core protocols of eth2.
 /// This core behaviour is managed by `Behaviour` which adds peer management to all core
@@ -154,6 +153,11 @@ impl<TSubstream: AsyncRead + AsyncWrite> Behaviour<TSubstream> {
         self.gossipsub.subscribe(topic)
     }
 
+    /// Unsubscribes from a gossipsub topic.
+    pub fn unsubscribe(&mut self, topic: Topic) -> bool {
+        self.gossipsub.unsubscribe(topic)
+    }
+
     /// Publishes a message on the pubsub (gossipsub) behaviour.
     pub fn publish(&mut self, topics: Vec<Topic>, message: PubsubMessage) {
         let message_bytes = ssz_encode(&message);
@@ -185,9 +189,13 @@ pub enum BehaviourEvent {
 #[derive(Debug, Clone, PartialEq)]
 pub enum PubsubMessage {
     /// Gossipsub message providing notification of a new block.
-    Block(BeaconBlock),
+    ///
+    /// The block is SSZ-encoded, since decoding it requires knowledge of the `EthSpec`.
+    Block(Vec<u8>),
     /// Gossipsub message providing notification of a new attestation.
-    Attestation(Attestation),
+    ///
+    /// The attestation is SSZ-encoded, since decoding it requires knowledge of the `EthSpec`.
+    Attestation(Vec<u8>),
 }
 
 //TODO: Correctly encode/decode enums. Prefixing with integer for now.
@@ -205,14 +213,14 @@ impl Encode for PubsubMessage {
             PubsubMessage::Block(block_gossip) => {
                 encoder.append(&0_u32);
 
-                // Encode the gossip as a Vec<u8>;
-                encoder.append(&block_gossip.as_ssz_bytes());
+                // The block is already encoded as a Vec<u8>.
+                encoder.append(block_gossip);
             }
             PubsubMessage::Attestation(attestation_gossip) => {
                 encoder.append(&1_u32);
 
-                // Encode the gossip as a Vec<u8>;
-                encoder.append(&attestation_gossip.as_ssz_bytes());
+                // The attestation is already encoded as a Vec<u8>.
+                encoder.append(attestation_gossip);
             }
         }
 
@@ -237,10 +245,8 @@ impl Decode for PubsubMessage {
         let body: Vec<u8> = decoder.decode_next()?;
 
         match id {
-            0 => Ok(PubsubMessage::Block(BeaconBlock::from_ssz_bytes(&body)?)),
-            1 => Ok(PubsubMessage::Attestation(Attestation::from_ssz_bytes(
-                &body,
-            )?)),
+            0 => Ok(PubsubMessage::Block(body)),
+            1 => Ok(PubsubMessage::Attestation(body)),
             _ => Err(DecodeError::BytesInvalid(
                 "Invalid PubsubMessage id".to_string(),
             )),
@@ -255,7 +261,9 @@ mod test {
 
     #[test]
     fn ssz_encoding() {
-        let original = PubsubMessage::Block(BeaconBlock::empty(&MainnetEthSpec::default_spec()));
+        let original = PubsubMessage::Block(
+            BeaconBlock::<MainnetEthSpec>::empty(&MainnetEthSpec::default_spec()).as_ssz_bytes(),
+        );
 
         let encoded = ssz_encode(&original);
 
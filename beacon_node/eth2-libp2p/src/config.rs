@@ -10,6 +10,18 @@ pub const BEACON_PUBSUB_TOPIC: &str = "beacon_block";
 pub const BEACON_ATTESTATION_TOPIC: &str = "beacon_attestation";
 pub const SHARD_TOPIC_PREFIX: &str = "shard";
 
+/// Returns the name of the gossipsub `topic` for the chain at the given `fork_version`.
+///
+/// Including the fork version ensures messages are not gossiped between peers on either side of
+/// a hard fork.
+pub fn fork_topic(topic: &str, fork_version: [u8; 4]) -> String {
+    let version: String = fork_version
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("{}/{}", topic, version)
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 /// Network configuration for lighthouse.
@@ -44,6 +56,11 @@ pub struct Config {
 
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<String>,
+
+    /// The fork version used to name the default gossipsub topics. Set from the beacon chain
+    /// at startup, rather than by the user.
+    #[serde(skip)]
+    pub fork_version: [u8; 4],
 }
 
 impl Default for Config {
@@ -68,6 +85,7 @@ impl Default for Config {
             boot_nodes: vec![],
             client_version: version::version(),
             topics: Vec::new(),
+            fork_version: [0; 4],
         }
     }
 }
@@ -2,7 +2,7 @@
 
 use ssz::{impl_decode_via_from, impl_encode_via_from};
 use ssz_derive::{Decode, Encode};
-use types::{BeaconBlockBody, BeaconBlockHeader, Epoch, Hash256, Slot};
+use types::{BeaconBlockHeader, Epoch, Hash256, Slot};
 
 #[derive(Debug)]
 /// Available Serenity Libp2p RPC methods
@@ -219,10 +219,13 @@ pub struct BeaconBlockBodiesRequest {
 }
 
 /// Response containing the list of requested beacon block bodies.
+///
+/// The bodies are SSZ-encoded as a list, since decoding them requires knowledge of the
+/// `EthSpec`.
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
 pub struct BeaconBlockBodiesResponse {
-    /// The list of beacon block bodies being requested.
-    pub block_bodies: Vec<BeaconBlockBody>,
+    /// The SSZ-encoded list of beacon block bodies being requested.
+    pub block_bodies: Vec<u8>,
 }
 
 /// Request values for tree hashes which yield a blocks `state_root`.
@@ -3,8 +3,8 @@ use crate::error;
 use crate::multiaddr::Protocol;
 use crate::rpc::RPCEvent;
 use crate::NetworkConfig;
+use crate::{fork_topic, BEACON_ATTESTATION_TOPIC, BEACON_PUBSUB_TOPIC};
 use crate::{TopicBuilder, TopicHash};
-use crate::{BEACON_ATTESTATION_TOPIC, BEACON_PUBSUB_TOPIC};
 use futures::prelude::*;
 use futures::Stream;
 use libp2p::core::{
@@ -79,8 +79,8 @@ impl Service {
         let mut topics = vec![];
         //TODO: Handle multiple shard attestations. For now we simply use a separate topic for
         //attestations
-        topics.push(BEACON_ATTESTATION_TOPIC.to_string());
-        topics.push(BEACON_PUBSUB_TOPIC.to_string());
+        topics.push(fork_topic(BEACON_ATTESTATION_TOPIC, config.fork_version));
+        topics.push(fork_topic(BEACON_PUBSUB_TOPIC, config.fork_version));
         topics.append(&mut config.topics.clone());
 
         let mut subscribed_topics = vec![];
@@ -1,5 +1,7 @@
 use crate::{key::BeaconChainKey, map_persistent_err_to_500};
-use beacon_chain::{BeaconChain, BeaconChainTypes};
+use beacon_chain::{
+    lmd_ghost::LmdGhostSnapshot, BeaconChain, BeaconChainError, BeaconChainTypes, RewardReport,
+};
 use iron::prelude::*;
 use iron::{
     headers::{CacheControl, CacheDirective, ContentType},
@@ -8,8 +10,10 @@ use iron::{
 };
 use persistent::Read;
 use router::Router;
+use serde::Serialize;
 use serde_json::json;
 use std::sync::Arc;
+use types::Epoch;
 
 /// Yields a handler for the HTTP API.
 pub fn build_handler<T: BeaconChainTypes + 'static>(
@@ -18,6 +22,24 @@ pub fn build_handler<T: BeaconChainTypes + 'static>(
     let mut router = Router::new();
 
     router.get("/node/fork", handle_fork::<T>, "fork");
+    router.get("/beacon/head", handle_head::<T>, "head");
+    router.get("/beacon/head/state", handle_head_state::<T>, "head_state");
+    router.get("/beacon/rewards/:epoch", handle_rewards::<T>, "rewards");
+    router.get(
+        "/beacon/rewards/:epoch/:validator_index",
+        handle_validator_rewards::<T>,
+        "validator_rewards",
+    );
+    router.get(
+        "/debug/fork_choice",
+        handle_fork_choice_json::<T>,
+        "fork_choice_json",
+    );
+    router.get(
+        "/debug/fork_choice/dot",
+        handle_fork_choice_dot::<T>,
+        "fork_choice_dot",
+    );
 
     let mut chain = Chain::new(router);
 
@@ -69,3 +91,166 @@ fn handle_fork<T: BeaconChainTypes + 'static>(req: &mut Request) -> IronResult<R
 
     Ok(Response::with((Status::Ok, response.to_string())))
 }
+
+/// Returns the head block and its root, along with the root of the head state, as JSON.
+fn handle_head<T: BeaconChainTypes + 'static>(req: &mut Request) -> IronResult<Response> {
+    let beacon_chain = req
+        .get::<Read<BeaconChainKey<T>>>()
+        .map_err(map_persistent_err_to_500)?;
+    let head = beacon_chain.head();
+
+    let response = json!({
+        "block_root": head.beacon_block_root,
+        "block": head.beacon_block,
+        "state_root": head.beacon_state_root,
+    });
+
+    Ok(Response::with((Status::Ok, response.to_string())))
+}
+
+/// Returns the head state as JSON.
+fn handle_head_state<T: BeaconChainTypes + 'static>(req: &mut Request) -> IronResult<Response> {
+    let beacon_chain = req
+        .get::<Read<BeaconChainKey<T>>>()
+        .map_err(map_persistent_err_to_500)?;
+
+    json_response(&beacon_chain.head().beacon_state)
+}
+
+/// Returns the rewards and penalties of every validator from the processing of an epoch as JSON.
+fn handle_rewards<T: BeaconChainTypes + 'static>(req: &mut Request) -> IronResult<Response> {
+    let report = reward_report::<T>(req)?;
+
+    json_response(&*report)
+}
+
+/// Returns the rewards and penalties of a single validator from the processing of an epoch as
+/// JSON.
+fn handle_validator_rewards<T: BeaconChainTypes + 'static>(
+    req: &mut Request,
+) -> IronResult<Response> {
+    let validator_index: usize = path_param(req, "validator_index")?;
+    let report = reward_report::<T>(req)?;
+
+    let rewards = report.validators.get(validator_index).ok_or_else(|| {
+        IronError::new(
+            StringError(format!("Unknown validator index: {}", validator_index)),
+            Status::NotFound,
+        )
+    })?;
+
+    let total = rewards.total().map_err(|e| {
+        IronError::new(
+            StringError(format!("Unable to total rewards: {:?}", e)),
+            Status::InternalServerError,
+        )
+    })?;
+
+    let response = json!({
+        "epoch": report.epoch,
+        "validator_index": validator_index,
+        "rewards": rewards,
+        "total": total,
+    });
+
+    Ok(Response::with((Status::Ok, response.to_string())))
+}
+
+/// Returns the reward report for the epoch in the request path, which is cached by the
+/// `BeaconChain` so that repeated requests do not replay the epoch.
+fn reward_report<T: BeaconChainTypes + 'static>(
+    req: &mut Request,
+) -> IronResult<Arc<RewardReport>> {
+    let epoch = Epoch::new(path_param(req, "epoch")?);
+
+    let beacon_chain = req
+        .get::<Read<BeaconChainKey<T>>>()
+        .map_err(map_persistent_err_to_500)?;
+
+    beacon_chain.reward_report(epoch).map_err(|e| match e {
+        BeaconChainError::EpochNotYetProcessed(_) => IronError::new(
+            StringError(format!("Epoch {} has not yet been processed", epoch)),
+            Status::NotFound,
+        ),
+        e => IronError::new(
+            StringError(format!("Unable to compute reward report: {:?}", e)),
+            Status::InternalServerError,
+        ),
+    })
+}
+
+/// Returns a response containing `item` as JSON.
+///
+/// Types from `eth2/types` serialize to their canonical (spec-conformant) JSON representation.
+fn json_response<T: Serialize>(item: &T) -> IronResult<Response> {
+    let json = serde_json::to_string(item).map_err(|e| {
+        IronError::new(
+            StringError(format!("Unable to serialize response: {:?}", e)),
+            Status::InternalServerError,
+        )
+    })?;
+
+    Ok(Response::with((Status::Ok, json)))
+}
+
+/// Parses the named parameter from the request path.
+fn path_param<T: std::str::FromStr>(req: &Request, name: &str) -> IronResult<T> {
+    req.extensions
+        .get::<Router>()
+        .and_then(|params| params.find(name))
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| {
+            IronError::new(
+                StringError(format!("Invalid {} in request path", name)),
+                Status::BadRequest,
+            )
+        })
+}
+
+/// Returns all blocks and latest messages known to fork choice as JSON.
+fn handle_fork_choice_json<T: BeaconChainTypes + 'static>(
+    req: &mut Request,
+) -> IronResult<Response> {
+    let snapshot = fork_choice_snapshot::<T>(req)?;
+
+    Ok(Response::with((Status::Ok, snapshot.to_json())))
+}
+
+/// Returns all blocks known to fork choice as a Graphviz DOT graph.
+fn handle_fork_choice_dot<T: BeaconChainTypes + 'static>(
+    req: &mut Request,
+) -> IronResult<Response> {
+    let snapshot = fork_choice_snapshot::<T>(req)?;
+
+    let mut response = Response::with((Status::Ok, snapshot.to_dot()));
+    response.headers.set(ContentType::plaintext());
+
+    Ok(response)
+}
+
+fn fork_choice_snapshot<T: BeaconChainTypes + 'static>(
+    req: &mut Request,
+) -> IronResult<LmdGhostSnapshot> {
+    let beacon_chain = req
+        .get::<Read<BeaconChainKey<T>>>()
+        .map_err(map_persistent_err_to_500)?;
+
+    beacon_chain.fork_choice.introspect().map_err(|e| {
+        IronError::new(
+            StringError(format!("Unable to introspect fork choice: {:?}", e)),
+            Status::InternalServerError,
+        )
+    })
+}
+
+/// A simple error wrapper, allowing a `String` to be used as an `IronError`.
+#[derive(Debug)]
+struct StringError(String);
+
+impl std::fmt::Display for StringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl iron::Error for StringError {}
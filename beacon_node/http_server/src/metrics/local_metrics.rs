@@ -18,6 +18,12 @@ pub struct LocalMetrics {
     finalized_epoch: IntGauge,
     validator_balances_sum: IntGauge,
     database_size: IntGauge,
+    op_pool_attestations: IntGauge,
+    op_pool_attester_slashings: IntGauge,
+    op_pool_proposer_slashings: IntGauge,
+    op_pool_voluntary_exits: IntGauge,
+    op_pool_transfers: IntGauge,
+    op_pool_deposits: IntGauge,
 }
 
 impl LocalMetrics {
@@ -56,6 +62,39 @@ impl LocalMetrics {
                 let opts = Opts::new("database_size", "size_of_on_disk_db_in_mb");
                 IntGauge::with_opts(opts)?
             },
+            op_pool_attestations: {
+                let opts = Opts::new("op_pool_attestations", "number_of_attestations_in_op_pool");
+                IntGauge::with_opts(opts)?
+            },
+            op_pool_attester_slashings: {
+                let opts = Opts::new(
+                    "op_pool_attester_slashings",
+                    "number_of_attester_slashings_in_op_pool",
+                );
+                IntGauge::with_opts(opts)?
+            },
+            op_pool_proposer_slashings: {
+                let opts = Opts::new(
+                    "op_pool_proposer_slashings",
+                    "number_of_proposer_slashings_in_op_pool",
+                );
+                IntGauge::with_opts(opts)?
+            },
+            op_pool_voluntary_exits: {
+                let opts = Opts::new(
+                    "op_pool_voluntary_exits",
+                    "number_of_voluntary_exits_in_op_pool",
+                );
+                IntGauge::with_opts(opts)?
+            },
+            op_pool_transfers: {
+                let opts = Opts::new("op_pool_transfers", "number_of_transfers_in_op_pool");
+                IntGauge::with_opts(opts)?
+            },
+            op_pool_deposits: {
+                let opts = Opts::new("op_pool_deposits", "number_of_deposits_in_op_pool");
+                IntGauge::with_opts(opts)?
+            },
         })
     }
 
@@ -69,6 +108,12 @@ impl LocalMetrics {
         registry.register(Box::new(self.justified_epoch.clone()))?;
         registry.register(Box::new(self.validator_balances_sum.clone()))?;
         registry.register(Box::new(self.database_size.clone()))?;
+        registry.register(Box::new(self.op_pool_attestations.clone()))?;
+        registry.register(Box::new(self.op_pool_attester_slashings.clone()))?;
+        registry.register(Box::new(self.op_pool_proposer_slashings.clone()))?;
+        registry.register(Box::new(self.op_pool_voluntary_exits.clone()))?;
+        registry.register(Box::new(self.op_pool_transfers.clone()))?;
+        registry.register(Box::new(self.op_pool_deposits.clone()))?;
 
         Ok(())
     }
@@ -87,12 +132,11 @@ impl LocalMetrics {
             .set(present_slot.epoch(T::EthSpec::slots_per_epoch()).as_u64() as i64);
 
         self.best_slot.set(state.slot.as_u64() as i64);
-        self.validator_count
-            .set(state.validator_registry.len() as i64);
+        self.validator_count.set(state.validators.len() as i64);
         self.justified_epoch
-            .set(state.current_justified_epoch.as_u64() as i64);
+            .set(state.current_justified_checkpoint.epoch.as_u64() as i64);
         self.finalized_epoch
-            .set(state.finalized_epoch.as_u64() as i64);
+            .set(state.finalized_checkpoint.epoch.as_u64() as i64);
         if SHOULD_SUM_VALIDATOR_BALANCES {
             self.validator_balances_sum
                 .set(state.balances.iter().sum::<u64>() as i64);
@@ -102,5 +146,17 @@ impl LocalMetrics {
             .and_then(|m| Ok(m.len()))
             .unwrap_or(0);
         self.database_size.set(db_size as i64);
+
+        let op_pool = &beacon_chain.op_pool;
+        self.op_pool_attestations
+            .set(op_pool.num_attestations() as i64);
+        self.op_pool_attester_slashings
+            .set(op_pool.num_attester_slashings() as i64);
+        self.op_pool_proposer_slashings
+            .set(op_pool.num_proposer_slashings() as i64);
+        self.op_pool_voluntary_exits
+            .set(op_pool.num_voluntary_exits() as i64);
+        self.op_pool_transfers.set(op_pool.num_transfers() as i64);
+        self.op_pool_deposits.set(op_pool.num_deposits() as i64);
     }
 }
@@ -10,10 +10,12 @@ use eth2_libp2p::{
 use futures::future::Future;
 use futures::stream::Stream;
 use slog::{debug, warn};
+use ssz::Decode;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::mpsc;
+use types::{Attestation, BeaconBlock};
 
 /// Timeout for RPC requests.
 // const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
@@ -209,13 +211,38 @@ impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
     fn handle_gossip(&mut self, peer_id: PeerId, gossip_message: PubsubMessage) {
         match gossip_message {
             PubsubMessage::Block(message) => {
-                let _should_foward_on =
-                    self.sync
-                        .on_block_gossip(peer_id, message, &mut self.network_context);
+                match BeaconBlock::<T::EthSpec>::from_ssz_bytes(&message) {
+                    Ok(block) => {
+                        let _should_foward_on =
+                            self.sync
+                                .on_block_gossip(peer_id, block, &mut self.network_context);
+                    }
+                    Err(e) => {
+                        debug!(
+                            self.log,
+                            "Invalid gossiped block";
+                            "peer_id" => format!("{:?}", peer_id),
+                            "error" => format!("{:?}", e)
+                        );
+                    }
+                }
             }
             PubsubMessage::Attestation(message) => {
-                self.sync
-                    .on_attestation_gossip(peer_id, message, &mut self.network_context)
+                match Attestation::<T::EthSpec>::from_ssz_bytes(&message) {
+                    Ok(attestation) => self.sync.on_attestation_gossip(
+                        peer_id,
+                        attestation,
+                        &mut self.network_context,
+                    ),
+                    Err(e) => {
+                        debug!(
+                            self.log,
+                            "Invalid gossiped attestation";
+                            "peer_id" => format!("{:?}", peer_id),
+                            "error" => format!("{:?}", e)
+                        );
+                    }
+                }
             }
         }
     }
@@ -30,6 +30,13 @@ impl<T: BeaconChainTypes + 'static> Service<T> {
         executor: &TaskExecutor,
         log: slog::Logger,
     ) -> error::Result<(Arc<Self>, mpsc::UnboundedSender<NetworkMessage>)> {
+        // Name the gossipsub topics after the fork version of the present epoch.
+        let mut config = config.clone();
+        config.fork_version = {
+            let epoch = beacon_chain.current_state().current_epoch();
+            beacon_chain.spec.fork_version_at(epoch)
+        };
+
         // build the network channel
         let (network_send, network_recv) = mpsc::unbounded_channel::<NetworkMessage>();
         // launch message handler thread
@@ -43,7 +50,7 @@ impl<T: BeaconChainTypes + 'static> Service<T> {
 
         // launch libp2p service
         let libp2p_log = log.new(o!("Service" => "Libp2p"));
-        let libp2p_service = LibP2PService::new(config.clone(), libp2p_log)?;
+        let libp2p_service = LibP2PService::new(config, libp2p_log)?;
 
         // TODO: Spawn thread to handle libp2p messages and pass to message handler thread.
         let libp2p_exit = spawn_service(
@@ -135,6 +142,20 @@ fn network_service(
                             debug!(log, "Sending pubsub message"; "topics" => format!("{:?}",topics));
                             libp2p_service.swarm.publish(topics, *message);
                         }
+                        NetworkMessage::Subscribe { topics } => {
+                            for topic in topics {
+                                if libp2p_service.swarm.subscribe(topic.clone()) {
+                                    info!(log, "Subscribed to topic"; "topic" => format!("{:?}", topic));
+                                }
+                            }
+                        }
+                        NetworkMessage::Unsubscribe { topics } => {
+                            for topic in topics {
+                                if libp2p_service.swarm.unsubscribe(topic.clone()) {
+                                    info!(log, "Unsubscribed from topic"; "topic" => format!("{:?}", topic));
+                                }
+                            }
+                        }
                     }
                 }
                 Ok(Async::NotReady) => not_ready_count += 1,
@@ -191,6 +212,10 @@ pub enum NetworkMessage {
         topics: Vec<Topic>,
         message: Box<PubsubMessage>,
     },
+    /// Subscribe to the given pubsub topics (e.g., those of an upcoming fork).
+    Subscribe { topics: Vec<Topic> },
+    /// Unsubscribe from the given pubsub topics (e.g., those of a superseded fork).
+    Unsubscribe { topics: Vec<Topic> },
 }
 
 /// Type of outgoing messages that can be sent through the network service.
@@ -5,6 +5,7 @@ use eth2_libp2p::rpc::methods::*;
 use eth2_libp2p::rpc::{RPCRequest, RPCResponse, RequestId};
 use eth2_libp2p::PeerId;
 use slog::{debug, error, info, o, trace, warn};
+use ssz::{Decode, Encode};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -212,7 +213,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
         } else if self
             .chain
             .store
-            .exists::<BeaconBlock>(&remote.best_root)
+            .exists::<BeaconBlock<T::EthSpec>>(&remote.best_root)
             .unwrap_or_else(|_| false)
         {
             // If the node's best-block is already known to us, we have nothing to request.
@@ -402,7 +403,11 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
             .into_iter()
             .step_by(req.skip_slots as usize + 1)
             .filter_map(|root| {
-                let block = self.chain.store.get::<BeaconBlock>(&root).ok()?;
+                let block = self
+                    .chain
+                    .store
+                    .get::<BeaconBlock<T::EthSpec>>(&root)
+                    .ok()?;
                 Some(block?.block_header())
             })
             .collect();
@@ -455,11 +460,11 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
         req: BeaconBlockBodiesRequest,
         network: &mut NetworkContext,
     ) {
-        let block_bodies: Vec<BeaconBlockBody> = req
+        let block_bodies: Vec<BeaconBlockBody<T::EthSpec>> = req
             .block_roots
             .iter()
             .filter_map(|root| {
-                if let Ok(Some(block)) = self.chain.store.get::<BeaconBlock>(root) {
+                if let Ok(Some(block)) = self.chain.store.get::<BeaconBlock<T::EthSpec>>(root) {
                     Some(block.body)
                 } else {
                     debug!(
@@ -485,7 +490,9 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
         network.send_rpc_response(
             peer_id,
             request_id,
-            RPCResponse::BeaconBlockBodies(BeaconBlockBodiesResponse { block_bodies }),
+            RPCResponse::BeaconBlockBodies(BeaconBlockBodiesResponse {
+                block_bodies: block_bodies.as_ssz_bytes(),
+            }),
         )
     }
 
@@ -496,18 +503,32 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
         res: BeaconBlockBodiesResponse,
         network: &mut NetworkContext,
     ) {
+        let block_bodies =
+            match Vec::<BeaconBlockBody<T::EthSpec>>::from_ssz_bytes(&res.block_bodies) {
+                Ok(block_bodies) => block_bodies,
+                Err(e) => {
+                    warn!(
+                        self.log,
+                        "Invalid BlockBodiesResponse";
+                        "peer" => format!("{:?}", peer_id),
+                        "error" => format!("{:?}", e),
+                    );
+                    return;
+                }
+            };
+
         debug!(
             self.log,
             "BlockBodiesResponse";
             "peer" => format!("{:?}", peer_id),
-            "count" => res.block_bodies.len(),
+            "count" => block_bodies.len(),
         );
 
-        if !res.block_bodies.is_empty() {
+        if !block_bodies.is_empty() {
             // Import all blocks to queue
             let last_root = self
                 .import_queue
-                .enqueue_bodies(res.block_bodies, peer_id.clone());
+                .enqueue_bodies(block_bodies, peer_id.clone());
 
             // Attempt to process all recieved bodies by recursively processing the latest block
             if let Some(root) = last_root {
@@ -533,7 +554,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
     pub fn on_block_gossip(
         &mut self,
         peer_id: PeerId,
-        block: BeaconBlock,
+        block: BeaconBlock<T::EthSpec>,
         network: &mut NetworkContext,
     ) -> bool {
         if let Some(outcome) =
@@ -557,7 +578,8 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
                         .chain
                         .head()
                         .beacon_state
-                        .finalized_epoch
+                        .finalized_checkpoint
+                        .epoch
                         .start_slot(T::EthSpec::slots_per_epoch());
                     self.request_block_roots(
                         peer_id,
@@ -601,7 +623,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
     pub fn on_attestation_gossip(
         &mut self,
         _peer_id: PeerId,
-        msg: Attestation,
+        msg: Attestation<T::EthSpec>,
         _network: &mut NetworkContext,
     ) {
         match self.chain.process_attestation(msg) {
@@ -786,7 +808,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
     fn process_block(
         &mut self,
         peer_id: PeerId,
-        block: BeaconBlock,
+        block: BeaconBlock<T::EthSpec>,
         network: &mut NetworkContext,
         source: &str,
     ) -> Option<BlockProcessingOutcome> {
@@ -859,6 +881,21 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
                         );
                     }
                 }
+                BlockProcessingOutcome::PerBlockProcessingError(_)
+                | BlockProcessingOutcome::StateRootMismatch
+                | BlockProcessingOutcome::BlockIsKnownInvalid
+                | BlockProcessingOutcome::ParentIsInvalid { .. } => {
+                    // The block is invalid (or descends from an invalid block), the peer is
+                    // faulty.
+                    warn!(
+                        self.log, "InvalidBlock";
+                        "source" => source,
+                        "msg" => "peer sent invalid block, disconnecting",
+                        "outcome" => format!("{:?}", outcome),
+                        "peer" => format!("{:?}", peer_id),
+                    );
+                    network.disconnect(peer_id, GoodbyeReason::Fault);
+                }
                 _ => {
                     debug!(
                         self.log, "InvalidBlock";
@@ -891,8 +928,8 @@ fn hello_message<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) -> HelloMes
 
     HelloMessage {
         network_id: spec.chain_id,
-        latest_finalized_root: state.finalized_root,
-        latest_finalized_epoch: state.finalized_epoch,
+        latest_finalized_root: state.finalized_checkpoint.root,
+        latest_finalized_epoch: state.finalized_checkpoint.epoch,
         best_root: beacon_chain.head().beacon_block_root,
         best_slot: state.slot,
     }
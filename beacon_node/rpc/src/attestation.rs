@@ -1,7 +1,7 @@
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::PubsubMessage;
 use eth2_libp2p::TopicBuilder;
-use eth2_libp2p::BEACON_ATTESTATION_TOPIC;
+use eth2_libp2p::{fork_topic, BEACON_ATTESTATION_TOPIC};
 use futures::Future;
 use grpcio::{RpcContext, RpcStatus, RpcStatusCode, UnarySink};
 use network::NetworkMessage;
@@ -116,19 +116,20 @@ impl<T: BeaconChainTypes> AttestationService for AttestationServiceInstance<T> {
         let mut resp = PublishAttestationResponse::new();
         let ssz_serialized_attestation = req.get_attestation().get_ssz();
 
-        let attestation = match Attestation::from_ssz_bytes(ssz_serialized_attestation) {
-            Ok(v) => v,
-            Err(_) => {
-                let log_clone = self.log.clone();
-                let f = sink
-                    .fail(RpcStatus::new(
-                        RpcStatusCode::InvalidArgument,
-                        Some("Invalid attestation".to_string()),
-                    ))
-                    .map_err(move |_| warn!(log_clone, "failed to reply {:?}", req));
-                return ctx.spawn(f);
-            }
-        };
+        let attestation =
+            match Attestation::<T::EthSpec>::from_ssz_bytes(ssz_serialized_attestation) {
+                Ok(v) => v,
+                Err(_) => {
+                    let log_clone = self.log.clone();
+                    let f = sink
+                        .fail(RpcStatus::new(
+                            RpcStatusCode::InvalidArgument,
+                            Some("Invalid attestation".to_string()),
+                        ))
+                        .map_err(move |_| warn!(log_clone, "failed to reply {:?}", req));
+                    return ctx.spawn(f);
+                }
+            };
 
         match self.chain.process_attestation(attestation.clone()) {
             Ok(_) => {
@@ -139,9 +140,14 @@ impl<T: BeaconChainTypes> AttestationService for AttestationServiceInstance<T> {
                     "type" => "valid_attestation",
                 );
 
-                // valid attestation, propagate to the network
-                let topic = TopicBuilder::new(BEACON_ATTESTATION_TOPIC).build();
-                let message = PubsubMessage::Attestation(attestation);
+                // valid attestation, propagate to the network on the topic for its fork
+                let fork_version = self
+                    .chain
+                    .spec
+                    .fork_version_at(attestation.data.target.epoch);
+                let topic =
+                    TopicBuilder::new(fork_topic(BEACON_ATTESTATION_TOPIC, fork_version)).build();
+                let message = PubsubMessage::Attestation(ssz_encode(&attestation));
 
                 self.network_chan
                     .try_send(NetworkMessage::Publish {
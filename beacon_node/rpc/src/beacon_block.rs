@@ -1,5 +1,5 @@
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockProcessingOutcome};
-use eth2_libp2p::BEACON_PUBSUB_TOPIC;
+use eth2_libp2p::{fork_topic, BEACON_PUBSUB_TOPIC};
 use eth2_libp2p::{PubsubMessage, TopicBuilder};
 use futures::Future;
 use grpcio::{RpcContext, RpcStatus, RpcStatusCode, UnarySink};
@@ -14,7 +14,7 @@ use slog::{error, info, trace, warn};
 use ssz::{ssz_encode, Decode};
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use types::{BeaconBlock, Signature, Slot};
+use types::{BeaconBlock, EthSpec, Signature, Slot};
 
 #[derive(Clone)]
 pub struct BeaconBlockServiceInstance<T: BeaconChainTypes> {
@@ -92,7 +92,7 @@ impl<T: BeaconChainTypes> BeaconBlockService for BeaconBlockServiceInstance<T> {
 
         let ssz_serialized_block = req.get_block().get_ssz();
 
-        match BeaconBlock::from_ssz_bytes(ssz_serialized_block) {
+        match BeaconBlock::<T::EthSpec>::from_ssz_bytes(ssz_serialized_block) {
             Ok(block) => {
                 match self.chain.process_block(block.clone()) {
                     Ok(outcome) => {
@@ -105,9 +105,15 @@ impl<T: BeaconChainTypes> BeaconBlockService for BeaconBlockServiceInstance<T> {
                                 "block_root" => format!("{}", block_root),
                             );
 
-                            // get the network topic to send on
-                            let topic = TopicBuilder::new(BEACON_PUBSUB_TOPIC).build();
-                            let message = PubsubMessage::Block(block);
+                            // get the network topic to send on, for the fork of the block
+                            let fork_version = self
+                                .chain
+                                .spec
+                                .fork_version_at(block.slot.epoch(T::EthSpec::slots_per_epoch()));
+                            let topic =
+                                TopicBuilder::new(fork_topic(BEACON_PUBSUB_TOPIC, fork_version))
+                                    .build();
+                            let message = PubsubMessage::Block(ssz_encode(&block));
 
                             // Publish the block to the p2p network via gossipsub.
                             self.network_chan
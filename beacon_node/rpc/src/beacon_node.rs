@@ -5,6 +5,7 @@ use protos::services::{Empty, Fork, NodeInfoResponse};
 use protos::services_grpc::BeaconNodeService;
 use slog::{trace, warn};
 use std::sync::Arc;
+use types::YamlConfig;
 
 #[derive(Clone)]
 pub struct BeaconNodeServiceInstance<T: BeaconChainTypes> {
@@ -39,6 +40,11 @@ impl<T: BeaconChainTypes> BeaconNodeService for BeaconNodeServiceInstance<T> {
         node_info.set_genesis_slot(spec.genesis_slot.as_u64());
         node_info.set_chain_id(u32::from(spec.chain_id));
 
+        match YamlConfig::from_spec::<T::EthSpec>(spec).to_yaml_string() {
+            Ok(spec_config) => node_info.set_spec_config(spec_config),
+            Err(e) => warn!(self.log, "Unable to encode spec config"; "error" => e),
+        }
+
         // send the node_info the requester
         let error_log = self.log.clone();
         let f = sink
@@ -0,0 +1,42 @@
+use clap::ArgMatches;
+use serde_derive::{Deserialize, Serialize};
+
+/// The default number of epochs of history for which surround votes are detected.
+pub const DEFAULT_HISTORY_LENGTH: u64 = 4_096;
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct SlasherConfig {
+    pub enabled: bool,
+    /// The number of epochs prior to the source of an attestation for which the min-span data is
+    /// updated (and, therefore, the maximum distance between the sources of two surrounding
+    /// attestations that will be detected).
+    ///
+    /// Records of block headers and attestations more than this many epochs prior to the current
+    /// epoch are pruned.
+    pub history_length: u64,
+}
+
+impl Default for SlasherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            history_length: DEFAULT_HISTORY_LENGTH,
+        }
+    }
+}
+
+impl SlasherConfig {
+    pub fn apply_cli_args(&mut self, args: &ArgMatches) -> Result<(), &'static str> {
+        if args.is_present("slasher") {
+            self.enabled = true;
+        }
+
+        if let Some(history_length) = args.value_of("slasher-history-length") {
+            self.history_length = history_length
+                .parse()
+                .map_err(|_| "Invalid slasher history length")?;
+        }
+
+        Ok(())
+    }
+}
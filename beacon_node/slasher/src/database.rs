@@ -0,0 +1,402 @@
+//! Reads and writes the slasher's records, each in its own `DBColumn`.
+//!
+//! Records are keyed by the big-endian bytes of their validator index, followed by the big-endian
+//! bytes of an epoch, slot or chunk index. Indexed attestations are keyed by their tree hash root.
+//!
+//! The store cannot iterate over keys, so the key of each record is also added to the
+//! `EpochIndex` of the epoch after which it may be pruned.
+use crate::Error;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::collections::HashMap;
+use store::{DBColumn, Store};
+use types::{BeaconBlockHeader, Epoch, EthSpec, Hash256, IndexedAttestation, Slot};
+
+/// The number of epochs of span data stored under a single key.
+pub const CHUNK_SIZE: u64 = 16;
+
+/// The key of the pruned epoch in the `SlasherEpochIndices` column, which is distinct from the
+/// keys of the indices since it is not 8 bytes long.
+const PRUNED_EPOCH_KEY: &[u8] = b"pruned_epoch";
+
+/// Indicates that no attestation contributes to a min-span.
+pub const MIN_SPAN_NONE: u64 = u64::max_value();
+/// Indicates that no attestation contributes to a max-span.
+pub const MAX_SPAN_NONE: u64 = 0;
+
+/// Records the attestation that a validator signed for some target epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct AttesterRecord {
+    /// The tree hash root of the `IndexedAttestation`.
+    pub attestation_root: Hash256,
+    /// The tree hash root of the `AttestationData` of the attestation.
+    pub data_root: Hash256,
+}
+
+/// Implies the min-spans of a validator prior to the lowest source of its attestations, which are
+/// therefore not stored in chunks.
+///
+/// For every epoch `e` prior to `source`, every attestation has a source greater than `e`, so
+/// `min_span[e] = target - e`.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct MinSpanFloor {
+    /// The lowest source epoch of all attestations recorded for the validator.
+    pub source: Epoch,
+    /// The lowest target epoch of all attestations recorded for the validator.
+    pub target: Epoch,
+}
+
+impl MinSpanFloor {
+    /// Returns the min-span at `epoch`, which must be prior to `self.source`.
+    pub fn span_at(&self, epoch: Epoch) -> u64 {
+        (self.target - epoch).as_u64()
+    }
+}
+
+/// The keys of the records that may be pruned once an epoch falls out of the history.
+#[derive(Debug, Default, Clone, PartialEq, Encode, Decode)]
+pub struct EpochIndex {
+    attester_records: Vec<Vec<u8>>,
+    indexed_attestations: Vec<Hash256>,
+    proposals: Vec<Vec<u8>>,
+    min_span_chunks: Vec<Vec<u8>>,
+    max_span_chunks: Vec<Vec<u8>>,
+}
+
+impl EpochIndex {
+    fn extend(&mut self, other: EpochIndex) {
+        self.attester_records.extend(other.attester_records);
+        self.indexed_attestations.extend(other.indexed_attestations);
+        self.proposals.extend(other.proposals);
+        self.min_span_chunks.extend(other.min_span_chunks);
+        self.max_span_chunks.extend(other.max_span_chunks);
+    }
+}
+
+/// Additions to the `EpochIndex` of each epoch, accumulated whilst processing a batch so that
+/// each index is only read and written once per batch.
+#[derive(Debug, Default)]
+pub struct IndexBatch(HashMap<Epoch, EpochIndex>);
+
+impl IndexBatch {
+    fn entry(&mut self, epoch: Epoch) -> &mut EpochIndex {
+        self.0.entry(epoch).or_default()
+    }
+
+    /// Adds all accumulated keys to the indices in the `store`.
+    pub fn flush<S: Store>(self, store: &S) -> Result<(), Error> {
+        for (epoch, additions) in self.0 {
+            let mut index = get_epoch_index(store, epoch)?.unwrap_or_default();
+            index.extend(additions);
+
+            store.put_bytes(
+                DBColumn::SlasherEpochIndices.into(),
+                &epoch.as_u64().to_be_bytes(),
+                &index.as_ssz_bytes(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Identifies one of the two span arrays kept for each validator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpanKind {
+    /// For each epoch `e`, the minimum `target - e` of all attestations with `source > e`.
+    Min,
+    /// For each epoch `e`, the maximum `target - e` of all attestations with `source < e`.
+    Max,
+}
+
+impl SpanKind {
+    fn column(self) -> DBColumn {
+        match self {
+            SpanKind::Min => DBColumn::SlasherMinSpans,
+            SpanKind::Max => DBColumn::SlasherMaxSpans,
+        }
+    }
+
+    fn index_keys(self, index: &mut EpochIndex) -> &mut Vec<Vec<u8>> {
+        match self {
+            SpanKind::Min => &mut index.min_span_chunks,
+            SpanKind::Max => &mut index.max_span_chunks,
+        }
+    }
+
+    /// The value of a span with no contributing attestations.
+    pub fn empty_value(self) -> u64 {
+        match self {
+            SpanKind::Min => MIN_SPAN_NONE,
+            SpanKind::Max => MAX_SPAN_NONE,
+        }
+    }
+}
+
+/// The spans of a single validator for `CHUNK_SIZE` consecutive epochs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    kind: SpanKind,
+    validator_index: u64,
+    chunk_index: u64,
+    values: Vec<u64>,
+    dirty: bool,
+    /// Set if the chunk was read from the store (and is therefore already indexed).
+    exists: bool,
+}
+
+impl Chunk {
+    /// Load the chunk containing `epoch`, or an empty chunk if it has never been written.
+    pub fn load<S: Store>(
+        store: &S,
+        kind: SpanKind,
+        validator_index: u64,
+        epoch: Epoch,
+    ) -> Result<Self, Error> {
+        let chunk_index = epoch.as_u64() / CHUNK_SIZE;
+        let key = key(validator_index, chunk_index);
+
+        let (values, exists) = match store.get_bytes(kind.column().into(), &key)? {
+            Some(bytes) => (Vec::from_ssz_bytes(&bytes)?, true),
+            None => (vec![kind.empty_value(); CHUNK_SIZE as usize], false),
+        };
+
+        Ok(Self {
+            kind,
+            validator_index,
+            chunk_index,
+            values,
+            dirty: false,
+            exists,
+        })
+    }
+
+    /// Returns `true` if `epoch` is stored in `self`.
+    pub fn contains(&self, epoch: Epoch) -> bool {
+        epoch.as_u64() / CHUNK_SIZE == self.chunk_index
+    }
+
+    /// Returns the span at `epoch`, which must be stored in `self`.
+    pub fn get(&self, epoch: Epoch) -> u64 {
+        self.values[(epoch.as_u64() % CHUNK_SIZE) as usize]
+    }
+
+    /// Sets the span at `epoch`, which must be stored in `self`.
+    pub fn set(&mut self, epoch: Epoch, value: u64) {
+        self.values[(epoch.as_u64() % CHUNK_SIZE) as usize] = value;
+        self.dirty = true;
+    }
+
+    /// Writes `self` to the `store`, if it has been modified since it was loaded.
+    ///
+    /// New chunks are indexed under their last epoch.
+    pub fn store<S: Store>(&self, store: &S, batch: &mut IndexBatch) -> Result<(), Error> {
+        if self.dirty {
+            let key = key(self.validator_index, self.chunk_index);
+
+            store.put_bytes(self.kind.column().into(), &key, &self.values.as_ssz_bytes())?;
+
+            if !self.exists {
+                let last_epoch = Epoch::new((self.chunk_index + 1) * CHUNK_SIZE - 1);
+                self.kind.index_keys(batch.entry(last_epoch)).push(key);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn get_attester_record<S: Store>(
+    store: &S,
+    validator_index: u64,
+    target: Epoch,
+) -> Result<Option<AttesterRecord>, Error> {
+    store
+        .get_bytes(
+            DBColumn::SlasherAttesterRecords.into(),
+            &key(validator_index, target.as_u64()),
+        )?
+        .map(|bytes| AttesterRecord::from_ssz_bytes(&bytes).map_err(Into::into))
+        .transpose()
+}
+
+pub fn put_attester_record<S: Store>(
+    store: &S,
+    validator_index: u64,
+    target: Epoch,
+    record: &AttesterRecord,
+    batch: &mut IndexBatch,
+) -> Result<(), Error> {
+    let key = key(validator_index, target.as_u64());
+
+    store.put_bytes(
+        DBColumn::SlasherAttesterRecords.into(),
+        &key,
+        &record.as_ssz_bytes(),
+    )?;
+    batch.entry(target).attester_records.push(key);
+
+    Ok(())
+}
+
+pub fn get_indexed_attestation<S: Store, E: EthSpec>(
+    store: &S,
+    attestation_root: Hash256,
+) -> Result<IndexedAttestation<E>, Error> {
+    let bytes = store
+        .get_bytes(
+            DBColumn::SlasherIndexedAttestations.into(),
+            attestation_root.as_bytes(),
+        )?
+        .ok_or_else(|| Error::MissingIndexedAttestation(attestation_root))?;
+
+    IndexedAttestation::from_ssz_bytes(&bytes).map_err(Into::into)
+}
+
+/// Stores the `attestation`, unless it is already stored.
+pub fn put_indexed_attestation<S: Store, E: EthSpec>(
+    store: &S,
+    attestation_root: Hash256,
+    attestation: &IndexedAttestation<E>,
+    batch: &mut IndexBatch,
+) -> Result<(), Error> {
+    let column = DBColumn::SlasherIndexedAttestations.into();
+
+    if !store.key_exists(column, attestation_root.as_bytes())? {
+        store.put_bytes(
+            column,
+            attestation_root.as_bytes(),
+            &attestation.as_ssz_bytes(),
+        )?;
+        batch
+            .entry(attestation.data.target.epoch)
+            .indexed_attestations
+            .push(attestation_root);
+    }
+
+    Ok(())
+}
+
+pub fn get_proposal<S: Store>(
+    store: &S,
+    proposer_index: u64,
+    slot: Slot,
+) -> Result<Option<BeaconBlockHeader>, Error> {
+    store
+        .get_bytes(
+            DBColumn::SlasherProposals.into(),
+            &key(proposer_index, slot.as_u64()),
+        )?
+        .map(|bytes| BeaconBlockHeader::from_ssz_bytes(&bytes).map_err(Into::into))
+        .transpose()
+}
+
+pub fn put_proposal<S: Store, E: EthSpec>(
+    store: &S,
+    proposer_index: u64,
+    header: &BeaconBlockHeader,
+    batch: &mut IndexBatch,
+) -> Result<(), Error> {
+    let key = key(proposer_index, header.slot.as_u64());
+
+    store.put_bytes(
+        DBColumn::SlasherProposals.into(),
+        &key,
+        &header.as_ssz_bytes(),
+    )?;
+    batch
+        .entry(header.slot.epoch(E::slots_per_epoch()))
+        .proposals
+        .push(key);
+
+    Ok(())
+}
+
+pub fn get_min_span_floor<S: Store>(
+    store: &S,
+    validator_index: u64,
+) -> Result<Option<MinSpanFloor>, Error> {
+    store
+        .get_bytes(
+            DBColumn::SlasherMinSpanFloors.into(),
+            &validator_index.to_be_bytes(),
+        )?
+        .map(|bytes| MinSpanFloor::from_ssz_bytes(&bytes).map_err(Into::into))
+        .transpose()
+}
+
+pub fn put_min_span_floor<S: Store>(
+    store: &S,
+    validator_index: u64,
+    floor: &MinSpanFloor,
+) -> Result<(), Error> {
+    store
+        .put_bytes(
+            DBColumn::SlasherMinSpanFloors.into(),
+            &validator_index.to_be_bytes(),
+            &floor.as_ssz_bytes(),
+        )
+        .map_err(Into::into)
+}
+
+fn get_epoch_index<S: Store>(store: &S, epoch: Epoch) -> Result<Option<EpochIndex>, Error> {
+    store
+        .get_bytes(
+            DBColumn::SlasherEpochIndices.into(),
+            &epoch.as_u64().to_be_bytes(),
+        )?
+        .map(|bytes| EpochIndex::from_ssz_bytes(&bytes).map_err(Into::into))
+        .transpose()
+}
+
+/// Deletes every record in the `EpochIndex` of `epoch`, along with the index itself.
+pub fn prune_epoch<S: Store>(store: &S, epoch: Epoch) -> Result<(), Error> {
+    if let Some(index) = get_epoch_index(store, epoch)? {
+        for key in &index.attester_records {
+            store.key_delete(DBColumn::SlasherAttesterRecords.into(), key)?;
+        }
+        for root in &index.indexed_attestations {
+            store.key_delete(DBColumn::SlasherIndexedAttestations.into(), root.as_bytes())?;
+        }
+        for key in &index.proposals {
+            store.key_delete(DBColumn::SlasherProposals.into(), key)?;
+        }
+        for key in &index.min_span_chunks {
+            store.key_delete(DBColumn::SlasherMinSpans.into(), key)?;
+        }
+        for key in &index.max_span_chunks {
+            store.key_delete(DBColumn::SlasherMaxSpans.into(), key)?;
+        }
+
+        store.key_delete(
+            DBColumn::SlasherEpochIndices.into(),
+            &epoch.as_u64().to_be_bytes(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Returns the epoch prior to which all records have been pruned.
+pub fn get_pruned_epoch<S: Store>(store: &S) -> Result<Epoch, Error> {
+    store
+        .get_bytes(DBColumn::SlasherEpochIndices.into(), PRUNED_EPOCH_KEY)?
+        .map(|bytes| Epoch::from_ssz_bytes(&bytes).map_err(Into::into))
+        .unwrap_or_else(|| Ok(Epoch::new(0)))
+}
+
+pub fn put_pruned_epoch<S: Store>(store: &S, epoch: Epoch) -> Result<(), Error> {
+    store
+        .put_bytes(
+            DBColumn::SlasherEpochIndices.into(),
+            PRUNED_EPOCH_KEY,
+            &epoch.as_ssz_bytes(),
+        )
+        .map_err(Into::into)
+}
+
+pub fn key(validator_index: u64, index: u64) -> Vec<u8> {
+    let mut key = validator_index.to_be_bytes().to_vec();
+    key.extend_from_slice(&index.to_be_bytes());
+    key
+}
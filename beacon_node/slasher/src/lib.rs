@@ -0,0 +1,730 @@
+//! Detects slashable offences in the block headers and attestations seen by the node.
+//!
+//! Double proposals are detected by storing the first header seen from each proposer at each
+//! slot. Double votes are detected by storing the first attestation seen from each validator for
+//! each target epoch. Surround votes are detected using "min-max spans": for each validator and
+//! each epoch `e`, we store the minimum (and maximum) distance from `e` to the target of any
+//! attestation with a source greater (lesser) than `e`. A new attestation with source `s` and
+//! target `t` then:
+//!
+//! - Surrounds an existing attestation if `min_span[s] < t - s`.
+//! - Is surrounded by an existing attestation if `max_span[s] > t - s`.
+//!
+//! Min-spans prior to the lowest source of a validator's attestations are implied by a single
+//! `MinSpanFloor` record, so that a validator's first attestation does not write a span for every
+//! epoch of the history.
+//!
+//! Inputs are queued by `accept_block_header` and `accept_attestation` (which are cheap) and
+//! processed in batches by `process_queued`. Records which fall out of the history are deleted by
+//! `prune`.
+mod config;
+mod database;
+
+use database::{
+    AttesterRecord, Chunk, IndexBatch, MinSpanFloor, SpanKind, MAX_SPAN_NONE, MIN_SPAN_NONE,
+};
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::sync::Arc;
+use store::Store;
+use tree_hash::TreeHash;
+use types::{
+    AttesterSlashing, BeaconBlockHeader, Epoch, EthSpec, Hash256, IndexedAttestation,
+    ProposerSlashing,
+};
+
+pub use config::SlasherConfig;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    StoreError(store::Error),
+    SszDecodeError(ssz::DecodeError),
+    MissingIndexedAttestation(Hash256),
+}
+
+impl From<store::Error> for Error {
+    fn from(e: store::Error) -> Error {
+        Error::StoreError(e)
+    }
+}
+
+impl From<ssz::DecodeError> for Error {
+    fn from(e: ssz::DecodeError) -> Error {
+        Error::SszDecodeError(e)
+    }
+}
+
+/// The slashings detected by a call to `Slasher::process_queued`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlasherOutput<E: EthSpec> {
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing<E>>,
+}
+
+impl<E: EthSpec> Default for SlasherOutput<E> {
+    fn default() -> Self {
+        Self {
+            proposer_slashings: vec![],
+            attester_slashings: vec![],
+        }
+    }
+}
+
+impl<E: EthSpec> SlasherOutput<E> {
+    pub fn is_empty(&self) -> bool {
+        self.proposer_slashings.is_empty() && self.attester_slashings.is_empty()
+    }
+}
+
+/// The context of a single call to `Slasher::process_queued`.
+struct Batch {
+    /// All records prior to this epoch have been pruned, so none may be written.
+    pruned_epoch: Epoch,
+    /// The keys of all records written, so that they can be pruned later.
+    index: IndexBatch,
+}
+
+pub struct Slasher<S, E: EthSpec> {
+    store: Arc<S>,
+    config: SlasherConfig,
+    /// Block headers (and the indices of their proposers) awaiting processing.
+    header_queue: Mutex<Vec<(u64, BeaconBlockHeader)>>,
+    /// Attestations awaiting processing.
+    attestation_queue: Mutex<Vec<IndexedAttestation<E>>>,
+    /// Held whilst processing, so that records are never read and written concurrently.
+    processing_lock: Mutex<()>,
+}
+
+impl<S: Store, E: EthSpec> Slasher<S, E> {
+    pub fn new(store: Arc<S>, config: SlasherConfig) -> Self {
+        Self {
+            store,
+            config,
+            header_queue: Mutex::new(vec![]),
+            attestation_queue: Mutex::new(vec![]),
+            processing_lock: Mutex::new(()),
+        }
+    }
+
+    /// Queue a block header, proposed by `proposer_index`, for processing.
+    pub fn accept_block_header(&self, proposer_index: u64, header: BeaconBlockHeader) {
+        self.header_queue.lock().push((proposer_index, header));
+    }
+
+    /// Queue an attestation for processing.
+    pub fn accept_attestation(&self, attestation: IndexedAttestation<E>) {
+        self.attestation_queue.lock().push(attestation);
+    }
+
+    /// Returns the number of block headers and attestations awaiting processing.
+    pub fn queue_len(&self) -> usize {
+        self.header_queue.lock().len() + self.attestation_queue.lock().len()
+    }
+
+    /// Process all queued block headers and attestations, returning any slashings detected.
+    ///
+    /// The slashings are not verified (e.g., the offending validator may already be slashed);
+    /// this is left to the operation pool.
+    pub fn process_queued(&self) -> Result<SlasherOutput<E>, Error> {
+        let _processing = self.processing_lock.lock();
+
+        let headers = std::mem::replace(&mut *self.header_queue.lock(), vec![]);
+        let attestations = std::mem::replace(&mut *self.attestation_queue.lock(), vec![]);
+
+        let pruned_epoch = database::get_pruned_epoch(&*self.store)?;
+        let mut batch = Batch {
+            pruned_epoch,
+            index: IndexBatch::default(),
+        };
+        let mut output = SlasherOutput::default();
+
+        // Inputs which have already fallen out of the history are ignored.
+        for (proposer_index, header) in headers
+            .into_iter()
+            .filter(|(_, header)| header.slot.epoch(E::slots_per_epoch()) >= pruned_epoch)
+        {
+            if let Some(slashing) = self.process_block_header(proposer_index, header, &mut batch)? {
+                output.proposer_slashings.push(slashing);
+            }
+        }
+
+        // Each pair of conflicting attestations is only reported once, regardless of how many
+        // validators signed both.
+        let mut seen_pairs = HashSet::new();
+
+        for attestation in attestations
+            .into_iter()
+            .filter(|attestation| attestation.data.target.epoch >= pruned_epoch)
+        {
+            for slashing in self.process_attestation(attestation, &mut batch)? {
+                let pair = (
+                    slashing.attestation_1.tree_hash_root(),
+                    slashing.attestation_2.tree_hash_root(),
+                );
+
+                if seen_pairs.insert(pair) {
+                    output.attester_slashings.push(slashing);
+                }
+            }
+        }
+
+        batch.index.flush(&*self.store)?;
+
+        Ok(output)
+    }
+
+    /// Deletes all records of block headers and attestations (by target) from epochs more than
+    /// `history_length` epochs prior to `current_epoch`.
+    pub fn prune(&self, current_epoch: Epoch) -> Result<(), Error> {
+        let _processing = self.processing_lock.lock();
+
+        let store = &*self.store;
+        let pruned_epoch = database::get_pruned_epoch(store)?;
+        let new_pruned_epoch = Epoch::new(
+            current_epoch
+                .as_u64()
+                .saturating_sub(self.config.history_length),
+        );
+
+        if new_pruned_epoch > pruned_epoch {
+            for epoch in pruned_epoch.as_u64()..new_pruned_epoch.as_u64() {
+                database::prune_epoch(store, Epoch::new(epoch))?;
+            }
+
+            database::put_pruned_epoch(store, new_pruned_epoch)?;
+        }
+
+        Ok(())
+    }
+
+    /// Stores the `header` if it is the first seen from `proposer_index` at its slot, otherwise
+    /// returns a slashing if it conflicts with the stored header.
+    fn process_block_header(
+        &self,
+        proposer_index: u64,
+        header: BeaconBlockHeader,
+        batch: &mut Batch,
+    ) -> Result<Option<ProposerSlashing>, Error> {
+        match database::get_proposal(&*self.store, proposer_index, header.slot)? {
+            Some(existing) => {
+                if existing != header {
+                    Ok(Some(ProposerSlashing {
+                        proposer_index,
+                        header_1: existing,
+                        header_2: header,
+                    }))
+                } else {
+                    Ok(None)
+                }
+            }
+            None => {
+                database::put_proposal::<_, E>(
+                    &*self.store,
+                    proposer_index,
+                    &header,
+                    &mut batch.index,
+                )?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Checks the `attestation` against the records of each of its attesters, returning any
+    /// slashings detected, then updates those records.
+    fn process_attestation(
+        &self,
+        attestation: IndexedAttestation<E>,
+        batch: &mut Batch,
+    ) -> Result<Vec<AttesterSlashing<E>>, Error> {
+        let attestation_root = Hash256::from_slice(&attestation.tree_hash_root());
+        let record = AttesterRecord {
+            attestation_root,
+            data_root: Hash256::from_slice(&attestation.data.tree_hash_root()),
+        };
+
+        database::put_indexed_attestation(
+            &*self.store,
+            attestation_root,
+            &attestation,
+            &mut batch.index,
+        )?;
+
+        let mut slashings = vec![];
+
+        for &validator_index in attestation
+            .custody_bit_0_indices
+            .iter()
+            .chain(attestation.custody_bit_1_indices.iter())
+        {
+            if let Some(existing_root) = self.process_attestation_for_validator(
+                validator_index,
+                &attestation,
+                &record,
+                batch,
+            )? {
+                let existing = database::get_indexed_attestation(&*self.store, existing_root)?;
+
+                // The op pool requires that `attestation_1` surrounds `attestation_2` for
+                // surround votes.
+                let slashing = if attestation.is_surround_vote(&existing) {
+                    AttesterSlashing {
+                        attestation_1: attestation.clone(),
+                        attestation_2: existing,
+                    }
+                } else {
+                    AttesterSlashing {
+                        attestation_1: existing,
+                        attestation_2: attestation.clone(),
+                    }
+                };
+
+                slashings.push(slashing);
+            }
+        }
+
+        Ok(slashings)
+    }
+
+    /// Checks the `attestation` against the records of a single validator, returning the root of
+    /// an existing, conflicting attestation (if any).
+    ///
+    /// The attestation is recorded unless it is a double vote (in which case the first attestation
+    /// for the target is kept).
+    fn process_attestation_for_validator(
+        &self,
+        validator_index: u64,
+        attestation: &IndexedAttestation<E>,
+        record: &AttesterRecord,
+        batch: &mut Batch,
+    ) -> Result<Option<Hash256>, Error> {
+        let store = &*self.store;
+        let source = attestation.data.source.epoch;
+        let target = attestation.data.target.epoch;
+
+        // Check for a double vote.
+        if let Some(existing) = database::get_attester_record(store, validator_index, target)? {
+            if existing.data_root != record.data_root {
+                return Ok(Some(existing.attestation_root));
+            } else {
+                return Ok(None);
+            }
+        }
+
+        // Check for surround votes.
+        let floor = database::get_min_span_floor(store, validator_index)?;
+        let mut conflicting_target = None;
+
+        if target > source {
+            let distance = (target - source).as_u64();
+
+            let min_span = match floor {
+                Some(floor) if source < floor.source => floor.span_at(source),
+                Some(_) => Chunk::load(store, SpanKind::Min, validator_index, source)?.get(source),
+                None => MIN_SPAN_NONE,
+            };
+            let max_span = Chunk::load(store, SpanKind::Max, validator_index, source)?.get(source);
+
+            if min_span != MIN_SPAN_NONE && min_span < distance {
+                // The new attestation surrounds an existing one.
+                conflicting_target = Some(source + min_span);
+            } else if max_span != MAX_SPAN_NONE && max_span > distance {
+                // The new attestation is surrounded by an existing one.
+                conflicting_target = Some(source + max_span);
+            }
+        }
+
+        database::put_attester_record(store, validator_index, target, record, &mut batch.index)?;
+        self.update_min_spans(validator_index, source, target, floor, batch)?;
+        self.update_max_spans(validator_index, source, target, batch)?;
+
+        match conflicting_target {
+            Some(conflicting_target) => {
+                Ok(
+                    database::get_attester_record(store, validator_index, conflicting_target)?
+                        .map(|existing| existing.attestation_root),
+                )
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Updates the min-spans of a validator with an existing `floor` (if any) for a new
+    /// attestation from `source` to `target`.
+    ///
+    /// For each stored epoch `e` prior to `source` (and within the history length), lowers
+    /// `min_span[e]` to `target - e`. Stops early once a span is already lower, since all spans at
+    /// prior epochs must also be lower. Spans prior to the floor are implied by it, so they are
+    /// updated by lowering the floor's target.
+    ///
+    /// If `source` is prior to the floor, the spans between `source` and the floor can no longer
+    /// be implied by it, so they are stored.
+    fn update_min_spans(
+        &self,
+        validator_index: u64,
+        source: Epoch,
+        target: Epoch,
+        floor: Option<MinSpanFloor>,
+        batch: &mut Batch,
+    ) -> Result<(), Error> {
+        let lowest_epoch = std::cmp::max(
+            source.as_u64().saturating_sub(self.config.history_length),
+            batch.pruned_epoch.as_u64(),
+        );
+
+        let new_floor = match floor {
+            None => MinSpanFloor { source, target },
+            Some(floor) if source >= floor.source => {
+                self.update_spans(
+                    SpanKind::Min,
+                    validator_index,
+                    (std::cmp::max(lowest_epoch, floor.source.as_u64())..source.as_u64()).rev(),
+                    |epoch, existing| {
+                        let distance = target.as_u64().saturating_sub(epoch);
+                        if distance < existing {
+                            Some(distance)
+                        } else {
+                            None
+                        }
+                    },
+                    &mut batch.index,
+                )?;
+
+                MinSpanFloor {
+                    source: floor.source,
+                    target: std::cmp::min(floor.target, target),
+                }
+            }
+            Some(floor) => {
+                self.update_spans(
+                    SpanKind::Min,
+                    validator_index,
+                    std::cmp::max(lowest_epoch, source.as_u64())..floor.source.as_u64(),
+                    |epoch, _existing| Some(floor.span_at(Epoch::new(epoch))),
+                    &mut batch.index,
+                )?;
+
+                MinSpanFloor {
+                    source,
+                    target: std::cmp::min(floor.target, target),
+                }
+            }
+        };
+
+        if floor != Some(new_floor) {
+            database::put_min_span_floor(&*self.store, validator_index, &new_floor)?;
+        }
+
+        Ok(())
+    }
+
+    /// For each epoch `e` between `source` and `target` (exclusive, and within the history
+    /// length), raises `max_span[e]` to `target - e`.
+    ///
+    /// Stops early once a span is already higher, since all spans at later epochs must also be
+    /// higher.
+    fn update_max_spans(
+        &self,
+        validator_index: u64,
+        source: Epoch,
+        target: Epoch,
+        batch: &mut Batch,
+    ) -> Result<(), Error> {
+        let lowest_epoch = std::cmp::max(
+            std::cmp::max(source.as_u64() + 1, batch.pruned_epoch.as_u64()),
+            target.as_u64().saturating_sub(self.config.history_length),
+        );
+
+        self.update_spans(
+            SpanKind::Max,
+            validator_index,
+            lowest_epoch..target.as_u64(),
+            |epoch, existing| {
+                let distance = target.as_u64() - epoch;
+                if distance > existing {
+                    Some(distance)
+                } else {
+                    None
+                }
+            },
+            &mut batch.index,
+        )
+    }
+
+    /// Visits the `epochs` in order, setting each span to the value returned by `new_value`, until
+    /// `new_value` returns `None`.
+    fn update_spans<I, F>(
+        &self,
+        kind: SpanKind,
+        validator_index: u64,
+        epochs: I,
+        new_value: F,
+        index: &mut IndexBatch,
+    ) -> Result<(), Error>
+    where
+        I: Iterator<Item = u64>,
+        F: Fn(u64, u64) -> Option<u64>,
+    {
+        let store = &*self.store;
+        let mut chunk: Option<Chunk> = None;
+
+        for epoch in epochs.map(Epoch::new) {
+            // Write the previous chunk and load the next when crossing a chunk boundary.
+            if !chunk.as_ref().map_or(false, |chunk| chunk.contains(epoch)) {
+                if let Some(previous) = chunk.take() {
+                    previous.store(store, index)?;
+                }
+                chunk = Some(Chunk::load(store, kind, validator_index, epoch)?);
+            }
+
+            let chunk = chunk.as_mut().expect("chunk is loaded above");
+
+            match new_value(epoch.as_u64(), chunk.get(epoch)) {
+                Some(value) => chunk.set(epoch, value),
+                None => break,
+            }
+        }
+
+        if let Some(chunk) = chunk {
+            chunk.store(store, index)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::{DBColumn, MemoryStore};
+    use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use types::{MinimalEthSpec, Slot, VariableList};
+
+    type E = MinimalEthSpec;
+
+    fn slasher() -> Slasher<MemoryStore, E> {
+        Slasher::new(Arc::new(MemoryStore::open()), SlasherConfig::default())
+    }
+
+    fn attestation(
+        validators: &[u64],
+        source: u64,
+        target: u64,
+        seed: u8,
+    ) -> IndexedAttestation<E> {
+        let mut rng = XorShiftRng::from_seed([seed; 16]);
+        let mut attestation = IndexedAttestation::random_for_test(&mut rng);
+
+        attestation.custody_bit_0_indices = VariableList::from(validators.to_vec());
+        attestation.custody_bit_1_indices = VariableList::empty();
+        attestation.data.source.epoch = Epoch::new(source);
+        attestation.data.target.epoch = Epoch::new(target);
+
+        attestation
+    }
+
+    fn process_attestations(
+        slasher: &Slasher<MemoryStore, E>,
+        attestations: Vec<IndexedAttestation<E>>,
+    ) -> Vec<AttesterSlashing<E>> {
+        for attestation in attestations {
+            slasher.accept_attestation(attestation);
+        }
+
+        let output = slasher.process_queued().unwrap();
+        assert!(output.proposer_slashings.is_empty());
+
+        output.attester_slashings
+    }
+
+    #[test]
+    fn no_slashing_for_consistent_attestations() {
+        let slasher = slasher();
+
+        let slashings = process_attestations(
+            &slasher,
+            vec![
+                attestation(&[0, 1], 0, 1, 1),
+                attestation(&[0, 1], 1, 2, 2),
+                attestation(&[0, 1], 2, 3, 3),
+                attestation(&[0, 1], 2, 3, 3),
+                attestation(&[2], 0, 3, 4),
+            ],
+        );
+
+        assert!(slashings.is_empty());
+    }
+
+    #[test]
+    fn double_vote() {
+        let slasher = slasher();
+
+        let first = attestation(&[0, 1], 0, 1, 1);
+        let second = attestation(&[1, 2], 0, 1, 2);
+
+        let slashings = process_attestations(&slasher, vec![first.clone(), second.clone()]);
+
+        assert_eq!(
+            slashings,
+            vec![AttesterSlashing {
+                attestation_1: first,
+                attestation_2: second,
+            }]
+        );
+    }
+
+    #[test]
+    fn new_attestation_surrounds_existing() {
+        let slasher = slasher();
+
+        let surrounded = attestation(&[3], 5, 6, 1);
+        let surrounding = attestation(&[3], 4, 8, 2);
+
+        assert!(process_attestations(&slasher, vec![surrounded.clone()]).is_empty());
+        assert_eq!(
+            process_attestations(&slasher, vec![surrounding.clone()]),
+            vec![AttesterSlashing {
+                attestation_1: surrounding,
+                attestation_2: surrounded,
+            }]
+        );
+    }
+
+    #[test]
+    fn new_attestation_is_surrounded_by_existing() {
+        let slasher = slasher();
+
+        // Spans cross a chunk boundary.
+        let surrounding = attestation(&[3], 10, 40, 1);
+        let surrounded = attestation(&[3], 20, 30, 2);
+
+        assert!(process_attestations(&slasher, vec![surrounding.clone()]).is_empty());
+        assert_eq!(
+            process_attestations(&slasher, vec![surrounded.clone()]),
+            vec![AttesterSlashing {
+                attestation_1: surrounding,
+                attestation_2: surrounded,
+            }]
+        );
+    }
+
+    #[test]
+    fn first_attestation_stores_no_min_spans() {
+        let slasher = slasher();
+
+        process_attestations(&slasher, vec![attestation(&[3], 1_000, 1_001, 1)]);
+
+        for chunk_index in 0..=1_000 / database::CHUNK_SIZE {
+            assert!(!slasher
+                .store
+                .key_exists(
+                    DBColumn::SlasherMinSpans.into(),
+                    &database::key(3, chunk_index)
+                )
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn surround_detected_after_source_moves_backwards() {
+        let slasher = slasher();
+
+        let surrounded = attestation(&[3], 10, 11, 1);
+        let earlier = attestation(&[3], 5, 6, 2);
+        let surrounding = attestation(&[3], 7, 12, 3);
+
+        assert!(process_attestations(&slasher, vec![surrounded.clone(), earlier]).is_empty());
+        assert_eq!(
+            process_attestations(&slasher, vec![surrounding.clone()]),
+            vec![AttesterSlashing {
+                attestation_1: surrounding,
+                attestation_2: surrounded,
+            }]
+        );
+    }
+
+    #[test]
+    fn prune_deletes_records_outside_history() {
+        let config = SlasherConfig {
+            history_length: 8,
+            ..SlasherConfig::default()
+        };
+        let slasher: Slasher<MemoryStore, E> = Slasher::new(Arc::new(MemoryStore::open()), config);
+        let store = &*slasher.store;
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+
+        let old = attestation(&[3], 1, 4, 1);
+        let recent = attestation(&[3], 30, 31, 2);
+        let old_root = Hash256::from_slice(&old.tree_hash_root());
+        let recent_root = Hash256::from_slice(&recent.tree_hash_root());
+
+        let mut old_header = BeaconBlockHeader::random_for_test(&mut rng);
+        old_header.slot = Epoch::new(4).start_slot(E::slots_per_epoch());
+        let mut recent_header = old_header.clone();
+        recent_header.slot = Epoch::new(31).start_slot(E::slots_per_epoch());
+
+        slasher.accept_block_header(7, old_header.clone());
+        slasher.accept_block_header(7, recent_header.clone());
+        process_attestations(&slasher, vec![old.clone(), recent]);
+
+        let max_span_chunk_exists = || {
+            store
+                .key_exists(DBColumn::SlasherMaxSpans.into(), &database::key(3, 0))
+                .unwrap()
+        };
+        assert!(max_span_chunk_exists());
+
+        slasher.prune(Epoch::new(32)).unwrap();
+
+        assert_eq!(
+            database::get_attester_record(store, 3, Epoch::new(4)),
+            Ok(None)
+        );
+        assert!(database::get_indexed_attestation::<_, E>(store, old_root).is_err());
+        assert_eq!(database::get_proposal(store, 7, old_header.slot), Ok(None));
+        assert!(!max_span_chunk_exists());
+
+        assert!(database::get_attester_record(store, 3, Epoch::new(31))
+            .unwrap()
+            .is_some());
+        assert!(database::get_indexed_attestation::<_, E>(store, recent_root).is_ok());
+        assert!(database::get_proposal(store, 7, recent_header.slot)
+            .unwrap()
+            .is_some());
+
+        // Inputs from pruned epochs are ignored.
+        process_attestations(&slasher, vec![old]);
+        assert_eq!(
+            database::get_attester_record(store, 3, Epoch::new(4)),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn double_proposal() {
+        let slasher = slasher();
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+
+        let mut header_1 = BeaconBlockHeader::random_for_test(&mut rng);
+        header_1.slot = Slot::new(10);
+        let mut header_2 = header_1.clone();
+        header_2.state_root = Hash256::from_slice(&[1; 32]);
+
+        slasher.accept_block_header(7, header_1.clone());
+        slasher.accept_block_header(7, header_1.clone());
+        slasher.accept_block_header(8, header_2.clone());
+        slasher.accept_block_header(7, header_2.clone());
+
+        let output = slasher.process_queued().unwrap();
+
+        assert_eq!(
+            output.proposer_slashings,
+            vec![ProposerSlashing {
+                proposer_index: 7,
+                header_1,
+                header_2,
+            }]
+        );
+        assert!(output.attester_slashings.is_empty());
+        assert_eq!(slasher.queue_len(), 0);
+    }
+}
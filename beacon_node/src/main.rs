@@ -120,6 +120,56 @@ fn main() {
                 .help("Listen port for the HTTP server.")
                 .takes_value(true),
         )
+        // Slasher related arguments
+        .arg(
+            Arg::with_name("slasher")
+                .long("slasher")
+                .help("Enable the slasher, which detects slashable offences and submits them to the operation pool.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("slasher-history-length")
+                .long("slasher-history-length")
+                .value_name("EPOCHS")
+                .help("Number of epochs of history used to detect surround votes. Older records are pruned.")
+                .takes_value(true),
+        )
+        // Operation pool related arguments
+        .arg(
+            Arg::with_name("op-pool-max-attestations")
+                .long("op-pool-max-attestations")
+                .value_name("COUNT")
+                .help("Maximum number of attestations, counting attestations that could not be aggregated separately held by the operation pool.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("op-pool-max-attester-slashings")
+                .long("op-pool-max-attester-slashings")
+                .value_name("COUNT")
+                .help("Maximum number of attester slashings held by the operation pool.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("op-pool-max-proposer-slashings")
+                .long("op-pool-max-proposer-slashings")
+                .value_name("COUNT")
+                .help("Maximum number of proposer slashings held by the operation pool.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("op-pool-max-voluntary-exits")
+                .long("op-pool-max-voluntary-exits")
+                .value_name("COUNT")
+                .help("Maximum number of voluntary exits held by the operation pool.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("op-pool-max-transfers")
+                .long("op-pool-max-transfers")
+                .value_name("COUNT")
+                .help("Maximum number of transfers held by the operation pool.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("db")
                 .long("db")
@@ -139,6 +189,13 @@ fn main() {
                 .possible_values(&["mainnet", "minimal"])
                 .default_value("minimal"),
         )
+        .arg(
+            Arg::with_name("spec-config")
+                .long("spec-config")
+                .value_name("YAML_FILE")
+                .help("Path to a spec config file (in the eth2.0-specs YAML format) to override the spec constants.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("recent-genesis")
                 .long("recent-genesis")
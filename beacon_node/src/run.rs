@@ -13,7 +13,7 @@ use tokio::runtime::Builder;
 use tokio::runtime::Runtime;
 use tokio::runtime::TaskExecutor;
 use tokio_timer::clock::Clock;
-use types::{MainnetEthSpec, MinimalEthSpec};
+use types::{EthSpec, MainnetEthSpec, MinimalEthSpec};
 
 pub fn run_beacon_node(
     client_config: ClientConfig,
@@ -104,6 +104,11 @@ where
     T: BeaconChainTypes + InitialiseBeaconChain<T> + Clone + Send + Sync + 'static,
     T::Store: OpenDatabase,
 {
+    eth2_config
+        .spec
+        .verify_fork_schedule(T::EthSpec::genesis_epoch())
+        .map_err(|e| format!("Invalid fork schedule: {}", e))?;
+
     let store = T::Store::open_database(&db_path)?;
 
     let client: Client<T> = Client::new(client_config, eth2_config, store, log.clone(), &executor)?;
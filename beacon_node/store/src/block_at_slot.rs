@@ -2,7 +2,7 @@ use super::*;
 use ssz::{Decode, DecodeError};
 
 fn get_block_bytes<T: Store>(store: &T, root: Hash256) -> Result<Option<Vec<u8>>, Error> {
-    store.get_bytes(BeaconBlock::db_column().into(), &root[..])
+    store.get_bytes(DBColumn::BeaconBlock.into(), &root[..])
 }
 
 fn read_slot_from_block_bytes(bytes: &[u8]) -> Result<Slot, DecodeError> {
@@ -11,7 +11,7 @@ fn read_slot_from_block_bytes(bytes: &[u8]) -> Result<Slot, DecodeError> {
     Slot::from_ssz_bytes(&bytes[0..end])
 }
 
-fn read_previous_block_root_from_block_bytes(bytes: &[u8]) -> Result<Hash256, DecodeError> {
+fn read_parent_root_from_block_bytes(bytes: &[u8]) -> Result<Hash256, DecodeError> {
     let previous_bytes = Slot::ssz_fixed_len();
     let slice = bytes
         .get(previous_bytes..previous_bytes + Hash256::ssz_fixed_len())
@@ -20,11 +20,11 @@ fn read_previous_block_root_from_block_bytes(bytes: &[u8]) -> Result<Hash256, De
     Hash256::from_ssz_bytes(slice)
 }
 
-pub fn get_block_at_preceeding_slot<T: Store>(
+pub fn get_block_at_preceeding_slot<T: Store, E: EthSpec>(
     store: &T,
     slot: Slot,
     start_root: Hash256,
-) -> Result<Option<(Hash256, BeaconBlock)>, Error> {
+) -> Result<Option<(Hash256, BeaconBlock<E>)>, Error> {
     Ok(match get_at_preceeding_slot(store, slot, start_root)? {
         Some((hash, bytes)) => Some((hash, BeaconBlock::from_ssz_bytes(&bytes)?)),
         None => None,
@@ -45,7 +45,7 @@ fn get_at_preceeding_slot<T: Store>(
             } else if this_slot < slot {
                 break Ok(None);
             } else {
-                root = read_previous_block_root_from_block_bytes(&bytes)?;
+                root = read_parent_root_from_block_bytes(&bytes)?;
             }
         } else {
             break Ok(None);
@@ -64,7 +64,7 @@ mod tests {
         let spec = MinimalEthSpec::default_spec();
 
         let test_slot = |slot: Slot| {
-            let mut block = BeaconBlock::empty(&spec);
+            let mut block = BeaconBlock::<MinimalEthSpec>::empty(&spec);
             block.slot = slot;
             let bytes = block.as_ssz_bytes();
             assert_eq!(read_slot_from_block_bytes(&bytes).unwrap(), slot);
@@ -84,17 +84,14 @@ mod tests {
     }
 
     #[test]
-    fn read_previous_block_root() {
+    fn read_parent_root() {
         let spec = MinimalEthSpec::default_spec();
 
         let test_root = |root: Hash256| {
-            let mut block = BeaconBlock::empty(&spec);
-            block.previous_block_root = root;
+            let mut block = BeaconBlock::<MinimalEthSpec>::empty(&spec);
+            block.parent_root = root;
             let bytes = block.as_ssz_bytes();
-            assert_eq!(
-                read_previous_block_root_from_block_bytes(&bytes).unwrap(),
-                root
-            );
+            assert_eq!(read_parent_root_from_block_bytes(&bytes).unwrap(), root);
         };
 
         test_root(Hash256::random());
@@ -106,15 +103,15 @@ mod tests {
         store: &impl Store,
         slots: &[usize],
         spec: &ChainSpec,
-    ) -> Vec<(Hash256, BeaconBlock)> {
-        let mut blocks_and_roots: Vec<(Hash256, BeaconBlock)> = vec![];
+    ) -> Vec<(Hash256, BeaconBlock<MinimalEthSpec>)> {
+        let mut blocks_and_roots: Vec<(Hash256, BeaconBlock<MinimalEthSpec>)> = vec![];
 
         for (i, slot) in slots.iter().enumerate() {
             let mut block = BeaconBlock::empty(spec);
             block.slot = Slot::from(*slot);
 
             if i > 0 {
-                block.previous_block_root = blocks_and_roots[i - 1].0;
+                block.parent_root = blocks_and_roots[i - 1].0;
             }
 
             let root = Hash256::from_slice(&block.tree_hash_root());
@@ -177,14 +174,14 @@ mod tests {
         // Slot that doesn't exist
         let (source_root, _source_block) = &blocks_and_roots[3];
         assert!(store
-            .get_block_at_preceeding_slot(*source_root, Slot::new(3))
+            .get_block_at_preceeding_slot::<MinimalEthSpec>(*source_root, Slot::new(3))
             .unwrap()
             .is_none());
 
         // Slot too high
         let (source_root, _source_block) = &blocks_and_roots[3];
         assert!(store
-            .get_block_at_preceeding_slot(*source_root, Slot::new(3))
+            .get_block_at_preceeding_slot::<MinimalEthSpec>(*source_root, Slot::new(3))
             .unwrap()
             .is_none());
     }
@@ -3,7 +3,7 @@ use ssz::{Decode, Encode};
 
 mod beacon_state;
 
-impl StoreItem for BeaconBlock {
+impl<T: EthSpec> StoreItem for BeaconBlock<T> {
     fn db_column() -> DBColumn {
         DBColumn::BeaconBlock
     }
@@ -1,10 +1,13 @@
 use crate::*;
-use ssz::{Decode, DecodeError, Encode};
+use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
-use std::convert::TryInto;
-use types::beacon_state::{CommitteeCache, CACHED_EPOCHS};
 
 /// A container for storing `BeaconState` components.
+///
+/// Committee caches are not stored: a loaded state rebuilds them on demand, taking the shufflings
+/// from its chain's `ShufflingCache`. The `committee_caches_bytes` are always written empty, but
+/// are retained so that states written by earlier versions (which hold full committee caches)
+/// still decode. Those caches are discarded.
 #[derive(Encode, Decode)]
 struct StorageContainer {
     state_bytes: Vec<u8>,
@@ -14,37 +17,22 @@ struct StorageContainer {
 impl StorageContainer {
     /// Create a new instance for storing a `BeaconState`.
     pub fn new<T: EthSpec>(state: &BeaconState<T>) -> Self {
-        let mut committee_caches_bytes = vec![];
-
-        for cache in state.committee_caches[..].iter() {
-            committee_caches_bytes.push(cache.as_ssz_bytes());
-        }
-
         Self {
             state_bytes: state.as_ssz_bytes(),
-            committee_caches_bytes,
+            committee_caches_bytes: vec![],
         }
     }
 }
 
-impl<T: EthSpec> TryInto<BeaconState<T>> for StorageContainer {
-    type Error = Error;
-
-    fn try_into(self) -> Result<BeaconState<T>, Error> {
-        let mut state: BeaconState<T> = BeaconState::from_ssz_bytes(&self.state_bytes)?;
-
-        for i in 0..CACHED_EPOCHS {
-            let bytes = &self.committee_caches_bytes.get(i).ok_or_else(|| {
-                Error::SszDecodeError(DecodeError::BytesInvalid(
-                    "Insufficient committees for BeaconState".to_string(),
-                ))
-            })?;
-
-            state.committee_caches[i] = CommitteeCache::from_ssz_bytes(bytes)?;
-        }
-
-        Ok(state)
-    }
+/// The decoding counterpart of `StorageContainer`.
+///
+/// Shares its SSZ layout, but decodes the state in place rather than first copying its bytes out
+/// of the container.
+#[derive(Decode)]
+struct DecodedStorageContainer<T: EthSpec> {
+    state: BeaconState<T>,
+    /// Discarded, see `StorageContainer`.
+    _committee_caches_bytes: Vec<Vec<u8>>,
 }
 
 impl<T: EthSpec> StoreItem for BeaconState<T> {
@@ -58,7 +46,31 @@ impl<T: EthSpec> StoreItem for BeaconState<T> {
     }
 
     fn from_store_bytes(bytes: &mut [u8]) -> Result<Self, Error> {
-        let container = StorageContainer::from_ssz_bytes(bytes)?;
-        container.try_into()
+        let container =
+            DecodedStorageContainer::<T>::from_ssz_reader(&mut &bytes[..], bytes.len())?;
+        Ok(container.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    #[test]
+    fn decodes_states_with_stored_committee_caches() {
+        let spec = MinimalEthSpec::default_spec();
+        let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+            TestingBeaconStateBuilder::from_single_keypair(8, &Keypair::random(), &spec);
+        let (state, _keypairs) = builder.build();
+
+        let mut bytes = StorageContainer {
+            state_bytes: state.as_ssz_bytes(),
+            committee_caches_bytes: vec![vec![42; 64]; 3],
+        }
+        .as_ssz_bytes();
+
+        let decoded = BeaconState::<MinimalEthSpec>::from_store_bytes(&mut bytes).unwrap();
+        assert_eq!(decoded, state);
     }
 }
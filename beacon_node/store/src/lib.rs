@@ -52,11 +52,11 @@ pub trait Store: Sync + Send + Sized {
     ///
     /// Returns `None` if no parent block exists at that slot, or if `slot` is greater than the
     /// slot of `start_block_root`.
-    fn get_block_at_preceeding_slot(
+    fn get_block_at_preceeding_slot<E: EthSpec>(
         &self,
         start_block_root: Hash256,
         slot: Slot,
-    ) -> Result<Option<(Hash256, BeaconBlock)>, Error> {
+    ) -> Result<Option<(Hash256, BeaconBlock<E>)>, Error> {
         block_at_slot::get_block_at_preceeding_slot(self, slot, start_block_root)
     }
 
@@ -78,6 +78,13 @@ pub enum DBColumn {
     BeaconBlock,
     BeaconState,
     BeaconChain,
+    SlasherMinSpans,
+    SlasherMaxSpans,
+    SlasherAttesterRecords,
+    SlasherIndexedAttestations,
+    SlasherProposals,
+    SlasherMinSpanFloors,
+    SlasherEpochIndices,
 }
 
 impl<'a> Into<&'a str> for DBColumn {
@@ -87,6 +94,13 @@ impl<'a> Into<&'a str> for DBColumn {
             DBColumn::BeaconBlock => &"blk",
             DBColumn::BeaconState => &"ste",
             DBColumn::BeaconChain => &"bch",
+            DBColumn::SlasherMinSpans => &"smn",
+            DBColumn::SlasherMaxSpans => &"smx",
+            DBColumn::SlasherAttesterRecords => &"sar",
+            DBColumn::SlasherIndexedAttestations => &"sia",
+            DBColumn::SlasherProposals => &"spr",
+            DBColumn::SlasherMinSpanFloors => &"smf",
+            DBColumn::SlasherEpochIndices => &"sei",
         }
     }
 }
@@ -0,0 +1,185 @@
+//! Provides a read-only view of the internal state of an `LmdGhost` implementation, for debugging.
+//!
+//! A `LmdGhostSnapshot` can be exported as JSON or as a Graphviz DOT graph. The nodes and latest
+//! messages in a snapshot are sorted, so that the snapshots of two nodes may be diffed directly.
+use serde_derive::{Deserialize, Serialize};
+use std::fmt::Write;
+use types::{Hash256, Slot};
+
+/// A single block in the fork choice tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotNode {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    pub parent: Option<Hash256>,
+    pub children: Vec<Hash256>,
+    /// The weight of the node (including all its descendants), as of the last call to
+    /// `find_head`.
+    pub weight: u64,
+    /// The number of validators whose latest message is for this exact node.
+    pub voter_count: usize,
+}
+
+/// The latest message that has been applied for some validator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatestMessage {
+    pub validator_index: usize,
+    pub block_root: Hash256,
+    pub slot: Slot,
+}
+
+/// A read-only copy of all the nodes and latest messages known to an `LmdGhost` implementation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LmdGhostSnapshot {
+    pub root: Hash256,
+    pub root_slot: Slot,
+    pub nodes: Vec<SnapshotNode>,
+    pub latest_messages: Vec<LatestMessage>,
+}
+
+impl LmdGhostSnapshot {
+    /// Create a new snapshot, sorting the `nodes` by slot (then root) and the `latest_messages` by
+    /// validator index.
+    pub fn new(
+        root: Hash256,
+        root_slot: Slot,
+        mut nodes: Vec<SnapshotNode>,
+        mut latest_messages: Vec<LatestMessage>,
+    ) -> Self {
+        for node in &mut nodes {
+            node.children.sort();
+        }
+        nodes.sort_by_key(|node| (node.slot, node.block_root));
+        latest_messages.sort_by_key(|message| message.validator_index);
+
+        Self {
+            root,
+            root_slot,
+            nodes,
+            latest_messages,
+        }
+    }
+
+    /// Returns the snapshot as a pretty-printed JSON string.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("snapshot should always serialize to JSON")
+    }
+
+    /// Returns the nodes of the snapshot as a Graphviz DOT directed graph, where each edge points
+    /// from parent to child.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph LmdGhost {\n");
+
+        for node in &self.nodes {
+            let shape = if node.block_root == self.root {
+                "box"
+            } else {
+                "ellipse"
+            };
+
+            // Note: writing to a `String` never fails.
+            let _ = writeln!(
+                dot,
+                "    \"{:?}\" [shape={}, label=\"{}\\nslot: {}\\nweight: {}\\nvoters: {}\"];",
+                node.block_root, shape, node.block_root, node.slot, node.weight, node.voter_count
+            );
+        }
+
+        for node in &self.nodes {
+            for child in &node.children {
+                let _ = writeln!(dot, "    \"{:?}\" -> \"{:?}\";", node.block_root, child);
+            }
+        }
+
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> LmdGhostSnapshot {
+        let root = Hash256::from_slice(&[1; 32]);
+        let a = Hash256::from_slice(&[2; 32]);
+        let b = Hash256::from_slice(&[3; 32]);
+
+        let node = |block_root, slot: u64, parent, children, weight, voter_count| SnapshotNode {
+            block_root,
+            slot: Slot::new(slot),
+            parent,
+            children,
+            weight,
+            voter_count,
+        };
+
+        LmdGhostSnapshot::new(
+            root,
+            Slot::new(0),
+            vec![
+                node(b, 1, Some(root), vec![], 32, 1),
+                node(root, 0, None, vec![b, a], 96, 0),
+                node(a, 1, Some(root), vec![], 64, 2),
+            ],
+            vec![
+                LatestMessage {
+                    validator_index: 2,
+                    block_root: a,
+                    slot: Slot::new(1),
+                },
+                LatestMessage {
+                    validator_index: 0,
+                    block_root: b,
+                    slot: Slot::new(1),
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn new_sorts_nodes_and_messages() {
+        let snapshot = snapshot();
+
+        let roots: Vec<Hash256> = snapshot.nodes.iter().map(|n| n.block_root).collect();
+        assert_eq!(
+            roots,
+            vec![
+                Hash256::from_slice(&[1; 32]),
+                Hash256::from_slice(&[2; 32]),
+                Hash256::from_slice(&[3; 32])
+            ]
+        );
+        assert_eq!(
+            snapshot.nodes[0].children,
+            vec![Hash256::from_slice(&[2; 32]), Hash256::from_slice(&[3; 32])]
+        );
+
+        let indices: Vec<usize> = snapshot
+            .latest_messages
+            .iter()
+            .map(|m| m.validator_index)
+            .collect();
+        assert_eq!(indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let snapshot = snapshot();
+
+        let decoded: LmdGhostSnapshot = serde_json::from_str(&snapshot.to_json()).unwrap();
+
+        assert_eq!(decoded, snapshot);
+    }
+
+    #[test]
+    fn dot_contains_all_edges() {
+        let snapshot = snapshot();
+        let dot = snapshot.to_dot();
+
+        assert!(dot.starts_with("digraph LmdGhost {"));
+        assert_eq!(dot.matches(" -> ").count(), 2);
+        assert_eq!(dot.matches("shape=box").count(), 1);
+    }
+}
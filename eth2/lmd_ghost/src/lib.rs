@@ -1,16 +1,22 @@
+mod introspection;
+mod naive;
+mod proto_array;
 mod reduced_tree;
 
 use std::sync::Arc;
 use store::Store;
 use types::{BeaconBlock, EthSpec, Hash256, Slot};
 
+pub use introspection::{LatestMessage, LmdGhostSnapshot, SnapshotNode};
+pub use naive::ThreadSafeNaiveLmdGhost;
+pub use proto_array::ThreadSafeProtoArray;
 pub use reduced_tree::ThreadSafeReducedTree;
 
 pub type Result<T> = std::result::Result<T, String>;
 
 pub trait LmdGhost<S: Store, E: EthSpec>: Send + Sync {
     /// Create a new instance, with the given `store` and `finalized_root`.
-    fn new(store: Arc<S>, finalized_block: &BeaconBlock, finalized_root: Hash256) -> Self;
+    fn new(store: Arc<S>, finalized_block: &BeaconBlock<E>, finalized_root: Hash256) -> Self;
 
     /// Process an attestation message from some validator that attests to some `block_hash`
     /// representing a block at some `block_slot`.
@@ -22,7 +28,7 @@ pub trait LmdGhost<S: Store, E: EthSpec>: Send + Sync {
     ) -> Result<()>;
 
     /// Process a block that was seen on the network.
-    fn process_block(&self, block: &BeaconBlock, block_hash: Hash256) -> Result<()>;
+    fn process_block(&self, block: &BeaconBlock<E>, block_hash: Hash256) -> Result<()>;
 
     /// Returns the head of the chain, starting the search at `start_block_root` and moving upwards
     /// (in block height).
@@ -40,7 +46,25 @@ pub trait LmdGhost<S: Store, E: EthSpec>: Send + Sync {
     /// `finalized_block_root` must be the root of `finalized_block`.
     fn update_finalized_root(
         &self,
-        finalized_block: &BeaconBlock,
+        finalized_block: &BeaconBlock<E>,
         finalized_block_root: Hash256,
     ) -> Result<()>;
+
+    /// Returns `true` if `block_root` is a node of `self`.
+    ///
+    /// The head returned by `find_head` and the root set by `update_finalized_root` are always
+    /// nodes, other blocks may have been pruned.
+    fn contains_block(&self, block_root: Hash256) -> bool;
+
+    /// Returns a read-only copy of all nodes and latest messages known to `self`, for debugging.
+    fn introspect(&self) -> Result<LmdGhostSnapshot>;
+
+    /// Returns the internal state of `self` as SSZ bytes, so that it may be persisted across
+    /// restarts.
+    fn as_bytes(&self) -> Vec<u8>;
+
+    /// Create a new instance from bytes produced by `as_bytes`, with the given `store`.
+    fn from_bytes(bytes: &[u8], store: Arc<S>) -> Result<Self>
+    where
+        Self: Sized;
 }
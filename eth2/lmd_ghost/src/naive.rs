@@ -0,0 +1,342 @@
+//! A naive, spec-literal implementation of LMD GHOST fork choice.
+//!
+//! Each call to `find_head` sums the balances of the latest messages in the subtree of every
+//! known block, then walks from the start block to the child with the greatest weight until a
+//! leaf is reached. This is very inefficient, but it is simple enough to be obviously correct and
+//! serves as a reference when testing the other `LmdGhost` implementations.
+use super::{LatestMessage, LmdGhost, LmdGhostSnapshot, Result as SuperResult, SnapshotNode};
+use parking_lot::RwLock;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use types::{BeaconBlock, EthSpec, Hash256, Slot};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    ValidatorWeightUnknown(usize),
+}
+
+pub struct ThreadSafeNaiveLmdGhost<T, E> {
+    core: RwLock<NaiveLmdGhost<T, E>>,
+}
+
+impl<T, E> LmdGhost<T, E> for ThreadSafeNaiveLmdGhost<T, E>
+where
+    T: store::Store,
+    E: EthSpec,
+{
+    fn new(_store: Arc<T>, genesis_block: &BeaconBlock<E>, genesis_root: Hash256) -> Self {
+        ThreadSafeNaiveLmdGhost {
+            core: RwLock::new(NaiveLmdGhost::new(genesis_block, genesis_root)),
+        }
+    }
+
+    fn process_attestation(
+        &self,
+        validator_index: usize,
+        block_hash: Hash256,
+        block_slot: Slot,
+    ) -> SuperResult<()> {
+        self.core
+            .write()
+            .process_message(validator_index, block_hash, block_slot);
+
+        Ok(())
+    }
+
+    /// Process a block that was seen on the network.
+    fn process_block(&self, block: &BeaconBlock<E>, block_hash: Hash256) -> SuperResult<()> {
+        self.core.write().process_block(block, block_hash);
+
+        Ok(())
+    }
+
+    fn find_head<F>(
+        &self,
+        _start_block_slot: Slot,
+        start_block_root: Hash256,
+        weight_fn: F,
+    ) -> SuperResult<Hash256>
+    where
+        F: Fn(usize) -> Option<u64> + Copy,
+    {
+        self.core
+            .write()
+            .find_head(start_block_root, weight_fn)
+            .map_err(|e| format!("find_head failed: {:?}", e))
+    }
+
+    fn update_finalized_root(
+        &self,
+        new_block: &BeaconBlock<E>,
+        new_root: Hash256,
+    ) -> SuperResult<()> {
+        self.core.write().update_root(new_block.slot, new_root);
+
+        Ok(())
+    }
+
+    fn contains_block(&self, block_root: Hash256) -> bool {
+        self.core.read().blocks.contains_key(&block_root)
+    }
+
+    fn introspect(&self) -> SuperResult<LmdGhostSnapshot> {
+        Ok(self.core.read().introspect())
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.core.read().as_ssz_container().as_ssz_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8], _store: Arc<T>) -> SuperResult<Self> {
+        let ssz_container = NaiveLmdGhostSsz::from_ssz_bytes(bytes)
+            .map_err(|e| format!("invalid naive lmd ghost bytes: {:?}", e))?;
+
+        Ok(ThreadSafeNaiveLmdGhost {
+            core: RwLock::new(NaiveLmdGhost::from_ssz_container(ssz_container)),
+        })
+    }
+}
+
+/// A compact representation of a `NaiveLmdGhost`, used for persisting it.
+#[derive(Encode, Decode)]
+struct NaiveLmdGhostSsz {
+    blocks: Vec<Block>,
+    latest_messages: Vec<Option<Message>>,
+    root_hash: Hash256,
+    root_slot: Slot,
+}
+
+struct NaiveLmdGhost<T, E> {
+    /// All blocks that have been processed since the root, keyed by block root.
+    blocks: HashMap<Hash256, Block>,
+    /// Maps validator indices to their latest messages.
+    latest_messages: Vec<Option<Message>>,
+    /// The weight of each block, as of the last call to `find_head`.
+    weights: HashMap<Hash256, u64>,
+    /// Stores the root of the tree, used for pruning.
+    root: (Hash256, Slot),
+    _phantom: PhantomData<(T, E)>,
+}
+
+impl<T, E: EthSpec> NaiveLmdGhost<T, E> {
+    pub fn new(genesis_block: &BeaconBlock<E>, genesis_root: Hash256) -> Self {
+        let mut blocks = HashMap::new();
+
+        blocks.insert(
+            genesis_root,
+            Block {
+                root: genesis_root,
+                slot: genesis_block.slot,
+                parent_root: genesis_block.parent_root,
+            },
+        );
+
+        Self {
+            blocks,
+            latest_messages: vec![],
+            weights: HashMap::new(),
+            root: (genesis_root, genesis_block.slot),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn as_ssz_container(&self) -> NaiveLmdGhostSsz {
+        NaiveLmdGhostSsz {
+            blocks: self.blocks.values().cloned().collect(),
+            latest_messages: self.latest_messages.clone(),
+            root_hash: self.root.0,
+            root_slot: self.root.1,
+        }
+    }
+
+    fn from_ssz_container(ssz_container: NaiveLmdGhostSsz) -> Self {
+        Self {
+            blocks: ssz_container
+                .blocks
+                .into_iter()
+                .map(|block| (block.root, block))
+                .collect(),
+            latest_messages: ssz_container.latest_messages,
+            weights: HashMap::new(),
+            root: (ssz_container.root_hash, ssz_container.root_slot),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Prunes all blocks that are not descendants of `new_root`.
+    pub fn update_root(&mut self, new_slot: Slot, new_root: Hash256) {
+        self.blocks.insert(
+            new_root,
+            Block {
+                root: new_root,
+                slot: new_slot,
+                parent_root: Hash256::zero(),
+            },
+        );
+
+        let descendants: Vec<Hash256> = self
+            .blocks
+            .keys()
+            .filter(|root| self.get_ancestor(**root, new_slot) == Some(new_root))
+            .cloned()
+            .collect();
+
+        self.blocks.retain(|root, _| descendants.contains(root));
+        self.weights.clear();
+        self.root = (new_root, new_slot);
+    }
+
+    /// Updates the latest message of the validator, if `slot` is later than that of its existing
+    /// latest message.
+    pub fn process_message(&mut self, validator_index: usize, block_hash: Hash256, slot: Slot) {
+        if slot >= self.root.1 {
+            if self.latest_messages.len() <= validator_index {
+                self.latest_messages
+                    .resize_with(validator_index + 1, Default::default);
+            }
+
+            let is_newer = match &self.latest_messages[validator_index] {
+                Some(message) => message.slot < slot,
+                None => true,
+            };
+
+            if is_newer {
+                self.latest_messages[validator_index] = Some(Message {
+                    root: block_hash,
+                    slot,
+                });
+            }
+        }
+    }
+
+    pub fn process_block(&mut self, block: &BeaconBlock<E>, block_hash: Hash256) {
+        if block.slot >= self.root.1 {
+            self.blocks.entry(block_hash).or_insert_with(|| Block {
+                root: block_hash,
+                slot: block.slot,
+                parent_root: block.parent_root,
+            });
+        }
+    }
+
+    /// Starting at `start_block_root`, repeatedly move to the child with the greatest weight
+    /// (breaking ties by the highest root) until a block without children is reached.
+    pub fn find_head<F>(&mut self, start_block_root: Hash256, weight_fn: F) -> Result<Hash256>
+    where
+        F: Fn(usize) -> Option<u64>,
+    {
+        self.weights = self
+            .blocks
+            .keys()
+            .map(|&root| Ok((root, self.get_latest_attesting_balance(root, &weight_fn)?)))
+            .collect::<Result<_>>()?;
+
+        let mut head = start_block_root;
+
+        loop {
+            let best_child = self
+                .get_children(head)
+                .into_iter()
+                .max_by_key(|child| (self.weights[child], *child));
+
+            match best_child {
+                Some(child) => head = child,
+                None => return Ok(head),
+            }
+        }
+    }
+
+    /// Returns the sum of the balances of all validators whose latest message is for `root` or
+    /// one of its descendants.
+    fn get_latest_attesting_balance<F>(&self, root: Hash256, weight_fn: &F) -> Result<u64>
+    where
+        F: Fn(usize) -> Option<u64>,
+    {
+        let slot = self.blocks[&root].slot;
+
+        self.latest_messages
+            .iter()
+            .enumerate()
+            .filter_map(|(validator_index, message)| message.map(|m| (validator_index, m)))
+            .filter(|(_, message)| self.get_ancestor(message.root, slot) == Some(root))
+            .map(|(validator_index, _)| {
+                weight_fn(validator_index)
+                    .ok_or_else(|| Error::ValidatorWeightUnknown(validator_index))
+            })
+            .sum()
+    }
+
+    /// Returns the root of the ancestor of `root` at `slot` (which may be `root` itself).
+    ///
+    /// If there is no known block at `slot`, the root of the ancestor at the highest prior slot is
+    /// returned. Returns `None` if the chain of known blocks ends before `slot`.
+    fn get_ancestor(&self, root: Hash256, slot: Slot) -> Option<Hash256> {
+        let block = self.blocks.get(&root)?;
+
+        if block.slot > slot {
+            self.get_ancestor(block.parent_root, slot)
+        } else {
+            Some(root)
+        }
+    }
+
+    fn get_children(&self, root: Hash256) -> Vec<Hash256> {
+        self.blocks
+            .values()
+            .filter(|block| block.parent_root == root && block.root != root)
+            .map(|block| block.root)
+            .collect()
+    }
+
+    fn introspect(&self) -> LmdGhostSnapshot {
+        let nodes = self
+            .blocks
+            .values()
+            .map(|block| SnapshotNode {
+                block_root: block.root,
+                slot: block.slot,
+                parent: Some(block.parent_root).filter(|root| self.blocks.contains_key(root)),
+                children: self.get_children(block.root),
+                weight: self.weights.get(&block.root).cloned().unwrap_or(0),
+                voter_count: self
+                    .latest_messages
+                    .iter()
+                    .filter(|message| message.map(|m| m.root) == Some(block.root))
+                    .count(),
+            })
+            .collect();
+
+        let latest_messages = self
+            .latest_messages
+            .iter()
+            .enumerate()
+            .filter_map(|(validator_index, message)| {
+                message.map(|message| LatestMessage {
+                    validator_index,
+                    block_root: message.root,
+                    slot: message.slot,
+                })
+            })
+            .collect();
+
+        LmdGhostSnapshot::new(self.root.0, self.root.1, nodes, latest_messages)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub struct Block {
+    root: Hash256,
+    slot: Slot,
+    parent_root: Hash256,
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub struct Message {
+    root: Hash256,
+    slot: Slot,
+}
@@ -0,0 +1,723 @@
+//! An implementation of LMD GHOST fork choice using a flat "proto-array".
+//!
+//! Blocks are stored in a `Vec`, ordered such that a parent is always at a lower index than its
+//! children. Changes in votes are applied as balance deltas and the weights and
+//! best-child/best-descendant pointers are updated in a single backwards pass over the `Vec`. The
+//! store is only read when a block arrives whose ancestors are unknown, never in `find_head`.
+use super::{LatestMessage, LmdGhost, LmdGhostSnapshot, Result as SuperResult, SnapshotNode};
+use parking_lot::RwLock;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use store::{Error as StoreError, Store};
+use types::{BeaconBlock, EthSpec, Hash256, Slot};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    MissingBlock(Hash256),
+    NotInTree(Hash256),
+    InvalidNodeIndex(usize),
+    DeltaOverflow(usize),
+    StoreError(StoreError),
+}
+
+impl From<StoreError> for Error {
+    fn from(e: StoreError) -> Error {
+        Error::StoreError(e)
+    }
+}
+
+pub struct ThreadSafeProtoArray<T, E> {
+    core: RwLock<ProtoArray<T, E>>,
+}
+
+impl<T, E> LmdGhost<T, E> for ThreadSafeProtoArray<T, E>
+where
+    T: Store,
+    E: EthSpec,
+{
+    fn new(store: Arc<T>, genesis_block: &BeaconBlock<E>, genesis_root: Hash256) -> Self {
+        ThreadSafeProtoArray {
+            core: RwLock::new(ProtoArray::new(store, genesis_block, genesis_root)),
+        }
+    }
+
+    fn process_attestation(
+        &self,
+        validator_index: usize,
+        block_hash: Hash256,
+        block_slot: Slot,
+    ) -> SuperResult<()> {
+        self.core
+            .write()
+            .process_message(validator_index, block_hash, block_slot);
+
+        Ok(())
+    }
+
+    /// Process a block that was seen on the network.
+    fn process_block(&self, block: &BeaconBlock<E>, block_hash: Hash256) -> SuperResult<()> {
+        self.core
+            .write()
+            .process_block(block, block_hash)
+            .map_err(|e| format!("process_block failed: {:?}", e))
+    }
+
+    fn find_head<F>(
+        &self,
+        start_block_slot: Slot,
+        start_block_root: Hash256,
+        weight_fn: F,
+    ) -> SuperResult<Hash256>
+    where
+        F: Fn(usize) -> Option<u64> + Copy,
+    {
+        self.core
+            .write()
+            .update_weights_and_find_head(start_block_slot, start_block_root, weight_fn)
+            .map_err(|e| format!("find_head failed: {:?}", e))
+    }
+
+    fn update_finalized_root(
+        &self,
+        new_block: &BeaconBlock<E>,
+        new_root: Hash256,
+    ) -> SuperResult<()> {
+        self.core
+            .write()
+            .update_root(new_block.slot, new_root)
+            .map_err(|e| format!("update_finalized_root failed: {:?}", e))
+    }
+
+    fn contains_block(&self, block_root: Hash256) -> bool {
+        self.core.read().indices.contains_key(&block_root)
+    }
+
+    fn introspect(&self) -> SuperResult<LmdGhostSnapshot> {
+        Ok(self.core.read().introspect())
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.core.read().as_ssz_container().as_ssz_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8], store: Arc<T>) -> SuperResult<Self> {
+        let ssz_container = ProtoArraySsz::from_ssz_bytes(bytes)
+            .map_err(|e| format!("invalid proto array bytes: {:?}", e))?;
+
+        let proto_array = ProtoArray::from_ssz_container(ssz_container, store)
+            .map_err(|e| format!("invalid proto array: {:?}", e))?;
+
+        Ok(ThreadSafeProtoArray {
+            core: RwLock::new(proto_array),
+        })
+    }
+}
+
+/// A compact representation of a `ProtoArray`, used for persisting it.
+#[derive(Encode, Decode)]
+struct ProtoArraySsz {
+    nodes: Vec<ProtoNode>,
+    votes: Vec<Option<VoteTracker>>,
+    root_hash: Hash256,
+    root_slot: Slot,
+}
+
+struct ProtoArray<T, E> {
+    store: Arc<T>,
+    /// All known blocks at or after the root, where a parent always has a lower index than its
+    /// children.
+    nodes: Vec<ProtoNode>,
+    /// Maps a block root to the index of its node in `nodes`.
+    indices: HashMap<Hash256, usize>,
+    /// Maps validator indices to their latest votes.
+    votes: Vec<Option<VoteTracker>>,
+    /// Stores the root of the tree, used for pruning.
+    root: (Hash256, Slot),
+    _phantom: PhantomData<E>,
+}
+
+impl<T, E> ProtoArray<T, E>
+where
+    T: Store,
+    E: EthSpec,
+{
+    pub fn new(store: Arc<T>, genesis_block: &BeaconBlock<E>, genesis_root: Hash256) -> Self {
+        let mut proto_array = Self {
+            store,
+            nodes: vec![],
+            indices: HashMap::new(),
+            votes: vec![],
+            root: (genesis_root, genesis_block.slot),
+            _phantom: PhantomData,
+        };
+
+        proto_array.insert_node(genesis_block.slot, genesis_root, None);
+
+        proto_array
+    }
+
+    fn introspect(&self) -> LmdGhostSnapshot {
+        let mut children = vec![vec![]; self.nodes.len()];
+        for node in &self.nodes {
+            if let Some(parent_index) = node.parent {
+                children[parent_index].push(node.root);
+            }
+        }
+
+        let nodes = self
+            .nodes
+            .iter()
+            .zip(children)
+            .map(|(node, children)| SnapshotNode {
+                block_root: node.root,
+                slot: node.slot,
+                parent: node.parent.map(|index| self.nodes[index].root),
+                children,
+                weight: node.weight,
+                voter_count: node.votes,
+            })
+            .collect();
+
+        let latest_messages = self
+            .votes
+            .iter()
+            .enumerate()
+            .filter_map(|(validator_index, vote)| {
+                vote.map(|vote| LatestMessage {
+                    validator_index,
+                    block_root: vote.next_root,
+                    slot: vote.next_slot,
+                })
+            })
+            .collect();
+
+        LmdGhostSnapshot::new(self.root.0, self.root.1, nodes, latest_messages)
+    }
+
+    fn as_ssz_container(&self) -> ProtoArraySsz {
+        ProtoArraySsz {
+            nodes: self.nodes.clone(),
+            votes: self.votes.clone(),
+            root_hash: self.root.0,
+            root_slot: self.root.1,
+        }
+    }
+
+    /// Returns an error if the nodes in `ssz_container` are not correctly ordered.
+    fn from_ssz_container(ssz_container: ProtoArraySsz, store: Arc<T>) -> Result<Self> {
+        let len = ssz_container.nodes.len();
+
+        for (index, node) in ssz_container.nodes.iter().enumerate() {
+            let is_descendant = |i: usize| i > index && i < len;
+
+            if !node.parent.map_or(true, |i| i < index)
+                || !node.best_child.map_or(true, is_descendant)
+                || !node.best_descendant.map_or(true, is_descendant)
+            {
+                return Err(Error::InvalidNodeIndex(index));
+            }
+        }
+
+        let indices = ssz_container
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.root, index))
+            .collect();
+
+        Ok(Self {
+            store,
+            nodes: ssz_container.nodes,
+            indices,
+            votes: ssz_container.votes,
+            root: (ssz_container.root_hash, ssz_container.root_slot),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Prunes all nodes that are not `new_root` or one of its descendants.
+    ///
+    /// If `new_root` is unknown, the tree is reset to contain only `new_root` and all votes will
+    /// be re-applied during the next call to `update_weights_and_find_head`.
+    pub fn update_root(&mut self, new_slot: Slot, new_root: Hash256) -> Result<()> {
+        match self.indices.get(&new_root).copied() {
+            Some(finalized_index) => {
+                // Maps the index of each retained node to its index after pruning. A parent always
+                // has a lower index than its children, so a node is a descendant of `new_root` if
+                // and only if its parent has already been retained.
+                let mut new_indices: Vec<Option<usize>> = vec![None; self.nodes.len()];
+                let mut retained = Vec::with_capacity(self.nodes.len() - finalized_index);
+
+                for (index, node) in self.nodes.drain(..).enumerate().skip(finalized_index) {
+                    let is_descendant = index == finalized_index
+                        || node
+                            .parent
+                            .map_or(false, |parent| new_indices[parent].is_some());
+
+                    if is_descendant {
+                        new_indices[index] = Some(retained.len());
+                        retained.push(node);
+                    } else {
+                        self.indices.remove(&node.root);
+                    }
+                }
+
+                // Note: a node's best child and best descendant are always its descendants, so
+                // they are never pruned.
+                for node in &mut retained {
+                    node.parent = node.parent.and_then(|index| new_indices[index]);
+                    node.best_child = node.best_child.and_then(|index| new_indices[index]);
+                    node.best_descendant =
+                        node.best_descendant.and_then(|index| new_indices[index]);
+                }
+
+                self.indices
+                    .retain(|_, index| new_indices[*index].is_some());
+                for index in self.indices.values_mut() {
+                    *index = new_indices[*index].expect("only retained nodes remain indexed");
+                }
+
+                self.nodes = retained;
+            }
+            None => {
+                self.nodes.clear();
+                self.indices.clear();
+                self.insert_node(new_slot, new_root, None);
+
+                for vote in self.votes.iter_mut().filter_map(Option::as_mut) {
+                    vote.current_root = None;
+                    vote.applied_balance = 0;
+                }
+            }
+        }
+
+        self.root = (new_root, new_slot);
+
+        Ok(())
+    }
+
+    pub fn process_message(&mut self, validator_index: usize, block_hash: Hash256, slot: Slot) {
+        if slot >= self.root_slot() {
+            if self.votes.len() <= validator_index {
+                self.votes
+                    .resize_with(validator_index + 1, Default::default);
+            }
+
+            let vote = &mut self.votes[validator_index];
+
+            match vote {
+                // Note: it is possible to do a cheap equivocation check here:
+                //
+                // slashable = (vote.next_slot == slot) && (vote.next_root != block_hash)
+                Some(vote) if vote.next_slot >= slot => {}
+                Some(vote) => {
+                    vote.next_root = block_hash;
+                    vote.next_slot = slot;
+                }
+                None => {
+                    *vote = Some(VoteTracker {
+                        current_root: None,
+                        next_root: block_hash,
+                        next_slot: slot,
+                        applied_balance: 0,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Adds `block` to the tree, unless it does not descend from the root (in which case it can
+    /// never become the head).
+    pub fn process_block(&mut self, block: &BeaconBlock<E>, block_hash: Hash256) -> Result<()> {
+        if block.slot >= self.root_slot() && !self.indices.contains_key(&block_hash) {
+            let parent_index = match self.ensure_in_tree(block.parent_root) {
+                Ok(index) => index,
+                Err(Error::NotInTree(_)) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            self.insert_node(block.slot, block_hash, Some(parent_index));
+        }
+
+        Ok(())
+    }
+
+    pub fn update_weights_and_find_head<F>(
+        &mut self,
+        start_block_slot: Slot,
+        start_block_root: Hash256,
+        weight_fn: F,
+    ) -> Result<Hash256>
+    where
+        F: Fn(usize) -> Option<u64> + Copy,
+    {
+        // It is possible that the given `start_block_root` is not in the tree.
+        //
+        // In this case, we add it (and any of its unknown ancestors) from the store.
+        let start_index = if start_block_slot >= self.root_slot() {
+            self.ensure_in_tree(start_block_root)?
+        } else {
+            *self
+                .indices
+                .get(&start_block_root)
+                .ok_or_else(|| Error::NotInTree(start_block_root))?
+        };
+
+        let deltas = self.compute_deltas(weight_fn)?;
+        self.apply_score_changes(deltas)?;
+
+        let start_node = self.get_node(start_index)?;
+
+        let head_index = start_node.best_descendant.unwrap_or(start_index);
+
+        Ok(self.get_node(head_index)?.root)
+    }
+
+    /// Returns a list of `deltas`, where there is one delta for each of the nodes in `self.nodes`.
+    ///
+    /// The deltas are formed by moving the balance of each validator from the node of its last
+    /// applied vote to the node of its latest vote, also accounting for any change in the
+    /// validators balance.
+    ///
+    /// A vote is not moved until the block it votes for is in the tree. Votes from validators
+    /// without a known weight are ignored.
+    fn compute_deltas<F>(&mut self, weight_fn: F) -> Result<Vec<i64>>
+    where
+        F: Fn(usize) -> Option<u64> + Copy,
+    {
+        let mut deltas = vec![0_i64; self.nodes.len()];
+
+        for (validator_index, vote) in self.votes.iter_mut().enumerate() {
+            let vote = match vote {
+                Some(vote) => vote,
+                None => continue,
+            };
+
+            let new_balance = match weight_fn(validator_index) {
+                Some(balance) => balance,
+                None => continue,
+            };
+
+            if vote.current_root == Some(vote.next_root) && vote.applied_balance == new_balance {
+                continue;
+            }
+
+            if let Some(&next_index) = self.indices.get(&vote.next_root) {
+                // If the node of the previous vote has been pruned, its balance has been removed
+                // from the tree with it.
+                let current_index = match vote.current_root {
+                    Some(current_root) => self.indices.get(&current_root).copied(),
+                    None => None,
+                };
+                if let Some(current_index) = current_index {
+                    let overflow = || Error::DeltaOverflow(current_index);
+
+                    let balance = i64::try_from(vote.applied_balance).map_err(|_| overflow())?;
+                    let delta = &mut deltas[current_index];
+                    *delta = delta.checked_sub(balance).ok_or_else(overflow)?;
+
+                    let node = &mut self.nodes[current_index];
+                    node.votes = node.votes.checked_sub(1).ok_or_else(overflow)?;
+                }
+
+                let overflow = || Error::DeltaOverflow(next_index);
+
+                let balance = i64::try_from(new_balance).map_err(|_| overflow())?;
+                let delta = &mut deltas[next_index];
+                *delta = delta.checked_add(balance).ok_or_else(overflow)?;
+
+                let node = &mut self.nodes[next_index];
+                node.votes = node.votes.checked_add(1).ok_or_else(overflow)?;
+
+                vote.current_root = Some(vote.next_root);
+                vote.applied_balance = new_balance;
+            }
+        }
+
+        Ok(deltas)
+    }
+
+    /// Applies the `deltas` to the weights of each node (and all its ancestors), then updates the
+    /// best child and best descendant of each node.
+    ///
+    /// Nodes are visited in a single backwards pass: by the time a node is visited all of its
+    /// descendants have been visited, so its weight and best descendant are final and it can be
+    /// compared against the other children of its parent.
+    fn apply_score_changes(&mut self, mut deltas: Vec<i64>) -> Result<()> {
+        for node_index in (0..self.nodes.len()).rev() {
+            let delta = deltas[node_index];
+
+            let node = self.get_node(node_index)?;
+
+            let weight = if delta < 0 {
+                delta
+                    .checked_neg()
+                    .and_then(|magnitude| node.weight.checked_sub(magnitude as u64))
+            } else {
+                node.weight.checked_add(delta as u64)
+            }
+            .ok_or_else(|| Error::DeltaOverflow(node_index))?;
+
+            let node = &mut self.nodes[node_index];
+            node.weight = weight;
+
+            if let Some(parent_index) = node.parent {
+                let parent_delta = &mut deltas[parent_index];
+                *parent_delta = parent_delta
+                    .checked_add(delta)
+                    .ok_or_else(|| Error::DeltaOverflow(parent_index))?;
+
+                self.maybe_update_best_child_and_descendant(parent_index, node_index)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets `child_index` as the best child of `parent_index` if it is heavier than the existing
+    /// best child, or if the existing best child has not yet been visited during this pass of
+    /// `apply_score_changes` (i.e., it has a lower index than `child_index`).
+    ///
+    /// Ties are broken by the highest block root.
+    fn maybe_update_best_child_and_descendant(
+        &mut self,
+        parent_index: usize,
+        child_index: usize,
+    ) -> Result<()> {
+        let child = self.get_node(child_index)?;
+
+        let should_update = match self.get_node(parent_index)?.best_child {
+            Some(best_child_index) if best_child_index > child_index => {
+                let best_child = self.get_node(best_child_index)?;
+
+                (child.weight, child.root) > (best_child.weight, best_child.root)
+            }
+            _ => true,
+        };
+
+        if should_update {
+            let best_descendant = child.best_descendant.unwrap_or(child_index);
+
+            let parent = &mut self.nodes[parent_index];
+            parent.best_child = Some(child_index);
+            parent.best_descendant = Some(best_descendant);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index of the node for `block_root`, adding it and all of its ancestors that
+    /// are not yet in the tree from the store.
+    fn ensure_in_tree(&mut self, block_root: Hash256) -> Result<usize> {
+        let mut missing = vec![];
+        let mut root = block_root;
+
+        let mut index = loop {
+            if let Some(&index) = self.indices.get(&root) {
+                break index;
+            }
+
+            let block = self.get_block(root)?;
+
+            if block.slot <= self.root_slot() {
+                return Err(Error::NotInTree(block_root));
+            }
+
+            missing.push((block.slot, root));
+            root = block.parent_root;
+        };
+
+        for (slot, root) in missing.into_iter().rev() {
+            index = self.insert_node(slot, root, Some(index));
+        }
+
+        Ok(index)
+    }
+
+    fn insert_node(&mut self, slot: Slot, root: Hash256, parent: Option<usize>) -> usize {
+        let index = self.nodes.len();
+
+        self.nodes.push(ProtoNode {
+            slot,
+            root,
+            parent,
+            votes: 0,
+            weight: 0,
+            best_child: None,
+            best_descendant: None,
+        });
+        self.indices.insert(root, index);
+
+        index
+    }
+
+    fn get_node(&self, index: usize) -> Result<&ProtoNode> {
+        self.nodes
+            .get(index)
+            .ok_or_else(|| Error::InvalidNodeIndex(index))
+    }
+
+    fn get_block(&self, block_root: Hash256) -> Result<BeaconBlock<E>> {
+        self.store
+            .get::<BeaconBlock<E>>(&block_root)?
+            .ok_or_else(|| Error::MissingBlock(block_root))
+    }
+
+    fn root_slot(&self) -> Slot {
+        self.root.1
+    }
+}
+
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct ProtoNode {
+    pub slot: Slot,
+    pub root: Hash256,
+    pub parent: Option<usize>,
+    /// The number of validators whose latest (applied) vote is for this node.
+    pub votes: usize,
+    pub weight: u64,
+    pub best_child: Option<usize>,
+    pub best_descendant: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub struct VoteTracker {
+    /// The root of the vote that has been applied to the weights in the tree, if any.
+    current_root: Option<Hash256>,
+    /// The root of the latest vote, which will be applied during the next `find_head`.
+    next_root: Hash256,
+    next_slot: Slot,
+    /// The balance that was applied to the weights in the tree for `current_root`.
+    applied_balance: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::MemoryStore;
+    use types::MinimalEthSpec;
+
+    type TestProtoArray = ProtoArray<MemoryStore, MinimalEthSpec>;
+
+    fn root(i: u64) -> Hash256 {
+        Hash256::from_low_u64_be(i)
+    }
+
+    fn block(slot: u64, parent_root: Hash256) -> BeaconBlock<MinimalEthSpec> {
+        let mut block = BeaconBlock::empty(&MinimalEthSpec::default_spec());
+        block.slot = Slot::new(slot);
+        block.parent_root = parent_root;
+        block
+    }
+
+    /// Builds the following tree, rooted at genesis:
+    ///
+    /// ```ignore
+    ///     0
+    ///    / \
+    ///   1   2
+    ///   |
+    ///   3
+    /// ```
+    fn tree() -> TestProtoArray {
+        let store = Arc::new(MemoryStore::open());
+        let genesis = block(0, Hash256::zero());
+        store.put(&root(0), &genesis).unwrap();
+
+        let mut proto_array = ProtoArray::new(store.clone(), &genesis, root(0));
+
+        for &(slot, parent, block_root) in &[(1, 0, 1), (2, 0, 2), (3, 1, 3)] {
+            let block = block(slot, root(parent));
+            store.put(&root(block_root), &block).unwrap();
+            proto_array.process_block(&block, root(block_root)).unwrap();
+        }
+
+        proto_array
+    }
+
+    #[test]
+    fn update_root_prunes_non_descendants() {
+        let mut proto_array = tree();
+
+        proto_array.update_root(Slot::new(1), root(1)).unwrap();
+
+        let roots: Vec<Hash256> = proto_array.nodes.iter().map(|node| node.root).collect();
+        assert_eq!(roots, vec![root(1), root(3)]);
+        assert_eq!(proto_array.nodes[0].parent, None);
+        assert_eq!(proto_array.nodes[1].parent, Some(0));
+        assert_eq!(proto_array.indices.len(), 2);
+        assert_eq!(proto_array.indices[&root(3)], 1);
+
+        // A child of a pruned block is ignored.
+        let child = block(4, root(2));
+        proto_array.store.put(&root(4), &child).unwrap();
+        proto_array.process_block(&child, root(4)).unwrap();
+        assert!(!proto_array.indices.contains_key(&root(4)));
+    }
+
+    #[test]
+    fn votes_without_a_weight_are_ignored() {
+        let mut proto_array = tree();
+
+        proto_array.process_message(0, root(2), Slot::new(2));
+        proto_array.process_message(1, root(3), Slot::new(3));
+
+        let weight_fn = |validator_index| if validator_index == 0 { None } else { Some(1) };
+
+        assert_eq!(
+            proto_array.update_weights_and_find_head(Slot::new(0), root(0), weight_fn),
+            Ok(root(3))
+        );
+    }
+
+    #[test]
+    fn balances_above_i64_max_are_rejected() {
+        let mut proto_array = tree();
+
+        proto_array.process_message(0, root(2), Slot::new(2));
+
+        assert_eq!(
+            proto_array
+                .update_weights_and_find_head(Slot::new(0), root(0), |_| Some(u64::max_value())),
+            Err(Error::DeltaOverflow(2))
+        );
+    }
+
+    #[test]
+    fn inconsistent_vote_counts_are_rejected() {
+        let mut proto_array = tree();
+
+        proto_array.process_message(0, root(2), Slot::new(2));
+        proto_array
+            .update_weights_and_find_head(Slot::new(0), root(0), |_| Some(1))
+            .unwrap();
+
+        // As if restored from bytes that do not count the vote for `root(2)`.
+        proto_array.nodes[2].votes = 0;
+        proto_array.process_message(0, root(3), Slot::new(3));
+
+        assert_eq!(
+            proto_array.update_weights_and_find_head(Slot::new(0), root(0), |_| Some(1)),
+            Err(Error::DeltaOverflow(2))
+        );
+    }
+
+    #[test]
+    fn ties_are_broken_by_highest_root() {
+        let mut proto_array = tree();
+
+        proto_array.process_message(0, root(2), Slot::new(2));
+        proto_array.process_message(1, root(3), Slot::new(3));
+
+        assert_eq!(
+            proto_array.update_weights_and_find_head(Slot::new(0), root(0), |_| Some(1)),
+            Ok(root(2))
+        );
+    }
+}
@@ -3,8 +3,10 @@
 //! This algorithm was concieved at IC3 Cornell, 2019.
 //!
 //! This implementation is incomplete and has known bugs. Do not use in production.
-use super::{LmdGhost, Result as SuperResult};
+use super::{LatestMessage, LmdGhost, LmdGhostSnapshot, Result as SuperResult, SnapshotNode};
 use parking_lot::RwLock;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -40,7 +42,7 @@ where
     T: Store,
     E: EthSpec,
 {
-    fn new(store: Arc<T>, genesis_block: &BeaconBlock, genesis_root: Hash256) -> Self {
+    fn new(store: Arc<T>, genesis_block: &BeaconBlock<E>, genesis_root: Hash256) -> Self {
         ThreadSafeReducedTree {
             core: RwLock::new(ReducedTree::new(store, genesis_block, genesis_root)),
         }
@@ -59,7 +61,7 @@ where
     }
 
     /// Process a block that was seen on the network.
-    fn process_block(&self, block: &BeaconBlock, block_hash: Hash256) -> SuperResult<()> {
+    fn process_block(&self, block: &BeaconBlock<E>, block_hash: Hash256) -> SuperResult<()> {
         self.core
             .write()
             .add_weightless_node(block.slot, block_hash)
@@ -81,12 +83,52 @@ where
             .map_err(|e| format!("find_head failed: {:?}", e))
     }
 
-    fn update_finalized_root(&self, new_block: &BeaconBlock, new_root: Hash256) -> SuperResult<()> {
+    fn update_finalized_root(
+        &self,
+        new_block: &BeaconBlock<E>,
+        new_root: Hash256,
+    ) -> SuperResult<()> {
         self.core
             .write()
             .update_root(new_block.slot, new_root)
             .map_err(|e| format!("update_finalized_root failed: {:?}", e))
     }
+
+    fn contains_block(&self, block_root: Hash256) -> bool {
+        self.core.read().nodes.contains_key(&block_root)
+    }
+
+    fn introspect(&self) -> SuperResult<LmdGhostSnapshot> {
+        self.core
+            .read()
+            .introspect()
+            .map_err(|e| format!("introspect failed: {:?}", e))
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.core.read().as_ssz_container().as_ssz_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8], store: Arc<T>) -> SuperResult<Self> {
+        let ssz_container = ReducedTreeSsz::from_ssz_bytes(bytes)
+            .map_err(|e| format!("invalid reduced tree bytes: {:?}", e))?;
+
+        let core = ReducedTree::from_ssz_container(ssz_container, store)
+            .map_err(|e| format!("invalid reduced tree: {:?}", e))?;
+
+        Ok(ThreadSafeReducedTree {
+            core: RwLock::new(core),
+        })
+    }
+}
+
+/// A compact representation of a `ReducedTree`, used for persisting it.
+#[derive(Encode, Decode)]
+struct ReducedTreeSsz {
+    nodes: Vec<Node>,
+    latest_votes: Vec<Option<Vote>>,
+    root_hash: Hash256,
+    root_slot: Slot,
 }
 
 struct ReducedTree<T, E> {
@@ -105,7 +147,7 @@ where
     T: Store,
     E: EthSpec,
 {
-    pub fn new(store: Arc<T>, genesis_block: &BeaconBlock, genesis_root: Hash256) -> Self {
+    pub fn new(store: Arc<T>, genesis_block: &BeaconBlock<E>, genesis_root: Hash256) -> Self {
         let mut nodes = HashMap::new();
 
         // Insert the genesis node.
@@ -126,6 +168,97 @@ where
         }
     }
 
+    fn introspect(&self) -> Result<LmdGhostSnapshot> {
+        let nodes = self
+            .nodes
+            .values()
+            .map(|node| {
+                Ok(SnapshotNode {
+                    block_root: node.block_hash,
+                    slot: self.get_block(node.block_hash)?.slot,
+                    parent: node.parent_hash,
+                    children: node.children.clone(),
+                    weight: node.weight,
+                    voter_count: node.voters.len(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let latest_messages = self
+            .latest_votes
+            .0
+            .iter()
+            .enumerate()
+            .filter_map(|(validator_index, vote)| {
+                vote.map(|vote| LatestMessage {
+                    validator_index,
+                    block_root: vote.hash,
+                    slot: vote.slot,
+                })
+            })
+            .collect();
+
+        Ok(LmdGhostSnapshot::new(
+            self.root.0,
+            self.root.1,
+            nodes,
+            latest_messages,
+        ))
+    }
+
+    fn as_ssz_container(&self) -> ReducedTreeSsz {
+        ReducedTreeSsz {
+            nodes: self.nodes.values().cloned().collect(),
+            latest_votes: self.latest_votes.0.clone(),
+            root_hash: self.root.0,
+            root_slot: self.root.1,
+        }
+    }
+
+    /// Rebuilds a tree from `ssz_container`, checking that every parent and child reference
+    /// points at a node in the tree which refers back to it.
+    fn from_ssz_container(ssz_container: ReducedTreeSsz, store: Arc<T>) -> Result<Self> {
+        let nodes: HashMap<Hash256, Node> = ssz_container
+            .nodes
+            .into_iter()
+            .map(|node| (node.block_hash, node))
+            .collect();
+
+        if !nodes.contains_key(&ssz_container.root_hash) {
+            return Err(Error::MissingNode(ssz_container.root_hash));
+        }
+
+        for node in nodes.values() {
+            if let Some(parent_hash) = node.parent_hash {
+                let parent = nodes
+                    .get(&parent_hash)
+                    .ok_or_else(|| Error::MissingNode(parent_hash))?;
+
+                if !parent.children.contains(&node.block_hash) {
+                    return Err(Error::MissingChild(node.block_hash));
+                }
+            }
+
+            for child_hash in &node.children {
+                let child = nodes
+                    .get(child_hash)
+                    .ok_or_else(|| Error::MissingChild(*child_hash))?;
+
+                if child.parent_hash != Some(node.block_hash) {
+                    return Err(Error::NotInTree(*child_hash));
+                }
+            }
+        }
+
+        Ok(Self {
+            store,
+            nodes,
+            latest_votes: ElasticList(ssz_container.latest_votes),
+            root: (ssz_container.root_hash, ssz_container.root_slot),
+            _phantom: PhantomData,
+        })
+    }
+
     pub fn update_root(&mut self, new_slot: Slot, new_root: Hash256) -> Result<()> {
         if !self.nodes.contains_key(&new_root) {
             let node = Node {
@@ -232,20 +365,40 @@ where
                 .map(|hash| self.get_node(*hash))
                 .collect::<Result<Vec<&Node>>>()?;
 
-            // TODO: check if `max_by` is `O(n^2)`.
-            let best_child = children
+            let max_weight = children
                 .iter()
-                .max_by(|a, b| {
-                    if a.weight != b.weight {
-                        a.weight.cmp(&b.weight)
-                    } else {
-                        a.block_hash.cmp(&b.block_hash)
-                    }
-                })
+                .map(|child| child.weight)
+                .max()
                 // There can only be no maximum if there are no children. This code path is guarded
                 // against that condition.
                 .expect("There must be a maximally weighted node.");
 
+            let mut heaviest = children
+                .into_iter()
+                .filter(|child| child.weight == max_weight)
+                .collect::<Vec<&Node>>();
+
+            // The spec breaks ties by the root of the direct child of `start_node`, which is not
+            // necessarily in the reduced tree. Only look it up when there is a tie, since it
+            // requires reading from the store.
+            let best_child = if heaviest.len() == 1 {
+                heaviest.remove(0)
+            } else {
+                heaviest
+                    .into_iter()
+                    .map(|child| {
+                        Ok((
+                            self.find_child_on_path(start_node.block_hash, child.block_hash)?,
+                            child,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .max_by_key(|(direct_child, _)| *direct_child)
+                    .map(|(_, child)| child)
+                    .expect("There must be a maximally weighted node.")
+            };
+
             self.find_head_from(best_child)
         }
     }
@@ -281,15 +434,21 @@ where
             // Unwrap is safe as prior `if` statements ensures the result is `Some`.
             let vote = self.latest_votes.get(validator_index).unwrap();
 
-            let should_delete = {
+            // The vote may be for a block that was pruned by `update_root`, in which case there is
+            // no node to remove the voter from.
+            let should_delete = if !self.nodes.contains_key(&vote.hash) {
+                false
+            } else {
                 self.get_mut_node(vote.hash)?.remove_voter(validator_index);
                 let node = self.get_node(vote.hash)?.clone();
 
                 if let Some(parent_hash) = node.parent_hash {
-                    if node.has_votes() || node.children.len() > 1 {
-                        // A node with votes or more than one child is never removed.
+                    if node.has_votes() || node.children.len() != 1 {
+                        // A node with votes or more than one child is never removed. Neither is a
+                        // node without children, since it is a leaf of the block tree and may
+                        // become the head even without any votes.
                         false
-                    } else if node.children.len() == 1 {
+                    } else {
                         // A node which has only one child may be removed.
                         //
                         // Load the child of the node and set it's parent to be the parent of this
@@ -298,24 +457,10 @@ where
                         child.parent_hash = node.parent_hash;
 
                         // Graft the parent of this node to it's child.
-                        if let Some(parent_hash) = node.parent_hash {
-                            let parent = self.get_mut_node(parent_hash)?;
-                            parent.replace_child(node.block_hash, node.children[0])?;
-                        }
-
-                        true
-                    } else if node.children.is_empty() {
-                        // A node which has no children may be deleted and potentially it's parent
-                        // too.
-                        self.maybe_delete_node(parent_hash)?;
+                        let parent = self.get_mut_node(parent_hash)?;
+                        parent.replace_child(node.block_hash, node.children[0])?;
 
                         true
-                    } else {
-                        // It is impossible for a node to have a number of children that is not 0, 1 or
-                        // greater than one.
-                        //
-                        // This code is strictly unnecessary, however we keep it for readability.
-                        unreachable!();
                     }
                 } else {
                     // A node without a parent is the genesis/finalized node and should never be removed.
@@ -476,6 +621,20 @@ where
         Ok(())
     }
 
+    /// Returns the root of the child of `ancestor` which is an ancestor of (or is) `descendant`.
+    fn find_child_on_path(&self, ancestor: Hash256, descendant: Hash256) -> Result<Hash256> {
+        let mut child = descendant;
+
+        for (root, _slot) in self.iter_ancestors(descendant)? {
+            if root == ancestor {
+                return Ok(child);
+            }
+            child = root;
+        }
+
+        Err(Error::NotInTree(descendant))
+    }
+
     /// For the given block `hash`, find it's highest (by slot) ancestor that exists in the reduced
     /// tree.
     fn find_prev_in_tree(&mut self, hash: Hash256) -> Option<Hash256> {
@@ -485,45 +644,28 @@ where
             .and_then(|(root, _slot)| Some(root))
     }
 
-    /// For the given `child` block hash, return the block's ancestor at the given `target` slot.
-    fn find_ancestor_at_slot(&self, child: Hash256, target: Slot) -> Result<Hash256> {
-        let (root, slot) = self
-            .iter_ancestors(child)?
-            .find(|(_block, slot)| *slot <= target)
-            .ok_or_else(|| Error::NotInTree(child))?;
-
-        // Explicitly check that the slot is the target in the case that the given child has a slot
-        // above target.
-        if slot == target {
-            Ok(root)
-        } else {
-            Err(Error::NotInTree(child))
-        }
-    }
-
     /// For the two given block roots (`a_root` and `b_root`), find the first block they share in
     /// the tree. Viz, find the block that these two distinct blocks forked from.
     fn find_least_common_ancestor(&self, a_root: Hash256, b_root: Hash256) -> Result<Hash256> {
-        // If the blocks behind `a_root` and `b_root` are not at the same slot, take the highest
-        // block (by slot) down to be equal with the lower slot.
-        //
-        // The result is two roots which identify two blocks at the same height.
-        let (a_root, b_root) = {
-            let a = self.get_block(a_root)?;
-            let b = self.get_block(b_root)?;
-
-            if a.slot > b.slot {
-                (self.find_ancestor_at_slot(a_root, b.slot)?, b_root)
-            } else if b.slot > a.slot {
-                (a_root, self.find_ancestor_at_slot(b_root, a.slot)?)
-            } else {
-                (a_root, b_root)
-            }
-        };
+        let a_slot = self.get_block(a_root)?.slot;
+        let b_slot = self.get_block(b_root)?.slot;
 
-        let ((a_root, _a_slot), (_b_root, _b_slot)) = self
-            .iter_ancestors(a_root)?
-            .zip(self.iter_ancestors(b_root)?)
+        // Walk back from the lowest of the two slots, so that both chains are compared
+        // slot-by-slot.
+        //
+        // The ancestor iterators yield a root for every slot (including skipped slots) so
+        // aligning them by slot is sufficient. The blocks themselves are included in case one is
+        // an ancestor of the other (e.g., the genesis block, which has no ancestors).
+        let slot = std::cmp::min(a_slot, b_slot);
+
+        let ((a_root, _a_slot), (_b_root, _b_slot)) = std::iter::once((a_root, a_slot))
+            .chain(self.iter_ancestors(a_root)?)
+            .skip_while(|(_, s)| *s > slot)
+            .zip(
+                std::iter::once((b_root, b_slot))
+                    .chain(self.iter_ancestors(b_root)?)
+                    .skip_while(|(_, s)| *s > slot),
+            )
             .find(|((a_root, _), (b_root, _))| a_root == b_root)
             .ok_or_else(|| Error::NoCommonAncestor((a_root, b_root)))?;
 
@@ -553,9 +695,9 @@ where
             .ok_or_else(|| Error::MissingNode(hash))
     }
 
-    fn get_block(&self, block_root: Hash256) -> Result<BeaconBlock> {
+    fn get_block(&self, block_root: Hash256) -> Result<BeaconBlock<E>> {
         self.store
-            .get::<BeaconBlock>(&block_root)?
+            .get::<BeaconBlock<E>>(&block_root)?
             .ok_or_else(|| Error::MissingBlock(block_root))
     }
 
@@ -570,7 +712,7 @@ where
     }
 }
 
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Encode, Decode)]
 pub struct Node {
     pub parent_hash: Option<Hash256>,
     pub children: Vec<Hash256>,
@@ -595,6 +737,17 @@ impl Node {
         Ok(())
     }
 
+    pub fn remove_child(&mut self, child: Hash256) -> Result<()> {
+        let i = self
+            .children
+            .iter()
+            .position(|&c| c == child)
+            .ok_or_else(|| Error::MissingChild(child))?;
+        self.children.remove(i);
+
+        Ok(())
+    }
+
     pub fn remove_voter(&mut self, voter: usize) -> Option<usize> {
         let i = self.voters.iter().position(|&v| v == voter)?;
         Some(self.voters.remove(i))
@@ -609,7 +762,7 @@ impl Node {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Encode, Decode)]
 pub struct Vote {
     hash: Hash256,
     slot: Slot,
@@ -648,3 +801,86 @@ impl From<Error> for String {
         format!("{:?}", e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::MemoryStore;
+    use types::MinimalEthSpec;
+
+    type TestReducedTree = ReducedTree<MemoryStore, MinimalEthSpec>;
+
+    fn root(i: u64) -> Hash256 {
+        Hash256::from_low_u64_be(i)
+    }
+
+    fn node(block_hash: Hash256, parent_hash: Option<Hash256>, children: Vec<Hash256>) -> Node {
+        Node {
+            block_hash,
+            parent_hash,
+            children,
+            ..Node::default()
+        }
+    }
+
+    fn decode(nodes: Vec<Node>) -> Result<TestReducedTree> {
+        let ssz_container = ReducedTreeSsz {
+            nodes,
+            latest_votes: vec![],
+            root_hash: root(0),
+            root_slot: Slot::new(0),
+        };
+        TestReducedTree::from_ssz_container(ssz_container, Arc::new(MemoryStore::open()))
+    }
+
+    #[test]
+    fn from_ssz_container_accepts_consistent_tree() {
+        let nodes = vec![
+            node(root(0), None, vec![root(1)]),
+            node(root(1), Some(root(0)), vec![]),
+        ];
+        assert!(decode(nodes).is_ok());
+    }
+
+    #[test]
+    fn from_ssz_container_rejects_missing_root() {
+        let nodes = vec![node(root(1), None, vec![])];
+        assert_eq!(decode(nodes).err(), Some(Error::MissingNode(root(0))));
+    }
+
+    #[test]
+    fn from_ssz_container_rejects_dangling_references() {
+        let unknown_parent = vec![
+            node(root(0), None, vec![]),
+            node(root(1), Some(root(2)), vec![]),
+        ];
+        assert_eq!(
+            decode(unknown_parent).err(),
+            Some(Error::MissingNode(root(2)))
+        );
+
+        let unknown_child = vec![node(root(0), None, vec![root(1)])];
+        assert_eq!(
+            decode(unknown_child).err(),
+            Some(Error::MissingChild(root(1)))
+        );
+    }
+
+    #[test]
+    fn from_ssz_container_rejects_inconsistent_links() {
+        let orphaned_child = vec![
+            node(root(0), None, vec![]),
+            node(root(1), Some(root(0)), vec![]),
+        ];
+        assert_eq!(
+            decode(orphaned_child).err(),
+            Some(Error::MissingChild(root(1)))
+        );
+
+        let wrong_parent = vec![
+            node(root(0), None, vec![root(1)]),
+            node(root(1), None, vec![]),
+        ];
+        assert_eq!(decode(wrong_parent).err(), Some(Error::NotInTree(root(1))));
+    }
+}
@@ -0,0 +1,381 @@
+//! Randomized differential testing of `ThreadSafeReducedTree` and `ThreadSafeProtoArray` against
+//! the naive, spec-literal `ThreadSafeNaiveLmdGhost`.
+//!
+//! Each case builds a random block tree (with fabricated states, so that the `ReducedTree` can
+//! read ancestors from the store) and a random sequence of votes, feeding identical inputs to all
+//! implementations and asserting that they always agree on the head.
+//!
+//! Cases cover every combination of:
+//!
+//! - Distinct, equal or small random balances (the latter two produce ties between subtrees).
+//! - Every leaf anchored by a vote, or leaves that may have no votes at all.
+//! - Finalizing a block on the canonical chain partway through, or never finalizing.
+//! - Starting from genesis, or from a block some slots after genesis.
+use lmd_ghost::{LmdGhost, ThreadSafeNaiveLmdGhost, ThreadSafeProtoArray, ThreadSafeReducedTree};
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Arc;
+use store::{MemoryStore, Store};
+use types::test_utils::{SeedableRng, XorShiftRng};
+use types::{BeaconBlock, BeaconState, Eth1Data, EthSpec, Hash256, MinimalEthSpec, Slot};
+
+type TestEthSpec = MinimalEthSpec;
+type ReducedTree = ThreadSafeReducedTree<MemoryStore, TestEthSpec>;
+type ProtoArray = ThreadSafeProtoArray<MemoryStore, TestEthSpec>;
+type NaiveLmdGhost = ThreadSafeNaiveLmdGhost<MemoryStore, TestEthSpec>;
+
+const VALIDATOR_COUNT: usize = 48;
+const SEEDS_PER_CONFIG: u8 = 4;
+const ROUNDS: usize = 6;
+const BLOCKS_PER_ROUND: usize = 5;
+/// Each validator that is not anchoring a leaf votes with a probability of `1 / VOTE_ODDS` per
+/// round.
+const VOTE_ODDS: u32 = 4;
+/// The slot of the first block in the tree, for cases that do not start at genesis.
+const NON_GENESIS_START_SLOT: u64 = 8;
+/// Blocks must stay within `SlotsPerHistoricalRoot` of genesis, so that the ancestors of every
+/// block can be read from its fabricated state.
+const MAX_SLOT: u64 = 60;
+/// Votes are made at slots after every block, so that no vote is ignored for being older than
+/// the finalized root.
+const FIRST_VOTE_SLOT: u64 = MAX_SLOT + 4;
+
+#[derive(Clone, Copy, Debug)]
+enum Balances {
+    /// Each validator has a distinct power-of-two balance, so the weights of disjoint subtrees
+    /// can never be equal.
+    PowersOfTwo,
+    /// Every validator has the same balance, so subtrees with the same number of votes tie.
+    Equal,
+    /// Balances are drawn from a small range, so ties are common but not universal.
+    Small,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Config {
+    balances: Balances,
+    /// If `true`, every leaf always has a vote, so every subtree has a non-zero weight.
+    anchored: bool,
+    /// If `true`, a block on the canonical chain is finalized halfway through the case.
+    finalize: bool,
+    /// The slot of the block that the tree starts from.
+    start_slot: u64,
+}
+
+impl Config {
+    fn all() -> Vec<Config> {
+        let mut configs = vec![];
+
+        for &balances in &[Balances::PowersOfTwo, Balances::Equal, Balances::Small] {
+            for &anchored in &[true, false] {
+                for &finalize in &[false, true] {
+                    for &start_slot in &[0, NON_GENESIS_START_SLOT] {
+                        configs.push(Config {
+                            balances,
+                            anchored,
+                            finalize,
+                            start_slot,
+                        });
+                    }
+                }
+            }
+        }
+
+        configs
+    }
+}
+
+struct Tester {
+    config: Config,
+    rng: XorShiftRng,
+    store: Arc<MemoryStore>,
+    reduced_tree: ReducedTree,
+    proto_array: ProtoArray,
+    naive: NaiveLmdGhost,
+    balances: Vec<u64>,
+    /// The root and slot of the block that `find_head` starts from.
+    root: (Hash256, Slot),
+    /// Maps each block root that descends from `root` to its block and the state at its slot.
+    blocks: HashMap<Hash256, (BeaconBlock<TestEthSpec>, BeaconState<TestEthSpec>)>,
+    /// Maps each leaf of the block tree to the validator that keeps a vote on it.
+    ///
+    /// Only used if `config.anchored` is `true`.
+    anchors: HashMap<Hash256, usize>,
+    /// The slot of the next vote. Incremented after each vote, so every vote replaces the
+    /// validator's previous one.
+    vote_slot: u64,
+}
+
+impl Tester {
+    fn new(config: Config, seed: u8) -> Self {
+        let spec = TestEthSpec::default_spec();
+        let store = Arc::new(MemoryStore::open());
+        let mut rng = XorShiftRng::from_seed([seed; 16]);
+
+        let balances = (0..VALIDATOR_COUNT)
+            .map(|i| match config.balances {
+                Balances::PowersOfTwo => 1 << i,
+                Balances::Equal => 1,
+                Balances::Small => rng.gen_range(1, 4),
+            })
+            .collect();
+
+        let state = BeaconState::genesis(
+            0,
+            Eth1Data {
+                deposit_root: Hash256::zero(),
+                deposit_count: 0,
+                block_hash: Hash256::zero(),
+            },
+            &spec,
+        );
+
+        let mut block = BeaconBlock::empty(&spec);
+        block.state_root = state.canonical_root();
+        let mut root = block.block_header().canonical_root();
+
+        store.put(&block.state_root, &state).unwrap();
+        store.put(&root, &block).unwrap();
+
+        let mut parent = (block, state);
+
+        // Build a chain of blocks up to the start slot, which is never seen by fork choice.
+        while parent.0.slot < config.start_slot {
+            let (block, state) = build_block(&parent, root, parent.0.slot + 1);
+            root = block.block_header().canonical_root();
+
+            store.put(&block.state_root, &state).unwrap();
+            store.put(&root, &block).unwrap();
+
+            parent = (block, state);
+        }
+
+        let (block, state) = parent;
+
+        let mut tester = Self {
+            config,
+            rng,
+            reduced_tree: ReducedTree::new(store.clone(), &block, root),
+            proto_array: ProtoArray::new(store.clone(), &block, root),
+            naive: NaiveLmdGhost::new(store.clone(), &block, root),
+            store,
+            balances,
+            root: (root, block.slot),
+            blocks: HashMap::new(),
+            anchors: HashMap::new(),
+            vote_slot: FIRST_VOTE_SLOT,
+        };
+
+        tester.blocks.insert(root, (block, state));
+
+        if config.anchored {
+            tester.anchors.insert(root, 0);
+            tester.vote(0, root);
+        }
+
+        tester
+    }
+
+    fn weight(&self) -> impl Fn(usize) -> Option<u64> + Copy + '_ {
+        move |validator_index| self.balances.get(validator_index).cloned()
+    }
+
+    fn vote(&mut self, validator_index: usize, block_root: Hash256) {
+        let slot = Slot::new(self.vote_slot);
+        self.vote_slot += 1;
+
+        self.reduced_tree
+            .process_attestation(validator_index, block_root, slot)
+            .unwrap();
+        self.proto_array
+            .process_attestation(validator_index, block_root, slot)
+            .unwrap();
+        self.naive
+            .process_attestation(validator_index, block_root, slot)
+            .unwrap();
+    }
+
+    /// Adds a block with a random parent and slot.
+    ///
+    /// If the case is anchored, the parent's anchor vote is moved onto the new block (if the
+    /// parent was a leaf) or a new anchor is assigned (if the block creates a fork).
+    fn add_block(&mut self) {
+        let parents: Vec<Hash256> = self
+            .sorted_roots()
+            .into_iter()
+            .filter(|root| self.blocks[root].0.slot < MAX_SLOT)
+            .collect();
+        let parent_root = parents[self.rng.gen_range(0, parents.len())];
+        let parent = &self.blocks[&parent_root];
+
+        let slot = parent.0.slot + self.rng.gen_range(1, 4);
+        let (block, state) = build_block(parent, parent_root, slot);
+        let root = block.block_header().canonical_root();
+
+        self.store.put(&block.state_root, &state).unwrap();
+        self.store.put(&root, &block).unwrap();
+
+        self.reduced_tree.process_block(&block, root).unwrap();
+        self.proto_array.process_block(&block, root).unwrap();
+        self.naive.process_block(&block, root).unwrap();
+
+        self.blocks.insert(root, (block, state));
+
+        if self.config.anchored {
+            let anchor = match self.anchors.remove(&parent_root) {
+                Some(anchor) => anchor,
+                None => self
+                    .floaters()
+                    .pop()
+                    .expect("there should be more validators than leaves"),
+            };
+            self.anchors.insert(root, anchor);
+            self.vote(anchor, root);
+        }
+    }
+
+    /// Returns the roots of all blocks in the tree, in a deterministic order so that a seed always
+    /// produces the same case.
+    fn sorted_roots(&self) -> Vec<Hash256> {
+        let mut roots: Vec<Hash256> = self.blocks.keys().cloned().collect();
+        roots.sort();
+        roots
+    }
+
+    /// Returns the validators that are not anchoring a leaf.
+    fn floaters(&self) -> Vec<usize> {
+        (0..VALIDATOR_COUNT)
+            .filter(|i| !self.anchors.values().any(|anchor| anchor == i))
+            .collect()
+    }
+
+    /// Some random subset of the validators that are not anchoring a leaf vote for random blocks.
+    ///
+    /// Votes are sparse, so that many blocks have no votes of their own and are skipped over by
+    /// the `ReducedTree`.
+    fn random_votes(&mut self) {
+        let roots = self.sorted_roots();
+
+        for validator_index in self.floaters() {
+            if self.rng.gen_range(0, VOTE_ODDS) == 0 {
+                let root = roots[self.rng.gen_range(0, roots.len())];
+                self.vote(validator_index, root);
+            }
+        }
+    }
+
+    /// Finalizes a random block between the current root and head (inclusive), pruning all
+    /// blocks that do not descend from it.
+    fn finalize(&mut self) {
+        let head = self.head();
+        let mut canonical = vec![head];
+        while canonical[canonical.len() - 1] != self.root.0 {
+            let block = &self.blocks[&canonical[canonical.len() - 1]].0;
+            canonical.push(block.parent_root);
+        }
+
+        let new_root = canonical[self.rng.gen_range(0, canonical.len())];
+        let new_block = self.blocks[&new_root].0.clone();
+
+        self.reduced_tree
+            .update_finalized_root(&new_block, new_root)
+            .unwrap();
+        self.proto_array
+            .update_finalized_root(&new_block, new_root)
+            .unwrap();
+        self.naive
+            .update_finalized_root(&new_block, new_root)
+            .unwrap();
+
+        let descendants: Vec<Hash256> = self
+            .blocks
+            .keys()
+            .filter(|root| self.is_descendant(**root, new_root))
+            .cloned()
+            .collect();
+        self.blocks.retain(|root, _| descendants.contains(root));
+        self.anchors.retain(|root, _| descendants.contains(root));
+        self.root = (new_root, new_block.slot);
+    }
+
+    fn is_descendant(&self, mut root: Hash256, ancestor: Hash256) -> bool {
+        while let Some((block, _)) = self.blocks.get(&root) {
+            if root == ancestor {
+                return true;
+            }
+            root = block.parent_root;
+        }
+        false
+    }
+
+    fn head(&self) -> Hash256 {
+        let (root, slot) = self.root;
+        self.naive.find_head(slot, root, self.weight()).unwrap()
+    }
+
+    fn assert_heads_match(&self, seed: u8, round: usize) {
+        let (root, slot) = self.root;
+        let expected = self.naive.find_head(slot, root, self.weight());
+
+        assert_eq!(
+            self.reduced_tree.find_head(slot, root, self.weight()),
+            expected,
+            "reduced tree head should match in {:?} with seed {} after round {}",
+            self.config,
+            seed,
+            round
+        );
+        assert_eq!(
+            self.proto_array.find_head(slot, root, self.weight()),
+            expected,
+            "proto-array head should match in {:?} with seed {} after round {}",
+            self.config,
+            seed,
+            round
+        );
+    }
+}
+
+/// Returns a block at `slot` that is a child of `parent`, along with its state.
+fn build_block(
+    parent: &(BeaconBlock<TestEthSpec>, BeaconState<TestEthSpec>),
+    parent_root: Hash256,
+    slot: Slot,
+) -> (BeaconBlock<TestEthSpec>, BeaconState<TestEthSpec>) {
+    let (parent_block, parent_state) = parent;
+
+    let mut state = parent_state.clone();
+    state.slot = slot;
+    for i in parent_block.slot.as_u64()..slot.as_u64() {
+        state.set_block_root(Slot::new(i), parent_root).unwrap();
+    }
+
+    let mut block = BeaconBlock::empty(&TestEthSpec::default_spec());
+    block.slot = slot;
+    block.parent_root = parent_root;
+    block.state_root = state.canonical_root();
+
+    (block, state)
+}
+
+#[test]
+fn implementations_match_naive() {
+    for config in Config::all() {
+        for seed in 0..SEEDS_PER_CONFIG {
+            let mut tester = Tester::new(config, seed);
+
+            for round in 0..ROUNDS {
+                if config.finalize && round == ROUNDS / 2 {
+                    tester.finalize();
+                }
+
+                for _ in 0..BLOCKS_PER_ROUND {
+                    tester.add_block();
+                }
+                tester.random_votes();
+
+                tester.assert_heads_match(seed, round);
+            }
+        }
+    }
+}
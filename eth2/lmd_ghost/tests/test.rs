@@ -0,0 +1,146 @@
+#![cfg(not(debug_assertions))]
+
+use beacon_chain::test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy};
+use lmd_ghost::{LmdGhost, ThreadSafeProtoArray, ThreadSafeReducedTree};
+use store::MemoryStore;
+use types::{EthSpec, Hash256, MinimalEthSpec, Slot};
+
+// Should ideally be divisible by 3.
+pub const VALIDATOR_COUNT: usize = 24;
+
+type ReducedTree = ThreadSafeReducedTree<MemoryStore, MinimalEthSpec>;
+type ProtoArray = ThreadSafeProtoArray<MemoryStore, MinimalEthSpec>;
+
+/// A single action to be applied to a `BeaconChainHarness`.
+#[derive(Clone, Debug)]
+enum Step {
+    Extend {
+        num_blocks: usize,
+        block_strategy: BlockStrategy,
+        attestation_strategy: AttestationStrategy,
+    },
+    AdvanceSlot,
+}
+
+/// Applies each of the `steps` to a new harness using `L` as the fork choice backend, returning
+/// the head block root after each step.
+fn run_scenario<L: LmdGhost<MemoryStore, MinimalEthSpec>>(steps: &[Step]) -> Vec<Hash256> {
+    let harness: BeaconChainHarness<L, MinimalEthSpec> = BeaconChainHarness::new(VALIDATOR_COUNT);
+
+    // Move past the zero slot.
+    harness.advance_slot();
+
+    steps
+        .iter()
+        .map(|step| {
+            match step {
+                Step::Extend {
+                    num_blocks,
+                    block_strategy,
+                    attestation_strategy,
+                } => {
+                    harness.extend_chain(
+                        *num_blocks,
+                        *block_strategy,
+                        attestation_strategy.clone(),
+                    );
+                }
+                Step::AdvanceSlot => harness.advance_slot(),
+            }
+
+            harness.chain.head().beacon_block_root
+        })
+        .collect()
+}
+
+/// Runs the `steps` against both `ReducedTree` and `ProtoArray`, asserting that they choose the
+/// same head after each step.
+fn assert_identical_heads(steps: Vec<Step>) {
+    let reduced_tree_heads = run_scenario::<ReducedTree>(&steps);
+    let proto_array_heads = run_scenario::<ProtoArray>(&steps);
+
+    assert_eq!(
+        reduced_tree_heads, proto_array_heads,
+        "heads should be identical after each step of {:?}",
+        steps
+    );
+}
+
+fn extend_canonical(num_blocks: usize, attestation_strategy: AttestationStrategy) -> Step {
+    Step::Extend {
+        num_blocks,
+        block_strategy: BlockStrategy::OnCanonicalHead,
+        attestation_strategy,
+    }
+}
+
+fn fork_scenario(honest_fork_blocks: usize, faulty_fork_blocks: usize) -> Vec<Step> {
+    let two_thirds = (VALIDATOR_COUNT / 3) * 2;
+    let delay = MinimalEthSpec::default_spec().min_attestation_inclusion_delay as usize;
+    let initial_blocks = delay + 1;
+
+    let honest_validators: Vec<usize> = (0..two_thirds).collect();
+    let faulty_validators: Vec<usize> = (two_thirds..VALIDATOR_COUNT).collect();
+
+    vec![
+        extend_canonical(initial_blocks, AttestationStrategy::AllValidators),
+        Step::AdvanceSlot,
+        extend_canonical(
+            honest_fork_blocks,
+            AttestationStrategy::SomeValidators(honest_validators),
+        ),
+        Step::Extend {
+            num_blocks: faulty_fork_blocks,
+            block_strategy: BlockStrategy::ForkCanonicalChainAt {
+                previous_slot: Slot::from(initial_blocks),
+                first_slot: Slot::from(initial_blocks + 2),
+            },
+            attestation_strategy: AttestationStrategy::SomeValidators(faulty_validators),
+        },
+    ]
+}
+
+#[test]
+fn fork() {
+    let delay = MinimalEthSpec::default_spec().min_attestation_inclusion_delay as usize;
+
+    assert_identical_heads(fork_scenario(delay + 1, delay + 2));
+}
+
+#[test]
+fn fork_across_epochs() {
+    let slots_per_epoch = MinimalEthSpec::slots_per_epoch() as usize;
+
+    assert_identical_heads(fork_scenario(slots_per_epoch * 2, slots_per_epoch * 3));
+}
+
+#[test]
+fn full_participation() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() as usize * 5;
+
+    assert_identical_heads(vec![extend_canonical(
+        num_blocks_produced,
+        AttestationStrategy::AllValidators,
+    )]);
+}
+
+#[test]
+fn two_thirds_participation() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() as usize * 5;
+    let two_thirds = (VALIDATOR_COUNT / 3) * 2;
+
+    assert_identical_heads(vec![extend_canonical(
+        num_blocks_produced,
+        AttestationStrategy::SomeValidators((0..two_thirds).collect()),
+    )]);
+}
+
+#[test]
+fn no_participation() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() as usize * 5;
+
+    assert_identical_heads(vec![extend_canonical(
+        num_blocks_produced,
+        AttestationStrategy::SomeValidators(vec![]),
+    )]);
+}
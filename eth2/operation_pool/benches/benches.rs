@@ -0,0 +1,187 @@
+use criterion::Criterion;
+use criterion::{black_box, criterion_group, criterion_main, Benchmark};
+use operation_pool::max_cover::{maximum_cover, MaxCover};
+use operation_pool::{inclusion_reward, AttMaxCover, OperationPool};
+use state_processing::common::{get_attesting_indices, get_base_reward};
+use std::collections::HashSet;
+use types::test_utils::*;
+use types::*;
+
+type E = MainnetEthSpec;
+type Bitfield = BitList<<E as EthSpec>::MaxValidatorsPerCommittee>;
+
+/// The number of crosslink committees at each slot.
+///
+/// There must be more attestations than `MAX_ATTESTATIONS`, otherwise both packing schemes include
+/// every attestation.
+pub const COMMITTEES_PER_SLOT: usize = 4;
+
+/// The maximum number of groups of signers that each committee is split into. Every attestation is
+/// also signed by the 0th committee member, so that they are not aggregated.
+pub const ATTESTATIONS_PER_COMMITTEE: usize = 3;
+
+/// Packs attestations by the number of fresh validators they include, ignoring balances and
+/// inclusion distance. This was the weighting used prior to reward-aware packing.
+struct CountMaxCover<'a>(AttMaxCover<'a, E>);
+
+impl<'a> MaxCover for CountMaxCover<'a> {
+    type Object = Attestation<E>;
+    type Set = Bitfield;
+
+    fn object(&self) -> Attestation<E> {
+        self.0.object()
+    }
+
+    fn covering_set(&self) -> &Bitfield {
+        self.0.covering_set()
+    }
+
+    fn update_covering_set(&mut self, best_att: &Attestation<E>, covered: &Bitfield) {
+        self.0.update_covering_set(best_att, covered)
+    }
+
+    fn score(&self) -> usize {
+        self.covering_set().num_set_bits()
+    }
+}
+
+/// Build a state in the middle of an epoch with varied effective balances, along with
+/// attestations for every committee that may be included at the state's slot.
+fn build_state_and_attestations() -> (BeaconState<E>, Vec<Attestation<E>>, ChainSpec) {
+    let spec = E::default_spec();
+    let rng = &mut XorShiftRng::from_seed([42; 16]);
+
+    let num_validators =
+        COMMITTEES_PER_SLOT * E::slots_per_epoch() as usize * spec.target_committee_size;
+    let mut state_builder =
+        TestingBeaconStateBuilder::from_default_keypairs_file_if_exists(num_validators, &spec);
+    let slot = spec.genesis_slot + 1000 * E::slots_per_epoch() + E::slots_per_epoch() / 2;
+    state_builder.teleport_to_slot(slot);
+    state_builder.build_caches(&spec).unwrap();
+    let (mut state, keypairs) = state_builder.build();
+
+    // Effective balances between half and all of `max_effective_balance`.
+    let max_increments = spec.max_effective_balance / spec.effective_balance_increment;
+    for validator in state.validators.iter_mut() {
+        let increments = max_increments / 2 + rng.next_u64() % (max_increments / 2 + 1);
+        validator.effective_balance = increments * spec.effective_balance_increment;
+    }
+
+    let first_slot = state.slot - E::slots_per_epoch();
+    let last_slot = state.slot - spec.min_attestation_inclusion_delay;
+
+    let mut attestations = vec![];
+    for slot in first_slot.as_u64()..=last_slot.as_u64() {
+        let slot = Slot::new(slot);
+        let committees = state
+            .get_crosslink_committees_at_slot(slot)
+            .unwrap()
+            .into_iter()
+            .map(CrosslinkCommittee::into_owned)
+            .collect::<Vec<_>>();
+
+        for cc in committees {
+            // Randomly split the committee (after the 0th member) into contiguous groups.
+            let mut bounds = (0..ATTESTATIONS_PER_COMMITTEE - 1)
+                .map(|_| 1 + rng.next_u64() as usize % (cc.committee.len() - 1))
+                .collect::<Vec<_>>();
+            bounds.push(1);
+            bounds.push(cc.committee.len());
+            bounds.sort_unstable();
+            bounds.dedup();
+
+            for window in bounds.windows(2) {
+                let mut signers = vec![cc.committee[0]];
+                signers.extend_from_slice(&cc.committee[window[0]..window[1]]);
+                let secret_keys = signers.iter().map(|&i| &keypairs[i].sk).collect::<Vec<_>>();
+
+                let mut builder =
+                    TestingAttestationBuilder::new(&state, &cc.committee, slot, cc.shard, &spec);
+                builder.sign(&signers, &secret_keys, &state.fork, &spec);
+                attestations.push(builder.build());
+            }
+        }
+    }
+
+    (state, attestations, spec)
+}
+
+fn total_active_balance(state: &BeaconState<E>, spec: &ChainSpec) -> u64 {
+    let active_indices = state
+        .get_cached_active_validator_indices(RelativeEpoch::Current)
+        .unwrap();
+    state.get_total_balance(active_indices, spec).unwrap()
+}
+
+/// Returns the total proposer reward for including `attestations` in a block, counting each
+/// validator only once.
+fn packed_reward(attestations: &[Attestation<E>], state: &BeaconState<E>, spec: &ChainSpec) -> u64 {
+    let total_active_balance = total_active_balance(state, spec);
+    let mut rewarded = HashSet::new();
+    let mut total = 0;
+
+    for att in attestations {
+        for index in get_attesting_indices(state, &att.data, &att.aggregation_bits).unwrap() {
+            if rewarded.insert(index) {
+                let base_reward =
+                    get_base_reward(state, index, total_active_balance, spec).unwrap();
+                total += inclusion_reward(base_reward, spec);
+            }
+        }
+    }
+
+    total
+}
+
+/// Compare the total reward of the attestations packed by count with those packed by reward, then
+/// benchmark packing.
+pub fn attestation_packing(c: &mut Criterion) {
+    let (state, attestations, spec) = build_state_and_attestations();
+    let max_attestations = spec.max_attestations as usize;
+    assert!(
+        attestations.len() > max_attestations,
+        "should build more than {} attestations, built {}",
+        max_attestations,
+        attestations.len()
+    );
+
+    let op_pool = OperationPool::new();
+    for att in &attestations {
+        op_pool
+            .insert_attestation(att.clone(), &state, &spec)
+            .unwrap();
+    }
+    assert_eq!(op_pool.num_attestations(), attestations.len());
+
+    let total_active_balance = total_active_balance(&state, &spec);
+    let by_count = maximum_cover(
+        attestations.iter().map(|att| {
+            CountMaxCover(AttMaxCover::new(att, &state, total_active_balance, &spec).unwrap())
+        }),
+        max_attestations,
+    );
+    let by_reward = op_pool.get_attestations(&state, &spec);
+
+    let count_reward = packed_reward(&by_count, &state, &spec);
+    let reward_reward = packed_reward(&by_reward, &state, &spec);
+    println!(
+        "Packed {} of {} attestations. Total reward packing by count: {} Gwei, packing by reward: \
+         {} Gwei ({:+.2}%)",
+        by_reward.len(),
+        attestations.len(),
+        count_reward,
+        reward_reward,
+        (reward_reward as f64 / count_reward as f64 - 1.0) * 100.0
+    );
+
+    c.bench(
+        &format!("{}_attestations", attestations.len()),
+        Benchmark::new("get_attestations", move |b| {
+            b.iter(|| black_box(op_pool.get_attestations(&state, &spec)))
+        })
+        .sample_size(10),
+    );
+}
+
+criterion_group!(benches, attestation_packing);
+criterion_main!(benches);
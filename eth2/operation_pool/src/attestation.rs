@@ -1,32 +1,62 @@
 use crate::max_cover::MaxCover;
-use boolean_bitfield::BooleanBitfield;
-use types::{Attestation, BeaconState, EthSpec};
+use state_processing::common::get_base_reward;
+use types::{
+    Attestation, BeaconState, BeaconStateError, BitList, ChainSpec, EthSpec, RelativeEpoch,
+};
 
-pub struct AttMaxCover<'a> {
+pub struct AttMaxCover<'a, T: EthSpec> {
     /// Underlying attestation.
-    att: &'a Attestation,
+    att: &'a Attestation<T>,
     /// Bitfield of validators that are covered by this attestation.
-    fresh_validators: BooleanBitfield,
+    fresh_validators: BitList<T::MaxValidatorsPerCommittee>,
+    /// The proposer's reward for including each member of the committee, indexed by committee
+    /// position.
+    rewards: Vec<u64>,
 }
 
-impl<'a> AttMaxCover<'a> {
-    pub fn new(att: &'a Attestation, fresh_validators: BooleanBitfield) -> Self {
-        Self {
+impl<'a, T: EthSpec> AttMaxCover<'a, T> {
+    /// Create a cover for `att`, weighting each validator for which `att` would be its earliest
+    /// attestation by the proposer's reward for including it.
+    pub fn new(
+        att: &'a Attestation<T>,
+        state: &BeaconState<T>,
+        total_active_balance: u64,
+        spec: &ChainSpec,
+    ) -> Result<Self, BeaconStateError> {
+        let fresh_validators = earliest_attestation_validators(att, state);
+
+        let relative_epoch =
+            RelativeEpoch::from_epoch(state.current_epoch(), att.data.target.epoch)?;
+        let committee =
+            state.get_crosslink_committee_for_shard(att.data.crosslink.shard, relative_epoch)?;
+
+        let rewards = committee
+            .committee
+            .iter()
+            .map(|&validator_index| {
+                let base_reward =
+                    get_base_reward(state, validator_index, total_active_balance, spec)?;
+                Ok(inclusion_reward(base_reward, spec))
+            })
+            .collect::<Result<_, BeaconStateError>>()?;
+
+        Ok(Self {
             att,
             fresh_validators,
-        }
+            rewards,
+        })
     }
 }
 
-impl<'a> MaxCover for AttMaxCover<'a> {
-    type Object = Attestation;
-    type Set = BooleanBitfield;
+impl<'a, T: EthSpec> MaxCover for AttMaxCover<'a, T> {
+    type Object = Attestation<T>;
+    type Set = BitList<T::MaxValidatorsPerCommittee>;
 
-    fn object(&self) -> Attestation {
+    fn object(&self) -> Attestation<T> {
         self.att.clone()
     }
 
-    fn covering_set(&self) -> &BooleanBitfield {
+    fn covering_set(&self) -> &BitList<T::MaxValidatorsPerCommittee> {
         &self.fresh_validators
     }
 
@@ -37,43 +67,58 @@ impl<'a> MaxCover for AttMaxCover<'a> {
     /// that a shard and epoch uniquely identify a committee.
     fn update_covering_set(
         &mut self,
-        best_att: &Attestation,
-        covered_validators: &BooleanBitfield,
+        best_att: &Attestation<T>,
+        covered_validators: &BitList<T::MaxValidatorsPerCommittee>,
     ) {
-        if self.att.data.shard == best_att.data.shard
-            && self.att.data.target_epoch == best_att.data.target_epoch
+        if self.att.data.crosslink.shard == best_att.data.crosslink.shard
+            && self.att.data.target.epoch == best_att.data.target.epoch
         {
             self.fresh_validators.difference_inplace(covered_validators);
         }
     }
 
+    /// The total proposer reward for including the validators that are still fresh.
     fn score(&self) -> usize {
-        self.fresh_validators.num_set_bits()
+        self.rewards
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.fresh_validators.get(*i).unwrap_or(false))
+            .map(|(_, reward)| *reward as usize)
+            .sum()
     }
 }
 
+/// The reward paid to the proposer for including the earliest attestation of a validator with
+/// `base_reward`.
+///
+/// Spec v0.8.1
+pub fn inclusion_reward(base_reward: u64, spec: &ChainSpec) -> u64 {
+    base_reward / spec.proposer_reward_quotient
+}
+
 /// Extract the validators for which `attestation` would be their earliest in the epoch.
 ///
 /// The reward paid to a proposer for including an attestation is proportional to the number
 /// of validators for which the included attestation is their first in the epoch. The attestation
 /// is judged against the state's `current_epoch_attestations` or `previous_epoch_attestations`
 /// depending on when it was created, and all those validators who have already attested are
-/// removed from the `aggregation_bitfield` before returning it.
+/// removed from the `aggregation_bits` before returning it.
 // TODO: This could be optimised with a map from validator index to whether that validator has
 // attested in each of the current and previous epochs. Currently quadratic in number of validators.
 pub fn earliest_attestation_validators<T: EthSpec>(
-    attestation: &Attestation,
+    attestation: &Attestation<T>,
     state: &BeaconState<T>,
-) -> BooleanBitfield {
+) -> BitList<T::MaxValidatorsPerCommittee> {
     // Bitfield of validators whose attestations are new/fresh.
-    let mut new_validators = attestation.aggregation_bitfield.clone();
+    let mut new_validators = attestation.aggregation_bits.clone();
 
-    let state_attestations = if attestation.data.target_epoch == state.current_epoch() {
+    let state_attestations = if attestation.data.target.epoch == state.current_epoch() {
         &state.current_epoch_attestations
-    } else if attestation.data.target_epoch == state.previous_epoch() {
+    } else if attestation.data.target.epoch == state.previous_epoch() {
         &state.previous_epoch_attestations
     } else {
-        return BooleanBitfield::from_elem(attestation.aggregation_bitfield.len(), false);
+        return BitList::with_capacity(attestation.aggregation_bits.len())
+            .expect("bitfield of the same length as a valid bitfield is valid");
     };
 
     state_attestations
@@ -81,10 +126,12 @@ pub fn earliest_attestation_validators<T: EthSpec>(
         // In a single epoch, an attester should only be attesting for one shard.
         // TODO: we avoid including slashable attestations in the state here,
         // but maybe we should do something else with them (like construct slashings).
-        .filter(|existing_attestation| existing_attestation.data.shard == attestation.data.shard)
+        .filter(|existing_attestation| {
+            existing_attestation.data.crosslink.shard == attestation.data.crosslink.shard
+        })
         .for_each(|existing_attestation| {
             // Remove the validators who have signed the existing attestation (they are not new)
-            new_validators.difference_inplace(&existing_attestation.aggregation_bitfield);
+            new_validators.difference_inplace(&existing_attestation.aggregation_bits);
         });
 
     new_validators
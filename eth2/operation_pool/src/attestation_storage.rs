@@ -0,0 +1,191 @@
+use crate::attestation_id::AttestationId;
+use crate::InsertError;
+use std::collections::{BTreeMap, HashMap};
+use std::iter::FromIterator;
+use types::{Attestation, Epoch, EthSpec};
+
+/// The priority of an attestation for eviction: the oldest attestations with the fewest signers
+/// are evicted first.
+type Priority = (Epoch, usize);
+
+/// The attestations held by the `OperationPool`, grouped by `AttestationId`.
+///
+/// The attestations in a group all have the same data, but could not be aggregated with one
+/// another. The total number of attestations and the priority of each are tracked as attestations
+/// are inserted, aggregated and removed, so that checking the size of the pool and evicting an
+/// attestation does not require a scan of the pool.
+#[derive(Debug, PartialEq)]
+pub struct AttestationStorage<T: EthSpec> {
+    buckets: HashMap<AttestationId, Vec<Attestation<T>>>,
+    /// The number of attestations with each priority in each bucket.
+    priorities: BTreeMap<(Priority, AttestationId), usize>,
+    /// The total number of attestations across all buckets.
+    len: usize,
+}
+
+impl<T: EthSpec> Default for AttestationStorage<T> {
+    fn default() -> Self {
+        Self {
+            buckets: HashMap::new(),
+            priorities: BTreeMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T: EthSpec> AttestationStorage<T> {
+    /// The total number of attestations, including attestations with the same data.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Iterate over each `AttestationId` and the attestations with that ID.
+    pub fn iter(&self) -> impl Iterator<Item = (&AttestationId, &Vec<Attestation<T>>)> {
+        self.buckets.iter()
+    }
+
+    /// Aggregate `attestation` with the existing attestations with the same `id`, or store it
+    /// separately if it can't be aggregated.
+    ///
+    /// If the attestation is stored separately and `limit` attestations are already stored, the
+    /// attestation with the lowest priority is evicted, unless its priority is at least that of
+    /// `attestation`.
+    pub fn insert<E>(
+        &mut self,
+        id: AttestationId,
+        attestation: Attestation<T>,
+        limit: usize,
+    ) -> Result<(), InsertError<E>> {
+        if let Some(existing_attestations) = self.buckets.get_mut(&id) {
+            let mut aggregated = false;
+            for existing_attestation in existing_attestations.iter_mut() {
+                if existing_attestation.signers_disjoint_from(&attestation) {
+                    decrement(&mut self.priorities, priority(existing_attestation), &id);
+                    existing_attestation.aggregate(&attestation);
+                    increment(&mut self.priorities, priority(existing_attestation), &id);
+                    aggregated = true;
+                } else if *existing_attestation == attestation {
+                    aggregated = true;
+                }
+            }
+
+            if aggregated {
+                return Ok(());
+            }
+        }
+
+        if self.len >= limit {
+            match self.priorities.keys().next().cloned() {
+                Some((lowest, lowest_id)) if lowest < priority(&attestation) => {
+                    self.remove(&lowest_id, lowest);
+                }
+                _ => return Err(InsertError::PoolFull { limit }),
+            }
+        }
+
+        self.push(id, attestation);
+
+        Ok(())
+    }
+
+    /// Evict the attestations with the lowest priority until at most `limit` remain.
+    pub fn truncate(&mut self, limit: usize) {
+        while self.len > limit {
+            match self.priorities.keys().next().cloned() {
+                Some((lowest, lowest_id)) => self.remove(&lowest_id, lowest),
+                None => break,
+            }
+        }
+    }
+
+    /// Retain only the buckets for which `f` returns `true`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&AttestationId, &mut Vec<Attestation<T>>) -> bool,
+    {
+        let priorities = &mut self.priorities;
+        let len = &mut self.len;
+
+        self.buckets.retain(|id, attestations| {
+            let keep = f(id, attestations);
+            if !keep {
+                for attestation in attestations.iter() {
+                    decrement(priorities, priority(attestation), id);
+                }
+                *len -= attestations.len();
+            }
+            keep
+        });
+    }
+
+    /// Store `attestation` separately from the other attestations with the same `id`.
+    fn push(&mut self, id: AttestationId, attestation: Attestation<T>) {
+        increment(&mut self.priorities, priority(&attestation), &id);
+        self.buckets
+            .entry(id)
+            .or_insert_with(Vec::new)
+            .push(attestation);
+        self.len += 1;
+    }
+
+    /// Remove an attestation with the given `priority` from the bucket for `id`.
+    fn remove(&mut self, id: &AttestationId, lowest: Priority) {
+        let bucket = match self.buckets.get_mut(id) {
+            Some(bucket) => bucket,
+            None => return,
+        };
+
+        if let Some(i) = bucket.iter().position(|att| priority(att) == lowest) {
+            bucket.remove(i);
+            decrement(&mut self.priorities, lowest, id);
+            self.len -= 1;
+        }
+        if bucket.is_empty() {
+            self.buckets.remove(id);
+        }
+    }
+}
+
+impl<T: EthSpec> FromIterator<(AttestationId, Vec<Attestation<T>>)> for AttestationStorage<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (AttestationId, Vec<Attestation<T>>)>,
+    {
+        let mut storage = Self::default();
+        for (id, attestations) in iter {
+            for attestation in attestations {
+                storage.push(id.clone(), attestation);
+            }
+        }
+        storage
+    }
+}
+
+fn priority<T: EthSpec>(attestation: &Attestation<T>) -> Priority {
+    (
+        attestation.data.target.epoch,
+        attestation.aggregation_bits.num_set_bits(),
+    )
+}
+
+fn increment(
+    priorities: &mut BTreeMap<(Priority, AttestationId), usize>,
+    priority: Priority,
+    id: &AttestationId,
+) {
+    *priorities.entry((priority, id.clone())).or_insert(0) += 1;
+}
+
+fn decrement(
+    priorities: &mut BTreeMap<(Priority, AttestationId), usize>,
+    priority: Priority,
+    id: &AttestationId,
+) {
+    let key = (priority, id.clone());
+    if let Some(count) = priorities.get_mut(&key) {
+        *count -= 1;
+        if *count == 0 {
+            priorities.remove(&key);
+        }
+    }
+}
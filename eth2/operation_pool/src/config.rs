@@ -0,0 +1,29 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Limits on the number of operations of each type held by the `OperationPool`.
+///
+/// Once a limit is reached, a new operation is only accepted if it takes precedence over some
+/// operation in the pool, which is evicted to make room for it. Attestations, slashings and exits
+/// are evicted oldest first, while transfers are evicted lowest fee first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationPoolConfig {
+    /// The maximum number of attestations, counting each attestation that could not be
+    /// aggregated separately.
+    pub max_attestations: usize,
+    pub max_attester_slashings: usize,
+    pub max_proposer_slashings: usize,
+    pub max_voluntary_exits: usize,
+    pub max_transfers: usize,
+}
+
+impl Default for OperationPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_attestations: 16_384,
+            max_attester_slashings: 1_024,
+            max_proposer_slashings: 1_024,
+            max_voluntary_exits: 4_096,
+            max_transfers: 4_096,
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use hashing::hash;
+use int_to_bytes::int_to_bytes32;
+use types::Hash256;
+
+/// An incremental Merkle tree of deposit data roots, mirroring the tree kept by the eth1 deposit
+/// contract.
+///
+/// Only complete subtrees are stored, so appending a leaf costs at most `log2(len)` hashes. The
+/// root (and proofs against the root) of the tree formed by any prefix of the leaves can be
+/// computed, which allows proofs to be generated for whichever `deposit_count` the beacon state
+/// has voted in via `eth1_data`.
+///
+/// The tree does not have a fixed depth; the depth of the deposit contract's tree is supplied
+/// when computing roots and proofs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepositTree {
+    /// `layers[h][j]` is the root of the complete subtree of height `h` covering the leaves
+    /// `j * 2^h..(j + 1) * 2^h`. `layers[0]` holds the leaves themselves.
+    layers: Vec<Vec<Hash256>>,
+}
+
+impl DepositTree {
+    /// Create an empty tree.
+    pub fn new() -> Self {
+        Self {
+            layers: vec![vec![]],
+        }
+    }
+
+    /// Create a tree containing `leaves`.
+    pub fn from_leaves(leaves: Vec<Hash256>) -> Self {
+        let mut tree = Self::new();
+        for leaf in leaves {
+            tree.push(leaf);
+        }
+        tree
+    }
+
+    /// The number of leaves in the tree.
+    pub fn len(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    /// All the leaves of the tree, in order.
+    pub fn leaves(&self) -> &[Hash256] {
+        &self.layers[0]
+    }
+
+    /// Returns the leaf at `index`, if any.
+    pub fn leaf(&self, index: usize) -> Option<Hash256> {
+        self.layers[0].get(index).cloned()
+    }
+
+    /// Append a leaf to the tree.
+    pub fn push(&mut self, leaf: Hash256) {
+        self.layers[0].push(leaf);
+
+        let mut h = 0;
+        while self.layers[h].len() % 2 == 0 {
+            let layer = &self.layers[h];
+            let parent = hash_pair(&layer[layer.len() - 2], &layer[layer.len() - 1]);
+
+            if self.layers.len() == h + 1 {
+                self.layers.push(vec![]);
+            }
+            self.layers[h + 1].push(parent);
+            h += 1;
+        }
+    }
+
+    /// Remove all leaves with an index greater than or equal to `len`.
+    pub fn truncate(&mut self, len: usize) {
+        for (h, layer) in self.layers.iter_mut().enumerate() {
+            layer.truncate(len >> h);
+        }
+        while self.layers.len() > 1 && self.layers[self.layers.len() - 1].is_empty() {
+            self.layers.pop();
+        }
+    }
+
+    /// Returns the root of the tree of the given `depth` formed by the first `count` leaves, or
+    /// `None` if `count` exceeds the number of leaves or the capacity of the tree.
+    pub fn root(&self, depth: usize, count: usize) -> Option<Hash256> {
+        if count > self.len() || !fits(depth, count) {
+            None
+        } else {
+            Some(self.node(&zero_hashes(depth), depth, 0, count))
+        }
+    }
+
+    /// Returns a proof (ordered from the leaf upwards) that the leaf at `index` is included in the
+    /// tree of the given `depth` formed by the first `count` leaves, or `None` if `index >= count`
+    /// or `count` exceeds the number of leaves or the capacity of the tree.
+    pub fn proof(&self, depth: usize, index: usize, count: usize) -> Option<Vec<Hash256>> {
+        if index >= count || count > self.len() || !fits(depth, count) {
+            return None;
+        }
+
+        let zero_hashes = zero_hashes(depth);
+        Some(
+            (0..depth)
+                .map(|h| self.node(&zero_hashes, h, (index >> h) ^ 1, count))
+                .collect(),
+        )
+    }
+
+    /// Returns the root of the first `count` leaves with `count` mixed in, as returned by the
+    /// deposit contract and committed to in `Eth1Data::deposit_root`.
+    pub fn deposit_root(&self, depth: usize, count: usize) -> Option<Hash256> {
+        self.root(depth, count)
+            .map(|root| hash_pair(&root, &length_node(count)))
+    }
+
+    /// Returns a proof that the leaf at `index` is included in the `deposit_root` of the first
+    /// `count` leaves. The proof is one node longer than `depth`, to account for the mixed-in
+    /// length.
+    pub fn deposit_proof(&self, depth: usize, index: usize, count: usize) -> Option<Vec<Hash256>> {
+        let mut proof = self.proof(depth, index, count)?;
+        proof.push(length_node(count));
+        Some(proof)
+    }
+
+    /// Returns node `j` at height `h` of the tree formed by the first `count` leaves.
+    fn node(&self, zero_hashes: &[Hash256], h: usize, j: usize, count: usize) -> Hash256 {
+        let first_leaf = j << h;
+        let end_leaf = (j + 1) << h;
+
+        if end_leaf <= count {
+            // The subtree is complete, so it is stored.
+            self.layers[h][j]
+        } else if first_leaf >= count {
+            // The subtree is entirely beyond the first `count` leaves.
+            zero_hashes[h]
+        } else {
+            // The subtree is partially filled, so compute it from its children. At most one of
+            // the children is itself partially filled.
+            hash_pair(
+                &self.node(zero_hashes, h - 1, 2 * j, count),
+                &self.node(zero_hashes, h - 1, 2 * j + 1, count),
+            )
+        }
+    }
+}
+
+impl Default for DepositTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns `true` if a tree of the given `depth` can hold `count` leaves.
+fn fits(depth: usize, count: usize) -> bool {
+    depth >= std::mem::size_of::<usize>() * 8 || count <= 1 << depth
+}
+
+/// Returns the roots of all-zero subtrees, where the `h`th root is of a subtree of height `h`.
+fn zero_hashes(depth: usize) -> Vec<Hash256> {
+    let mut zero_hashes = vec![Hash256::zero()];
+    for h in 0..depth {
+        let zero = hash_pair(&zero_hashes[h], &zero_hashes[h]);
+        zero_hashes.push(zero);
+    }
+    zero_hashes
+}
+
+/// The little-endian encoding of `count`, as it is mixed into the deposit root.
+fn length_node(count: usize) -> Hash256 {
+    Hash256::from_slice(&int_to_bytes32(count as u64))
+}
+
+fn hash_pair(left: &Hash256, right: &Hash256) -> Hash256 {
+    let mut preimage = left.as_bytes().to_vec();
+    preimage.extend_from_slice(right.as_bytes());
+    Hash256::from_slice(&hash(&preimage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merkle_proof::verify_merkle_proof;
+
+    const DEPTH: usize = 32;
+
+    fn leaves(n: usize) -> Vec<Hash256> {
+        (0..n)
+            .map(|i| Hash256::from_slice(&hash(&i.to_le_bytes())))
+            .collect()
+    }
+
+    /// Computes the root of the first `count` leaves naively, by hashing every layer.
+    fn naive_root(leaves: &[Hash256], depth: usize) -> Hash256 {
+        let mut layer = leaves.to_vec();
+        let mut zero = Hash256::zero();
+
+        for _ in 0..depth {
+            if layer.len() % 2 == 1 {
+                layer.push(zero);
+            }
+            layer = layer
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], &pair[1]))
+                .collect();
+            zero = hash_pair(&zero, &zero);
+        }
+
+        layer.first().cloned().unwrap_or(zero)
+    }
+
+    #[test]
+    fn roots_match_naive_roots() {
+        let leaves = leaves(17);
+        let tree = DepositTree::from_leaves(leaves.clone());
+
+        for count in 0..=leaves.len() {
+            assert_eq!(
+                tree.root(DEPTH, count),
+                Some(naive_root(&leaves[0..count], DEPTH)),
+                "root for count {}",
+                count
+            );
+        }
+        assert_eq!(tree.root(DEPTH, leaves.len() + 1), None);
+    }
+
+    #[test]
+    fn proofs_verify_against_prefix_roots() {
+        let leaves = leaves(13);
+        let tree = DepositTree::from_leaves(leaves.clone());
+
+        for count in 1..=leaves.len() {
+            let root = tree.root(DEPTH, count).unwrap();
+
+            for index in 0..count {
+                let proof = tree.proof(DEPTH, index, count).unwrap();
+                assert!(
+                    verify_merkle_proof(leaves[index], &proof, DEPTH, index, root),
+                    "proof for index {} of count {}",
+                    index,
+                    count
+                );
+            }
+
+            assert_eq!(tree.proof(DEPTH, count, count), None);
+        }
+    }
+
+    #[test]
+    fn deposit_proofs_verify_against_deposit_roots() {
+        let leaves = leaves(13);
+        let tree = DepositTree::from_leaves(leaves.clone());
+
+        for count in 1..=leaves.len() {
+            let root = tree.deposit_root(DEPTH, count).unwrap();
+
+            for index in 0..count {
+                let proof = tree.deposit_proof(DEPTH, index, count).unwrap();
+                assert!(
+                    verify_merkle_proof(leaves[index], &proof, DEPTH + 1, index, root),
+                    "proof for index {} of count {}",
+                    index,
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truncate_and_push() {
+        let mut original = leaves(10);
+        let mut tree = DepositTree::from_leaves(original.clone());
+
+        let replacement = Hash256::from_slice(&[42; 32]);
+        tree.truncate(5);
+        tree.push(replacement);
+
+        original.truncate(5);
+        original.push(replacement);
+
+        assert_eq!(tree, DepositTree::from_leaves(original));
+    }
+
+    #[test]
+    fn roots_at_other_depths() {
+        let leaves = leaves(5);
+        let tree = DepositTree::from_leaves(leaves.clone());
+
+        for depth in 3..6 {
+            assert_eq!(tree.root(depth, 5), Some(naive_root(&leaves, depth)));
+        }
+        // A tree of depth 2 only holds 4 leaves.
+        assert_eq!(tree.root(2, 5), None);
+        assert_eq!(tree.root(2, 4), Some(naive_root(&leaves[0..4], 2)));
+    }
+}
@@ -1,53 +1,89 @@
 mod attestation;
 mod attestation_id;
-mod max_cover;
+mod attestation_storage;
+mod config;
+mod deposit_tree;
+pub mod max_cover;
 mod persistence;
+mod transfer;
 
+pub use attestation::{inclusion_reward, AttMaxCover};
+pub use config::OperationPoolConfig;
 pub use persistence::PersistedOperationPool;
 
-use attestation::{earliest_attestation_validators, AttMaxCover};
 use attestation_id::AttestationId;
-use itertools::Itertools;
+use attestation_storage::AttestationStorage;
+use deposit_tree::DepositTree;
 use max_cover::maximum_cover;
 use parking_lot::RwLock;
 use state_processing::per_block_processing::errors::{
-    AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
-    ExitValidationError, ProposerSlashingValidationError, TransferValidationError,
+    AttestationValidationError, AttesterSlashingValidationError, DepositInvalid,
+    DepositValidationError, ExitValidationError, ProposerSlashingValidationError,
+    TransferValidationError,
 };
 use state_processing::per_block_processing::{
     get_slashable_indices_modular, validate_attestation,
     validate_attestation_time_independent_only, verify_attester_slashing, verify_exit,
-    verify_exit_time_independent_only, verify_proposer_slashing, verify_transfer,
+    verify_exit_time_independent_only, verify_proposer_slashing,
     verify_transfer_time_independent_only,
 };
-use std::collections::{btree_map::Entry, hash_map, BTreeMap, HashMap, HashSet};
+use std::collections::{btree_map::Entry, BTreeMap, HashMap, HashSet};
+use std::hash::Hash;
 use std::marker::PhantomData;
+use transfer::{partition_fundable, select_transfers};
+use tree_hash::TreeHash;
 use types::{
-    Attestation, AttesterSlashing, BeaconState, ChainSpec, Deposit, EthSpec, ProposerSlashing,
-    Transfer, Validator, VoluntaryExit,
+    Attestation, AttesterSlashing, BeaconState, ChainSpec, Deposit, DepositData, Epoch, EthSpec,
+    FixedLenVec, Hash256, ProposerSlashing, RelativeEpoch, Slot, Transfer, Validator,
+    VoluntaryExit,
 };
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct OperationPool<T: EthSpec + Default> {
     /// Map from attestation ID (see below) to vectors of attestations.
-    attestations: RwLock<HashMap<AttestationId, Vec<Attestation>>>,
+    attestations: RwLock<AttestationStorage<T>>,
     /// Map from deposit index to deposit data.
-    // NOTE: We assume that there is only one deposit per index
-    // because the Eth1 data is updated (at most) once per epoch,
-    // and the spec doesn't seem to accomodate for re-orgs on a time-frame
-    // longer than an epoch
-    deposits: RwLock<BTreeMap<u64, Deposit>>,
+    ///
+    /// Proofs are not stored, since they are invalidated by each new deposit. Instead, they are
+    /// generated from `deposit_tree` when producing a block.
+    deposits: RwLock<BTreeMap<u64, DepositData>>,
+    /// The roots of all deposits known to the pool (including those since pruned from
+    /// `deposits`), from index zero up to the first unknown index.
+    deposit_tree: RwLock<DepositTree>,
     /// Map from two attestation IDs to a slashing for those IDs.
-    attester_slashings: RwLock<HashMap<(AttestationId, AttestationId), AttesterSlashing>>,
+    attester_slashings: RwLock<HashMap<(AttestationId, AttestationId), AttesterSlashing<T>>>,
     /// Map from proposer index to slashing.
     proposer_slashings: RwLock<HashMap<u64, ProposerSlashing>>,
     /// Map from exiting validator to their exit data.
     voluntary_exits: RwLock<HashMap<u64, VoluntaryExit>>,
     /// Set of transfers.
     transfers: RwLock<HashSet<Transfer>>,
+    /// Limits on the number of operations of each type.
+    config: OperationPoolConfig,
     _phantom: PhantomData<T>,
 }
 
+/// The reason an operation was not inserted into the pool.
+#[derive(Debug, PartialEq, Clone)]
+pub enum InsertError<E> {
+    /// The operation is invalid.
+    Invalid(E),
+    /// The pool holds its maximum number of operations of this type, none of which would be
+    /// evicted in favour of the new operation.
+    PoolFull { limit: usize },
+}
+
+/// The reason deposits for a block could not be produced.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DepositError {
+    /// The pool has never seen the deposit at `index`. Proofs can only be generated if the pool
+    /// has seen every deposit from index zero up to the `deposit_count` of the state's eth1 data.
+    MissingDeposit { index: u64 },
+    /// The pool's deposits do not match the deposit root of the state's eth1 data (e.g., the pool
+    /// has seen an eth1 chain that was not voted in).
+    DepositRootMismatch,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum DepositInsertStatus {
     /// The deposit was not already in the pool.
@@ -55,7 +91,23 @@ pub enum DepositInsertStatus {
     /// The deposit already existed in the pool.
     Duplicate,
     /// The deposit conflicted with an existing deposit, which was replaced.
-    Replaced(Box<Deposit>),
+    Replaced(Box<DepositData>),
+}
+
+impl<T: EthSpec + Default> Default for OperationPool<T> {
+    fn default() -> Self {
+        Self {
+            attestations: Default::default(),
+            deposits: Default::default(),
+            deposit_tree: RwLock::new(DepositTree::new()),
+            attester_slashings: Default::default(),
+            proposer_slashings: Default::default(),
+            voluntary_exits: Default::default(),
+            transfers: Default::default(),
+            config: OperationPoolConfig::default(),
+            _phantom: PhantomData,
+        }
+    }
 }
 
 impl<T: EthSpec> OperationPool<T> {
@@ -64,53 +116,95 @@ impl<T: EthSpec> OperationPool<T> {
         Self::default()
     }
 
+    /// Create a new operation pool with the limits in `config`.
+    pub fn with_config(config: OperationPoolConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Replace the limits of the pool with those in `config`, evicting the lowest priority
+    /// operations of each type until the pool is within the new limits.
+    pub fn set_config(&mut self, config: OperationPoolConfig) {
+        self.attestations
+            .get_mut()
+            .truncate(config.max_attestations);
+        truncate_to_limit(
+            self.attester_slashings.get_mut(),
+            config.max_attester_slashings,
+            attester_slashing_priority,
+        );
+        truncate_to_limit(
+            self.proposer_slashings.get_mut(),
+            config.max_proposer_slashings,
+            proposer_slashing_priority,
+        );
+        truncate_to_limit(
+            self.voluntary_exits.get_mut(),
+            config.max_voluntary_exits,
+            |exit| exit.epoch,
+        );
+
+        let transfers = self.transfers.get_mut();
+        if transfers.len() > config.max_transfers {
+            let mut by_fee = transfers.drain().collect::<Vec<_>>();
+            by_fee.sort_by_key(|transfer| std::cmp::Reverse(transfer.fee));
+            by_fee.truncate(config.max_transfers);
+            transfers.extend(by_fee);
+        }
+
+        self.config = config;
+    }
+
+    pub fn config(&self) -> &OperationPoolConfig {
+        &self.config
+    }
+
     /// Insert an attestation into the pool, aggregating it with existing attestations if possible.
+    ///
+    /// If the attestation cannot be aggregated and the pool is full, the oldest attestation (with
+    /// the fewest signers) is evicted, unless it is newer than the attestation being inserted.
     pub fn insert_attestation(
         &self,
-        attestation: Attestation,
+        attestation: Attestation<T>,
         state: &BeaconState<T>,
         spec: &ChainSpec,
-    ) -> Result<(), AttestationValidationError> {
+    ) -> Result<(), InsertError<AttestationValidationError>> {
         // Check that attestation signatures are valid.
-        validate_attestation_time_independent_only(state, &attestation, spec)?;
+        validate_attestation_time_independent_only(state, &attestation, spec)
+            .map_err(InsertError::Invalid)?;
 
         let id = AttestationId::from_data(&attestation.data, state, spec);
 
-        // Take a write lock on the attestations map.
-        let mut attestations = self.attestations.write();
-
-        let existing_attestations = match attestations.entry(id) {
-            hash_map::Entry::Vacant(entry) => {
-                entry.insert(vec![attestation]);
-                return Ok(());
-            }
-            hash_map::Entry::Occupied(entry) => entry.into_mut(),
-        };
-
-        let mut aggregated = false;
-        for existing_attestation in existing_attestations.iter_mut() {
-            if existing_attestation.signers_disjoint_from(&attestation) {
-                existing_attestation.aggregate(&attestation);
-                aggregated = true;
-            } else if *existing_attestation == attestation {
-                aggregated = true;
-            }
-        }
-
-        if !aggregated {
-            existing_attestations.push(attestation);
-        }
-
-        Ok(())
+        self.attestations
+            .write()
+            .insert(id, attestation, self.config.max_attestations)
     }
 
     /// Total number of attestations in the pool, including attestations for the same data.
     pub fn num_attestations(&self) -> usize {
-        self.attestations.read().values().map(Vec::len).sum()
+        self.attestations.read().len()
     }
 
     /// Get a list of attestations for inclusion in a block.
-    pub fn get_attestations(&self, state: &BeaconState<T>, spec: &ChainSpec) -> Vec<Attestation> {
+    ///
+    /// Attestations are chosen to approximately maximise the total reward paid to the proposer
+    /// for the validators they include, which is proportional to each validator's effective
+    /// balance.
+    pub fn get_attestations(
+        &self,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> Vec<Attestation<T>> {
+        let total_active_balance = match state
+            .get_cached_active_validator_indices(RelativeEpoch::Current)
+            .and_then(|indices| state.get_total_balance(indices, spec))
+        {
+            Ok(balance) => balance,
+            Err(_) => return vec![],
+        };
+
         // Attestations for the current fork, which may be from the current or previous epoch.
         let prev_epoch = state.previous_epoch();
         let current_epoch = state.current_epoch();
@@ -126,7 +220,7 @@ impl<T: EthSpec> OperationPool<T> {
             .flat_map(|(_, attestations)| attestations)
             // That are valid...
             .filter(|attestation| validate_attestation(state, attestation, spec).is_ok())
-            .map(|att| AttMaxCover::new(att, earliest_attestation_validators(att, state)));
+            .filter_map(|att| AttMaxCover::new(att, state, total_active_balance, spec).ok());
 
         maximum_cover(valid_attestations, spec.max_attestations as usize)
     }
@@ -141,53 +235,122 @@ impl<T: EthSpec> OperationPool<T> {
             // All the attestations in this bucket have the same data, so we only need to
             // check the first one.
             attestations.first().map_or(false, |att| {
-                finalized_state.current_epoch() <= att.data.target_epoch + 1
+                finalized_state.current_epoch() <= att.data.target.epoch + 1
             })
         });
     }
 
-    /// Add a deposit to the pool.
+    /// Add a deposit to the pool, with the given `index` in the deposit contract.
     ///
-    /// No two distinct deposits should be added with the same index.
+    /// If the deposit conflicts with an existing deposit at `index` (i.e., the eth1 chain has
+    /// re-organised) the existing deposit is replaced, and all deposits with a greater index are
+    /// removed since they were made on the abandoned eth1 chain. A conflict with a deposit that has
+    /// already been pruned is an error, since that deposit has been finalized.
     pub fn insert_deposit(
         &self,
-        deposit: Deposit,
+        index: u64,
+        deposit_data: DepositData,
     ) -> Result<DepositInsertStatus, DepositValidationError> {
         use DepositInsertStatus::*;
 
-        match self.deposits.write().entry(deposit.index) {
-            Entry::Vacant(entry) => {
-                entry.insert(deposit);
-                Ok(Fresh)
-            }
+        let leaf = Hash256::from_slice(&deposit_data.tree_hash_root());
+
+        let mut deposits = self.deposits.write();
+        let mut deposit_tree = self.deposit_tree.write();
+
+        let status = match deposits.entry(index) {
+            Entry::Vacant(entry) => match deposit_tree.leaf(index as usize) {
+                Some(existing_leaf) if existing_leaf == leaf => return Ok(Duplicate),
+                Some(_) => {
+                    return Err(DepositValidationError::Invalid(DepositInvalid::BadIndex {
+                        state: deposit_tree.len() as u64,
+                        deposit: index,
+                    }))
+                }
+                None => {
+                    entry.insert(deposit_data);
+                    Fresh
+                }
+            },
             Entry::Occupied(mut entry) => {
-                if entry.get() == &deposit {
-                    Ok(Duplicate)
+                if entry.get() == &deposit_data {
+                    return Ok(Duplicate);
                 } else {
-                    Ok(Replaced(Box::new(entry.insert(deposit))))
+                    Replaced(Box::new(entry.insert(deposit_data)))
                 }
             }
+        };
+
+        if let Replaced(_) = status {
+            deposits.split_off(&(index + 1));
+            let len = std::cmp::min(index as usize, deposit_tree.len());
+            deposit_tree.truncate(len);
+        }
+
+        // Extend the tree with any deposits that are now contiguous with it.
+        while let Some(deposit_data) = deposits.get(&(deposit_tree.len() as u64)) {
+            deposit_tree.push(Hash256::from_slice(&deposit_data.tree_hash_root()));
         }
+
+        Ok(status)
     }
 
     /// Get an ordered list of deposits for inclusion in a block.
     ///
     /// Take at most the maximum number of deposits, beginning from the current deposit index.
-    pub fn get_deposits(&self, state: &BeaconState<T>, spec: &ChainSpec) -> Vec<Deposit> {
-        // TODO: We need to update the Merkle proofs for existing deposits as more deposits
-        // are added. It probably makes sense to construct the proofs from scratch when forming
-        // a block, using fresh info from the ETH1 chain for the current deposit root.
-        let start_idx = state.deposit_index;
-        (start_idx..start_idx + spec.max_deposits)
-            .map(|idx| self.deposits.read().get(&idx).cloned())
-            .take_while(Option::is_some)
-            .flatten()
+    /// Proofs are generated against the deposit root of `state.eth1_data`, so an error is
+    /// returned if the pool cannot reproduce that root (e.g., it is missing deposits, or has seen
+    /// an eth1 chain that was not voted in).
+    pub fn get_deposits(
+        &self,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> Result<Vec<Deposit>, DepositError> {
+        let start_idx = state.eth1_deposit_index;
+        let deposit_count = state.eth1_data.deposit_count;
+        if start_idx >= deposit_count {
+            return Ok(vec![]);
+        }
+
+        let deposits = self.deposits.read();
+        let deposit_tree = self.deposit_tree.read();
+        let depth = spec.deposit_contract_tree_depth as usize;
+
+        if (deposit_tree.len() as u64) < deposit_count {
+            return Err(DepositError::MissingDeposit {
+                index: deposit_tree.len() as u64,
+            });
+        }
+        if deposit_tree.deposit_root(depth, deposit_count as usize)
+            != Some(state.eth1_data.deposit_root)
+        {
+            return Err(DepositError::DepositRootMismatch);
+        }
+
+        let end_idx = std::cmp::min(start_idx + spec.max_deposits, deposit_count);
+
+        (start_idx..end_idx)
+            .map(|idx| {
+                let data = deposits
+                    .get(&idx)
+                    .ok_or(DepositError::MissingDeposit { index: idx })?;
+                let proof = deposit_tree
+                    .deposit_proof(depth, idx as usize, deposit_count as usize)
+                    .ok_or(DepositError::MissingDeposit { index: idx })?;
+
+                Ok(Deposit {
+                    proof: FixedLenVec::from(proof),
+                    data: data.clone(),
+                })
+            })
             .collect()
     }
 
     /// Remove all deposits with index less than the deposit index of the latest finalised block.
-    pub fn prune_deposits(&self, state: &BeaconState<T>) -> BTreeMap<u64, Deposit> {
-        let deposits_keep = self.deposits.write().split_off(&state.deposit_index);
+    ///
+    /// The roots of pruned deposits are kept, since they are required to generate proofs.
+    pub fn prune_deposits(&self, state: &BeaconState<T>) -> BTreeMap<u64, DepositData> {
+        let deposits_keep = self.deposits.write().split_off(&state.eth1_deposit_index);
         std::mem::replace(&mut self.deposits.write(), deposits_keep)
     }
 
@@ -197,26 +360,36 @@ impl<T: EthSpec> OperationPool<T> {
     }
 
     /// Insert a proposer slashing into the pool.
+    ///
+    /// If the pool is full, the slashing for the oldest proposal is evicted.
     pub fn insert_proposer_slashing(
         &self,
         slashing: ProposerSlashing,
         state: &BeaconState<T>,
         spec: &ChainSpec,
-    ) -> Result<(), ProposerSlashingValidationError> {
+    ) -> Result<(), InsertError<ProposerSlashingValidationError>> {
         // TODO: should maybe insert anyway if the proposer is unknown in the validator index,
         // because they could *become* known later
-        verify_proposer_slashing(&slashing, state, spec)?;
-        self.proposer_slashings
-            .write()
-            .insert(slashing.proposer_index, slashing);
-        Ok(())
+        verify_proposer_slashing(&slashing, state, spec).map_err(InsertError::Invalid)?;
+        insert_with_limit(
+            &mut self.proposer_slashings.write(),
+            slashing.proposer_index,
+            slashing,
+            self.config.max_proposer_slashings,
+            proposer_slashing_priority,
+        )
+    }
+
+    /// The number of proposer slashings stored in the pool.
+    pub fn num_proposer_slashings(&self) -> usize {
+        self.proposer_slashings.read().len()
     }
 
     /// Compute the tuple ID that is used to identify an attester slashing.
     ///
     /// Depends on the fork field of the state, but not on the state's epoch.
     fn attester_slashing_id(
-        slashing: &AttesterSlashing,
+        slashing: &AttesterSlashing<T>,
         state: &BeaconState<T>,
         spec: &ChainSpec,
     ) -> (AttestationId, AttestationId) {
@@ -227,16 +400,28 @@ impl<T: EthSpec> OperationPool<T> {
     }
 
     /// Insert an attester slashing into the pool.
+    ///
+    /// If the pool is full, the slashing with the oldest target epoch is evicted.
     pub fn insert_attester_slashing(
         &self,
-        slashing: AttesterSlashing,
+        slashing: AttesterSlashing<T>,
         state: &BeaconState<T>,
         spec: &ChainSpec,
-    ) -> Result<(), AttesterSlashingValidationError> {
-        verify_attester_slashing(state, &slashing, true, spec)?;
+    ) -> Result<(), InsertError<AttesterSlashingValidationError>> {
+        verify_attester_slashing(state, &slashing, true, spec).map_err(InsertError::Invalid)?;
         let id = Self::attester_slashing_id(&slashing, state, spec);
-        self.attester_slashings.write().insert(id, slashing);
-        Ok(())
+        insert_with_limit(
+            &mut self.attester_slashings.write(),
+            id,
+            slashing,
+            self.config.max_attester_slashings,
+            attester_slashing_priority,
+        )
+    }
+
+    /// The number of attester slashings stored in the pool.
+    pub fn num_attester_slashings(&self) -> usize {
+        self.attester_slashings.read().len()
     }
 
     /// Get proposer and attester slashings for inclusion in a block.
@@ -248,12 +433,12 @@ impl<T: EthSpec> OperationPool<T> {
         &self,
         state: &BeaconState<T>,
         spec: &ChainSpec,
-    ) -> (Vec<ProposerSlashing>, Vec<AttesterSlashing>) {
+    ) -> (Vec<ProposerSlashing>, Vec<AttesterSlashing<T>>) {
         let proposer_slashings = filter_limit_operations(
             self.proposer_slashings.read().values(),
             |slashing| {
                 state
-                    .validator_registry
+                    .validators
                     .get(slashing.proposer_index as usize)
                     .map_or(false, |validator| !validator.slashed)
             },
@@ -325,17 +510,27 @@ impl<T: EthSpec> OperationPool<T> {
     }
 
     /// Insert a voluntary exit, validating it almost-entirely (future exits are permitted).
+    ///
+    /// If the pool is full, the exit with the earliest epoch is evicted.
     pub fn insert_voluntary_exit(
         &self,
         exit: VoluntaryExit,
         state: &BeaconState<T>,
         spec: &ChainSpec,
-    ) -> Result<(), ExitValidationError> {
-        verify_exit_time_independent_only(state, &exit, spec)?;
-        self.voluntary_exits
-            .write()
-            .insert(exit.validator_index, exit);
-        Ok(())
+    ) -> Result<(), InsertError<ExitValidationError>> {
+        verify_exit_time_independent_only(state, &exit, spec).map_err(InsertError::Invalid)?;
+        insert_with_limit(
+            &mut self.voluntary_exits.write(),
+            exit.validator_index,
+            exit,
+            self.config.max_voluntary_exits,
+            |exit| exit.epoch,
+        )
+    }
+
+    /// The number of voluntary exits stored in the pool.
+    pub fn num_voluntary_exits(&self) -> usize {
+        self.voluntary_exits.read().len()
     }
 
     /// Get a list of voluntary exits for inclusion in a block.
@@ -361,32 +556,62 @@ impl<T: EthSpec> OperationPool<T> {
     }
 
     /// Insert a transfer into the pool, checking it for validity in the process.
+    ///
+    /// If the pool is full, the transfer with the lowest fee is evicted, unless its fee is at least
+    /// that of the new transfer.
     pub fn insert_transfer(
         &self,
         transfer: Transfer,
         state: &BeaconState<T>,
         spec: &ChainSpec,
-    ) -> Result<(), TransferValidationError> {
+    ) -> Result<(), InsertError<TransferValidationError>> {
         // The signature of the transfer isn't hashed, but because we check
         // it before we insert into the HashSet, we can't end up with duplicate
         // transactions.
-        verify_transfer_time_independent_only(state, &transfer, spec)?;
-        self.transfers.write().insert(transfer);
+        verify_transfer_time_independent_only(state, &transfer, spec)
+            .map_err(InsertError::Invalid)?;
+
+        let mut transfers = self.transfers.write();
+        let limit = self.config.max_transfers;
+        if !transfers.contains(&transfer) && transfers.len() >= limit {
+            let lowest = transfers
+                .iter()
+                .min_by_key(|existing| existing.fee)
+                .cloned();
+
+            match lowest {
+                Some(lowest) if lowest.fee < transfer.fee => {
+                    transfers.remove(&lowest);
+                }
+                _ => return Err(InsertError::PoolFull { limit }),
+            }
+        }
+        transfers.insert(transfer);
+
         Ok(())
     }
 
+    /// The number of transfers stored in the pool.
+    pub fn num_transfers(&self) -> usize {
+        self.transfers.read().len()
+    }
+
     /// Get a list of transfers for inclusion in a block.
-    // TODO: improve the economic optimality of this function by accounting for
-    // dependencies between transfers in the same block e.g. A pays B, B pays C
+    ///
+    /// Transfers are chosen to maximise the total fee, accounting for the balance spent by each
+    /// chosen transfer (see `select_transfers`).
     pub fn get_transfers(&self, state: &BeaconState<T>, spec: &ChainSpec) -> Vec<Transfer> {
-        self.transfers
+        let valid = self
+            .transfers
             .read()
             .iter()
-            .filter(|transfer| verify_transfer(state, transfer, spec).is_ok())
-            .sorted_by_key(|transfer| std::cmp::Reverse(transfer.fee))
-            .take(spec.max_transfers as usize)
+            .filter(|transfer| transfer.slot == state.slot)
+            .filter(|transfer| verify_transfer_time_independent_only(state, transfer, spec).is_ok())
             .cloned()
-            .collect()
+            .collect();
+        let (fundable, _) = partition_fundable(valid, state, spec);
+
+        select_transfers(fundable, state, spec)
     }
 
     /// Prune the set of transfers by removing all those whose slot has already passed.
@@ -422,6 +647,78 @@ where
         .collect()
 }
 
+/// The priority of a proposer slashing for eviction: slashings for the oldest proposals are
+/// evicted first.
+fn proposer_slashing_priority(slashing: &ProposerSlashing) -> Slot {
+    slashing.header_1.slot
+}
+
+/// The priority of an attester slashing for eviction: slashings with the oldest target epoch are
+/// evicted first.
+fn attester_slashing_priority<T: EthSpec>(slashing: &AttesterSlashing<T>) -> Epoch {
+    std::cmp::max(
+        slashing.attestation_1.data.target.epoch,
+        slashing.attestation_2.data.target.epoch,
+    )
+}
+
+/// Insert `value` into `map` at `key`, first evicting the entry with the lowest `priority` if
+/// `key` is new and `map` already holds `limit` entries.
+///
+/// Returns an error if the pool is full and no entry has a lower priority than `value`.
+fn insert_with_limit<K, V, P, F, E>(
+    map: &mut HashMap<K, V>,
+    key: K,
+    value: V,
+    limit: usize,
+    priority: F,
+) -> Result<(), InsertError<E>>
+where
+    K: Hash + Eq + Clone,
+    P: Ord,
+    F: Fn(&V) -> P,
+{
+    if !map.contains_key(&key) && map.len() >= limit {
+        let lowest = map
+            .iter()
+            .min_by_key(|(_, existing)| priority(*existing))
+            .map(|(key, existing)| (key.clone(), priority(existing)));
+
+        match lowest {
+            Some((lowest_key, lowest_priority)) if lowest_priority < priority(&value) => {
+                map.remove(&lowest_key);
+            }
+            _ => return Err(InsertError::PoolFull { limit }),
+        }
+    }
+
+    map.insert(key, value);
+
+    Ok(())
+}
+
+/// Remove the entries of `map` with the lowest `priority` until at most `limit` remain.
+fn truncate_to_limit<K, V, P, F>(map: &mut HashMap<K, V>, limit: usize, priority: F)
+where
+    K: Hash + Eq + Clone,
+    P: Ord,
+    F: Fn(&V) -> P,
+{
+    if map.len() <= limit {
+        return;
+    }
+
+    let mut by_priority = map
+        .iter()
+        .map(|(key, value)| (priority(value), key.clone()))
+        .collect::<Vec<_>>();
+    by_priority.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (_, key) in by_priority.into_iter().take(map.len() - limit) {
+        map.remove(&key);
+    }
+}
+
 /// Remove all entries from the given hash map for which `prune_if` returns true.
 ///
 /// The keys in the map should be validator indices, which will be looked up
@@ -436,7 +733,7 @@ fn prune_validator_hash_map<T, F, E: EthSpec>(
 {
     map.retain(|&validator_index, _| {
         finalized_state
-            .validator_registry
+            .validators
             .get(validator_index as usize)
             .map_or(true, |validator| !prune_if(validator))
     });
@@ -447,6 +744,7 @@ impl<T: EthSpec + Default> PartialEq for OperationPool<T> {
     fn eq(&self, other: &Self) -> bool {
         *self.attestations.read() == *other.attestations.read()
             && *self.deposits.read() == *other.deposits.read()
+            && *self.deposit_tree.read() == *other.deposit_tree.read()
             && *self.attester_slashings.read() == *other.attester_slashings.read()
             && *self.proposer_slashings.read() == *other.proposer_slashings.read()
             && *self.voluntary_exits.read() == *other.voluntary_exits.read()
@@ -458,6 +756,7 @@ impl<T: EthSpec + Default> PartialEq for OperationPool<T> {
 mod tests {
     use super::DepositInsertStatus::*;
     use super::*;
+    use merkle_proof::verify_merkle_proof;
     use types::test_utils::*;
     use types::*;
 
@@ -466,44 +765,133 @@ mod tests {
         let rng = &mut XorShiftRng::from_seed([42; 16]);
         let op_pool = OperationPool::<MinimalEthSpec>::new();
         let deposit1 = make_deposit(rng);
-        let mut deposit2 = make_deposit(rng);
-        deposit2.index = deposit1.index;
+        let deposit2 = make_deposit(rng);
 
-        assert_eq!(op_pool.insert_deposit(deposit1.clone()), Ok(Fresh));
-        assert_eq!(op_pool.insert_deposit(deposit1.clone()), Ok(Duplicate));
+        assert_eq!(op_pool.insert_deposit(0, deposit1.clone()), Ok(Fresh));
+        assert_eq!(op_pool.insert_deposit(0, deposit1.clone()), Ok(Duplicate));
         assert_eq!(
-            op_pool.insert_deposit(deposit2),
+            op_pool.insert_deposit(0, deposit2),
             Ok(Replaced(Box::new(deposit1)))
         );
     }
 
+    #[test]
+    fn replaced_deposit_removes_later_deposits() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let op_pool = OperationPool::<MinimalEthSpec>::new();
+        let count = 5;
+
+        for (index, deposit) in dummy_deposits(rng, 0, count) {
+            assert_eq!(op_pool.insert_deposit(index, deposit), Ok(Fresh));
+        }
+        assert_eq!(op_pool.deposit_tree.read().len(), count as usize);
+
+        let replacement = make_deposit(rng);
+        assert!(op_pool.insert_deposit(2, replacement.clone()).is_ok());
+
+        assert_eq!(op_pool.num_deposits(), 3);
+        assert_eq!(op_pool.deposit_tree.read().len(), 3);
+        assert_eq!(
+            op_pool.deposit_tree.read().leaf(2),
+            Some(Hash256::from_slice(&replacement.tree_hash_root()))
+        );
+    }
+
+    #[test]
+    fn conflict_with_pruned_deposit_is_rejected() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let (_, mut state) = test_state(rng);
+        let op_pool = OperationPool::new();
+
+        for (index, deposit) in dummy_deposits(rng, 0, 3) {
+            assert_eq!(op_pool.insert_deposit(index, deposit), Ok(Fresh));
+        }
+
+        state.eth1_deposit_index = 2;
+        let pruned = op_pool.prune_deposits(&state);
+
+        assert_eq!(op_pool.insert_deposit(0, pruned[&0].clone()), Ok(Duplicate));
+        assert!(op_pool.insert_deposit(0, make_deposit(rng)).is_err());
+    }
+
     #[test]
     fn get_deposits_max() {
         let rng = &mut XorShiftRng::from_seed([42; 16]);
         let (spec, mut state) = test_state(rng);
         let op_pool = OperationPool::new();
-        let start = 10000;
         let max_deposits = spec.max_deposits;
         let extra = 5;
         let offset = 1;
         assert!(offset <= extra);
 
-        let deposits = dummy_deposits(rng, start, max_deposits + extra);
+        let deposits = dummy_deposits(rng, 0, max_deposits + extra);
 
-        for deposit in &deposits {
-            assert_eq!(op_pool.insert_deposit(deposit.clone()), Ok(Fresh));
+        for (index, deposit) in &deposits {
+            assert_eq!(op_pool.insert_deposit(*index, deposit.clone()), Ok(Fresh));
         }
 
-        state.deposit_index = start + offset;
-        let deposits_for_block = op_pool.get_deposits(&state, &spec);
+        // The eth1 data excludes the last deposit, so proofs must be generated against an
+        // earlier root than that of the pool's deposit tree.
+        let deposit_count = max_deposits + extra - 1;
+        state.eth1_deposit_index = offset;
+        state.eth1_data.deposit_count = deposit_count;
+        state.eth1_data.deposit_root = op_pool
+            .deposit_tree
+            .read()
+            .deposit_root(
+                spec.deposit_contract_tree_depth as usize,
+                deposit_count as usize,
+            )
+            .unwrap();
+
+        let deposits_for_block = op_pool.get_deposits(&state, &spec).unwrap();
 
         assert_eq!(deposits_for_block.len() as u64, max_deposits);
+        for (deposit, (index, data)) in deposits_for_block
+            .iter()
+            .zip(&deposits[offset as usize..(offset + max_deposits) as usize])
+        {
+            assert_eq!(deposit.data, *data);
+            assert!(verify_merkle_proof(
+                Hash256::from_slice(&data.tree_hash_root()),
+                &deposit.proof.to_vec(),
+                spec.deposit_contract_tree_depth as usize + 1,
+                *index as usize,
+                state.eth1_data.deposit_root,
+            ));
+        }
+
+        // No deposits can be included if the pool disagrees with the eth1 data.
+        state.eth1_data.deposit_root = Hash256::zero();
         assert_eq!(
-            deposits_for_block[..],
-            deposits[offset as usize..(offset + max_deposits) as usize]
+            op_pool.get_deposits(&state, &spec),
+            Err(DepositError::DepositRootMismatch)
         );
     }
 
+    #[test]
+    fn get_deposits_requires_deposits_from_index_zero() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let (spec, mut state) = test_state(rng);
+        let op_pool = OperationPool::new();
+
+        // The pool is missing the first deposit, so it can't build the deposit tree.
+        for (index, deposit) in dummy_deposits(rng, 1, 4) {
+            assert_eq!(op_pool.insert_deposit(index, deposit), Ok(Fresh));
+        }
+
+        state.eth1_deposit_index = 2;
+        state.eth1_data.deposit_count = 5;
+        assert_eq!(
+            op_pool.get_deposits(&state, &spec),
+            Err(DepositError::MissingDeposit { index: 0 })
+        );
+
+        // Nothing is required once all deposits in the eth1 data have been processed.
+        state.eth1_deposit_index = 5;
+        assert_eq!(op_pool.get_deposits(&state, &spec), Ok(vec![]));
+    }
+
     #[test]
     fn prune_deposits() {
         let rng = &mut XorShiftRng::from_seed([42; 16]);
@@ -518,20 +906,20 @@ mod tests {
         let deposits1 = dummy_deposits(rng, start1, count);
         let deposits2 = dummy_deposits(rng, start2, count);
 
-        for d in deposits1.into_iter().chain(deposits2) {
-            assert!(op_pool.insert_deposit(d).is_ok());
+        for (index, d) in deposits1.into_iter().chain(deposits2) {
+            assert!(op_pool.insert_deposit(index, d).is_ok());
         }
 
         assert_eq!(op_pool.num_deposits(), 2 * count as usize);
 
         let mut state = BeaconState::random_for_test(rng);
-        state.deposit_index = start1;
+        state.eth1_deposit_index = start1;
 
         // Pruning the first bunch of deposits in batches of 5 should work.
         let step = 5;
         let mut pool_size = step + 2 * count as usize;
         for i in (start1..=(start1 + count)).step_by(step) {
-            state.deposit_index = i;
+            state.eth1_deposit_index = i;
             op_pool.prune_deposits(&state);
             pool_size -= step;
             assert_eq!(op_pool.num_deposits(), pool_size);
@@ -539,14 +927,14 @@ mod tests {
         assert_eq!(pool_size, count as usize);
         // Pruning in the gap should do nothing.
         for i in (start1 + count..start2).step_by(step) {
-            state.deposit_index = i;
+            state.eth1_deposit_index = i;
             op_pool.prune_deposits(&state);
             assert_eq!(op_pool.num_deposits(), count as usize);
         }
         // Same again for the later deposits.
         pool_size += step;
         for i in (start2..=(start2 + count)).step_by(step) {
-            state.deposit_index = i;
+            state.eth1_deposit_index = i;
             op_pool.prune_deposits(&state);
             pool_size -= step;
             assert_eq!(op_pool.num_deposits(), pool_size);
@@ -554,23 +942,49 @@ mod tests {
         assert_eq!(op_pool.num_deposits(), 0);
     }
 
-    // Create a random deposit
-    fn make_deposit(rng: &mut XorShiftRng) -> Deposit {
-        Deposit::random_for_test(rng)
+    // Create random deposit data
+    fn make_deposit(rng: &mut XorShiftRng) -> DepositData {
+        DepositData::random_for_test(rng)
     }
 
     // Create `count` dummy deposits with sequential deposit IDs beginning from `start`.
-    fn dummy_deposits(rng: &mut XorShiftRng, start: u64, count: u64) -> Vec<Deposit> {
-        let proto_deposit = make_deposit(rng);
+    fn dummy_deposits(rng: &mut XorShiftRng, start: u64, count: u64) -> Vec<(u64, DepositData)> {
         (start..start + count)
-            .map(|index| {
-                let mut deposit = proto_deposit.clone();
-                deposit.index = index;
-                deposit
-            })
+            .map(|index| (index, make_deposit(rng)))
             .collect()
     }
 
+    #[test]
+    fn insert_with_limit_evicts_lowest_priority() {
+        let mut map = HashMap::new();
+        let insert = |map: &mut HashMap<u64, u64>, key, value| -> Result<(), InsertError<()>> {
+            insert_with_limit(map, key, value, 2, |value| *value)
+        };
+
+        assert_eq!(insert(&mut map, 0, 10), Ok(()));
+        assert_eq!(insert(&mut map, 1, 20), Ok(()));
+
+        // Replacing an existing entry doesn't require any room.
+        assert_eq!(insert(&mut map, 0, 5), Ok(()));
+
+        // A new entry of lower priority than everything in the pool is rejected.
+        assert_eq!(
+            insert(&mut map, 2, 5),
+            Err(InsertError::PoolFull { limit: 2 })
+        );
+        assert_eq!(
+            insert(&mut map, 2, 3),
+            Err(InsertError::PoolFull { limit: 2 })
+        );
+
+        // A new entry of higher priority evicts the lowest priority entry.
+        assert_eq!(insert(&mut map, 2, 15), Ok(()));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&0), None);
+        assert_eq!(map.get(&1), Some(&20));
+        assert_eq!(map.get(&2), Some(&15));
+    }
+
     fn test_state(rng: &mut XorShiftRng) -> (ChainSpec, BeaconState<MainnetEthSpec>) {
         let spec = MainnetEthSpec::default_spec();
 
@@ -584,6 +998,7 @@ mod tests {
     #[cfg(not(debug_assertions))]
     mod release_tests {
         use super::*;
+        use crate::attestation::earliest_attestation_validators;
 
         /// Create a signed attestation for use in tests.
         /// Signed by all validators in `committee[signing_range]` and `committee[extra_signer]`.
@@ -596,7 +1011,7 @@ mod tests {
             state: &BeaconState<E>,
             spec: &ChainSpec,
             extra_signer: Option<usize>,
-        ) -> Attestation {
+        ) -> Attestation<E> {
             let mut builder = TestingAttestationBuilder::new(state, committee, slot, shard, spec);
             let signers = &committee[signing_range];
             let committee_keys = signers.iter().map(|&i| &keypairs[i].sk).collect::<Vec<_>>();
@@ -668,11 +1083,11 @@ mod tests {
                 );
 
                 assert_eq!(
-                    att1.aggregation_bitfield.num_set_bits(),
+                    att1.aggregation_bits.num_set_bits(),
                     earliest_attestation_validators(&att1, state).num_set_bits()
                 );
                 state.current_epoch_attestations.push(PendingAttestation {
-                    aggregation_bitfield: att1.aggregation_bitfield.clone(),
+                    aggregation_bits: att1.aggregation_bits.clone(),
                     data: att1.data.clone(),
                     inclusion_delay: 0,
                     proposer_index: 0,
@@ -724,10 +1139,11 @@ mod tests {
                 }
             }
 
-            assert_eq!(op_pool.attestations.read().len(), committees.len());
+            assert_eq!(op_pool.attestations.read().iter().count(), committees.len());
             assert_eq!(op_pool.num_attestations(), committees.len());
 
             // Before the min attestation inclusion delay, get_attestations shouldn't return anything.
+            state.slot = slot;
             assert_eq!(op_pool.get_attestations(state, spec).len(), 0);
 
             // Then once the delay has elapsed, we should get a single aggregated attestation.
@@ -738,7 +1154,7 @@ mod tests {
 
             let agg_att = &block_attestations[0];
             assert_eq!(
-                agg_att.aggregation_bitfield.num_set_bits(),
+                agg_att.aggregation_bits.num_set_bits(),
                 spec.target_committee_size as usize
             );
 
@@ -827,7 +1243,7 @@ mod tests {
 
             // The attestations should get aggregated into two attestations that comprise all
             // validators.
-            assert_eq!(op_pool.attestations.read().len(), committees.len());
+            assert_eq!(op_pool.attestations.read().iter().count(), committees.len());
             assert_eq!(op_pool.num_attestations(), 2 * committees.len());
         }
 
@@ -884,7 +1300,7 @@ mod tests {
             let num_small = target_committee_size / small_step_size;
             let num_big = target_committee_size / big_step_size;
 
-            assert_eq!(op_pool.attestations.read().len(), committees.len());
+            assert_eq!(op_pool.attestations.read().iter().count(), committees.len());
             assert_eq!(
                 op_pool.num_attestations(),
                 (num_small + num_big) * committees.len()
@@ -897,8 +1313,200 @@ mod tests {
 
             // All the best attestations should be signed by at least `big_step_size` (4) validators.
             for att in &best_attestations {
-                assert!(att.aggregation_bitfield.num_set_bits() >= big_step_size);
+                assert!(att.aggregation_bits.num_set_bits() >= big_step_size);
+            }
+        }
+
+        /// An attestation signed by a few validators with high effective balances should be
+        /// preferred to one signed by more validators with low effective balances.
+        #[test]
+        fn attestation_get_max_reward() {
+            let (ref mut state, ref keypairs, ref mut spec) =
+                attestation_test_state::<MainnetEthSpec>(1);
+            spec.max_attestations = 1;
+
+            let op_pool = OperationPool::new();
+
+            let slot = state.slot - 1;
+            let cc = state
+                .get_crosslink_committees_at_slot(slot)
+                .unwrap()
+                .into_iter()
+                .map(CrosslinkCommittee::into_owned)
+                .next()
+                .unwrap();
+
+            // Validators 3..7 of the committee are worth far less than validators 1 and 2.
+            for &validator_index in &cc.committee[3..7] {
+                state.validators[validator_index].effective_balance =
+                    spec.effective_balance_increment;
             }
+
+            // Both attestations are signed by the 0th member of the committee, so that they are
+            // not aggregated.
+            let high_value = signed_attestation(
+                &cc.committee,
+                cc.shard,
+                keypairs,
+                0..3,
+                slot,
+                state,
+                spec,
+                None,
+            );
+            let low_value = signed_attestation(
+                &cc.committee,
+                cc.shard,
+                keypairs,
+                3..7,
+                slot,
+                state,
+                spec,
+                Some(0),
+            );
+            op_pool
+                .insert_attestation(high_value.clone(), state, spec)
+                .unwrap();
+            op_pool.insert_attestation(low_value, state, spec).unwrap();
+            assert_eq!(op_pool.num_attestations(), 2);
+
+            state.slot += spec.min_attestation_inclusion_delay;
+            assert_eq!(op_pool.get_attestations(state, spec), vec![high_value]);
+        }
+
+        /// Once the pool is full, an attestation should only be inserted if it evicts an
+        /// attestation with fewer signers.
+        #[test]
+        fn attestation_pool_full() {
+            let (ref mut state, ref keypairs, ref spec) =
+                attestation_test_state::<MainnetEthSpec>(1);
+
+            let op_pool = OperationPool::with_config(OperationPoolConfig {
+                max_attestations: 2,
+                ..OperationPoolConfig::default()
+            });
+
+            let slot = state.slot - 1;
+            let cc = state
+                .get_crosslink_committees_at_slot(slot)
+                .unwrap()
+                .into_iter()
+                .map(CrosslinkCommittee::into_owned)
+                .next()
+                .unwrap();
+
+            // All attestations are signed by the 0th member of the committee, so that they are
+            // not aggregated.
+            let attestation = |signing_range, extra_signer| {
+                signed_attestation(
+                    &cc.committee,
+                    cc.shard,
+                    keypairs,
+                    signing_range,
+                    slot,
+                    state,
+                    spec,
+                    extra_signer,
+                )
+            };
+            let two_signers = attestation(0..2, None);
+            let three_signers = attestation(2..4, Some(0));
+            let one_signer = attestation(0..1, None);
+            let four_signers = attestation(4..7, Some(0));
+
+            op_pool
+                .insert_attestation(two_signers, state, spec)
+                .unwrap();
+            op_pool
+                .insert_attestation(three_signers.clone(), state, spec)
+                .unwrap();
+            assert_eq!(
+                op_pool.insert_attestation(one_signer, state, spec),
+                Err(InsertError::PoolFull { limit: 2 })
+            );
+            op_pool
+                .insert_attestation(four_signers.clone(), state, spec)
+                .unwrap();
+
+            assert_eq!(op_pool.num_attestations(), 2);
+            assert_eq!(
+                sorted_attestations(&op_pool),
+                vec![three_signers, four_signers]
+            );
+        }
+
+        /// Lowering the limits should evict the lowest priority attestations immediately.
+        #[test]
+        fn set_config_evicts_to_new_limits() {
+            let (ref mut state, ref keypairs, ref spec) =
+                attestation_test_state::<MainnetEthSpec>(1);
+
+            let mut op_pool = OperationPool::new();
+
+            let slot = state.slot - 1;
+            let cc = state
+                .get_crosslink_committees_at_slot(slot)
+                .unwrap()
+                .into_iter()
+                .map(CrosslinkCommittee::into_owned)
+                .next()
+                .unwrap();
+
+            let attestations = (1..4)
+                .map(|signers| {
+                    signed_attestation(
+                        &cc.committee,
+                        cc.shard,
+                        keypairs,
+                        signers..2 * signers,
+                        slot,
+                        state,
+                        spec,
+                        Some(0),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for attestation in &attestations {
+                op_pool
+                    .insert_attestation(attestation.clone(), state, spec)
+                    .unwrap();
+            }
+            assert_eq!(op_pool.num_attestations(), 3);
+
+            op_pool.set_config(OperationPoolConfig {
+                max_attestations: 1,
+                ..OperationPoolConfig::default()
+            });
+            assert_eq!(op_pool.num_attestations(), 1);
+            assert_eq!(sorted_attestations(&op_pool), vec![attestations[2].clone()]);
+
+            // The persisted pool is restored with the given limits.
+            let persisted = PersistedOperationPool::from_operation_pool(&op_pool);
+            let restored = persisted.into_operation_pool(
+                state,
+                spec,
+                OperationPoolConfig {
+                    max_attestations: 0,
+                    ..OperationPoolConfig::default()
+                },
+            );
+            assert_eq!(restored.num_attestations(), 0);
+            assert_eq!(restored.config().max_attestations, 0);
+        }
+
+        /// All the attestations in `op_pool`, ordered by number of signers.
+        fn sorted_attestations(
+            op_pool: &OperationPool<MainnetEthSpec>,
+        ) -> Vec<Attestation<MainnetEthSpec>> {
+            let mut attestations = op_pool
+                .attestations
+                .read()
+                .iter()
+                .flat_map(|(_, attestations)| attestations)
+                .cloned()
+                .collect::<Vec<_>>();
+            attestations.sort_by_key(|att| att.aggregation_bits.num_set_bits());
+            attestations
         }
     }
 
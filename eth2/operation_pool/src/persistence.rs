@@ -1,5 +1,6 @@
 use crate::attestation_id::AttestationId;
-use crate::OperationPool;
+use crate::deposit_tree::DepositTree;
+use crate::{OperationPool, OperationPoolConfig};
 use parking_lot::RwLock;
 use ssz_derive::{Decode, Encode};
 use types::*;
@@ -9,14 +10,17 @@ use types::*;
 /// Operations are stored in arbitrary order, so it's not a good idea to compare instances
 /// of this type (or its encoded form) for equality. Convert back to an `OperationPool` first.
 #[derive(Encode, Decode)]
-pub struct PersistedOperationPool {
+pub struct PersistedOperationPool<T: EthSpec> {
     /// Mapping from attestation ID to attestation mappings.
     // We could save space by not storing the attestation ID, but it might
     // be difficult to make that roundtrip due to eager aggregation.
-    attestations: Vec<(AttestationId, Vec<Attestation>)>,
-    deposits: Vec<Deposit>,
+    attestations: Vec<(AttestationId, Vec<Attestation<T>>)>,
+    /// Deposit data, with the index of each deposit.
+    deposits: Vec<(u64, DepositData)>,
+    /// Roots of all known deposits, including those pruned from `deposits`.
+    deposit_tree_leaves: Vec<Hash256>,
     /// Attester slashings.
-    attester_slashings: Vec<AttesterSlashing>,
+    attester_slashings: Vec<AttesterSlashing<T>>,
     /// Proposer slashings.
     proposer_slashings: Vec<ProposerSlashing>,
     /// Voluntary exits.
@@ -25,9 +29,9 @@ pub struct PersistedOperationPool {
     transfers: Vec<Transfer>,
 }
 
-impl PersistedOperationPool {
+impl<T: EthSpec> PersistedOperationPool<T> {
     /// Convert an `OperationPool` into serializable form.
-    pub fn from_operation_pool<T: EthSpec>(operation_pool: &OperationPool<T>) -> Self {
+    pub fn from_operation_pool(operation_pool: &OperationPool<T>) -> Self {
         let attestations = operation_pool
             .attestations
             .read()
@@ -39,9 +43,11 @@ impl PersistedOperationPool {
             .deposits
             .read()
             .iter()
-            .map(|(_, d)| d.clone())
+            .map(|(index, d)| (*index, d.clone()))
             .collect();
 
+        let deposit_tree_leaves = operation_pool.deposit_tree.read().leaves().to_vec();
+
         let attester_slashings = operation_pool
             .attester_slashings
             .read()
@@ -68,6 +74,7 @@ impl PersistedOperationPool {
         Self {
             attestations,
             deposits,
+            deposit_tree_leaves,
             attester_slashings,
             proposer_slashings,
             voluntary_exits,
@@ -75,14 +82,17 @@ impl PersistedOperationPool {
         }
     }
 
-    /// Reconstruct an `OperationPool`.
-    pub fn into_operation_pool<T: EthSpec>(
+    /// Reconstruct an `OperationPool` with the limits in `config`, evicting operations if the
+    /// persisted pool exceeds them.
+    pub fn into_operation_pool(
         self,
         state: &BeaconState<T>,
         spec: &ChainSpec,
+        config: OperationPoolConfig,
     ) -> OperationPool<T> {
         let attestations = RwLock::new(self.attestations.into_iter().collect());
-        let deposits = RwLock::new(self.deposits.into_iter().map(|d| (d.index, d)).collect());
+        let deposits = RwLock::new(self.deposits.into_iter().collect());
+        let deposit_tree = RwLock::new(DepositTree::from_leaves(self.deposit_tree_leaves));
         let attester_slashings = RwLock::new(
             self.attester_slashings
                 .into_iter()
@@ -108,14 +118,18 @@ impl PersistedOperationPool {
         );
         let transfers = RwLock::new(self.transfers.into_iter().collect());
 
-        OperationPool {
+        let mut op_pool = OperationPool {
             attestations,
             deposits,
+            deposit_tree,
             attester_slashings,
             proposer_slashings,
             voluntary_exits,
             transfers,
+            config: config.clone(),
             _phantom: Default::default(),
-        }
+        };
+        op_pool.set_config(config);
+        op_pool
     }
 }
@@ -0,0 +1,408 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use types::{BeaconState, ChainSpec, EthSpec, RelativeEpoch, Transfer};
+
+/// The number of candidate checks that `select_transfers` may make beyond those needed to find its
+/// first selection, before returning the best selection found so far.
+///
+/// Bounds the work done when the pool holds many candidates for the same slot. The first selection
+/// found is that of choosing transfers greedily by fee (taking at most `max_transfers` checks of
+/// each candidate), so the result is never worse than it.
+const MAX_SEARCH_STEPS: usize = 1 << 16;
+
+/// Select up to `spec.max_transfers` of the `candidates` for inclusion in a block at `state.slot`,
+/// maximising the total fee paid to the proposer.
+///
+/// Each candidate must be for `state.slot`, and pass the checks of
+/// `verify_transfer_time_independent_only` against `state`. The remaining (balance-dependent)
+/// checks are made against balances simulated across the chosen transfers, in the order they will
+/// appear in the block. This ensures a sender is never chosen to spend more than its balance,
+/// while allowing a transfer that is only valid once another transfer has paid its sender (e.g.,
+/// A pays B, B pays C) to be chosen.
+///
+/// The selection is found by a branch-and-bound search over the sets of candidates that can be
+/// applied in some order, and is optimal unless the search exceeds `MAX_SEARCH_STEPS`.
+pub fn select_transfers<T: EthSpec>(
+    mut candidates: Vec<Transfer>,
+    state: &BeaconState<T>,
+    spec: &ChainSpec,
+) -> Vec<Transfer> {
+    // Extending selections with the highest fees first finds good selections early, allowing more
+    // of the search to be pruned.
+    candidates.sort_by_key(|transfer| (Reverse(transfer.fee), transfer.amount));
+
+    let max_transfers = spec.max_transfers as usize;
+
+    let mut search = TransferSearch {
+        candidates: &candidates,
+        max_transfers,
+        // The proposer is paid each fee as its transfer is processed. If the proposer is unknown
+        // its balance is not credited, which can only cause valid transfers to be passed over.
+        proposer_index: proposer_index(state, spec),
+        spec,
+        selected: vec![false; candidates.len()],
+        visited: HashSet::new(),
+        steps_remaining: max_transfers
+            .saturating_mul(candidates.len())
+            .saturating_add(MAX_SEARCH_STEPS),
+        best: (0, vec![]),
+    };
+    search.extend(&SimulatedBalances::new(state), &mut vec![], 0);
+
+    search
+        .best
+        .1
+        .into_iter()
+        .map(|index| candidates[index].clone())
+        .collect()
+}
+
+/// The state of the search performed by `select_transfers`.
+struct TransferSearch<'a> {
+    /// Sorted by decreasing fee.
+    candidates: &'a [Transfer],
+    max_transfers: usize,
+    proposer_index: Option<u64>,
+    spec: &'a ChainSpec,
+    /// Whether each candidate is in the selection currently being extended.
+    selected: Vec<bool>,
+    /// The (sorted) sets of candidates that have been extended. The simulated balances after a set
+    /// of transfers do not depend on the order in which they were applied, so each set need only
+    /// be extended once.
+    visited: HashSet<Vec<usize>>,
+    steps_remaining: usize,
+    /// The total fee and (ordered) candidate indices of the best selection found so far.
+    best: (u64, Vec<usize>),
+}
+
+impl<'a> TransferSearch<'a> {
+    /// Search all selections that extend `chosen` (which has a total fee of `fee` and results in
+    /// `balances`) for one that is better than `self.best`.
+    fn extend<T: EthSpec>(
+        &mut self,
+        balances: &SimulatedBalances<T>,
+        chosen: &mut Vec<usize>,
+        fee: u64,
+    ) {
+        // Of the selections paying the same fee, prefer those including the most transfers.
+        if fee > self.best.0 || (fee == self.best.0 && chosen.len() > self.best.1.len()) {
+            self.best = (fee, chosen.clone());
+        }
+
+        if chosen.len() >= self.max_transfers {
+            return;
+        }
+
+        // No extension can pay more than the highest fees of the remaining candidates, nor include
+        // more transfers than there are remaining candidates.
+        let (bound, num_remaining) = self
+            .candidates
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, &selected)| !selected)
+            .take(self.max_transfers - chosen.len())
+            .fold((fee, 0), |(bound, count), (transfer, _)| {
+                (bound.saturating_add(transfer.fee), count + 1)
+            });
+        if (bound, chosen.len() + num_remaining) <= (self.best.0, self.best.1.len()) {
+            return;
+        }
+
+        for index in 0..self.candidates.len() {
+            if self.steps_remaining == 0 {
+                return;
+            }
+            self.steps_remaining -= 1;
+
+            let transfer = &self.candidates[index];
+            if self.selected[index] || !balances.transfer_is_valid(transfer, self.spec) {
+                continue;
+            }
+
+            let mut set = chosen.clone();
+            set.push(index);
+            set.sort_unstable();
+            if !self.visited.insert(set) {
+                continue;
+            }
+
+            let mut balances = balances.clone();
+            balances.apply_transfer(transfer, self.proposer_index);
+
+            self.selected[index] = true;
+            chosen.push(index);
+            self.extend(&balances, chosen, fee.saturating_add(transfer.fee));
+            chosen.pop();
+            self.selected[index] = false;
+        }
+    }
+}
+
+/// Split `transfers` (which must all be for `state.slot`) into those that could be funded in a
+/// block at `state.slot`, and those whose sender could not afford them even after receiving every
+/// other transfer (and, if it is the proposer, every fee).
+///
+/// Since a transfer is only valid at its own slot, the latter can never become valid.
+pub fn partition_fundable<T: EthSpec>(
+    transfers: Vec<Transfer>,
+    state: &BeaconState<T>,
+    spec: &ChainSpec,
+) -> (Vec<Transfer>, Vec<Transfer>) {
+    let proposer_index = proposer_index(state, spec);
+
+    let mut max_incoming: HashMap<u64, u64> = HashMap::new();
+    let mut total_fees: u64 = 0;
+    for transfer in &transfers {
+        let incoming = max_incoming.entry(transfer.recipient).or_insert(0);
+        *incoming = incoming.saturating_add(transfer.amount);
+        total_fees = total_fees.saturating_add(transfer.fee);
+    }
+
+    transfers.into_iter().partition(|transfer| {
+        // If the proposer is unknown, any sender might receive the fees.
+        let fees = if proposer_index.map_or(true, |index| index == transfer.sender) {
+            total_fees
+        } else {
+            0
+        };
+        let max_balance = state
+            .balances
+            .get(transfer.sender as usize)
+            .map_or(0, |balance| {
+                balance
+                    .saturating_add(max_incoming.get(&transfer.sender).cloned().unwrap_or(0))
+                    .saturating_add(fees)
+            });
+
+        transfer
+            .amount
+            .checked_add(transfer.fee)
+            .map_or(false, |total_amount| total_amount <= max_balance)
+    })
+}
+
+/// The index of the proposer at `state.slot`, if it can be determined.
+fn proposer_index<T: EthSpec>(state: &BeaconState<T>, spec: &ChainSpec) -> Option<u64> {
+    state
+        .get_beacon_proposer_index(state.slot, RelativeEpoch::Current, spec)
+        .ok()
+        .map(|index| index as u64)
+}
+
+/// The balances of `state`, updated by the transfers chosen so far.
+#[derive(Clone)]
+struct SimulatedBalances<'a, T: EthSpec> {
+    state: &'a BeaconState<T>,
+    updated: HashMap<u64, u64>,
+}
+
+impl<'a, T: EthSpec> SimulatedBalances<'a, T> {
+    fn new(state: &'a BeaconState<T>) -> Self {
+        Self {
+            state,
+            updated: HashMap::new(),
+        }
+    }
+
+    /// Returns the balance of the validator at `index`, or `None` if it is unknown.
+    fn get(&self, index: u64) -> Option<u64> {
+        self.updated
+            .get(&index)
+            .cloned()
+            .or_else(|| self.state.balances.get(index as usize).cloned())
+    }
+
+    /// Mirrors the balance-dependent checks of `verify_transfer`, using the simulated balances.
+    fn transfer_is_valid(&self, transfer: &Transfer, spec: &ChainSpec) -> bool {
+        let state = self.state;
+        let (sender_balance, recipient_balance) =
+            match (self.get(transfer.sender), self.get(transfer.recipient)) {
+                (Some(sender_balance), Some(recipient_balance)) => {
+                    (sender_balance, recipient_balance)
+                }
+                _ => return false,
+            };
+        let sender_validator = match state.validators.get(transfer.sender as usize) {
+            Some(validator) => validator,
+            None => return false,
+        };
+        let total_amount = match transfer.amount.checked_add(transfer.fee) {
+            Some(total_amount) => total_amount,
+            None => return false,
+        };
+        let epoch = state.slot.epoch(T::slots_per_epoch());
+
+        sender_balance >= transfer.amount
+            && (sender_balance == total_amount
+                || total_amount
+                    .checked_add(spec.min_deposit_amount)
+                    .map_or(false, |required| sender_balance >= required))
+            && recipient_balance.saturating_add(transfer.amount) >= spec.min_deposit_amount
+            && (sender_validator.activation_eligibility_epoch == spec.far_future_epoch
+                || sender_validator.is_withdrawable_at(epoch)
+                || total_amount
+                    .checked_add(spec.max_effective_balance)
+                    .map_or(false, |required| required <= sender_balance))
+    }
+
+    /// Update the simulated balances as per `execute_transfer`. The transfer must be valid.
+    fn apply_transfer(&mut self, transfer: &Transfer, proposer_index: Option<u64>) {
+        self.update(transfer.sender, |balance| {
+            balance - (transfer.amount + transfer.fee)
+        });
+        self.update(transfer.recipient, |balance| {
+            balance.saturating_add(transfer.amount)
+        });
+        if let Some(proposer_index) = proposer_index {
+            self.update(proposer_index, |balance| {
+                balance.saturating_add(transfer.fee)
+            });
+        }
+    }
+
+    /// Replace the balance of the validator at `index` (if known) with `f(balance)`.
+    fn update<F: FnOnce(u64) -> u64>(&mut self, index: u64, f: F) {
+        if let Some(balance) = self.get(index) {
+            self.updated.insert(index, f(balance));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::*;
+    use types::*;
+
+    const ETH: u64 = 1_000_000_000;
+
+    /// A state with the given `balances`, whose validators may all make transfers.
+    fn test_state(balances: &[u64]) -> (BeaconState<MinimalEthSpec>, ChainSpec) {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let mut spec = MinimalEthSpec::default_spec();
+        spec.max_transfers = 16;
+
+        let mut state = BeaconState::random_for_test(rng);
+        state.balances = balances.to_vec().into();
+        state.validators = balances
+            .iter()
+            .map(|_| {
+                let mut validator = Validator::random_for_test(rng);
+                validator.activation_eligibility_epoch = spec.far_future_epoch;
+                validator
+            })
+            .collect();
+
+        (state, spec)
+    }
+
+    fn transfer(sender: u64, recipient: u64, amount: u64, fee: u64) -> Transfer {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        Transfer {
+            sender,
+            recipient,
+            amount,
+            fee,
+            ..Transfer::random_for_test(rng)
+        }
+    }
+
+    #[test]
+    fn sender_balance_is_not_overspent() {
+        let (state, spec) = test_state(&[5 * ETH, 32 * ETH]);
+
+        let high_fee = transfer(0, 1, 3 * ETH, ETH);
+        let low_fee_large = transfer(0, 1, ETH, ETH / 2);
+        let low_fee_small = transfer(0, 1, ETH / 2, ETH / 2);
+
+        // Each transfer is affordable in isolation, but the sender can only afford the highest
+        // fee transfer along with the smaller of the others.
+        let chosen = select_transfers(
+            vec![low_fee_large, low_fee_small.clone(), high_fee.clone()],
+            &state,
+            &spec,
+        );
+        assert_eq!(chosen, vec![high_fee, low_fee_small]);
+    }
+
+    #[test]
+    fn lower_fee_transfers_are_chosen_if_they_pay_more_in_total() {
+        let (state, spec) = test_state(&[10 * ETH, 32 * ETH]);
+
+        // The sender can afford either the highest fee transfer, or both of the others.
+        let high_fee = transfer(0, 1, 8 * ETH, 2 * ETH);
+        let first_low_fee = transfer(0, 1, 3 * ETH, 3 * ETH / 2);
+        let second_low_fee = transfer(0, 1, 3 * ETH, 3 * ETH / 2);
+
+        let chosen = select_transfers(
+            vec![high_fee, first_low_fee.clone(), second_low_fee.clone()],
+            &state,
+            &spec,
+        );
+        assert_eq!(chosen, vec![first_low_fee, second_low_fee]);
+    }
+
+    #[test]
+    fn zero_fee_transfers_are_chosen() {
+        let (state, spec) = test_state(&[32 * ETH, 32 * ETH]);
+
+        let transfers = vec![transfer(0, 1, ETH, 0), transfer(1, 0, ETH, 0)];
+
+        let chosen = select_transfers(transfers.clone(), &state, &spec);
+        assert_eq!(chosen, transfers);
+    }
+
+    #[test]
+    fn chained_transfers_are_chosen() {
+        let (state, spec) = test_state(&[32 * ETH, ETH / 2, 32 * ETH]);
+
+        // Validator 1 can only pay validator 2 once it has been paid by validator 0.
+        let first = transfer(0, 1, ETH, ETH / 10);
+        let second = transfer(1, 2, ETH + ETH / 4, ETH / 4);
+
+        let chosen = select_transfers(vec![second.clone(), first.clone()], &state, &spec);
+        assert_eq!(chosen, vec![first, second]);
+    }
+
+    #[test]
+    fn max_transfers_respected() {
+        let (state, mut spec) = test_state(&[32 * ETH, 32 * ETH]);
+        spec.max_transfers = 2;
+
+        let transfers = (1..=4)
+            .map(|fee| transfer(0, 1, ETH, fee))
+            .collect::<Vec<_>>();
+
+        let chosen = select_transfers(transfers.clone(), &state, &spec);
+        assert_eq!(chosen, vec![transfers[3].clone(), transfers[2].clone()]);
+    }
+
+    #[test]
+    fn overflowing_amounts_are_rejected() {
+        let (mut state, spec) = test_state(&[u64::max_value(), 32 * ETH]);
+        state.validators[0].activation_eligibility_epoch = Epoch::new(0);
+
+        // The sender's balance can cover the transfer, but not the transfer plus the minimum
+        // remaining (or maximum effective) balance, which overflows.
+        let overflowing = transfer(0, 1, u64::max_value() - ETH, ETH / 2);
+        assert!(select_transfers(vec![overflowing], &state, &spec).is_empty());
+    }
+
+    #[test]
+    fn unfundable_transfers_are_partitioned() {
+        let (state, spec) = test_state(&[ETH, ETH, 32 * ETH]);
+
+        // Validator 0 can be paid enough by validator 2 to make its transfer, validator 1 can not.
+        let funding = transfer(2, 0, 2 * ETH, 1);
+        let funded = transfer(0, 2, 3 * ETH - 2, 1);
+        let unfunded = transfer(1, 2, 2 * ETH, 1);
+
+        let (fundable, unfundable) = partition_fundable(
+            vec![funding.clone(), funded.clone(), unfunded.clone()],
+            &state,
+            &spec,
+        );
+        assert_eq!(fundable, vec![funding, funded]);
+        assert_eq!(unfundable, vec![unfunded]);
+    }
+}
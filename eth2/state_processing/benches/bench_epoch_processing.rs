@@ -1,263 +1,283 @@
+//! Benchmarks each step of epoch processing, and compares a single thread against the default
+//! `rayon` thread pool to show the speed-up from processing validators in parallel.
+//!
+//! Building the 300,000 validator state is slow unless the keypairs have been generated to
+//! `$HOME/.lighthouse/keypairs.raw_keypairs` beforehand.
 use criterion::Criterion;
-use criterion::{black_box, Benchmark};
-use state_processing::{
-    per_epoch_processing,
-    per_epoch_processing::{
-        clean_attestations, initialize_validator_statuses, process_crosslinks, process_eth1_data,
-        process_justification, process_rewards_and_penalities, process_validator_registry,
-        update_active_tree_index_roots, update_latest_slashed_balances,
-    },
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Benchmark};
+use rayon::ThreadPoolBuilder;
+use state_processing::per_epoch_processing::{
+    apply_rewards::process_rewards_and_penalties, per_epoch_processing, process_crosslinks,
+    process_final_updates, process_justification_and_finalization,
+    process_slashings::process_slashings, registry_updates::process_registry_updates,
+    validator_statuses::ValidatorStatuses,
 };
 use tree_hash::TreeHash;
 use types::test_utils::TestingBeaconStateBuilder;
 use types::*;
 
-pub const BENCHING_SAMPLE_SIZE: usize = 10;
-pub const SMALL_BENCHING_SAMPLE_SIZE: usize = 10;
+type E = MainnetEthSpec;
 
-/// Run the benchmarking suite on a foundation spec with 16,384 validators.
-pub fn bench_epoch_processing_n_validators(c: &mut Criterion, validator_count: usize) {
-    let spec = ChainSpec::mainnet();
+pub const SAMPLE_SIZE: usize = 10;
+pub const SMALL_SAMPLE_SIZE: usize = 10;
 
-    let mut builder =
-        TestingBeaconStateBuilder::from_default_keypairs_file_if_exists(validator_count, &spec);
+/// Build a state in the last slot of the 4th epoch, with an attestation from every committee.
+fn build_state(validator_count: usize, spec: &ChainSpec) -> BeaconState<E> {
+    let mut builder: TestingBeaconStateBuilder<E> =
+        TestingBeaconStateBuilder::from_default_keypairs_file_if_exists(validator_count, spec);
 
-    // Set the state to be just before an epoch transition.
-    let target_slot = (T::genesis_epoch() + 4).end_slot(T::slots_per_epoch());
-    builder.teleport_to_slot(target_slot, &spec);
+    let target_slot = (E::genesis_epoch() + 4).end_slot(E::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
 
     // Builds all caches; benches will not contain shuffling/committee building times.
-    builder.build_caches(&spec).unwrap();
+    builder.build_caches(spec).unwrap();
 
-    // Inserts one attestation with full participation for each committee able to include an
-    // attestation in this state.
-    builder.insert_attestations(&spec);
+    builder.insert_attestations(spec);
 
     let (state, _keypairs) = builder.build();
+    state
+}
 
-    // Assert that the state has an attestations for each committee that is able to include an
-    // attestation in the state.
-    let committees_per_epoch = spec.get_epoch_committee_count(validator_count);
-    let committees_per_slot = committees_per_epoch / T::slots_per_epoch();
-    let previous_epoch_attestations = committees_per_epoch;
-    let current_epoch_attestations =
-        committees_per_slot * (T::slots_per_epoch() - spec.min_attestation_inclusion_delay);
-    assert_eq!(
-        state.latest_attestations.len() as u64,
-        previous_epoch_attestations + current_epoch_attestations,
-        "The state should have an attestation for each committee."
-    );
-
-    // Assert that we will run the first arm of process_rewards_and_penalities
-    let epochs_since_finality = state.next_epoch(&spec) - state.finalized_epoch;
-    assert_eq!(
-        epochs_since_finality, 4,
-        "Epochs since finality should be 4"
-    );
+/// Bench `f` applied to a clone of `state`, once using a single thread and once using the
+/// default `rayon` thread pool.
+fn bench_single_and_multi_threaded<F>(
+    c: &mut Criterion,
+    desc: &str,
+    name: &str,
+    state: &BeaconState<E>,
+    f: F,
+) where
+    F: Fn(&mut BeaconState<E>) + Send + Sync + Clone + 'static,
+{
+    let single_thread_pool = ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .expect("should build thread pool");
 
-    bench_epoch_processing(c, &state, &spec, &format!("{}_validators", validator_count));
-}
+    let single_state = state.clone();
+    let single_f = f.clone();
+    let multi_state = state.clone();
 
-/// Run the detailed benchmarking suite on the given `BeaconState`.
-///
-/// `desc` will be added to the title of each bench.
-fn bench_epoch_processing(c: &mut Criterion, state: &BeaconState, spec: &ChainSpec, desc: &str) {
-    let state_clone = state.clone();
-    let spec_clone = spec.clone();
     c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("process_eth1_data", move |b| {
+        desc,
+        Benchmark::new(format!("{}/single_thread", name), move |b| {
             b.iter_batched(
-                || state_clone.clone(),
+                || single_state.clone(),
                 |mut state| {
-                    process_eth1_data(&mut state, &spec_clone);
+                    single_thread_pool.install(|| single_f(&mut state));
                     state
                 },
-                criterion::BatchSize::SmallInput,
+                BatchSize::LargeInput,
             )
         })
-        .sample_size(BENCHING_SAMPLE_SIZE),
+        .with_function(format!("{}/multi_thread", name), move |b| {
+            b.iter_batched(
+                || multi_state.clone(),
+                |mut state| {
+                    f(&mut state);
+                    state
+                },
+                BatchSize::LargeInput,
+            )
+        })
+        .sample_size(SAMPLE_SIZE),
     );
+}
 
+/// Bench each step of epoch processing on a clone of `state`, using the default `rayon` thread
+/// pool.
+fn bench_epoch_processing_steps(
+    c: &mut Criterion,
+    desc: &str,
+    state: &BeaconState<E>,
+    spec: &ChainSpec,
+) {
     let state_clone = state.clone();
     let spec_clone = spec.clone();
     c.bench(
-        &format!("{}/epoch_processing", desc),
+        desc,
         Benchmark::new("initialize_validator_statuses", move |b| {
             b.iter_batched(
                 || state_clone.clone(),
-                |mut state| {
-                    initialize_validator_statuses(&mut state, &spec_clone).unwrap();
-                    state
+                |state| {
+                    let mut statuses = ValidatorStatuses::new(&state, &spec_clone).unwrap();
+                    statuses.process_attestations(&state, &spec_clone).unwrap();
+                    black_box(statuses)
                 },
-                criterion::BatchSize::SmallInput,
+                BatchSize::SmallInput,
             )
         })
-        .sample_size(BENCHING_SAMPLE_SIZE),
+        .sample_size(SAMPLE_SIZE),
     );
 
+    let mut statuses = ValidatorStatuses::new(state, spec).unwrap();
+    statuses.process_attestations(state, spec).unwrap();
+
     let state_clone = state.clone();
-    let spec_clone = spec.clone();
-    let attesters = initialize_validator_statuses(&state, &spec).unwrap();
+    let total_balances = statuses.total_balances.clone();
     c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("process_justification", move |b| {
+        desc,
+        Benchmark::new("process_justification_and_finalization", move |b| {
             b.iter_batched(
                 || state_clone.clone(),
                 |mut state| {
-                    process_justification(&mut state, &attesters.total_balances, &spec_clone);
+                    process_justification_and_finalization(&mut state, &total_balances).unwrap();
                     state
                 },
-                criterion::BatchSize::SmallInput,
+                BatchSize::SmallInput,
             )
         })
-        .sample_size(10),
+        .sample_size(SAMPLE_SIZE),
     );
 
     let state_clone = state.clone();
     let spec_clone = spec.clone();
     c.bench(
-        &format!("{}/epoch_processing", desc),
+        desc,
         Benchmark::new("process_crosslinks", move |b| {
             b.iter_batched(
                 || state_clone.clone(),
                 |mut state| black_box(process_crosslinks(&mut state, &spec_clone).unwrap()),
-                criterion::BatchSize::SmallInput,
+                BatchSize::SmallInput,
             )
         })
-        .sample_size(BENCHING_SAMPLE_SIZE),
+        .sample_size(SAMPLE_SIZE),
     );
 
     let mut state_clone = state.clone();
     let spec_clone = spec.clone();
-    let attesters = initialize_validator_statuses(&state, &spec).unwrap();
-    let winning_root_for_shards = process_crosslinks(&mut state_clone, &spec).unwrap();
+    let winning_roots = process_crosslinks(&mut state_clone, spec).unwrap();
+    let statuses_clone = statuses.clone();
     c.bench(
-        &format!("{}/epoch_processing", desc),
+        desc,
         Benchmark::new("process_rewards_and_penalties", move |b| {
             b.iter_batched(
-                || (state_clone.clone(), attesters.clone()),
-                |(mut state, mut attesters)| {
-                    process_rewards_and_penalities(
-                        &mut state,
-                        &mut attesters,
-                        &winning_root_for_shards,
-                        &spec_clone,
-                    )
-                    .unwrap();
+                || (state_clone.clone(), statuses_clone.clone()),
+                |(mut state, mut statuses)| {
+                    black_box(
+                        process_rewards_and_penalties(
+                            &mut state,
+                            &mut statuses,
+                            &winning_roots,
+                            &spec_clone,
+                        )
+                        .unwrap(),
+                    );
                     state
                 },
-                criterion::BatchSize::SmallInput,
+                BatchSize::SmallInput,
             )
         })
-        .sample_size(SMALL_BENCHING_SAMPLE_SIZE),
+        .sample_size(SMALL_SAMPLE_SIZE),
     );
 
     let state_clone = state.clone();
     let spec_clone = spec.clone();
     c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("process_ejections", move |b| {
+        desc,
+        Benchmark::new("process_registry_updates", move |b| {
             b.iter_batched(
                 || state_clone.clone(),
                 |mut state| {
-                    state.process_ejections(&spec_clone);
+                    process_registry_updates(&mut state, &spec_clone).unwrap();
                     state
                 },
-                criterion::BatchSize::SmallInput,
+                BatchSize::SmallInput,
             )
         })
-        .sample_size(BENCHING_SAMPLE_SIZE),
+        .sample_size(SAMPLE_SIZE),
     );
 
     let state_clone = state.clone();
     let spec_clone = spec.clone();
+    let current_epoch_balance = statuses.total_balances.current_epoch;
     c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("process_validator_registry", move |b| {
+        desc,
+        Benchmark::new("process_slashings", move |b| {
             b.iter_batched(
                 || state_clone.clone(),
                 |mut state| {
-                    process_validator_registry(&mut state, &spec_clone).unwrap();
+                    black_box(
+                        process_slashings(&mut state, current_epoch_balance, &spec_clone).unwrap(),
+                    );
                     state
                 },
-                criterion::BatchSize::SmallInput,
+                BatchSize::SmallInput,
             )
         })
-        .sample_size(BENCHING_SAMPLE_SIZE),
+        .sample_size(SAMPLE_SIZE),
     );
 
     let state_clone = state.clone();
     let spec_clone = spec.clone();
     c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("update_active_tree_index_roots", move |b| {
+        desc,
+        Benchmark::new("process_final_updates", move |b| {
             b.iter_batched(
                 || state_clone.clone(),
                 |mut state| {
-                    update_active_tree_index_roots(&mut state, &spec_clone).unwrap();
+                    process_final_updates(&mut state, &spec_clone).unwrap();
                     state
                 },
-                criterion::BatchSize::SmallInput,
+                BatchSize::SmallInput,
             )
         })
-        .sample_size(BENCHING_SAMPLE_SIZE),
+        .sample_size(SAMPLE_SIZE),
     );
 
     let state_clone = state.clone();
-    let spec_clone = spec.clone();
     c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("update_latest_slashed_balances", move |b| {
-            b.iter_batched(
-                || state_clone.clone(),
-                |mut state| {
-                    update_latest_slashed_balances(&mut state, &spec_clone);
-                    state
-                },
-                criterion::BatchSize::SmallInput,
-            )
+        desc,
+        Benchmark::new("tree_hash_state", move |b| {
+            b.iter(|| black_box(state_clone.tree_hash_root()))
         })
-        .sample_size(BENCHING_SAMPLE_SIZE),
+        .sample_size(SMALL_SAMPLE_SIZE),
     );
+}
+
+fn bench_epoch_processing_n_validators(c: &mut Criterion, validator_count: usize) {
+    let spec = E::default_spec();
+    let state = build_state(validator_count, &spec);
+    let desc = format!("{}_validators/epoch_processing", validator_count);
+
+    bench_epoch_processing_steps(c, &desc, &state, &spec);
 
-    let state_clone = state.clone();
     let spec_clone = spec.clone();
-    c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("clean_attestations", move |b| {
-            b.iter_batched(
-                || state_clone.clone(),
-                |mut state| {
-                    clean_attestations(&mut state, &spec_clone);
-                    state
-                },
-                criterion::BatchSize::SmallInput,
-            )
-        })
-        .sample_size(BENCHING_SAMPLE_SIZE),
-    );
+    bench_single_and_multi_threaded(c, &desc, "validator_statuses", &state, move |state| {
+        let mut statuses = ValidatorStatuses::new(state, &spec_clone).unwrap();
+        statuses.process_attestations(state, &spec_clone).unwrap();
+        black_box(statuses);
+    });
 
-    let state_clone = state.clone();
+    // Rewards require the validator statuses and winning roots, so they are included in the bench.
     let spec_clone = spec.clone();
-    c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("per_epoch_processing", move |b| {
-            b.iter_batched(
-                || state_clone.clone(),
-                |mut state| black_box(per_epoch_processing(&mut state, &spec_clone).unwrap()),
-                criterion::BatchSize::SmallInput,
-            )
-        })
-        .sample_size(SMALL_BENCHING_SAMPLE_SIZE),
-    );
+    bench_single_and_multi_threaded(c, &desc, "rewards_and_penalties", &state, move |state| {
+        let mut statuses = ValidatorStatuses::new(state, &spec_clone).unwrap();
+        statuses.process_attestations(state, &spec_clone).unwrap();
+        let winning_roots = process_crosslinks(state, &spec_clone).unwrap();
+        black_box(
+            process_rewards_and_penalties(state, &mut statuses, &winning_roots, &spec_clone)
+                .unwrap(),
+        );
+    });
 
-    let state_clone = state.clone();
-    c.bench(
-        &format!("{}/epoch_processing", desc),
-        Benchmark::new("tree_hash_state", move |b| {
-            b.iter(|| black_box(state_clone.tree_hash_root()))
-        })
-        .sample_size(SMALL_BENCHING_SAMPLE_SIZE),
-    );
+    let spec_clone = spec.clone();
+    bench_single_and_multi_threaded(c, &desc, "per_epoch_processing", &state, move |state| {
+        per_epoch_processing(state, &spec_clone).unwrap()
+    });
+}
+
+fn epoch_processing_16k_validators(c: &mut Criterion) {
+    bench_epoch_processing_n_validators(c, 16_384);
 }
+
+fn epoch_processing_300k_validators(c: &mut Criterion) {
+    bench_epoch_processing_n_validators(c, 300_000);
+}
+
+criterion_group!(
+    benches,
+    epoch_processing_16k_validators,
+    epoch_processing_300k_validators
+);
+criterion_main!(benches);
@@ -120,13 +120,8 @@ impl BlockBenchingBuilder {
         );
 
         // Insert `Deposit` objects.
-        for i in 0..self.num_deposits {
-            builder.insert_deposit(
-                32_000_000_000,
-                state.deposit_index + (i as u64),
-                &state,
-                spec,
-            );
+        for _ in 0..self.num_deposits {
+            builder.insert_deposit(32_000_000_000, spec);
         }
         info!("Inserted {} deposits.", builder.block.body.deposits.len());
 
@@ -151,7 +146,7 @@ impl BlockBenchingBuilder {
             let validator_index = validators_iter.next().expect("Insufficient validators.");
 
             // Manually set the validator to be withdrawn.
-            state.validator_registry[validator_index as usize].withdrawable_epoch =
+            state.validators[validator_index as usize].withdrawable_epoch =
                 state.previous_epoch(spec);
 
             builder.insert_transfer(
@@ -3,11 +3,11 @@ use types::*;
 
 /// Returns validator indices which participated in the attestation, sorted by increasing index.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn get_attesting_indices<T: EthSpec>(
     state: &BeaconState<T>,
     attestation_data: &AttestationData,
-    bitfield: &Bitfield,
+    bitfield: &BitList<T::MaxValidatorsPerCommittee>,
 ) -> Result<Vec<usize>, BeaconStateError> {
     get_attesting_indices_unsorted(state, attestation_data, bitfield).map(|mut indices| {
         // Fast unstable sort is safe because validator indices are unique
@@ -18,17 +18,19 @@ pub fn get_attesting_indices<T: EthSpec>(
 
 /// Returns validator indices which participated in the attestation, unsorted.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn get_attesting_indices_unsorted<T: EthSpec>(
     state: &BeaconState<T>,
     attestation_data: &AttestationData,
-    bitfield: &Bitfield,
+    bitfield: &BitList<T::MaxValidatorsPerCommittee>,
 ) -> Result<Vec<usize>, BeaconStateError> {
     let target_relative_epoch =
-        RelativeEpoch::from_epoch(state.current_epoch(), attestation_data.target_epoch)?;
+        RelativeEpoch::from_epoch(state.current_epoch(), attestation_data.target.epoch)?;
 
-    let committee =
-        state.get_crosslink_committee_for_shard(attestation_data.shard, target_relative_epoch)?;
+    let committee = state.get_crosslink_committee_for_shard(
+        attestation_data.crosslink.shard,
+        target_relative_epoch,
+    )?;
 
     if !verify_bitfield_length(&bitfield, committee.committee.len()) {
         return Err(BeaconStateError::InvalidBitfield);
@@ -0,0 +1,42 @@
+use tree_hash::TreeHash;
+use types::*;
+
+/// Return the compact committee root for the given `relative_epoch`.
+///
+/// Note: Utilizes the cache and will fail if the appropriate cache is not initialized.
+///
+/// Spec v0.8.1
+pub fn get_compact_committees_root<T: EthSpec>(
+    state: &BeaconState<T>,
+    relative_epoch: RelativeEpoch,
+    spec: &ChainSpec,
+) -> Result<Hash256, BeaconStateError> {
+    let mut committees: FixedLenVec<CompactCommittee<T>, T::ShardCount> =
+        vec![CompactCommittee::<T>::default(); T::shard_count()].into();
+
+    let start_shard = state.get_epoch_start_shard(relative_epoch)?;
+
+    for committee_number in 0..state.get_committee_count(relative_epoch)? {
+        let shard = (start_shard + committee_number) % T::ShardCount::to_u64();
+        let committee = &mut committees[shard as usize];
+
+        for &index in state
+            .get_crosslink_committee_for_shard(shard, relative_epoch)?
+            .committee
+        {
+            let validator = state
+                .validators
+                .get(index)
+                .ok_or(BeaconStateError::UnknownValidator)?;
+            committee.pubkeys.push(validator.pubkey.clone())?;
+
+            let compact_balance = validator.effective_balance / spec.effective_balance_increment;
+            // `index` (top 6 bytes) + `slashed` (16th bit) + `compact_balance` (bottom 15 bits)
+            let compact_validator: u64 =
+                ((index as u64) << 16) + (u64::from(validator.slashed) << 15) + compact_balance;
+            committee.compact_validators.push(compact_validator)?;
+        }
+    }
+
+    Ok(Hash256::from_slice(&committees.tree_hash_root()))
+}
@@ -0,0 +1,47 @@
+use super::get_attesting_indices;
+use crate::per_block_processing::errors::{
+    AttestationInvalid as Invalid, AttestationValidationError as Error,
+};
+use itertools::{Either, Itertools};
+use types::*;
+
+/// Convert `attestation` to (almost) indexed-verifiable form.
+///
+/// Spec v0.8.1
+pub fn get_indexed_attestation<T: EthSpec>(
+    state: &BeaconState<T>,
+    attestation: &Attestation<T>,
+) -> Result<IndexedAttestation<T>, Error> {
+    let attesting_indices =
+        get_attesting_indices(state, &attestation.data, &attestation.aggregation_bits)?;
+
+    let custody_bit_1_indices =
+        get_attesting_indices(state, &attestation.data, &attestation.custody_bits)?;
+
+    // Both lists are sorted, so membership can be checked with a binary search.
+    verify!(
+        custody_bit_1_indices
+            .iter()
+            .all(|i| attesting_indices.binary_search(i).is_ok()),
+        Invalid::CustodyBitfieldNotSubset
+    );
+
+    let (custody_bit_0_indices, custody_bit_1_indices): (Vec<u64>, Vec<u64>) = attesting_indices
+        .into_iter()
+        .partition_map(|validator_idx| {
+            if custody_bit_1_indices.binary_search(&validator_idx).is_ok() {
+                Either::Right(validator_idx as u64)
+            } else {
+                Either::Left(validator_idx as u64)
+            }
+        });
+
+    Ok(IndexedAttestation {
+        custody_bit_0_indices: VariableList::new(custody_bit_0_indices)
+            .map_err(BeaconStateError::from)?,
+        custody_bit_1_indices: VariableList::new(custody_bit_1_indices)
+            .map_err(BeaconStateError::from)?,
+        data: attestation.data.clone(),
+        signature: attestation.signature.clone(),
+    })
+}
@@ -1,20 +1,21 @@
+use safe_arith::SafeArith;
 use std::cmp::max;
 use types::{BeaconStateError as Error, *};
 
 /// Initiate the exit of the validator of the given `index`.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn initiate_validator_exit<T: EthSpec>(
     state: &mut BeaconState<T>,
     index: usize,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    if index >= state.validator_registry.len() {
+    if index >= state.validators.len() {
         return Err(Error::UnknownValidator);
     }
 
     // Return if the validator already initiated exit
-    if state.validator_registry[index].exit_epoch != spec.far_future_epoch {
+    if state.validators[index].exit_epoch != spec.far_future_epoch {
         return Ok(());
     }
 
@@ -27,13 +28,13 @@ pub fn initiate_validator_exit<T: EthSpec>(
     let exit_queue_churn = state.exit_cache.get_churn_at(exit_queue_epoch);
 
     if exit_queue_churn >= state.get_churn_limit(spec)? {
-        exit_queue_epoch += 1;
+        exit_queue_epoch.safe_add_assign(1)?;
     }
 
     state.exit_cache.record_validator_exit(exit_queue_epoch);
-    state.validator_registry[index].exit_epoch = exit_queue_epoch;
-    state.validator_registry[index].withdrawable_epoch =
-        exit_queue_epoch + spec.min_validator_withdrawability_delay;
+    state.validators[index].exit_epoch = exit_queue_epoch;
+    state.validators[index].withdrawable_epoch =
+        exit_queue_epoch.safe_add(spec.min_validator_withdrawability_delay)?;
 
     Ok(())
 }
@@ -1,11 +1,15 @@
-mod convert_to_indexed;
+mod get_base_reward;
 mod get_attesting_indices;
+mod get_compact_committees_root;
+mod get_indexed_attestation;
 mod initiate_validator_exit;
 mod slash_validator;
 mod verify_bitfield;
 
-pub use convert_to_indexed::convert_to_indexed;
+pub use get_base_reward::get_base_reward;
 pub use get_attesting_indices::{get_attesting_indices, get_attesting_indices_unsorted};
+pub use get_compact_committees_root::get_compact_committees_root;
+pub use get_indexed_attestation::get_indexed_attestation;
 pub use initiate_validator_exit::initiate_validator_exit;
 pub use slash_validator::slash_validator;
 pub use verify_bitfield::verify_bitfield_length;
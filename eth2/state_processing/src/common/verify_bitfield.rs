@@ -2,78 +2,69 @@ use types::*;
 
 /// Verify ``bitfield`` against the ``committee_size``.
 ///
-/// Is title `verify_bitfield` in spec.
+/// The length of a `BitList` is explicit in its encoding, so it must exactly equal the committee
+/// size.
 ///
-/// Spec v0.6.3
-pub fn verify_bitfield_length(bitfield: &Bitfield, committee_size: usize) -> bool {
-    if bitfield.num_bytes() != ((committee_size + 7) / 8) {
-        return false;
-    }
-
-    for i in committee_size..(bitfield.num_bytes() * 8) {
-        if bitfield.get(i).unwrap_or(false) {
-            return false;
-        }
-    }
-
-    true
+/// Spec v0.8.1
+pub fn verify_bitfield_length<N: Unsigned + Clone>(
+    bitfield: &BitList<N>,
+    committee_size: usize,
+) -> bool {
+    bitfield.len() == committee_size
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use types::typenum::U32;
+
+    type TestBitList = BitList<U32>;
 
     #[test]
     fn bitfield_length() {
-        assert_eq!(
-            verify_bitfield_length(&Bitfield::from_bytes(&[0b0000_0001]), 4),
-            true
-        );
+        assert!(verify_bitfield_length(
+            &TestBitList::from_bytes(vec![0b0001_0000]).unwrap(),
+            4
+        ));
 
-        assert_eq!(
-            verify_bitfield_length(&Bitfield::from_bytes(&[0b0001_0001]), 4),
-            false
-        );
+        assert!(verify_bitfield_length(
+            &TestBitList::from_bytes(vec![0b0001_0101]).unwrap(),
+            4
+        ));
 
-        assert_eq!(
-            verify_bitfield_length(&Bitfield::from_bytes(&[0b0000_0000]), 4),
-            true
-        );
+        assert!(!verify_bitfield_length(
+            &TestBitList::from_bytes(vec![0b0001_0000]).unwrap(),
+            5
+        ));
 
-        assert_eq!(
-            verify_bitfield_length(&Bitfield::from_bytes(&[0b1000_0000]), 8),
-            true
-        );
+        assert!(verify_bitfield_length(
+            &TestBitList::from_bytes(vec![0b0000_0001]).unwrap(),
+            0
+        ));
 
-        assert_eq!(
-            verify_bitfield_length(&Bitfield::from_bytes(&[0b1000_0000, 0b0000_0000]), 16),
-            true
-        );
+        assert!(verify_bitfield_length(
+            &TestBitList::from_bytes(vec![0b1000_0000, 0b0000_0001]).unwrap(),
+            8
+        ));
 
-        assert_eq!(
-            verify_bitfield_length(&Bitfield::from_bytes(&[0b1000_0000, 0b0000_0000]), 15),
-            false
-        );
+        assert!(verify_bitfield_length(
+            &TestBitList::from_bytes(vec![0b0000_0000, 0b1000_0000]).unwrap(),
+            15
+        ));
 
-        assert_eq!(
-            verify_bitfield_length(&Bitfield::from_bytes(&[0b0000_0000, 0b0000_0000]), 8),
-            false
-        );
+        assert!(!verify_bitfield_length(
+            &TestBitList::from_bytes(vec![0b0000_0000, 0b1000_0000]).unwrap(),
+            16
+        ));
 
-        assert_eq!(
-            verify_bitfield_length(
-                &Bitfield::from_bytes(&[0b0000_0000, 0b0000_0000, 0b0000_0000]),
-                8
-            ),
-            false
-        );
+        assert!(verify_bitfield_length(
+            &TestBitList::with_capacity(24).unwrap(),
+            24
+        ));
 
-        assert_eq!(
-            verify_bitfield_length(
-                &Bitfield::from_bytes(&[0b0000_0000, 0b0000_0000, 0b0000_0000]),
-                24
-            ),
-            true
-        );
+        assert!(!verify_bitfield_length(
+            &TestBitList::with_capacity(24).unwrap(),
+            8
+        ));
     }
 }
@@ -2,15 +2,24 @@
 mod macros;
 
 pub mod common;
-pub mod get_genesis_state;
+pub mod genesis;
 pub mod per_block_processing;
 pub mod per_epoch_processing;
 pub mod per_slot_processing;
+pub mod state_diff;
+pub mod upgrade;
 
-pub use get_genesis_state::get_genesis_beacon_state;
+pub use genesis::{initialize_beacon_state_from_eth1, is_valid_genesis_state};
 pub use per_block_processing::{
     errors::{BlockInvalid, BlockProcessingError},
-    per_block_processing, per_block_processing_without_verifying_block_signature,
+    per_block_processing, per_block_processing_with_tracer,
+    per_block_processing_without_verifying_block_signature,
 };
-pub use per_epoch_processing::{errors::EpochProcessingError, per_epoch_processing};
-pub use per_slot_processing::{per_slot_processing, Error as SlotProcessingError};
+pub use per_epoch_processing::{
+    errors::EpochProcessingError, per_epoch_processing, per_epoch_processing_with_rewards,
+    per_epoch_processing_with_tracer, NoRewards, RewardCollector, RewardReport, ValidatorRewards,
+};
+pub use per_slot_processing::{
+    per_slot_processing, per_slot_processing_with_tracer, Error as SlotProcessingError,
+};
+pub use state_diff::{NoTracer, StateDiffReport, StateDiffTracer, StateTracer};
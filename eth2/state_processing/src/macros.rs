@@ -12,11 +12,9 @@ macro_rules! invalid {
     };
 }
 
-macro_rules! safe_add_assign {
-    ($a: expr, $b: expr) => {
-        $a = $a.saturating_add($b);
-    };
-}
+/// Subtract `$b` from `$a`, saturating at zero as per the spec's `decrease_balance`.
+///
+/// Increases should use `SafeArith::safe_add_assign`, as overflow indicates an invalid state.
 macro_rules! safe_sub_assign {
     ($a: expr, $b: expr) => {
         $a = $a.saturating_sub($b);
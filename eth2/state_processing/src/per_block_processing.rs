@@ -1,6 +1,10 @@
 use crate::common::{initiate_validator_exit, slash_validator};
-use errors::{BlockInvalid as Invalid, BlockProcessingError as Error, IntoWithIndex};
+use crate::state_diff::{NoTracer, StateTracer};
+use errors::{
+    BlockInvalid as Invalid, BlockProcessingError as Error, DepositValidationError, IntoWithIndex,
+};
 use rayon::prelude::*;
+use safe_arith::SafeArith;
 use tree_hash::{SignedRoot, TreeHash};
 use types::*;
 
@@ -13,8 +17,7 @@ pub use validate_attestation::{
     validate_attestation_without_signature,
 };
 pub use verify_deposit::{
-    get_existing_validator_index, verify_deposit_index, verify_deposit_merkle_proof,
-    verify_deposit_signature,
+    get_existing_validator_index, verify_deposit_merkle_proof, verify_deposit_signature,
 };
 pub use verify_exit::{verify_exit, verify_exit_time_independent_only};
 pub use verify_indexed_attestation::{
@@ -40,13 +43,13 @@ mod verify_transfer;
 /// Returns `Ok(())` if the block is valid and the state was successfully updated. Otherwise
 /// returns an error describing why the block was invalid or how the function failed to execute.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn per_block_processing<T: EthSpec>(
     state: &mut BeaconState<T>,
-    block: &BeaconBlock,
+    block: &BeaconBlock<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    per_block_processing_signature_optional(state, block, true, spec)
+    per_block_processing_signature_optional(state, block, true, spec, &mut NoTracer)
 }
 
 /// Updates the state for a new block, whilst validating that the block is valid, without actually
@@ -55,13 +58,36 @@ pub fn per_block_processing<T: EthSpec>(
 /// Returns `Ok(())` if the block is valid and the state was successfully updated. Otherwise
 /// returns an error describing why the block was invalid or how the function failed to execute.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn per_block_processing_without_verifying_block_signature<T: EthSpec>(
     state: &mut BeaconState<T>,
-    block: &BeaconBlock,
+    block: &BeaconBlock<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    per_block_processing_signature_optional(state, block, false, spec)
+    per_block_processing_signature_optional(state, block, false, spec, &mut NoTracer)
+}
+
+/// Updates the state for a new block, whilst validating that the block is valid, optionally
+/// checking the block proposer signature and passing the state to `tracer` after each step.
+///
+/// Returns `Ok(())` if the block is valid and the state was successfully updated. Otherwise
+/// returns an error describing why the block was invalid or how the function failed to execute.
+///
+/// Spec v0.8.1
+pub fn per_block_processing_with_tracer<T: EthSpec, U: StateTracer<T>>(
+    state: &mut BeaconState<T>,
+    block: &BeaconBlock<T>,
+    should_verify_block_signature: bool,
+    spec: &ChainSpec,
+    tracer: &mut U,
+) -> Result<(), Error> {
+    per_block_processing_signature_optional(
+        state,
+        block,
+        should_verify_block_signature,
+        spec,
+        tracer,
+    )
 }
 
 /// Updates the state for a new block, whilst validating that the block is valid, optionally
@@ -70,49 +96,62 @@ pub fn per_block_processing_without_verifying_block_signature<T: EthSpec>(
 /// Returns `Ok(())` if the block is valid and the state was successfully updated. Otherwise
 /// returns an error describing why the block was invalid or how the function failed to execute.
 ///
-/// Spec v0.6.3
-fn per_block_processing_signature_optional<T: EthSpec>(
+/// Spec v0.8.1
+fn per_block_processing_signature_optional<T: EthSpec, U: StateTracer<T>>(
     mut state: &mut BeaconState<T>,
-    block: &BeaconBlock,
+    block: &BeaconBlock<T>,
     should_verify_block_signature: bool,
     spec: &ChainSpec,
+    tracer: &mut U,
 ) -> Result<(), Error> {
+    // Use the constants of the block's epoch, as modified by any scheduled forks.
+    let spec_at_epoch = spec.at_epoch(state.current_epoch());
+    let spec: &ChainSpec = &spec_at_epoch;
+
     process_block_header(state, block, spec, should_verify_block_signature)?;
+    tracer.trace("process_block_header", state);
 
     // Ensure the current and previous epoch caches are built.
     state.build_committee_cache(RelativeEpoch::Previous, spec)?;
     state.build_committee_cache(RelativeEpoch::Current, spec)?;
 
     process_randao(&mut state, &block, &spec)?;
+    tracer.trace("process_randao", state);
     process_eth1_data(&mut state, &block.body.eth1_data, spec)?;
+    tracer.trace("process_eth1_data", state);
     process_proposer_slashings(&mut state, &block.body.proposer_slashings, spec)?;
+    tracer.trace("process_proposer_slashings", state);
     process_attester_slashings(&mut state, &block.body.attester_slashings, spec)?;
+    tracer.trace("process_attester_slashings", state);
     process_attestations(&mut state, &block.body.attestations, spec)?;
+    tracer.trace("process_attestations", state);
     process_deposits(&mut state, &block.body.deposits, spec)?;
+    tracer.trace("process_deposits", state);
     process_exits(&mut state, &block.body.voluntary_exits, spec)?;
+    tracer.trace("process_exits", state);
     process_transfers(&mut state, &block.body.transfers, spec)?;
+    tracer.trace("process_transfers", state);
 
     Ok(())
 }
 
 /// Processes the block header.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_block_header<T: EthSpec>(
     state: &mut BeaconState<T>,
-    block: &BeaconBlock,
+    block: &BeaconBlock<T>,
     spec: &ChainSpec,
     should_verify_block_signature: bool,
 ) -> Result<(), Error> {
     verify!(block.slot == state.slot, Invalid::StateSlotMismatch);
 
-    let expected_previous_block_root =
-        Hash256::from_slice(&state.latest_block_header.signed_root());
+    let expected_parent_root = Hash256::from_slice(&state.latest_block_header.signed_root());
     verify!(
-        block.previous_block_root == expected_previous_block_root,
+        block.parent_root == expected_parent_root,
         Invalid::ParentBlockRootMismatch {
-            state: expected_previous_block_root,
-            block: block.previous_block_root,
+            state: expected_parent_root,
+            block: block.parent_root,
         }
     );
 
@@ -120,7 +159,7 @@ pub fn process_block_header<T: EthSpec>(
 
     // Verify proposer is not slashed
     let proposer_idx = state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, spec)?;
-    let proposer = &state.validator_registry[proposer_idx];
+    let proposer = &state.validators[proposer_idx];
     verify!(!proposer.slashed, Invalid::ProposerSlashed(proposer_idx));
 
     if should_verify_block_signature {
@@ -132,13 +171,13 @@ pub fn process_block_header<T: EthSpec>(
 
 /// Verifies the signature of a block.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn verify_block_signature<T: EthSpec>(
     state: &BeaconState<T>,
-    block: &BeaconBlock,
+    block: &BeaconBlock<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    let block_proposer = &state.validator_registry
+    let block_proposer = &state.validators
         [state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, spec)?];
 
     let domain = spec.get_domain(
@@ -158,15 +197,15 @@ pub fn verify_block_signature<T: EthSpec>(
 }
 
 /// Verifies the `randao_reveal` against the block's proposer pubkey and updates
-/// `state.latest_randao_mixes`.
+/// `state.randao_mixes`.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_randao<T: EthSpec>(
     state: &mut BeaconState<T>,
-    block: &BeaconBlock,
+    block: &BeaconBlock<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    let block_proposer = &state.validator_registry
+    let block_proposer = &state.validators
         [state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, spec)?];
 
     // Verify the RANDAO is a valid signature of the proposer.
@@ -191,13 +230,16 @@ pub fn process_randao<T: EthSpec>(
 
 /// Update the `state.eth1_data_votes` based upon the `eth1_data` provided.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_eth1_data<T: EthSpec>(
     state: &mut BeaconState<T>,
     eth1_data: &Eth1Data,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    state.eth1_data_votes.push(eth1_data.clone());
+    state
+        .eth1_data_votes
+        .push(eth1_data.clone())
+        .map_err(BeaconStateError::from)?;
 
     let num_votes = state
         .eth1_data_votes
@@ -206,7 +248,7 @@ pub fn process_eth1_data<T: EthSpec>(
         .count() as u64;
 
     if num_votes * 2 > spec.slots_per_eth1_voting_period {
-        state.latest_eth1_data = eth1_data.clone();
+        state.eth1_data = eth1_data.clone();
     }
 
     Ok(())
@@ -217,7 +259,7 @@ pub fn process_eth1_data<T: EthSpec>(
 /// Returns `Ok(())` if the validation and state updates completed successfully, otherwise returns
 /// an `Err` describing the invalid object or cause of failure.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_proposer_slashings<T: EthSpec>(
     state: &mut BeaconState<T>,
     proposer_slashings: &[ProposerSlashing],
@@ -250,10 +292,10 @@ pub fn process_proposer_slashings<T: EthSpec>(
 /// Returns `Ok(())` if the validation and state updates completed successfully, otherwise returns
 /// an `Err` describing the invalid object or cause of failure.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_attester_slashings<T: EthSpec>(
     state: &mut BeaconState<T>,
-    attester_slashings: &[AttesterSlashing],
+    attester_slashings: &[AttesterSlashing<T>],
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     verify!(
@@ -263,7 +305,7 @@ pub fn process_attester_slashings<T: EthSpec>(
 
     // Verify the `IndexedAttestation`s in parallel (these are the resource-consuming objects, not
     // the `AttesterSlashing`s themselves).
-    let mut indexed_attestations: Vec<&IndexedAttestation> =
+    let mut indexed_attestations: Vec<&IndexedAttestation<T>> =
         Vec::with_capacity(attester_slashings.len() * 2);
     for attester_slashing in attester_slashings {
         indexed_attestations.push(&attester_slashing.attestation_1);
@@ -308,10 +350,10 @@ pub fn process_attester_slashings<T: EthSpec>(
 /// Returns `Ok(())` if the validation and state updates completed successfully, otherwise returns
 /// an `Err` describing the invalid object or cause of failure.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_attestations<T: EthSpec>(
     state: &mut BeaconState<T>,
-    attestations: &[Attestation],
+    attestations: &[Attestation<T>],
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     verify!(
@@ -334,18 +376,24 @@ pub fn process_attestations<T: EthSpec>(
     let proposer_index =
         state.get_beacon_proposer_index(state.slot, RelativeEpoch::Current, spec)? as u64;
     for attestation in attestations {
-        let attestation_slot = state.get_attestation_slot(&attestation.data)?;
+        let attestation_slot = state.get_attestation_data_slot(&attestation.data)?;
         let pending_attestation = PendingAttestation {
-            aggregation_bitfield: attestation.aggregation_bitfield.clone(),
+            aggregation_bits: attestation.aggregation_bits.clone(),
             data: attestation.data.clone(),
             inclusion_delay: (state.slot - attestation_slot).as_u64(),
             proposer_index,
         };
 
-        if attestation.data.target_epoch == state.current_epoch() {
-            state.current_epoch_attestations.push(pending_attestation)
+        if attestation.data.target.epoch == state.current_epoch() {
+            state
+                .current_epoch_attestations
+                .push(pending_attestation)
+                .map_err(BeaconStateError::from)?;
         } else {
-            state.previous_epoch_attestations.push(pending_attestation)
+            state
+                .previous_epoch_attestations
+                .push(pending_attestation)
+                .map_err(BeaconStateError::from)?;
         }
     }
 
@@ -357,73 +405,97 @@ pub fn process_attestations<T: EthSpec>(
 /// Returns `Ok(())` if the validation and state updates completed successfully, otherwise returns
 /// an `Err` describing the invalid object or cause of failure.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_deposits<T: EthSpec>(
     state: &mut BeaconState<T>,
     deposits: &[Deposit],
     spec: &ChainSpec,
 ) -> Result<(), Error> {
+    let pending_deposits = state
+        .eth1_data
+        .deposit_count
+        .safe_sub(state.eth1_deposit_index)?;
     verify!(
-        deposits.len() as u64
-            == std::cmp::min(
-                spec.max_deposits,
-                state.latest_eth1_data.deposit_count - state.deposit_index
-            ),
+        deposits.len() as u64 == std::cmp::min(spec.max_deposits, pending_deposits),
         Invalid::DepositCountInvalid
     );
 
-    // Verify deposits in parallel.
+    // Verify merkle proofs in parallel, each deposit is proven at the index it will be processed
+    // at.
     deposits
         .par_iter()
         .enumerate()
         .try_for_each(|(i, deposit)| {
-            verify_deposit_merkle_proof(state, deposit, spec).map_err(|e| e.into_with_index(i))
+            let deposit_index = state.eth1_deposit_index + i as u64;
+            verify_deposit_merkle_proof(state, deposit, deposit_index, spec)
+                .map_err(|e| e.into_with_index(i))
         })?;
 
-    // Check `state.deposit_index` and update the state in series.
+    // Update the state in series.
     for (i, deposit) in deposits.iter().enumerate() {
-        verify_deposit_index(state, deposit).map_err(|e| e.into_with_index(i))?;
+        process_deposit(state, deposit, spec, false).map_err(|e| e.into_with_index(i))?;
+    }
 
-        state.deposit_index += 1;
+    Ok(())
+}
 
-        // Ensure the state's pubkey cache is fully up-to-date, it will be used to check to see if the
-        // depositing validator already exists in the registry.
-        state.update_pubkey_cache()?;
+/// Process a single deposit, optionally verifying its merkle proof.
+///
+/// Spec v0.8.1
+pub fn process_deposit<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    deposit: &Deposit,
+    spec: &ChainSpec,
+    verify_merkle_proof: bool,
+) -> Result<(), DepositValidationError> {
+    if verify_merkle_proof {
+        verify_deposit_merkle_proof(state, deposit, state.eth1_deposit_index, spec)?;
+    }
 
-        // Get an `Option<u64>` where `u64` is the validator index if this deposit public key
-        // already exists in the beacon_state.
-        let validator_index =
-            get_existing_validator_index(state, deposit).map_err(|e| e.into_with_index(i))?;
+    state.eth1_deposit_index.safe_add_assign(1)?;
 
-        let amount = deposit.data.amount;
+    // Ensure the state's pubkey cache is fully up-to-date, it will be used to check to see if the
+    // depositing validator already exists in the registry.
+    state.update_pubkey_cache()?;
 
-        if let Some(index) = validator_index {
-            // Update the existing validator balance.
-            safe_add_assign!(state.balances[index as usize], amount);
-        } else {
-            // The signature should be checked for new validators. Return early for a bad
-            // signature.
-            if verify_deposit_signature(state, deposit, spec).is_err() {
-                return Ok(());
-            }
-
-            // Create a new validator.
-            let validator = Validator {
-                pubkey: deposit.data.pubkey.clone(),
-                withdrawal_credentials: deposit.data.withdrawal_credentials,
-                activation_eligibility_epoch: spec.far_future_epoch,
-                activation_epoch: spec.far_future_epoch,
-                exit_epoch: spec.far_future_epoch,
-                withdrawable_epoch: spec.far_future_epoch,
-                effective_balance: std::cmp::min(
-                    amount - amount % spec.effective_balance_increment,
-                    spec.max_effective_balance,
-                ),
-                slashed: false,
-            };
-            state.validator_registry.push(validator);
-            state.balances.push(deposit.data.amount);
+    // Get an `Option<u64>` where `u64` is the validator index if this deposit public key
+    // already exists in the beacon_state.
+    let validator_index = get_existing_validator_index(state, deposit)?;
+
+    let amount = deposit.data.amount;
+
+    if let Some(index) = validator_index {
+        // Update the existing validator balance.
+        state.balances[index as usize].safe_add_assign(amount)?;
+    } else {
+        // The signature should be checked for new validators. Return early for a bad
+        // signature.
+        if verify_deposit_signature(deposit, spec).is_err() {
+            return Ok(());
         }
+
+        // Create a new validator.
+        let validator = Validator {
+            pubkey: deposit.data.pubkey.clone(),
+            withdrawal_credentials: deposit.data.withdrawal_credentials,
+            activation_eligibility_epoch: spec.far_future_epoch,
+            activation_epoch: spec.far_future_epoch,
+            exit_epoch: spec.far_future_epoch,
+            withdrawable_epoch: spec.far_future_epoch,
+            effective_balance: std::cmp::min(
+                amount.safe_sub(amount.safe_rem(spec.effective_balance_increment)?)?,
+                spec.max_effective_balance,
+            ),
+            slashed: false,
+        };
+        state
+            .validators
+            .push(validator)
+            .map_err(BeaconStateError::from)?;
+        state
+            .balances
+            .push(deposit.data.amount)
+            .map_err(BeaconStateError::from)?;
     }
 
     Ok(())
@@ -434,7 +506,7 @@ pub fn process_deposits<T: EthSpec>(
 /// Returns `Ok(())` if the validation and state updates completed successfully, otherwise returns
 /// an `Err` describing the invalid object or cause of failure.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_exits<T: EthSpec>(
     state: &mut BeaconState<T>,
     voluntary_exits: &[VoluntaryExit],
@@ -466,7 +538,7 @@ pub fn process_exits<T: EthSpec>(
 /// Returns `Ok(())` if the validation and state updates completed successfully, otherwise returns
 /// an `Err` describing the invalid object or cause of failure.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_transfers<T: EthSpec>(
     state: &mut BeaconState<T>,
     transfers: &[Transfer],
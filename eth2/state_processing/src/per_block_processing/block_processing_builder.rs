@@ -4,7 +4,7 @@ use types::*;
 
 pub struct BlockProcessingBuilder<T: EthSpec> {
     pub state_builder: TestingBeaconStateBuilder<T>,
-    pub block_builder: TestingBeaconBlockBuilder,
+    pub block_builder: TestingBeaconBlockBuilder<T>,
 
     pub num_validators: usize,
 }
@@ -36,15 +36,15 @@ impl<T: EthSpec> BlockProcessingBuilder<T> {
         randao_sk: Option<SecretKey>,
         previous_block_root: Option<Hash256>,
         spec: &ChainSpec,
-    ) -> (BeaconBlock, BeaconState<T>) {
+    ) -> (BeaconBlock<T>, BeaconState<T>) {
         let (state, keypairs) = self.state_builder.build();
         let builder = &mut self.block_builder;
 
         builder.set_slot(state.slot);
 
         match previous_block_root {
-            Some(root) => builder.set_previous_block_root(root),
-            None => builder.set_previous_block_root(Hash256::from_slice(
+            Some(root) => builder.set_parent_root(root),
+            None => builder.set_parent_root(Hash256::from_slice(
                 &state.latest_block_header.signed_root(),
             )),
         }
@@ -55,13 +55,11 @@ impl<T: EthSpec> BlockProcessingBuilder<T> {
         let keypair = &keypairs[proposer_index];
 
         match randao_sk {
-            Some(sk) => builder.set_randao_reveal::<T>(&sk, &state.fork, spec),
-            None => builder.set_randao_reveal::<T>(&keypair.sk, &state.fork, spec),
+            Some(sk) => builder.set_randao_reveal(&sk, &state.fork, spec),
+            None => builder.set_randao_reveal(&keypair.sk, &state.fork, spec),
         }
 
-        let block = self
-            .block_builder
-            .build::<T>(&keypair.sk, &state.fork, spec);
+        let block = self.block_builder.build(&keypair.sk, &state.fork, spec);
 
         (block, state)
     }
@@ -1,3 +1,4 @@
+use safe_arith::ArithError;
 use types::*;
 
 macro_rules! impl_from_beacon_state_error {
@@ -59,10 +60,18 @@ pub enum BlockProcessingError {
     Invalid(BlockInvalid),
     /// Encountered a `BeaconStateError` whilst attempting to determine validity.
     BeaconStateError(BeaconStateError),
+    /// An arithmetic operation overflowed or divided by zero whilst processing the block.
+    ArithError(ArithError),
 }
 
 impl_from_beacon_state_error!(BlockProcessingError);
 
+impl From<ArithError> for BlockProcessingError {
+    fn from(e: ArithError) -> BlockProcessingError {
+        BlockProcessingError::ArithError(e)
+    }
+}
+
 /// Describes why an object is invalid.
 #[derive(Debug, PartialEq)]
 pub enum BlockInvalid {
@@ -128,38 +137,40 @@ pub enum AttestationInvalid {
     IncludedTooLate { state: Slot, attestation: Slot },
     /// Attestation target epoch does not match the current or previous epoch.
     BadTargetEpoch,
-    /// Attestation justified epoch does not match the states current or previous justified epoch.
+    /// Attestation references a shard greater than `SHARD_COUNT`.
+    BadShard,
+    /// Attestation source checkpoint does not match the state's current or previous justified
+    /// checkpoint.
     ///
     /// `is_current` is `true` if the attestation was compared to the
-    /// `state.current_justified_epoch`, `false` if compared to `state.previous_justified_epoch`.
-    WrongJustifiedEpoch {
-        state: Epoch,
-        attestation: Epoch,
-        is_current: bool,
-    },
-    /// Attestation justified epoch root does not match root known to the state.
-    ///
-    /// `is_current` is `true` if the attestation was compared to the
-    /// `state.current_justified_epoch`, `false` if compared to `state.previous_justified_epoch`.
-    WrongJustifiedRoot {
-        state: Hash256,
-        attestation: Hash256,
+    /// `state.current_justified_checkpoint`, `false` if compared to
+    /// `state.previous_justified_checkpoint`.
+    WrongJustifiedCheckpoint {
+        state: Checkpoint,
+        attestation: Checkpoint,
         is_current: bool,
     },
     /// Attestation crosslink root does not match the state crosslink root for the attestations
     /// slot.
-    BadPreviousCrosslink,
+    BadParentCrosslinkHash,
+    /// Attestation crosslink start epoch does not match the end epoch of the state crosslink.
+    BadParentCrosslinkStartEpoch,
+    /// Attestation crosslink end epoch does not match the expected value.
+    BadParentCrosslinkEndEpoch,
     /// The custody bitfield has some bits set `true`. This is not allowed in phase 0.
     CustodyBitfieldHasSetBits,
+    /// The custody bitfield has bits set for validators which are not in the aggregation
+    /// bitfield.
+    CustodyBitfieldNotSubset,
     /// There are no set bits on the attestation -- an attestation must be signed by at least one
     /// validator.
     AggregationBitfieldIsEmpty,
-    /// The custody bitfield length is not the smallest possible size to represent the committee.
+    /// The custody bitfield length does not match the committee size.
     BadCustodyBitfieldLength {
         committee_len: usize,
         bitfield_len: usize,
     },
-    /// The aggregation bitfield length is not the smallest possible size to represent the committee.
+    /// The aggregation bitfield length does not match the committee size.
     BadAggregationBitfieldLength {
         committee_len: usize,
         bitfield_len: usize,
@@ -319,7 +330,7 @@ pub enum DepositValidationError {
 /// Describes why an object is invalid.
 #[derive(Debug, PartialEq)]
 pub enum DepositInvalid {
-    /// The deposit index does not match the state index.
+    /// The deposit index conflicts with a deposit that is already known.
     BadIndex { state: u64, deposit: u64 },
     /// The signature (proof-of-possession) does not match the given pubkey.
     BadSignature,
@@ -331,6 +342,12 @@ pub enum DepositInvalid {
 impl_from_beacon_state_error!(DepositValidationError);
 impl_into_with_index_with_beacon_error!(DepositValidationError, DepositInvalid);
 
+impl From<ArithError> for DepositValidationError {
+    fn from(e: ArithError) -> DepositValidationError {
+        DepositValidationError::BeaconStateError(e.into())
+    }
+}
+
 /*
  * `Exit` Validation
  */
@@ -1,8 +1,12 @@
 #![cfg(all(test, not(feature = "fake_crypto")))]
 use super::block_processing_builder::BlockProcessingBuilder;
 use super::errors::*;
+use crate::common::slash_validator;
 use crate::per_block_processing;
+use crate::per_block_processing::verify_transfer;
+use safe_arith::ArithError;
 use tree_hash::SignedRoot;
+use types::test_utils::TestingTransferBuilder;
 use types::*;
 
 pub const VALIDATOR_COUNT: usize = 10;
@@ -51,7 +55,7 @@ fn invalid_parent_block_root() {
         Err(BlockProcessingError::Invalid(
             BlockInvalid::ParentBlockRootMismatch {
                 state: Hash256::from_slice(&state.latest_block_header.signed_root()),
-                block: block.previous_block_root
+                block: block.parent_root
             }
         ))
     );
@@ -100,6 +104,86 @@ fn invalid_randao_reveal_signature() {
     );
 }
 
+#[test]
+fn deposit_index_beyond_deposit_count() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec);
+    let (block, mut state) = builder.build(None, None, &spec);
+
+    // A crafted state where more deposits have been processed than exist.
+    state.eth1_deposit_index = state.eth1_data.deposit_count + 1;
+
+    let result = per_block_processing(&mut state, &block, &spec);
+
+    assert_eq!(
+        result,
+        Err(BlockProcessingError::ArithError(ArithError::Overflow))
+    );
+}
+
+#[test]
+fn transfer_to_max_balance() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec);
+    let (_block, mut state) = builder.build(None, None, &spec);
+
+    state.balances[1] = u64::max_value();
+    let transfer = TestingTransferBuilder::new(0, 1, 1, state.slot).build();
+
+    assert_eq!(
+        verify_transfer(&state, &transfer, &spec),
+        Err(TransferValidationError::Invalid(
+            TransferInvalid::ToBalanceOverflow(u64::max_value(), 1)
+        ))
+    );
+}
+
+#[test]
+fn transfer_fee_exceeding_balance() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec);
+    let (_block, state) = builder.build(None, None, &spec);
+
+    // The sender can afford the amount, but not the amount plus the fee.
+    let mut transfer = TestingTransferBuilder::new(0, 1, state.balances[0], state.slot).build();
+    transfer.fee = 1;
+
+    assert_eq!(
+        verify_transfer(&state, &transfer, &spec),
+        Err(TransferValidationError::Invalid(
+            TransferInvalid::SenderDust(0, spec.min_deposit_amount)
+        ))
+    );
+
+    // The amount and fee cannot be summed.
+    transfer.amount = u64::max_value();
+
+    assert_eq!(
+        verify_transfer(&state, &transfer, &spec),
+        Err(TransferValidationError::Invalid(
+            TransferInvalid::FeeOverflow(u64::max_value(), 1)
+        ))
+    );
+}
+
+#[test]
+fn whistleblower_reward_to_max_balance() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec);
+    let (_block, mut state) = builder.build(None, None, &spec);
+
+    let proposer_index = state
+        .get_beacon_proposer_index(state.slot, RelativeEpoch::Current, &spec)
+        .unwrap();
+    let slashed_index = (proposer_index + 1) % VALIDATOR_COUNT;
+    state.balances[proposer_index] = u64::max_value();
+
+    assert_eq!(
+        slash_validator(&mut state, slashed_index, None, &spec),
+        Err(BeaconStateError::ArithError(ArithError::Overflow))
+    );
+}
+
 fn get_builder(spec: &ChainSpec) -> (BlockProcessingBuilder<MainnetEthSpec>) {
     let mut builder = BlockProcessingBuilder::new(VALIDATOR_COUNT, &spec);
 
@@ -1,5 +1,5 @@
 use super::errors::{AttestationInvalid as Invalid, AttestationValidationError as Error};
-use crate::common::convert_to_indexed;
+use crate::common::get_indexed_attestation;
 use crate::per_block_processing::{
     verify_indexed_attestation, verify_indexed_attestation_without_signature,
 };
@@ -11,10 +11,10 @@ use types::*;
 ///
 /// Returns `Ok(())` if the `Attestation` is valid, otherwise indicates the reason for invalidity.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn validate_attestation<T: EthSpec>(
     state: &BeaconState<T>,
-    attestation: &Attestation,
+    attestation: &Attestation<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     validate_attestation_parametric(state, attestation, spec, true, false)
@@ -23,7 +23,7 @@ pub fn validate_attestation<T: EthSpec>(
 /// Like `validate_attestation` but doesn't run checks which may become true in future states.
 pub fn validate_attestation_time_independent_only<T: EthSpec>(
     state: &BeaconState<T>,
-    attestation: &Attestation,
+    attestation: &Attestation<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     validate_attestation_parametric(state, attestation, spec, true, true)
@@ -34,10 +34,10 @@ pub fn validate_attestation_time_independent_only<T: EthSpec>(
 ///
 /// Returns `Ok(())` if the `Attestation` is valid, otherwise indicates the reason for invalidity.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn validate_attestation_without_signature<T: EthSpec>(
     state: &BeaconState<T>,
-    attestation: &Attestation,
+    attestation: &Attestation<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     validate_attestation_parametric(state, attestation, spec, false, false)
@@ -47,17 +47,23 @@ pub fn validate_attestation_without_signature<T: EthSpec>(
 /// given state, optionally validating the aggregate signature.
 ///
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 fn validate_attestation_parametric<T: EthSpec>(
     state: &BeaconState<T>,
-    attestation: &Attestation,
+    attestation: &Attestation<T>,
     spec: &ChainSpec,
     verify_signature: bool,
     time_independent_only: bool,
 ) -> Result<(), Error> {
-    let attestation_slot = state.get_attestation_slot(&attestation.data)?;
+    let data = &attestation.data;
+    verify!(
+        data.crosslink.shard < T::ShardCount::to_u64(),
+        Invalid::BadShard
+    );
 
     // Check attestation slot.
+    let attestation_slot = state.get_attestation_data_slot(&data)?;
+
     verify!(
         time_independent_only
             || attestation_slot + spec.min_attestation_inclusion_delay <= state.slot,
@@ -75,19 +81,36 @@ fn validate_attestation_parametric<T: EthSpec>(
         }
     );
 
-    // Verify the Casper FFG vote.
+    // Verify the Casper FFG vote and crosslink data.
     if !time_independent_only {
-        verify_casper_ffg_vote(attestation, state)?;
+        let parent_crosslink = verify_casper_ffg_vote(attestation, state)?;
+
+        verify!(
+            data.crosslink.parent_root == Hash256::from_slice(&parent_crosslink.tree_hash_root()),
+            Invalid::BadParentCrosslinkHash
+        );
+        verify!(
+            data.crosslink.start_epoch == parent_crosslink.end_epoch,
+            Invalid::BadParentCrosslinkStartEpoch
+        );
+        verify!(
+            data.crosslink.end_epoch
+                == std::cmp::min(
+                    data.target.epoch,
+                    parent_crosslink.end_epoch + spec.max_epochs_per_crosslink
+                ),
+            Invalid::BadParentCrosslinkEndEpoch
+        );
     }
 
     // Crosslink data root is zero (to be removed in phase 1).
     verify!(
-        attestation.data.crosslink_data_root == spec.zero_hash,
+        attestation.data.crosslink.data_root == spec.zero_hash,
         Invalid::ShardBlockRootNotZero
     );
 
     // Check signature and bitfields
-    let indexed_attestation = convert_to_indexed(state, attestation)?;
+    let indexed_attestation = get_indexed_attestation(state, attestation)?;
     if verify_signature {
         verify_indexed_attestation(state, &indexed_attestation, spec)?;
     } else {
@@ -97,60 +120,37 @@ fn validate_attestation_parametric<T: EthSpec>(
     Ok(())
 }
 
-/// Check target epoch, source epoch, source root, and source crosslink.
+/// Check target epoch and source checkpoint.
 ///
-/// Spec v0.6.3
-fn verify_casper_ffg_vote<T: EthSpec>(
-    attestation: &Attestation,
-    state: &BeaconState<T>,
-) -> Result<(), Error> {
+/// Return the parent crosslink for further checks.
+///
+/// Spec v0.8.1
+fn verify_casper_ffg_vote<'a, T: EthSpec>(
+    attestation: &Attestation<T>,
+    state: &'a BeaconState<T>,
+) -> Result<&'a Crosslink, Error> {
     let data = &attestation.data;
-    if data.target_epoch == state.current_epoch() {
+    if data.target.epoch == state.current_epoch() {
         verify!(
-            data.source_epoch == state.current_justified_epoch,
-            Invalid::WrongJustifiedEpoch {
-                state: state.current_justified_epoch,
-                attestation: data.source_epoch,
+            data.source == state.current_justified_checkpoint,
+            Invalid::WrongJustifiedCheckpoint {
+                state: state.current_justified_checkpoint.clone(),
+                attestation: data.source.clone(),
                 is_current: true,
             }
         );
+        Ok(state.get_current_crosslink(data.crosslink.shard)?)
+    } else if data.target.epoch == state.previous_epoch() {
         verify!(
-            data.source_root == state.current_justified_root,
-            Invalid::WrongJustifiedRoot {
-                state: state.current_justified_root,
-                attestation: data.source_root,
-                is_current: true,
-            }
-        );
-        verify!(
-            data.previous_crosslink_root
-                == Hash256::from_slice(&state.get_current_crosslink(data.shard)?.tree_hash_root()),
-            Invalid::BadPreviousCrosslink
-        );
-    } else if data.target_epoch == state.previous_epoch() {
-        verify!(
-            data.source_epoch == state.previous_justified_epoch,
-            Invalid::WrongJustifiedEpoch {
-                state: state.previous_justified_epoch,
-                attestation: data.source_epoch,
+            data.source == state.previous_justified_checkpoint,
+            Invalid::WrongJustifiedCheckpoint {
+                state: state.previous_justified_checkpoint.clone(),
+                attestation: data.source.clone(),
                 is_current: false,
             }
         );
-        verify!(
-            data.source_root == state.previous_justified_root,
-            Invalid::WrongJustifiedRoot {
-                state: state.previous_justified_root,
-                attestation: data.source_root,
-                is_current: false,
-            }
-        );
-        verify!(
-            data.previous_crosslink_root
-                == Hash256::from_slice(&state.get_previous_crosslink(data.shard)?.tree_hash_root()),
-            Invalid::BadPreviousCrosslink
-        );
+        Ok(state.get_previous_crosslink(data.crosslink.shard)?)
     } else {
         invalid!(Invalid::BadTargetEpoch)
     }
-    Ok(())
 }
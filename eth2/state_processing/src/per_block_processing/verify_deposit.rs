@@ -5,16 +5,15 @@ use types::*;
 
 /// Verify `Deposit.pubkey` signed `Deposit.signature`.
 ///
-/// Spec v0.6.3
-pub fn verify_deposit_signature<T: EthSpec>(
-    state: &BeaconState<T>,
-    deposit: &Deposit,
-    spec: &ChainSpec,
-) -> Result<(), Error> {
+/// Deposits are valid across forks, so the deposit domain is computed with the genesis fork
+/// version rather than `state.fork`.
+///
+/// Spec v0.8.1
+pub fn verify_deposit_signature(deposit: &Deposit, spec: &ChainSpec) -> Result<(), Error> {
     verify!(
         deposit.data.signature.verify(
             &deposit.data.signed_root(),
-            spec.get_domain(state.current_epoch(), Domain::Deposit, &state.fork),
+            spec.get_deposit_domain(),
             &deposit.data.pubkey,
         ),
         Invalid::BadSignature
@@ -23,25 +22,7 @@ pub fn verify_deposit_signature<T: EthSpec>(
     Ok(())
 }
 
-/// Verify that the `Deposit` index is correct.
-///
-/// Spec v0.6.3
-pub fn verify_deposit_index<T: EthSpec>(
-    state: &BeaconState<T>,
-    deposit: &Deposit,
-) -> Result<(), Error> {
-    verify!(
-        deposit.index == state.deposit_index,
-        Invalid::BadIndex {
-            state: state.deposit_index,
-            deposit: deposit.index
-        }
-    );
-
-    Ok(())
-}
-
-/// Returns a `Some(validator index)` if a pubkey already exists in the `validator_registry`,
+/// Returns a `Some(validator index)` if a pubkey already exists in the `validators`,
 /// otherwise returns `None`.
 ///
 /// ## Errors
@@ -57,10 +38,14 @@ pub fn get_existing_validator_index<T: EthSpec>(
 
 /// Verify that a deposit is included in the state's eth1 deposit root.
 ///
-/// Spec v0.6.3
+/// The deposit is proven at `deposit_index`, which should be `state.eth1_deposit_index` at the
+/// time the deposit is processed.
+///
+/// Spec v0.8.1
 pub fn verify_deposit_merkle_proof<T: EthSpec>(
     state: &BeaconState<T>,
     deposit: &Deposit,
+    deposit_index: u64,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     let leaf = deposit.data.tree_hash_root();
@@ -68,10 +53,11 @@ pub fn verify_deposit_merkle_proof<T: EthSpec>(
     verify!(
         verify_merkle_proof(
             Hash256::from_slice(&leaf),
-            &deposit.proof[..],
-            spec.deposit_contract_tree_depth as usize,
-            deposit.index as usize,
-            state.latest_eth1_data.deposit_root,
+            &deposit.proof.to_vec(),
+            // Add 1 for the `List` length mix-in.
+            spec.deposit_contract_tree_depth as usize + 1,
+            deposit_index as usize,
+            state.eth1_data.deposit_root,
         ),
         Invalid::BadMerkleProof
     );
@@ -8,10 +8,10 @@ use types::*;
 
 /// Verify an `IndexedAttestation`.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn verify_indexed_attestation<T: EthSpec>(
     state: &BeaconState<T>,
-    indexed_attestation: &IndexedAttestation,
+    indexed_attestation: &IndexedAttestation<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     verify_indexed_attestation_parametric(state, indexed_attestation, spec, true)
@@ -19,10 +19,10 @@ pub fn verify_indexed_attestation<T: EthSpec>(
 
 /// Verify but don't check the signature.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn verify_indexed_attestation_without_signature<T: EthSpec>(
     state: &BeaconState<T>,
-    indexed_attestation: &IndexedAttestation,
+    indexed_attestation: &IndexedAttestation<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     verify_indexed_attestation_parametric(state, indexed_attestation, spec, false)
@@ -30,10 +30,10 @@ pub fn verify_indexed_attestation_without_signature<T: EthSpec>(
 
 /// Optionally check the signature.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 fn verify_indexed_attestation_parametric<T: EthSpec>(
     state: &BeaconState<T>,
-    indexed_attestation: &IndexedAttestation,
+    indexed_attestation: &IndexedAttestation<T>,
     spec: &ChainSpec,
     verify_signature: bool,
 ) -> Result<(), Error> {
@@ -41,8 +41,8 @@ fn verify_indexed_attestation_parametric<T: EthSpec>(
     let custody_bit_1_indices = &indexed_attestation.custody_bit_1_indices;
 
     // Ensure no duplicate indices across custody bits
-    let custody_bit_intersection: HashSet<&u64> =
-        &HashSet::from_iter(custody_bit_0_indices) & &HashSet::from_iter(custody_bit_1_indices);
+    let custody_bit_intersection: HashSet<&u64> = &HashSet::from_iter(custody_bit_0_indices.iter())
+        & &HashSet::from_iter(custody_bit_1_indices.iter());
     verify!(
         custody_bit_intersection.is_empty(),
         Invalid::CustodyBitValidatorsIntersect
@@ -56,12 +56,12 @@ fn verify_indexed_attestation_parametric<T: EthSpec>(
     let total_indices = custody_bit_0_indices.len() + custody_bit_1_indices.len();
     verify!(1 <= total_indices, Invalid::NoValidatorIndices);
     verify!(
-        total_indices as u64 <= spec.max_indices_per_attestation,
-        Invalid::MaxIndicesExceed(spec.max_indices_per_attestation, total_indices)
+        total_indices as u64 <= spec.max_validators_per_committee,
+        Invalid::MaxIndicesExceed(spec.max_validators_per_committee, total_indices)
     );
 
     // Check that both vectors of indices are sorted
-    let check_sorted = |list: &Vec<u64>| {
+    let check_sorted = |list: &[u64]| {
         list.windows(2).enumerate().try_for_each(|(i, pair)| {
             if pair[0] >= pair[1] {
                 invalid!(Invalid::BadValidatorIndicesOrdering(i));
@@ -94,7 +94,7 @@ where
         AggregatePublicKey::new(),
         |mut aggregate_pubkey, &validator_idx| {
             state
-                .validator_registry
+                .validators
                 .get(validator_idx as usize)
                 .ok_or_else(|| Error::Invalid(Invalid::UnknownValidator(validator_idx)))
                 .map(|validator| {
@@ -107,10 +107,10 @@ where
 
 /// Verify the signature of an IndexedAttestation.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 fn verify_indexed_attestation_signature<T: EthSpec>(
     state: &BeaconState<T>,
-    indexed_attestation: &IndexedAttestation,
+    indexed_attestation: &IndexedAttestation<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     let bit_0_pubkey = create_aggregate_pubkey(state, &indexed_attestation.custody_bit_0_indices)?;
@@ -140,7 +140,7 @@ fn verify_indexed_attestation_signature<T: EthSpec>(
     }
 
     let domain = spec.get_domain(
-        indexed_attestation.data.target_epoch,
+        indexed_attestation.data.target.epoch,
         Domain::Attestation,
         &state.fork,
     );
@@ -7,14 +7,14 @@ use types::*;
 ///
 /// Returns `Ok(())` if the `ProposerSlashing` is valid, otherwise indicates the reason for invalidity.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn verify_proposer_slashing<T: EthSpec>(
     proposer_slashing: &ProposerSlashing,
     state: &BeaconState<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     let proposer = state
-        .validator_registry
+        .validators
         .get(proposer_slashing.proposer_index as usize)
         .ok_or_else(|| {
             Error::Invalid(Invalid::ProposerUnknown(proposer_slashing.proposer_index))
@@ -65,7 +65,7 @@ pub fn verify_proposer_slashing<T: EthSpec>(
 ///
 /// Returns `true` if the signature is valid.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 fn verify_header_signature<T: EthSpec>(
     header: &BeaconBlockHeader,
     pubkey: &PublicKey,
@@ -1,5 +1,6 @@
 use super::errors::{TransferInvalid as Invalid, TransferValidationError as Error};
 use bls::get_withdrawal_credentials;
+use safe_arith::SafeArith;
 use tree_hash::SignedRoot;
 use types::*;
 
@@ -8,7 +9,7 @@ use types::*;
 ///
 /// Returns `Ok(())` if the `Transfer` is valid, otherwise indicates the reason for invalidity.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn verify_transfer<T: EthSpec>(
     state: &BeaconState<T>,
     transfer: &Transfer,
@@ -19,7 +20,7 @@ pub fn verify_transfer<T: EthSpec>(
 
 /// Like `verify_transfer` but doesn't run checks which may become true in future states.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn verify_transfer_time_independent_only<T: EthSpec>(
     state: &BeaconState<T>,
     transfer: &Transfer,
@@ -37,7 +38,7 @@ pub fn verify_transfer_time_independent_only<T: EthSpec>(
 ///     present or future.
 /// - Validator transfer eligibility (e.g., is withdrawable)
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 fn verify_transfer_parametric<T: EthSpec>(
     state: &BeaconState<T>,
     transfer: &Transfer,
@@ -57,8 +58,8 @@ fn verify_transfer_parametric<T: EthSpec>(
     // Safely determine `amount + fee`.
     let total_amount = transfer
         .amount
-        .checked_add(transfer.fee)
-        .ok_or_else(|| Error::Invalid(Invalid::FeeOverflow(transfer.amount, transfer.fee)))?;
+        .safe_add(transfer.fee)
+        .map_err(|_| Error::Invalid(Invalid::FeeOverflow(transfer.amount, transfer.fee)))?;
 
     // Verify the sender has adequate balance.
     verify!(
@@ -68,18 +69,29 @@ fn verify_transfer_parametric<T: EthSpec>(
 
     // Verify sender balance will not be "dust" (i.e., greater than zero but less than the minimum deposit
     // amount).
+    //
+    // The sender balance may be less than `amount + fee`, in which case the transfer is invalid.
+    let sender_remaining = sender_balance.safe_sub(total_amount).ok();
     verify!(
         time_independent_only
-            || (sender_balance == total_amount)
-            || (sender_balance >= (total_amount + spec.min_deposit_amount)),
-        Invalid::SenderDust(sender_balance - total_amount, spec.min_deposit_amount)
+            || sender_remaining == Some(0)
+            || sender_remaining.map_or(false, |remaining| remaining >= spec.min_deposit_amount),
+        Invalid::SenderDust(sender_remaining.unwrap_or(0), spec.min_deposit_amount)
     );
 
     // Verify the recipient balance will not be dust.
-    verify!(
-        time_independent_only || ((recipient_balance + transfer.amount) >= spec.min_deposit_amount),
-        Invalid::RecipientDust(sender_balance - total_amount, spec.min_deposit_amount)
-    );
+    if !time_independent_only {
+        let recipient_resulting = recipient_balance.safe_add(transfer.amount).map_err(|_| {
+            Error::Invalid(Invalid::ToBalanceOverflow(
+                recipient_balance,
+                transfer.amount,
+            ))
+        })?;
+        verify!(
+            recipient_resulting >= spec.min_deposit_amount,
+            Invalid::RecipientDust(recipient_resulting, spec.min_deposit_amount)
+        );
+    }
 
     // If loosely enforcing `transfer.slot`, ensure the slot is not in the past. Otherwise, ensure
     // the transfer slot equals the state slot.
@@ -97,7 +109,7 @@ fn verify_transfer_parametric<T: EthSpec>(
 
     // Load the sender `Validator` record from the state.
     let sender_validator = state
-        .validator_registry
+        .validators
         .get(transfer.sender as usize)
         .ok_or_else(|| Error::Invalid(Invalid::FromValidatorUnknown(transfer.sender)))?;
 
@@ -113,7 +125,9 @@ fn verify_transfer_parametric<T: EthSpec>(
         time_independent_only
             || sender_validator.activation_eligibility_epoch == spec.far_future_epoch
             || sender_validator.is_withdrawable_at(epoch)
-            || total_amount + spec.max_effective_balance <= sender_balance,
+            || total_amount
+                .safe_add(spec.max_effective_balance)
+                .map_or(false, |required| required <= sender_balance),
         Invalid::FromValidatorIneligableForTransfer(transfer.sender)
     );
 
@@ -154,7 +168,7 @@ fn verify_transfer_parametric<T: EthSpec>(
 ///
 /// Does not check that the transfer is valid, however checks for overflow in all actions.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn execute_transfer<T: EthSpec>(
     state: &mut BeaconState<T>,
     transfer: &Transfer,
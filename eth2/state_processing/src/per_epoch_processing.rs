@@ -1,7 +1,14 @@
+use crate::common::get_compact_committees_root;
+use crate::state_diff::{NoTracer, StateTracer};
 use apply_rewards::process_rewards_and_penalties;
+pub use apply_rewards::{
+    Delta, NoRewards, RewardAccumulator, RewardCollector, RewardComponent, RewardReport,
+    ValidatorRewards,
+};
 use errors::EpochProcessingError as Error;
 use process_slashings::process_slashings;
 use registry_updates::process_registry_updates;
+use safe_arith::SafeArith;
 use std::collections::HashMap;
 use tree_hash::TreeHash;
 use types::*;
@@ -26,11 +33,43 @@ pub type WinningRootHashSet = HashMap<u64, WinningRoot>;
 /// Mutates the given `BeaconState`, returning early if an error is encountered. If an error is
 /// returned, a state might be "half-processed" and therefore in an invalid state.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn per_epoch_processing<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
+    per_epoch_processing_with_tracer(state, spec, &mut NoTracer, &mut NoRewards)
+}
+
+/// Performs per-epoch processing on some BeaconState, returning the rewards and penalties applied
+/// to each validator.
+///
+/// Spec v0.8.1
+pub fn per_epoch_processing_with_rewards<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    spec: &ChainSpec,
+) -> Result<RewardReport, Error> {
+    let mut report = RewardReport::default();
+    per_epoch_processing_with_tracer(state, spec, &mut NoTracer, &mut report)?;
+    Ok(report)
+}
+
+/// Performs per-epoch processing on some BeaconState, passing the state to `tracer` after each
+/// step and the rewards and penalties applied to each validator to `rewards`.
+///
+/// Spec v0.8.1
+pub fn per_epoch_processing_with_tracer<T: EthSpec, U: StateTracer<T>, R: RewardCollector>(
+    state: &mut BeaconState<T>,
+    spec: &ChainSpec,
+    tracer: &mut U,
+    rewards: &mut R,
+) -> Result<(), Error> {
+    let epoch = state.current_epoch();
+
+    // Use the constants of the epoch being processed, as modified by any scheduled forks.
+    let spec_at_epoch = spec.at_epoch(epoch);
+    let spec: &ChainSpec = &spec_at_epoch;
+
     // Ensure the previous and next epoch caches are built.
     state.build_committee_cache(RelativeEpoch::Previous, spec)?;
     state.build_committee_cache(RelativeEpoch::Current, spec)?;
@@ -43,98 +82,142 @@ pub fn per_epoch_processing<T: EthSpec>(
 
     // Justification and finalization.
     process_justification_and_finalization(state, &validator_statuses.total_balances)?;
+    tracer.trace("process_justification_and_finalization", state);
 
     // Crosslinks.
     let winning_root_for_shards = process_crosslinks(state, spec)?;
+    tracer.trace("process_crosslinks", state);
 
     // Rewards and Penalities.
-    process_rewards_and_penalties(
+    let mut validator_rewards: Vec<R::Rewards> = process_rewards_and_penalties(
         state,
         &mut validator_statuses,
         &winning_root_for_shards,
         spec,
     )?;
+    tracer.trace("process_rewards_and_penalties", state);
 
     // Registry Updates.
     process_registry_updates(state, spec)?;
+    tracer.trace("process_registry_updates", state);
 
     // Slashings.
-    process_slashings(state, validator_statuses.total_balances.current_epoch, spec)?;
+    let slashings =
+        process_slashings(state, validator_statuses.total_balances.current_epoch, spec)?;
+    for (index, penalty) in slashings {
+        validator_rewards
+            .get_mut(index)
+            .ok_or(Error::ValidatorStatusesInconsistent)?
+            .component(RewardComponent::Slashing)
+            .penalize(penalty)?;
+    }
+    tracer.trace("process_slashings", state);
 
     // Final updates.
     process_final_updates(state, spec)?;
+    tracer.trace("process_final_updates", state);
 
     // Rotate the epoch caches to suit the epoch transition.
     state.advance_caches();
 
+    rewards.collect(epoch, validator_rewards);
+
     Ok(())
 }
 
 /// Update the following fields on the `BeaconState`:
 ///
-/// - `justification_bitfield`.
-/// - `previous_justified_epoch`
-/// - `previous_justified_root`
-/// - `current_justified_epoch`
-/// - `current_justified_root`
-/// - `finalized_epoch`
-/// - `finalized_root`
+/// - `justification_bits`.
+/// - `previous_justified_checkpoint`
+/// - `current_justified_checkpoint`
+/// - `finalized_checkpoint`
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_justification_and_finalization<T: EthSpec>(
     state: &mut BeaconState<T>,
     total_balances: &TotalBalances,
 ) -> Result<(), Error> {
-    if state.current_epoch() == T::genesis_epoch() {
+    if state.current_epoch() <= T::genesis_epoch() + 1 {
         return Ok(());
     }
 
     let previous_epoch = state.previous_epoch();
     let current_epoch = state.current_epoch();
 
-    let old_previous_justified_epoch = state.previous_justified_epoch;
-    let old_current_justified_epoch = state.current_justified_epoch;
+    let old_previous_justified_checkpoint = state.previous_justified_checkpoint.clone();
+    let old_current_justified_checkpoint = state.current_justified_checkpoint.clone();
 
     // Process justifications
-    state.previous_justified_epoch = state.current_justified_epoch;
-    state.previous_justified_root = state.current_justified_root;
-    state.justification_bitfield <<= 1;
-
-    if total_balances.previous_epoch_target_attesters * 3 >= total_balances.previous_epoch * 2 {
-        state.current_justified_epoch = previous_epoch;
-        state.current_justified_root =
-            *state.get_block_root_at_epoch(state.current_justified_epoch)?;
-        state.justification_bitfield |= 2;
+    state.previous_justified_checkpoint = state.current_justified_checkpoint.clone();
+    for i in (1..state.justification_bits.len()).rev() {
+        let bit = state
+            .justification_bits
+            .get(i - 1)
+            .map_err(BeaconStateError::from)?;
+        state
+            .justification_bits
+            .set(i, bit)
+            .map_err(BeaconStateError::from)?;
+    }
+    state
+        .justification_bits
+        .set(0, false)
+        .map_err(BeaconStateError::from)?;
+
+    if total_balances.previous_epoch_target_attesters.safe_mul(3)?
+        >= total_balances.current_epoch.safe_mul(2)?
+    {
+        state.current_justified_checkpoint = Checkpoint {
+            epoch: previous_epoch,
+            root: *state.get_block_root_at_epoch(previous_epoch)?,
+        };
+        state
+            .justification_bits
+            .set(1, true)
+            .map_err(BeaconStateError::from)?;
     }
     // If the current epoch gets justified, fill the last bit.
-    if total_balances.current_epoch_target_attesters * 3 >= total_balances.current_epoch * 2 {
-        state.current_justified_epoch = current_epoch;
-        state.current_justified_root =
-            *state.get_block_root_at_epoch(state.current_justified_epoch)?;
-        state.justification_bitfield |= 1;
+    if total_balances.current_epoch_target_attesters.safe_mul(3)?
+        >= total_balances.current_epoch.safe_mul(2)?
+    {
+        state.current_justified_checkpoint = Checkpoint {
+            epoch: current_epoch,
+            root: *state.get_block_root_at_epoch(current_epoch)?,
+        };
+        state
+            .justification_bits
+            .set(0, true)
+            .map_err(BeaconStateError::from)?;
     }
 
-    let bitfield = state.justification_bitfield;
+    let bits = &state.justification_bits;
+    let all_set = |range: std::ops::Range<usize>| -> Result<bool, Error> {
+        for i in range {
+            if !bits.get(i).map_err(BeaconStateError::from)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    };
 
     // The 2nd/3rd/4th most recent epochs are all justified, the 2nd using the 4th as source.
-    if (bitfield >> 1) % 8 == 0b111 && old_previous_justified_epoch == current_epoch - 3 {
-        state.finalized_epoch = old_previous_justified_epoch;
-        state.finalized_root = *state.get_block_root_at_epoch(state.finalized_epoch)?;
-    }
+    let finalize_previous_3 =
+        all_set(1..4)? && old_previous_justified_checkpoint.epoch + 3 == current_epoch;
     // The 2nd/3rd most recent epochs are both justified, the 2nd using the 3rd as source.
-    if (bitfield >> 1) % 4 == 0b11 && old_previous_justified_epoch == current_epoch - 2 {
-        state.finalized_epoch = old_previous_justified_epoch;
-        state.finalized_root = *state.get_block_root_at_epoch(state.finalized_epoch)?;
-    }
-    // The 1st/2nd/3rd most recent epochs are all justified, the 1st using the 2nd as source.
-    if bitfield % 8 == 0b111 && old_current_justified_epoch == current_epoch - 2 {
-        state.finalized_epoch = old_current_justified_epoch;
-        state.finalized_root = *state.get_block_root_at_epoch(state.finalized_epoch)?;
-    }
+    let finalize_previous_2 =
+        all_set(1..3)? && old_previous_justified_checkpoint.epoch + 2 == current_epoch;
+    // The 1st/2nd/3rd most recent epochs are all justified, the 1st using the 3rd as source.
+    let finalize_current_3 =
+        all_set(0..3)? && old_current_justified_checkpoint.epoch + 2 == current_epoch;
     // The 1st/2nd most recent epochs are both justified, the 1st using the 2nd as source.
-    if bitfield % 4 == 0b11 && old_current_justified_epoch == current_epoch - 1 {
-        state.finalized_epoch = old_current_justified_epoch;
-        state.finalized_root = *state.get_block_root_at_epoch(state.finalized_epoch)?;
+    let finalize_current_2 =
+        all_set(0..2)? && old_current_justified_checkpoint.epoch + 1 == current_epoch;
+
+    if finalize_previous_3 || finalize_previous_2 {
+        state.finalized_checkpoint = old_previous_justified_checkpoint;
+    }
+    if finalize_current_3 || finalize_current_2 {
+        state.finalized_checkpoint = old_current_justified_checkpoint;
     }
 
     Ok(())
@@ -145,9 +228,10 @@ pub fn process_justification_and_finalization<T: EthSpec>(
 /// - `previous_crosslinks`
 /// - `current_crosslinks`
 ///
-/// Also returns a `WinningRootHashSet` for later use during epoch processing.
+/// Also returns a `WinningRootHashSet` of the previous epoch's shards for later use during epoch
+/// processing.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_crosslinks<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
@@ -158,7 +242,7 @@ pub fn process_crosslinks<T: EthSpec>(
 
     for &relative_epoch in &[RelativeEpoch::Previous, RelativeEpoch::Current] {
         let epoch = relative_epoch.into_epoch(state.current_epoch());
-        for offset in 0..state.get_epoch_committee_count(relative_epoch)? {
+        for offset in 0..state.get_committee_count(relative_epoch)? {
             let shard =
                 (state.get_epoch_start_shard(relative_epoch)? + offset) % T::ShardCount::to_u64();
             let crosslink_committee =
@@ -170,20 +254,41 @@ pub fn process_crosslinks<T: EthSpec>(
                 let total_committee_balance =
                     state.get_total_balance(&crosslink_committee.committee, spec)?;
 
-                if 3 * winning_root.total_attesting_balance >= 2 * total_committee_balance {
+                if winning_root.total_attesting_balance.safe_mul(3)?
+                    >= total_committee_balance.safe_mul(2)?
+                {
                     state.current_crosslinks[shard as usize] = winning_root.crosslink.clone();
                 }
-                winning_root_for_shards.insert(shard, winning_root);
+
+                if relative_epoch == RelativeEpoch::Previous {
+                    winning_root_for_shards.insert(shard, winning_root);
+                }
             }
         }
     }
 
+    // Crosslink rewards are determined by the winning roots against the crosslinks as they stand
+    // after processing, so recompute the winning root of any previous epoch shard whose crosslink
+    // has since changed.
+    let previous_epoch = state.previous_epoch();
+    for offset in 0..state.get_committee_count(RelativeEpoch::Previous)? {
+        let shard = (state.get_epoch_start_shard(RelativeEpoch::Previous)? + offset)
+            % T::ShardCount::to_u64();
+
+        if state.get_current_crosslink(shard)? != state.get_previous_crosslink(shard)? {
+            match winning_root(state, shard, previous_epoch, spec)? {
+                Some(winning_root) => winning_root_for_shards.insert(shard, winning_root),
+                None => winning_root_for_shards.remove(&shard),
+            };
+        }
+    }
+
     Ok(winning_root_for_shards)
 }
 
 /// Finish up an epoch update.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn process_final_updates<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
@@ -193,25 +298,36 @@ pub fn process_final_updates<T: EthSpec>(
 
     // Reset eth1 data votes.
     if (state.slot + 1) % spec.slots_per_eth1_voting_period == 0 {
-        state.eth1_data_votes = vec![];
+        state.eth1_data_votes = VariableList::empty();
     }
 
     // Update effective balances with hysteresis (lag).
-    for (index, validator) in state.validator_registry.iter_mut().enumerate() {
+    for (index, validator) in state.validators.iter_mut().enumerate() {
         let balance = state.balances[index];
-        let half_increment = spec.effective_balance_increment / 2;
+        let half_increment = spec.effective_balance_increment.safe_div(2)?;
         if balance < validator.effective_balance
-            || validator.effective_balance + 3 * half_increment < balance
+            || validator
+                .effective_balance
+                .safe_add(half_increment.safe_mul(3)?)?
+                < balance
         {
             validator.effective_balance = std::cmp::min(
-                balance - balance % spec.effective_balance_increment,
+                balance.safe_sub(balance.safe_rem(spec.effective_balance_increment)?)?,
                 spec.max_effective_balance,
             );
         }
     }
 
+    // The compact committees root is computed from the committees of the next epoch, using the
+    // next epoch's start shard.
+    //
+    // Note: v0.8.1 of the spec computes this root after `start_shard` has been advanced, which
+    // double-counts the shard delta. This was fixed in v0.8.2; we follow the fix.
+    state.build_committee_cache(RelativeEpoch::Next, spec)?;
+    let compact_committees_root = get_compact_committees_root(state, RelativeEpoch::Next, spec)?;
+
     // Update start shard.
-    state.latest_start_shard = state.next_epoch_start_shard(spec)?;
+    state.start_shard = state.next_epoch_start_shard(spec)?;
 
     // This is a hack to allow us to update index roots and slashed balances for the next epoch.
     //
@@ -220,19 +336,22 @@ pub fn process_final_updates<T: EthSpec>(
         state.slot += 1;
 
         // Set active index root
-        let active_index_root = Hash256::from_slice(
-            &state
-                .get_active_validator_indices(next_epoch + spec.activation_exit_delay)
-                .tree_hash_root()[..],
-        );
+        let index_epoch = next_epoch + spec.activation_exit_delay;
+        let indices_list = VariableList::<usize, T::ValidatorRegistryLimit>::new(
+            state.get_active_validator_indices(index_epoch),
+        )
+        .map_err(BeaconStateError::from)?;
         state.set_active_index_root(
-            next_epoch + spec.activation_exit_delay,
-            active_index_root,
+            index_epoch,
+            Hash256::from_slice(&indices_list.tree_hash_root()),
             spec,
         )?;
 
-        // Set total slashed balances
-        state.set_slashed_balance(next_epoch, state.get_slashed_balance(current_epoch)?)?;
+        // Set committees root
+        state.set_compact_committee_root(next_epoch, compact_committees_root)?;
+
+        // Reset slashings
+        state.set_slashings(next_epoch, 0)?;
 
         // Set randao mix
         state.set_randao_mix(next_epoch, *state.get_randao_mix(current_epoch)?)?;
@@ -244,12 +363,13 @@ pub fn process_final_updates<T: EthSpec>(
         let historical_batch = state.historical_batch();
         state
             .historical_roots
-            .push(Hash256::from_slice(&historical_batch.tree_hash_root()[..]));
+            .push(Hash256::from_slice(&historical_batch.tree_hash_root()))
+            .map_err(BeaconStateError::from)?;
     }
 
     // Rotate current/previous epoch attestations
     state.previous_epoch_attestations =
-        std::mem::replace(&mut state.current_epoch_attestations, vec![]);
+        std::mem::replace(&mut state.current_epoch_attestations, VariableList::empty());
 
     Ok(())
 }
@@ -1,82 +1,251 @@
-use super::validator_statuses::{TotalBalances, ValidatorStatus, ValidatorStatuses};
+use super::validator_statuses::{first_error, TotalBalances, ValidatorStatus, ValidatorStatuses};
 use super::{Error, WinningRootHashSet};
-use integer_sqrt::IntegerSquareRoot;
+use crate::common::get_base_reward;
+use rayon::prelude::*;
+use safe_arith::SafeArith;
+use serde_derive::{Deserialize, Serialize};
 use types::*;
 
 /// Use to track the changes to a validators balance.
-#[derive(Default, Clone)]
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
 pub struct Delta {
-    rewards: u64,
-    penalties: u64,
+    pub rewards: u64,
+    pub penalties: u64,
 }
 
 impl Delta {
     /// Reward the validator with the `reward`.
-    pub fn reward(&mut self, reward: u64) {
-        self.rewards += reward;
+    pub fn reward(&mut self, reward: u64) -> Result<(), Error> {
+        self.rewards.safe_add_assign(reward)?;
+        Ok(())
     }
 
     /// Penalize the validator with the `penalty`.
-    pub fn penalize(&mut self, penalty: u64) {
-        self.penalties += penalty;
+    pub fn penalize(&mut self, penalty: u64) -> Result<(), Error> {
+        self.penalties.safe_add_assign(penalty)?;
+        Ok(())
+    }
+
+    /// Combine the rewards and penalties of `self` and `other`.
+    pub fn combine(&mut self, other: &Delta) -> Result<(), Error> {
+        self.reward(other.rewards)?;
+        self.penalize(other.penalties)
+    }
+}
+
+/// The components of the change to a single validator's balance during epoch processing.
+///
+/// Attestation components (`source`, `target`, `head`, `inclusion_delay` and `crosslink`) relate
+/// to the validator's duties in the previous epoch.
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+pub struct ValidatorRewards {
+    /// Matching the expected FFG source.
+    pub source: Delta,
+    /// Matching the expected FFG target.
+    pub target: Delta,
+    /// Matching the expected head.
+    pub head: Delta,
+    /// The bonus for the speed with which the validator's attestation was included.
+    pub inclusion_delay: Delta,
+    /// The penalty applied whilst the chain is failing to finalize.
+    pub inactivity: Delta,
+    /// Attesting to the winning crosslink.
+    pub crosslink: Delta,
+    /// Including the attestations of other validators in proposed blocks.
+    pub proposer: Delta,
+    /// The penalty applied by `process_slashings`.
+    pub slashing: Delta,
+}
+
+impl ValidatorRewards {
+    /// The sum of all components.
+    pub fn total(&self) -> Result<Delta, Error> {
+        let mut total = Delta::default();
+        for delta in &[
+            &self.source,
+            &self.target,
+            &self.head,
+            &self.inclusion_delay,
+            &self.inactivity,
+            &self.crosslink,
+            &self.proposer,
+            &self.slashing,
+        ] {
+            total.combine(delta)?;
+        }
+        Ok(total)
+    }
+}
+
+/// The components of a validator's rewards, as recorded by `ValidatorRewards`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RewardComponent {
+    Source,
+    Target,
+    Head,
+    InclusionDelay,
+    Inactivity,
+    Crosslink,
+    Proposer,
+    Slashing,
+}
+
+/// Accumulates the rewards and penalties applied to a single validator during epoch processing.
+pub trait RewardAccumulator: Default + Clone + Send + Sync {
+    /// Returns the `Delta` which accumulates `component`.
+    fn component(&mut self, component: RewardComponent) -> &mut Delta;
+
+    /// The total change to the validator's balance.
+    fn total(&self) -> Result<Delta, Error>;
+}
+
+/// Accumulates every component into a single `Delta`, which is all that is required to update
+/// the validator's balance.
+impl RewardAccumulator for Delta {
+    #[inline]
+    fn component(&mut self, _component: RewardComponent) -> &mut Delta {
+        self
+    }
+
+    fn total(&self) -> Result<Delta, Error> {
+        Ok(self.clone())
     }
 }
 
-impl std::ops::AddAssign for Delta {
-    /// Use wrapping addition as that is how it's defined in the spec.
-    fn add_assign(&mut self, other: Delta) {
-        self.rewards += other.rewards;
-        self.penalties += other.penalties;
+impl RewardAccumulator for ValidatorRewards {
+    fn component(&mut self, component: RewardComponent) -> &mut Delta {
+        match component {
+            RewardComponent::Source => &mut self.source,
+            RewardComponent::Target => &mut self.target,
+            RewardComponent::Head => &mut self.head,
+            RewardComponent::InclusionDelay => &mut self.inclusion_delay,
+            RewardComponent::Inactivity => &mut self.inactivity,
+            RewardComponent::Crosslink => &mut self.crosslink,
+            RewardComponent::Proposer => &mut self.proposer,
+            RewardComponent::Slashing => &mut self.slashing,
+        }
+    }
+
+    fn total(&self) -> Result<Delta, Error> {
+        ValidatorRewards::total(self)
+    }
+}
+
+/// Collects the rewards and penalties applied to each validator by epoch processing.
+///
+/// The choice of `Rewards` determines how much detail is kept, so that callers which do not need
+/// a report (e.g., block import) only pay for a single `Delta` per validator.
+pub trait RewardCollector {
+    type Rewards: RewardAccumulator;
+
+    /// Called once per epoch transition with the rewards of every validator, indexed by validator
+    /// index.
+    fn collect(&mut self, epoch: Epoch, validators: Vec<Self::Rewards>);
+}
+
+/// A `RewardCollector` which discards the rewards.
+pub struct NoRewards;
+
+impl RewardCollector for NoRewards {
+    type Rewards = Delta;
+
+    #[inline]
+    fn collect(&mut self, _epoch: Epoch, _validators: Vec<Delta>) {}
+}
+
+/// The `ValidatorRewards` of every validator, as computed during the processing of `epoch`.
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+pub struct RewardReport {
+    pub epoch: Epoch,
+    /// Indexed by validator index.
+    pub validators: Vec<ValidatorRewards>,
+}
+
+/// Keeps the report of the latest epoch transition.
+impl RewardCollector for RewardReport {
+    type Rewards = ValidatorRewards;
+
+    fn collect(&mut self, epoch: Epoch, validators: Vec<ValidatorRewards>) {
+        self.epoch = epoch;
+        self.validators = validators;
+    }
+}
+
+/// Keeps the report of every epoch transition, in order.
+impl RewardCollector for Vec<RewardReport> {
+    type Rewards = ValidatorRewards;
+
+    fn collect(&mut self, epoch: Epoch, validators: Vec<ValidatorRewards>) {
+        self.push(RewardReport { epoch, validators });
     }
 }
 
-/// Apply attester and proposer rewards.
+/// Apply attester and proposer rewards, returning the rewards and penalties of each validator.
 ///
-/// Spec v0.6.3
-pub fn process_rewards_and_penalties<T: EthSpec>(
+/// Attestation and crosslink deltas are computed, and balances updated, for each validator in
+/// parallel.
+///
+/// Spec v0.8.1
+pub fn process_rewards_and_penalties<T: EthSpec, R: RewardAccumulator>(
     state: &mut BeaconState<T>,
     validator_statuses: &mut ValidatorStatuses,
     winning_root_for_shards: &WinningRootHashSet,
     spec: &ChainSpec,
-) -> Result<(), Error> {
+) -> Result<Vec<R>, Error> {
+    let mut rewards = vec![R::default(); state.balances.len()];
+
     if state.current_epoch() == T::genesis_epoch() {
-        return Ok(());
+        return Ok(rewards);
     }
 
     // Guard against an out-of-bounds during the validator balance update.
     if validator_statuses.statuses.len() != state.balances.len()
-        || validator_statuses.statuses.len() != state.validator_registry.len()
+        || validator_statuses.statuses.len() != state.validators.len()
     {
         return Err(Error::ValidatorStatusesInconsistent);
     }
 
-    let mut deltas = vec![Delta::default(); state.balances.len()];
-
-    get_attestation_deltas(&mut deltas, state, &validator_statuses, spec)?;
-    get_crosslink_deltas(&mut deltas, state, &validator_statuses, spec)?;
+    get_attestation_deltas(&mut rewards, state, &validator_statuses, spec)?;
+    get_crosslink_deltas(&mut rewards, state, &validator_statuses, spec)?;
 
     get_proposer_deltas(
-        &mut deltas,
+        &mut rewards,
         state,
         validator_statuses,
         winning_root_for_shards,
         spec,
     )?;
 
-    // Apply the deltas, over-flowing but not under-flowing (saturating at 0 instead).
-    for (i, delta) in deltas.iter().enumerate() {
-        state.balances[i] += delta.rewards;
-        state.balances[i] = state.balances[i].saturating_sub(delta.penalties);
+    // Apply the deltas, erroring on overflow but not under-flowing (saturating at 0 instead).
+    //
+    // The new balances are computed in parallel, then only those which changed are written so
+    // that any chunks of `state.balances` which are unchanged remain shared with prior states.
+    let results = state
+        .balances
+        .par_iter()
+        .zip(rewards.par_iter())
+        .map(|(balance, validator_rewards)| -> Result<u64, Error> {
+            let delta = validator_rewards.total()?;
+            Ok(balance
+                .safe_add(delta.rewards)?
+                .saturating_sub(delta.penalties))
+        })
+        .collect::<Vec<_>>();
+
+    for (index, new_balance) in first_error(results)?.into_iter().enumerate() {
+        if state.balances[index] != new_balance {
+            state.balances[index] = new_balance;
+        }
     }
 
-    Ok(())
+    Ok(rewards)
 }
 
 /// For each attesting validator, reward the proposer who was first to include their attestation.
 ///
-/// Spec v0.6.3
-fn get_proposer_deltas<T: EthSpec>(
-    deltas: &mut Vec<Delta>,
+/// Spec v0.8.1
+fn get_proposer_deltas<T: EthSpec, R: RewardAccumulator>(
+    rewards: &mut Vec<R>,
     state: &BeaconState<T>,
     validator_statuses: &mut ValidatorStatuses,
     winning_root_for_shards: &WinningRootHashSet,
@@ -85,24 +254,26 @@ fn get_proposer_deltas<T: EthSpec>(
     // Update statuses with the information from winning roots.
     validator_statuses.process_winning_roots(state, winning_root_for_shards, spec)?;
 
-    for validator in &validator_statuses.statuses {
-        if validator.is_previous_epoch_attester {
+    for (index, validator) in validator_statuses.statuses.iter().enumerate() {
+        if validator.is_previous_epoch_attester && !validator.is_slashed {
             let inclusion = validator
                 .inclusion_info
-                .expect("It is a logic error for an attester not to have an inclusion distance.");
+                .expect("It is a logic error for an attester not to have an inclusion delay.");
 
             let base_reward = get_base_reward(
                 state,
-                inclusion.proposer_index,
+                index,
                 validator_statuses.total_balances.current_epoch,
                 spec,
             )?;
 
-            if inclusion.proposer_index >= deltas.len() {
+            if inclusion.proposer_index >= rewards.len() {
                 return Err(Error::ValidatorStatusesInconsistent);
             }
 
-            deltas[inclusion.proposer_index].reward(base_reward / spec.proposer_reward_quotient);
+            rewards[inclusion.proposer_index]
+                .component(RewardComponent::Proposer)
+                .reward(base_reward.safe_div(spec.proposer_reward_quotient)?)?;
         }
     }
 
@@ -111,56 +282,67 @@ fn get_proposer_deltas<T: EthSpec>(
 
 /// Apply rewards for participation in attestations during the previous epoch.
 ///
-/// Spec v0.6.3
-fn get_attestation_deltas<T: EthSpec>(
-    deltas: &mut Vec<Delta>,
+/// Spec v0.8.1
+fn get_attestation_deltas<T: EthSpec, R: RewardAccumulator>(
+    rewards: &mut Vec<R>,
     state: &BeaconState<T>,
     validator_statuses: &ValidatorStatuses,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    let finality_delay = (state.previous_epoch() - state.finalized_epoch).as_u64();
-
-    for (index, validator) in validator_statuses.statuses.iter().enumerate() {
-        let base_reward = get_base_reward(
-            state,
-            index,
-            validator_statuses.total_balances.current_epoch,
-            spec,
-        )?;
-
-        let delta = get_attestation_delta(
-            &validator,
-            &validator_statuses.total_balances,
-            base_reward,
-            finality_delay,
-            spec,
-        );
-
-        deltas[index] += delta;
-    }
+    let finality_delay = state
+        .previous_epoch()
+        .safe_sub(state.finalized_checkpoint.epoch)?
+        .as_u64();
+
+    let results = rewards
+        .par_iter_mut()
+        .zip(validator_statuses.statuses.par_iter())
+        .enumerate()
+        .map(
+            |(index, (validator_rewards, validator))| -> Result<(), Error> {
+                let base_reward = get_base_reward(
+                    state,
+                    index,
+                    validator_statuses.total_balances.current_epoch,
+                    spec,
+                )?;
+
+                get_attestation_delta(
+                    validator_rewards,
+                    validator,
+                    &validator_statuses.total_balances,
+                    base_reward,
+                    finality_delay,
+                    T::slots_per_epoch(),
+                    spec,
+                )
+            },
+        )
+        .collect::<Vec<_>>();
+    first_error(results)?;
 
     Ok(())
 }
 
-/// Determine the delta for a single validator, sans proposer rewards.
+/// Determine the attestation deltas for a single validator, sans proposer rewards.
 ///
-/// Spec v0.6.3
-fn get_attestation_delta(
+/// Spec v0.8.1
+fn get_attestation_delta<R: RewardAccumulator>(
+    rewards: &mut R,
     validator: &ValidatorStatus,
     total_balances: &TotalBalances,
     base_reward: u64,
     finality_delay: u64,
+    slots_per_epoch: u64,
     spec: &ChainSpec,
-) -> Delta {
-    let mut delta = Delta::default();
-
+) -> Result<(), Error> {
     // Is this validator eligible to be rewarded or penalized?
     // Spec: validator index in `eligible_validator_indices`
     let is_eligible = validator.is_active_in_previous_epoch
         || (validator.is_slashed && !validator.is_withdrawable_in_current_epoch);
 
     if !is_eligible {
-        return delta;
+        return Ok(());
     }
 
     let total_balance = total_balances.current_epoch;
@@ -172,45 +354,77 @@ fn get_attestation_delta(
     // Spec:
     // - validator index in `get_unslashed_attesting_indices(state, matching_source_attestations)`
     if validator.is_previous_epoch_attester && !validator.is_slashed {
-        delta.reward(base_reward * total_attesting_balance / total_balance);
+        rewards.component(RewardComponent::Source).reward(
+            base_reward
+                .safe_mul(total_attesting_balance)?
+                .safe_div(total_balance)?,
+        )?;
         // Inclusion speed bonus
         let inclusion = validator
             .inclusion_info
-            .expect("It is a logic error for an attester not to have an inclusion distance.");
-        delta.reward(base_reward * spec.min_attestation_inclusion_delay / inclusion.distance);
+            .expect("It is a logic error for an attester not to have an inclusion delay.");
+        let proposer_reward = base_reward.safe_div(spec.proposer_reward_quotient)?;
+        let max_attester_reward = base_reward.safe_sub(proposer_reward)?;
+        rewards.component(RewardComponent::InclusionDelay).reward(
+            max_attester_reward
+                .safe_mul(
+                    slots_per_epoch
+                        .safe_add(spec.min_attestation_inclusion_delay)?
+                        .safe_sub(inclusion.delay)?,
+                )?
+                .safe_div(slots_per_epoch)?,
+        )?;
     } else {
-        delta.penalize(base_reward);
+        rewards
+            .component(RewardComponent::Source)
+            .penalize(base_reward)?;
     }
 
     // Expected FFG target.
     // Spec:
     // - validator index in `get_unslashed_attesting_indices(state, matching_target_attestations)`
     if validator.is_previous_epoch_target_attester && !validator.is_slashed {
-        delta.reward(base_reward * matching_target_balance / total_balance);
+        rewards.component(RewardComponent::Target).reward(
+            base_reward
+                .safe_mul(matching_target_balance)?
+                .safe_div(total_balance)?,
+        )?;
     } else {
-        delta.penalize(base_reward);
+        rewards
+            .component(RewardComponent::Target)
+            .penalize(base_reward)?;
     }
 
     // Expected head.
     // Spec:
     // - validator index in `get_unslashed_attesting_indices(state, matching_head_attestations)`
     if validator.is_previous_epoch_head_attester && !validator.is_slashed {
-        delta.reward(base_reward * matching_head_balance / total_balance);
+        rewards.component(RewardComponent::Head).reward(
+            base_reward
+                .safe_mul(matching_head_balance)?
+                .safe_div(total_balance)?,
+        )?;
     } else {
-        delta.penalize(base_reward);
+        rewards
+            .component(RewardComponent::Head)
+            .penalize(base_reward)?;
     }
 
     // Inactivity penalty
     if finality_delay > spec.min_epochs_to_inactivity_penalty {
         // All eligible validators are penalized
-        delta.penalize(spec.base_rewards_per_epoch * base_reward);
+        rewards
+            .component(RewardComponent::Inactivity)
+            .penalize(spec.base_rewards_per_epoch.safe_mul(base_reward)?)?;
 
         // Additionally, all validators whose FFG target didn't match are penalized extra
         if !validator.is_previous_epoch_target_attester {
-            delta.penalize(
-                validator.current_epoch_effective_balance * finality_delay
-                    / spec.inactivity_penalty_quotient,
-            );
+            rewards.component(RewardComponent::Inactivity).penalize(
+                validator
+                    .current_epoch_effective_balance
+                    .safe_mul(finality_delay)?
+                    .safe_div(spec.inactivity_penalty_quotient)?,
+            )?;
         }
     }
 
@@ -219,59 +433,50 @@ fn get_attestation_delta(
     // This function only computes the delta for a single validator, so it cannot also return a
     // delta for a validator.
 
-    delta
+    Ok(())
 }
 
 /// Calculate the deltas based upon the winning roots for attestations during the previous epoch.
 ///
-/// Spec v0.6.3
-fn get_crosslink_deltas<T: EthSpec>(
-    deltas: &mut Vec<Delta>,
+/// Spec v0.8.1
+fn get_crosslink_deltas<T: EthSpec, R: RewardAccumulator>(
+    rewards: &mut Vec<R>,
     state: &BeaconState<T>,
     validator_statuses: &ValidatorStatuses,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    for (index, validator) in validator_statuses.statuses.iter().enumerate() {
-        let mut delta = Delta::default();
-
-        let base_reward = get_base_reward(
-            state,
-            index,
-            validator_statuses.total_balances.current_epoch,
-            spec,
-        )?;
-
-        if let Some(ref winning_root) = validator.winning_root_info {
-            delta.reward(
-                base_reward * winning_root.total_attesting_balance
-                    / winning_root.total_committee_balance,
-            );
-        } else {
-            delta.penalize(base_reward);
-        }
-
-        deltas[index] += delta;
-    }
+    let results = rewards
+        .par_iter_mut()
+        .zip(validator_statuses.statuses.par_iter())
+        .enumerate()
+        .map(
+            |(index, (validator_rewards, validator))| -> Result<(), Error> {
+                let base_reward = get_base_reward(
+                    state,
+                    index,
+                    validator_statuses.total_balances.current_epoch,
+                    spec,
+                )?;
+
+                let delta = validator_rewards.component(RewardComponent::Crosslink);
+
+                // Every validator active in the previous epoch was a member of exactly one crosslink
+                // committee.
+                if let Some(ref winning_root) = validator.winning_root_info {
+                    delta.reward(
+                        base_reward
+                            .safe_mul(winning_root.total_attesting_balance)?
+                            .safe_div(winning_root.total_committee_balance)?,
+                    )?;
+                } else if validator.is_active_in_previous_epoch {
+                    delta.penalize(base_reward)?;
+                }
+
+                Ok(())
+            },
+        )
+        .collect::<Vec<_>>();
+    first_error(results)?;
 
     Ok(())
 }
-
-/// Returns the base reward for some validator.
-///
-/// Spec v0.6.3
-fn get_base_reward<T: EthSpec>(
-    state: &BeaconState<T>,
-    index: usize,
-    // Should be == get_total_active_balance(state, spec)
-    total_active_balance: u64,
-    spec: &ChainSpec,
-) -> Result<u64, BeaconStateError> {
-    if total_active_balance == 0 {
-        Ok(0)
-    } else {
-        let adjusted_quotient = total_active_balance.integer_sqrt() / spec.base_reward_quotient;
-        Ok(state.get_effective_balance(index, spec)?
-            / adjusted_quotient
-            / spec.base_rewards_per_epoch)
-    }
-}
@@ -1,3 +1,4 @@
+use safe_arith::ArithError;
 use types::*;
 
 #[derive(Debug, PartialEq)]
@@ -17,6 +18,9 @@ pub enum EpochProcessingError {
     InclusionSlotsInconsistent(usize),
     BeaconStateError(BeaconStateError),
     InclusionError(InclusionError),
+    /// An arithmetic operation overflowed or divided by zero. This indicates an invalid state or
+    /// `ChainSpec`.
+    ArithError(ArithError),
 }
 
 impl From<InclusionError> for EpochProcessingError {
@@ -31,6 +35,12 @@ impl From<BeaconStateError> for EpochProcessingError {
     }
 }
 
+impl From<ArithError> for EpochProcessingError {
+    fn from(e: ArithError) -> EpochProcessingError {
+        EpochProcessingError::ArithError(e)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum InclusionError {
     /// The validator did not participate in an attestation in this period.
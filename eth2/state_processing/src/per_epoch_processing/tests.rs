@@ -1,6 +1,8 @@
 #![cfg(test)]
-use crate::per_epoch_processing::per_epoch_processing;
+use crate::per_epoch_processing::errors::EpochProcessingError;
+use crate::per_epoch_processing::{per_epoch_processing, per_epoch_processing_with_rewards};
 use env_logger::{Builder, Env};
+use safe_arith::ArithError;
 use types::test_utils::TestingBeaconStateBuilder;
 use types::*;
 
@@ -21,3 +23,145 @@ fn runs_without_error() {
 
     per_epoch_processing(&mut state, &spec).unwrap();
 }
+
+#[test]
+fn reward_report_matches_balance_changes() {
+    let spec = MinimalEthSpec::default_spec();
+
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+
+    let (mut state, _keypairs) = builder.build();
+    let balances_before = state.balances.clone();
+
+    let report = per_epoch_processing_with_rewards(&mut state, &spec).unwrap();
+
+    assert_eq!(report.epoch, MinimalEthSpec::genesis_epoch() + 4);
+    assert_eq!(report.validators.len(), balances_before.len());
+
+    for (i, rewards) in report.validators.iter().enumerate() {
+        // No attestations were included, so every validator misses every attestation reward.
+        assert_eq!(rewards.source.rewards, 0);
+        assert!(rewards.source.penalties > 0);
+        assert!(rewards.crosslink.penalties > 0);
+
+        let total = rewards.total().unwrap();
+        assert_eq!(
+            state.balances[i],
+            (balances_before[i] + total.rewards).saturating_sub(total.penalties)
+        );
+    }
+}
+
+fn state_at_end_of_epoch_4(spec: &ChainSpec) -> BeaconState<MinimalEthSpec> {
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(8, spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+
+    let (state, _keypairs) = builder.build();
+    state
+}
+
+#[test]
+fn total_balance_overflow_is_an_error() {
+    let spec = MinimalEthSpec::default_spec();
+    let mut state = state_at_end_of_epoch_4(&spec);
+
+    state.validators[0].effective_balance = u64::max_value();
+    state.validators[1].effective_balance = u64::max_value();
+
+    assert_eq!(
+        per_epoch_processing(&mut state, &spec),
+        Err(EpochProcessingError::BeaconStateError(
+            BeaconStateError::ArithError(ArithError::Overflow)
+        ))
+    );
+}
+
+#[test]
+fn zero_quotient_is_an_error() {
+    let mut spec = MinimalEthSpec::default_spec();
+    let mut state = state_at_end_of_epoch_4(&spec);
+
+    // A zero `base_rewards_per_epoch` leaves a zero divisor.
+    spec.base_rewards_per_epoch = 0;
+
+    assert_eq!(
+        per_epoch_processing(&mut state, &spec),
+        Err(EpochProcessingError::BeaconStateError(
+            BeaconStateError::ArithError(ArithError::DivisionByZero)
+        ))
+    );
+}
+
+#[test]
+fn parallel_processing_is_deterministic() {
+    let spec = MinimalEthSpec::default_spec();
+
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(64, &spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+    builder.build_caches(&spec).unwrap();
+    builder.insert_attestations(&spec);
+
+    let (state, _keypairs) = builder.build();
+
+    let process_with_threads = |num_threads: usize| {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        let mut state = state.clone();
+        let report = pool
+            .install(|| per_epoch_processing_with_rewards(&mut state, &spec))
+            .unwrap();
+        (state, report)
+    };
+
+    let (single_state, single_report) = process_with_threads(1);
+    let (parallel_state, parallel_report) = process_with_threads(4);
+
+    // Ensure the attestations were rewarded, so the comparison is meaningful.
+    assert!(single_report
+        .validators
+        .iter()
+        .any(|rewards| rewards.source.rewards > 0));
+
+    assert_eq!(single_state, parallel_state);
+    assert_eq!(single_report, parallel_report);
+}
+
+#[test]
+fn collecting_rewards_does_not_change_the_state() {
+    let spec = MinimalEthSpec::default_spec();
+
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(64, &spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+    builder.build_caches(&spec).unwrap();
+    builder.insert_attestations(&spec);
+
+    let (state, _keypairs) = builder.build();
+
+    let mut without_rewards = state.clone();
+    per_epoch_processing(&mut without_rewards, &spec).unwrap();
+
+    let mut with_rewards = state.clone();
+    per_epoch_processing_with_rewards(&mut with_rewards, &spec).unwrap();
+
+    assert_ne!(without_rewards.balances, state.balances);
+    assert_eq!(without_rewards, with_rewards);
+}
@@ -1,5 +1,7 @@
 use super::WinningRootHashSet;
 use crate::common::get_attesting_indices_unsorted;
+use rayon::prelude::*;
+use safe_arith::SafeArith;
 use types::*;
 
 /// Sets the boolean `var` on `self` to be true if it is true on `other`. Otherwise leaves `self`
@@ -25,33 +27,29 @@ pub struct WinningRootInfo {
 /// The information required to reward a block producer for including an attestation in a block.
 #[derive(Clone, Copy)]
 pub struct InclusionInfo {
-    /// The earliest slot a validator had an attestation included in the previous epoch.
-    pub slot: Slot,
     /// The distance between the attestation slot and the slot that attestation was included in a
     /// block.
-    pub distance: u64,
+    pub delay: u64,
     /// The index of the proposer at the slot where the attestation was included.
     pub proposer_index: usize,
 }
 
 impl Default for InclusionInfo {
-    /// Defaults to `slot` and `distance` at their maximum values and `proposer_index` at zero.
+    /// Defaults to `delay` at its maximum value and `proposer_index` at zero.
     fn default() -> Self {
         Self {
-            slot: Slot::max_value(),
-            distance: u64::max_value(),
+            delay: u64::max_value(),
             proposer_index: 0,
         }
     }
 }
 
 impl InclusionInfo {
-    /// Tests if some `other` `InclusionInfo` has a lower inclusion slot than `self`. If so,
+    /// Tests if some `other` `InclusionInfo` has a lower inclusion delay than `self`. If so,
     /// replaces `self` with `other`.
     pub fn update(&mut self, other: &Self) {
-        if other.slot < self.slot {
-            self.slot = other.slot;
-            self.distance = other.distance;
+        if other.delay < self.delay {
+            self.delay = other.delay;
             self.proposer_index = other.proposer_index;
         }
     }
@@ -162,35 +160,47 @@ impl ValidatorStatuses {
     /// - Active validators
     /// - Total balances for the current and previous epochs.
     ///
-    /// Spec v0.6.3
+    /// Validators are processed in parallel.
+    ///
+    /// Spec v0.8.1
     pub fn new<T: EthSpec>(
         state: &BeaconState<T>,
         spec: &ChainSpec,
     ) -> Result<Self, BeaconStateError> {
-        let mut statuses = Vec::with_capacity(state.validator_registry.len());
+        let current_epoch = state.current_epoch();
+        let previous_epoch = state.previous_epoch();
+
+        let statuses = state
+            .validators
+            .par_iter()
+            .enumerate()
+            .map(|(i, validator)| -> Result<_, BeaconStateError> {
+                Ok(ValidatorStatus {
+                    is_slashed: validator.slashed,
+                    is_withdrawable_in_current_epoch: validator.is_withdrawable_at(current_epoch),
+                    is_active_in_current_epoch: validator.is_active_at(current_epoch),
+                    is_active_in_previous_epoch: validator.is_active_at(previous_epoch),
+                    current_epoch_effective_balance: state.get_effective_balance(i, spec)?,
+                    ..ValidatorStatus::default()
+                })
+            })
+            .collect::<Vec<_>>();
+        let statuses = first_error(statuses)?;
+
         let mut total_balances = TotalBalances::default();
 
-        for (i, validator) in state.validator_registry.iter().enumerate() {
-            let effective_balance = state.get_effective_balance(i, spec)?;
-            let mut status = ValidatorStatus {
-                is_slashed: validator.slashed,
-                is_withdrawable_in_current_epoch: validator
-                    .is_withdrawable_at(state.current_epoch()),
-                current_epoch_effective_balance: effective_balance,
-                ..ValidatorStatus::default()
-            };
-
-            if validator.is_active_at(state.current_epoch()) {
-                status.is_active_in_current_epoch = true;
-                total_balances.current_epoch += effective_balance;
+        for status in &statuses {
+            if status.is_active_in_current_epoch {
+                total_balances
+                    .current_epoch
+                    .safe_add_assign(status.current_epoch_effective_balance)?;
             }
 
-            if validator.is_active_at(state.previous_epoch()) {
-                status.is_active_in_previous_epoch = true;
-                total_balances.previous_epoch += effective_balance;
+            if status.is_active_in_previous_epoch {
+                total_balances
+                    .previous_epoch
+                    .safe_add_assign(status.current_epoch_effective_balance)?;
             }
-
-            statuses.push(status);
         }
 
         Ok(Self {
@@ -202,58 +212,24 @@ impl ValidatorStatuses {
     /// Process some attestations from the given `state` updating the `statuses` and
     /// `total_balances` fields.
     ///
-    /// Spec v0.6.3
+    /// Attestations are profiled in parallel, then applied to the `statuses` in the order they
+    /// appear in the `state`.
+    ///
+    /// Spec v0.8.1
     pub fn process_attestations<T: EthSpec>(
         &mut self,
         state: &BeaconState<T>,
         spec: &ChainSpec,
     ) -> Result<(), BeaconStateError> {
-        for a in state
+        let profiles = state
             .previous_epoch_attestations
-            .iter()
-            .chain(state.current_epoch_attestations.iter())
-        {
-            let attesting_indices =
-                get_attesting_indices_unsorted(state, &a.data, &a.aggregation_bitfield)?;
+            .par_iter()
+            .chain(state.current_epoch_attestations.par_iter())
+            .map(|a| profile_attestation(a, state))
+            .collect::<Vec<_>>();
 
-            let mut status = ValidatorStatus::default();
-
-            // Profile this attestation, updating the total balances and generating an
-            // `ValidatorStatus` object that applies to all participants in the attestation.
-            if is_from_epoch(a, state.current_epoch()) {
-                status.is_current_epoch_attester = true;
-
-                if target_matches_epoch_start_block(a, state, state.current_epoch())? {
-                    status.is_current_epoch_target_attester = true;
-                }
-            } else if is_from_epoch(a, state.previous_epoch()) {
-                status.is_previous_epoch_attester = true;
-
-                // The inclusion slot and distance are only required for previous epoch attesters.
-                let attestation_slot = state.get_attestation_slot(&a.data)?;
-                let inclusion_slot = attestation_slot + a.inclusion_delay;
-                let relative_epoch =
-                    RelativeEpoch::from_slot(state.slot, inclusion_slot, T::slots_per_epoch())?;
-                status.inclusion_info = Some(InclusionInfo {
-                    slot: inclusion_slot,
-                    distance: a.inclusion_delay,
-                    proposer_index: state.get_beacon_proposer_index(
-                        inclusion_slot,
-                        relative_epoch,
-                        spec,
-                    )?,
-                });
-
-                if target_matches_epoch_start_block(a, state, state.previous_epoch())? {
-                    status.is_previous_epoch_target_attester = true;
-                }
-
-                if has_common_beacon_block_root(a, state)? {
-                    status.is_previous_epoch_head_attester = true;
-                }
-            }
-
-            // Loop through the participating validator indices and update the status vec.
+        // Loop through the participating validator indices and update the status vec.
+        for (attesting_indices, status) in first_error(profiles)? {
             for validator_index in attesting_indices {
                 self.statuses[validator_index].update(&status);
             }
@@ -266,19 +242,29 @@ impl ValidatorStatuses {
                 let validator_balance = state.get_effective_balance(index, spec)?;
 
                 if v.is_current_epoch_attester {
-                    self.total_balances.current_epoch_attesters += validator_balance;
+                    self.total_balances
+                        .current_epoch_attesters
+                        .safe_add_assign(validator_balance)?;
                 }
                 if v.is_current_epoch_target_attester {
-                    self.total_balances.current_epoch_target_attesters += validator_balance;
+                    self.total_balances
+                        .current_epoch_target_attesters
+                        .safe_add_assign(validator_balance)?;
                 }
                 if v.is_previous_epoch_attester {
-                    self.total_balances.previous_epoch_attesters += validator_balance;
+                    self.total_balances
+                        .previous_epoch_attesters
+                        .safe_add_assign(validator_balance)?;
                 }
                 if v.is_previous_epoch_target_attester {
-                    self.total_balances.previous_epoch_target_attesters += validator_balance;
+                    self.total_balances
+                        .previous_epoch_target_attesters
+                        .safe_add_assign(validator_balance)?;
                 }
                 if v.is_previous_epoch_head_attester {
-                    self.total_balances.previous_epoch_head_attesters += validator_balance;
+                    self.total_balances
+                        .previous_epoch_head_attesters
+                        .safe_add_assign(validator_balance)?;
                 }
             }
         }
@@ -289,7 +275,7 @@ impl ValidatorStatuses {
     /// Update the `statuses` for each validator based upon whether or not they attested to the
     /// "winning" shard block root for the previous epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn process_winning_roots<T: EthSpec>(
         &mut self,
         state: &BeaconState<T>,
@@ -321,37 +307,86 @@ impl ValidatorStatuses {
     }
 }
 
+/// Returns the first `Err` in `results` or, if there are none, all of the `Ok` values.
+///
+/// Used after parallel iteration so that the error returned is the first in iteration order,
+/// rather than whichever thread happened to fail first.
+pub fn first_error<T, E>(results: Vec<Result<T, E>>) -> Result<Vec<T>, E> {
+    results.into_iter().collect()
+}
+
+/// Returns the indices of the validators who participated in the `PendingAttestation` `a`, along
+/// with a `ValidatorStatus` which applies to all of them.
+///
+/// Spec v0.8.1
+fn profile_attestation<T: EthSpec>(
+    a: &PendingAttestation<T>,
+    state: &BeaconState<T>,
+) -> Result<(Vec<usize>, ValidatorStatus), BeaconStateError> {
+    let attesting_indices = get_attesting_indices_unsorted(state, &a.data, &a.aggregation_bits)?;
+
+    let mut status = ValidatorStatus::default();
+
+    // Profile this attestation, generating a `ValidatorStatus` object that applies to all
+    // participants in the attestation.
+    if is_from_epoch(a, state.current_epoch()) {
+        status.is_current_epoch_attester = true;
+
+        if target_matches_epoch_start_block(a, state, state.current_epoch())? {
+            status.is_current_epoch_target_attester = true;
+        }
+    } else if is_from_epoch(a, state.previous_epoch()) {
+        status.is_previous_epoch_attester = true;
+
+        // The inclusion delay and proposer are only required for previous epoch attesters.
+        status.inclusion_info = Some(InclusionInfo {
+            delay: a.inclusion_delay,
+            proposer_index: a.proposer_index as usize,
+        });
+
+        if target_matches_epoch_start_block(a, state, state.previous_epoch())? {
+            status.is_previous_epoch_target_attester = true;
+        }
+
+        if has_common_beacon_block_root(a, state)? {
+            status.is_previous_epoch_head_attester = true;
+        }
+    }
+
+    Ok((attesting_indices, status))
+}
+
 /// Returns `true` if some `PendingAttestation` is from the supplied `epoch`.
 ///
-/// Spec v0.6.3
-fn is_from_epoch(a: &PendingAttestation, epoch: Epoch) -> bool {
-    a.data.target_epoch == epoch
+/// Spec v0.8.1
+fn is_from_epoch<T: EthSpec>(a: &PendingAttestation<T>, epoch: Epoch) -> bool {
+    a.data.target.epoch == epoch
 }
 
 /// Returns `true` if the attestation's FFG target is equal to the hash of the `state`'s first
 /// beacon block in the given `epoch`.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 fn target_matches_epoch_start_block<T: EthSpec>(
-    a: &PendingAttestation,
+    a: &PendingAttestation<T>,
     state: &BeaconState<T>,
     epoch: Epoch,
 ) -> Result<bool, BeaconStateError> {
     let slot = epoch.start_slot(T::slots_per_epoch());
     let state_boundary_root = *state.get_block_root(slot)?;
 
-    Ok(a.data.target_root == state_boundary_root)
+    Ok(a.data.target.root == state_boundary_root)
 }
 
 /// Returns `true` if a `PendingAttestation` and `BeaconState` share the same beacon block hash for
 /// the current slot of the `PendingAttestation`.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 fn has_common_beacon_block_root<T: EthSpec>(
-    a: &PendingAttestation,
+    a: &PendingAttestation<T>,
     state: &BeaconState<T>,
 ) -> Result<bool, BeaconStateError> {
-    let attestation_slot = state.get_attestation_slot(&a.data)?;
+    let attestation_slot = state.get_attestation_data_slot(&a.data)?;
     let state_block_root = *state.get_block_root(attestation_slot)?;
 
     Ok(a.data.beacon_block_root == state_block_root)
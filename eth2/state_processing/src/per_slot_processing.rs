@@ -1,3 +1,6 @@
+use crate::per_epoch_processing::{per_epoch_processing_with_tracer, NoRewards, RewardCollector};
+use crate::state_diff::{NoTracer, StateTracer};
+use crate::upgrade::process_fork_upgrade;
 use crate::*;
 use types::*;
 
@@ -7,20 +10,43 @@ pub enum Error {
     EpochProcessingError(EpochProcessingError),
 }
 
-/// Advances a state forward by one slot, performing per-epoch processing if required.
+/// Advances a state forward by one slot, performing per-epoch processing and upgrading to a
+/// scheduled fork if required.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub fn per_slot_processing<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
+) -> Result<(), Error> {
+    per_slot_processing_with_tracer(state, spec, &mut NoTracer, &mut NoRewards)
+}
+
+/// Advances a state forward by one slot, performing per-epoch processing if required and passing
+/// the state to `tracer` after each step.
+///
+/// If per-epoch processing is performed, the rewards and penalties applied to each validator are
+/// passed to `rewards`.
+///
+/// Spec v0.8.1
+pub fn per_slot_processing_with_tracer<T: EthSpec, U: StateTracer<T>, R: RewardCollector>(
+    state: &mut BeaconState<T>,
+    spec: &ChainSpec,
+    tracer: &mut U,
+    rewards: &mut R,
 ) -> Result<(), Error> {
     cache_state(state, spec)?;
+    tracer.trace("cache_state", state);
 
     if (state.slot > spec.genesis_slot) && ((state.slot + 1) % T::slots_per_epoch() == 0) {
-        per_epoch_processing(state, spec)?;
+        per_epoch_processing_with_tracer(state, spec, tracer, rewards)?;
     }
 
     state.slot += 1;
+    tracer.trace("advance_slot", state);
+
+    if process_fork_upgrade(state, spec).is_some() {
+        tracer.trace("process_fork_upgrade", state);
+    }
 
     Ok(())
 }
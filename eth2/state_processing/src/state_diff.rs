@@ -0,0 +1,188 @@
+//! Optional tracing of the changes made to a `BeaconState` by each step of the state transition.
+//!
+//! The `per_*_processing_with_tracer` functions call `StateTracer::trace` after each step (e.g.,
+//! `process_randao`, `process_crosslinks`). The `StateDiffTracer` uses this to build a
+//! `StateDiffReport`, listing the fields of the state that were changed by each step.
+use compare_fields::{CompareFields, Comparison};
+use serde_derive::{Deserialize, Serialize};
+use types::{BeaconState, EthSpec, Slot};
+
+/// Observes a `BeaconState` after each step of a state transition.
+pub trait StateTracer<T: EthSpec> {
+    /// Called once `step` has been applied to `state`.
+    fn trace(&mut self, step: &str, state: &BeaconState<T>);
+}
+
+/// A `StateTracer` which does nothing, used when tracing is not required.
+pub struct NoTracer;
+
+impl<T: EthSpec> StateTracer<T> for NoTracer {
+    #[inline]
+    fn trace(&mut self, _step: &str, _state: &BeaconState<T>) {}
+}
+
+/// A single field (or element of a list field) which was changed.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct FieldDiff {
+    /// The name of the field, with the index appended for list elements (e.g., `balances[3]`).
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// The fields changed by a single step of the state transition.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct StepDiff {
+    pub step: String,
+    /// The slot of the state once the step was applied.
+    pub slot: Slot,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// The fields changed by each step of a state transition, in the order the steps were applied.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct StateDiffReport {
+    pub steps: Vec<StepDiff>,
+}
+
+impl StateDiffReport {
+    /// Returns only the steps which changed at least one field.
+    pub fn changed_steps(&self) -> impl Iterator<Item = &StepDiff> {
+        self.steps.iter().filter(|step| !step.fields.is_empty())
+    }
+}
+
+/// A `StateTracer` which records the fields changed by each step in a `StateDiffReport`.
+///
+/// Keeps a copy of the state as it was prior to each step, so it should only be used where the
+/// cost of cloning the state at every step is acceptable (e.g., debugging, offline tools).
+pub struct StateDiffTracer<T: EthSpec> {
+    previous: BeaconState<T>,
+    report: StateDiffReport,
+}
+
+impl<T: EthSpec> StateDiffTracer<T> {
+    /// Create a tracer for a state transition starting from `state`.
+    pub fn new(state: &BeaconState<T>) -> Self {
+        Self {
+            previous: state.clone(),
+            report: StateDiffReport::default(),
+        }
+    }
+
+    pub fn report(&self) -> &StateDiffReport {
+        &self.report
+    }
+
+    pub fn into_report(self) -> StateDiffReport {
+        self.report
+    }
+}
+
+impl<T: EthSpec> StateTracer<T> for StateDiffTracer<T> {
+    fn trace(&mut self, step: &str, state: &BeaconState<T>) {
+        self.report.steps.push(StepDiff {
+            step: step.to_string(),
+            slot: state.slot,
+            fields: diff_states(&self.previous, state),
+        });
+        self.previous = state.clone();
+    }
+}
+
+/// Returns the fields which differ between `a` and `b`, flattening list fields into their
+/// individual elements.
+///
+/// Caches are not compared.
+pub fn diff_states<T: EthSpec>(a: &BeaconState<T>, b: &BeaconState<T>) -> Vec<FieldDiff> {
+    a.compare_fields(b)
+        .into_iter()
+        .filter(Comparison::not_equal)
+        .flat_map(|comparison| match comparison {
+            Comparison::Child(fc) => vec![FieldDiff {
+                field: fc.field_name,
+                before: fc.a,
+                after: fc.b,
+            }],
+            Comparison::Parent {
+                field_name,
+                children,
+                ..
+            } => children
+                .into_iter()
+                .filter(|fc| fc.not_equal())
+                .map(|fc| FieldDiff {
+                    field: format!("{}[{}]", field_name, fc.field_name),
+                    before: fc.a,
+                    after: fc.b,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(all(test, not(feature = "fake_crypto")))]
+mod tests {
+    use super::*;
+    use crate::per_epoch_processing::NoRewards;
+    use crate::per_slot_processing::per_slot_processing_with_tracer;
+    use types::test_utils::TestingBeaconStateBuilder;
+    use types::*;
+
+    const VALIDATOR_COUNT: usize = 16;
+
+    fn test_state() -> (BeaconState<MinimalEthSpec>, ChainSpec) {
+        let spec = MinimalEthSpec::default_spec();
+        let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(VALIDATOR_COUNT, &spec);
+        builder.teleport_to_slot(
+            (MinimalEthSpec::genesis_epoch() + 4).start_slot(MinimalEthSpec::slots_per_epoch()),
+        );
+        let (state, _keypairs) = builder.build();
+        (state, spec)
+    }
+
+    #[test]
+    fn diff_lists_changed_fields_and_elements() {
+        let (a, _spec) = test_state();
+        let mut b = a.clone();
+        b.slot += 1;
+        b.balances[3] += 1;
+
+        let diff = diff_states(&a, &b);
+        let fields = diff.iter().map(|d| d.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(fields, vec!["slot", "balances[3]"]);
+        assert_eq!(diff[1].before, format!("Some({})", a.balances[3]));
+        assert_eq!(diff[1].after, format!("Some({})", b.balances[3]));
+
+        assert!(diff_states(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn slot_processing_is_traced() {
+        let (mut state, spec) = test_state();
+        let mut tracer = StateDiffTracer::new(&state);
+
+        // Cross an epoch boundary, so epoch processing is also traced.
+        for _ in 0..MinimalEthSpec::slots_per_epoch() {
+            per_slot_processing_with_tracer(&mut state, &spec, &mut tracer, &mut NoRewards)
+                .unwrap();
+        }
+
+        let report = tracer.into_report();
+        let steps = report
+            .steps
+            .iter()
+            .map(|step| step.step.as_str())
+            .collect::<Vec<_>>();
+
+        assert!(steps.contains(&"process_final_updates"));
+        assert_eq!(steps.last(), Some(&"advance_slot"));
+
+        // Every slot advance changes the slot and nothing else.
+        for step in report.steps.iter().filter(|s| s.step == "advance_slot") {
+            assert_eq!(step.fields.len(), 1);
+            assert_eq!(step.fields[0].field, "slot");
+        }
+    }
+}
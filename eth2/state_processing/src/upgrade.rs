@@ -0,0 +1,106 @@
+use types::*;
+
+/// If `state` is at the first slot of an epoch at which a fork in the `spec.fork_schedule`
+/// activates, upgrade `state` to that fork.
+///
+/// Returns the fork that was applied, if any.
+pub fn process_fork_upgrade<'a, T: EthSpec>(
+    state: &mut BeaconState<T>,
+    spec: &'a ChainSpec,
+) -> Option<&'a ScheduledFork> {
+    if state.slot % T::slots_per_epoch() != 0 {
+        return None;
+    }
+
+    let fork = spec.fork_starting_at(state.current_epoch())?;
+
+    upgrade_to_fork(state, fork, spec);
+
+    Some(fork)
+}
+
+/// Apply the changes made by `fork` to `state`.
+///
+/// Forks which change the contents of the `BeaconState` (rather than only the `ChainSpec`
+/// constants) should perform that migration here, based upon the `fork.name`.
+fn upgrade_to_fork<T: EthSpec>(state: &mut BeaconState<T>, fork: &ScheduledFork, spec: &ChainSpec) {
+    state.fork = spec.fork_at(fork.epoch, T::genesis_epoch());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::per_epoch_processing::RewardReport;
+    use crate::per_slot_processing::per_slot_processing_with_tracer;
+    use crate::state_diff::NoTracer;
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    const FORK_EPOCH: u64 = 5;
+
+    /// Advance `state` through two epoch transitions (the second at the end of `FORK_EPOCH`),
+    /// returning the reward reports and the `Fork` of `state` at each slot.
+    fn run_chain(spec: &ChainSpec) -> (Vec<RewardReport>, Vec<Fork>) {
+        let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(8, spec);
+        builder.teleport_to_slot(
+            Epoch::new(FORK_EPOCH - 1).start_slot(MinimalEthSpec::slots_per_epoch()),
+        );
+        let (mut state, _keypairs) = builder.build();
+
+        let mut reports = vec![];
+        let mut forks = vec![];
+
+        for _ in 0..2 * MinimalEthSpec::slots_per_epoch() {
+            per_slot_processing_with_tracer(&mut state, spec, &mut NoTracer, &mut reports).unwrap();
+            forks.push(state.fork.clone());
+        }
+
+        (reports, forks)
+    }
+
+    #[test]
+    fn toy_fork_changes_constant_mid_chain() {
+        let spec = MinimalEthSpec::default_spec();
+        let mut fork_spec = spec.clone();
+        fork_spec.fork_schedule = vec![ScheduledFork {
+            name: "toy".to_string(),
+            epoch: Epoch::new(FORK_EPOCH),
+            version: [0, 0, 0, 1],
+            overrides: SpecOverrides {
+                base_reward_factor: Some(spec.base_reward_factor * 2),
+                ..SpecOverrides::default()
+            },
+        }];
+
+        let (reports, forks) = run_chain(&spec);
+        let (fork_reports, fork_forks) = run_chain(&fork_spec);
+
+        // Without a fork schedule, the state remains on the genesis fork.
+        assert!(forks
+            .iter()
+            .all(|fork| *fork == Fork::genesis(MinimalEthSpec::genesis_epoch())));
+
+        // The state is upgraded at the first slot of the fork epoch.
+        let slots_per_epoch = MinimalEthSpec::slots_per_epoch() as usize;
+        assert_eq!(fork_forks[slots_per_epoch - 2], forks[slots_per_epoch - 2]);
+        assert_eq!(
+            fork_forks[slots_per_epoch - 1],
+            Fork {
+                previous_version: GENESIS_FORK_VERSION,
+                current_version: [0, 0, 0, 1],
+                epoch: Epoch::new(FORK_EPOCH),
+            }
+        );
+
+        // Epoch processing prior to the fork uses the original constants, after the fork it uses
+        // the new base reward factor.
+        assert_eq!(reports.len(), 2);
+        assert_eq!(fork_reports.len(), 2);
+        assert_eq!(reports[0], fork_reports[0]);
+        assert_eq!(fork_reports[1].epoch, Epoch::new(FORK_EPOCH));
+        assert!(
+            fork_reports[1].validators[0].source.penalties
+                > reports[1].validators[0].source.penalties
+        );
+    }
+}
@@ -1,15 +1,15 @@
-use super::{AggregateSignature, AttestationData, Bitfield};
+use super::{AggregateSignature, AttestationData, BitList, EthSpec};
 use crate::test_utils::TestRandom;
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
-use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
+use tree_hash_derive::{SignedRoot, TreeHash};
 
 /// Details an attestation that can be slashable.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     Clone,
@@ -19,36 +19,37 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
     Encode,
     Decode,
     TreeHash,
-    CachedTreeHash,
     TestRandom,
     SignedRoot,
 )]
-pub struct Attestation {
-    pub aggregation_bitfield: Bitfield,
+#[serde(bound = "T: EthSpec")]
+pub struct Attestation<T: EthSpec> {
+    pub aggregation_bits: BitList<T::MaxValidatorsPerCommittee>,
     pub data: AttestationData,
-    pub custody_bitfield: Bitfield,
+    pub custody_bits: BitList<T::MaxValidatorsPerCommittee>,
     #[signed_root(skip_hashing)]
     pub signature: AggregateSignature,
 }
 
-impl Attestation {
+impl<T: EthSpec> Attestation<T> {
     /// Are the aggregation bitfields of these attestations disjoint?
-    pub fn signers_disjoint_from(&self, other: &Attestation) -> bool {
-        self.aggregation_bitfield
-            .intersection(&other.aggregation_bitfield)
-            .is_zero()
+    ///
+    /// Bitfields of differing lengths are never considered disjoint.
+    pub fn signers_disjoint_from(&self, other: &Self) -> bool {
+        self.aggregation_bits
+            .intersection(&other.aggregation_bits)
+            .map_or(false, |intersection| intersection.is_zero())
     }
 
     /// Aggregate another Attestation into this one.
     ///
     /// The aggregation bitfields must be disjoint, and the data must be the same.
-    pub fn aggregate(&mut self, other: &Attestation) {
+    pub fn aggregate(&mut self, other: &Self) {
         debug_assert_eq!(self.data, other.data);
         debug_assert!(self.signers_disjoint_from(other));
 
-        self.aggregation_bitfield
-            .union_inplace(&other.aggregation_bitfield);
-        self.custody_bitfield.union_inplace(&other.custody_bitfield);
+        self.aggregation_bits.union_inplace(&other.aggregation_bits);
+        self.custody_bits.union_inplace(&other.custody_bits);
         self.signature.add_aggregate(&other.signature);
     }
 }
@@ -56,7 +57,37 @@ impl Attestation {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::MainnetEthSpec;
+
+    pub type FoundationAttestation = Attestation<MainnetEthSpec>;
+
+    ssz_tests!(FoundationAttestation);
+
+    serde_tests!(FoundationAttestation);
 
-    ssz_tests!(Attestation);
-    cached_tree_hash_tests!(Attestation);
+    #[test]
+    fn decode_rejects_oversized_bitfields() {
+        use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+        use crate::typenum::{Unsigned, U8192};
+        use ssz::{Decode, Encode};
+
+        #[derive(Encode)]
+        struct OversizedAttestation {
+            aggregation_bits: BitList<U8192>,
+            data: AttestationData,
+            custody_bits: BitList<U8192>,
+            signature: AggregateSignature,
+        }
+
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let len = <MainnetEthSpec as EthSpec>::MaxValidatorsPerCommittee::to_usize() + 1;
+        let oversized = OversizedAttestation {
+            aggregation_bits: BitList::with_capacity(len).unwrap(),
+            data: AttestationData::random_for_test(&mut rng),
+            custody_bits: BitList::with_capacity(len).unwrap(),
+            signature: AggregateSignature::random_for_test(&mut rng),
+        };
+
+        assert!(FoundationAttestation::from_ssz_bytes(&oversized.as_ssz_bytes()).is_err());
+    }
 }
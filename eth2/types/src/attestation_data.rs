@@ -1,5 +1,5 @@
 use crate::test_utils::TestRandom;
-use crate::{Epoch, Hash256};
+use crate::{Checkpoint, Crosslink, Hash256};
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
@@ -9,7 +9,7 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 
 /// The data upon which an attestation is based.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     Clone,
@@ -31,15 +31,11 @@ pub struct AttestationData {
     pub beacon_block_root: Hash256,
 
     // FFG Vote
-    pub source_epoch: Epoch,
-    pub source_root: Hash256,
-    pub target_epoch: Epoch,
-    pub target_root: Hash256,
+    pub source: Checkpoint,
+    pub target: Checkpoint,
 
     // Crosslink Vote
-    pub shard: u64,
-    pub previous_crosslink_root: Hash256,
-    pub crosslink_data_root: Hash256,
+    pub crosslink: Crosslink,
 }
 
 #[cfg(test)]
@@ -47,5 +43,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(AttestationData);
+
+    serde_tests!(AttestationData);
     cached_tree_hash_tests!(AttestationData);
 }
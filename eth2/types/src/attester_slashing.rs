@@ -1,34 +1,28 @@
-use crate::{test_utils::TestRandom, IndexedAttestation};
+use crate::{test_utils::TestRandom, EthSpec, IndexedAttestation};
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
-use tree_hash_derive::{CachedTreeHash, TreeHash};
+use tree_hash_derive::TreeHash;
 
 /// Two conflicting attestations.
 ///
-/// Spec v0.6.3
-#[derive(
-    Debug,
-    PartialEq,
-    Clone,
-    Serialize,
-    Deserialize,
-    Encode,
-    Decode,
-    TreeHash,
-    CachedTreeHash,
-    TestRandom,
-)]
-pub struct AttesterSlashing {
-    pub attestation_1: IndexedAttestation,
-    pub attestation_2: IndexedAttestation,
+/// Spec v0.8.1
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct AttesterSlashing<T: EthSpec> {
+    pub attestation_1: IndexedAttestation<T>,
+    pub attestation_2: IndexedAttestation<T>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::MainnetEthSpec;
 
-    ssz_tests!(AttesterSlashing);
-    cached_tree_hash_tests!(AttesterSlashing);
+    pub type FoundationAttesterSlashing = AttesterSlashing<MainnetEthSpec>;
+
+    ssz_tests!(FoundationAttesterSlashing);
+
+    serde_tests!(FoundationAttesterSlashing);
 }
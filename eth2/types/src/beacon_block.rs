@@ -6,11 +6,11 @@ use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
 use tree_hash::{SignedRoot, TreeHash};
-use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
+use tree_hash_derive::{SignedRoot, TreeHash};
 
 /// A block of the `BeaconChain`.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -20,27 +20,27 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
     Encode,
     Decode,
     TreeHash,
-    CachedTreeHash,
     TestRandom,
     SignedRoot,
 )]
-pub struct BeaconBlock {
+#[serde(bound = "T: EthSpec")]
+pub struct BeaconBlock<T: EthSpec> {
     pub slot: Slot,
-    pub previous_block_root: Hash256,
+    pub parent_root: Hash256,
     pub state_root: Hash256,
-    pub body: BeaconBlockBody,
+    pub body: BeaconBlockBody<T>,
     #[signed_root(skip_hashing)]
     pub signature: Signature,
 }
 
-impl BeaconBlock {
+impl<T: EthSpec> BeaconBlock<T> {
     /// Returns an empty block to be used during genesis.
     ///
-    /// Spec v0.6.3
-    pub fn empty(spec: &ChainSpec) -> BeaconBlock {
+    /// Spec v0.8.1
+    pub fn empty(spec: &ChainSpec) -> Self {
         BeaconBlock {
             slot: spec.genesis_slot,
-            previous_block_root: spec.zero_hash,
+            parent_root: spec.zero_hash,
             state_root: spec.zero_hash,
             body: BeaconBlockBody {
                 randao_reveal: Signature::empty_signature(),
@@ -50,12 +50,12 @@ impl BeaconBlock {
                     deposit_count: 0,
                 },
                 graffiti: [0; 32],
-                proposer_slashings: vec![],
-                attester_slashings: vec![],
-                attestations: vec![],
-                deposits: vec![],
-                voluntary_exits: vec![],
-                transfers: vec![],
+                proposer_slashings: VariableList::empty(),
+                attester_slashings: VariableList::empty(),
+                attestations: VariableList::empty(),
+                deposits: VariableList::empty(),
+                voluntary_exits: VariableList::empty(),
+                transfers: VariableList::empty(),
             },
             signature: Signature::empty_signature(),
         }
@@ -63,7 +63,7 @@ impl BeaconBlock {
 
     /// Returns the `signed_root` of the block.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn canonical_root(&self) -> Hash256 {
         Hash256::from_slice(&self.signed_root()[..])
     }
@@ -75,20 +75,20 @@ impl BeaconBlock {
     ///
     /// Note: performs a full tree-hash of `self.body`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn block_header(&self) -> BeaconBlockHeader {
         BeaconBlockHeader {
             slot: self.slot,
-            previous_block_root: self.previous_block_root,
+            parent_root: self.parent_root,
             state_root: self.state_root,
-            block_body_root: Hash256::from_slice(&self.body.tree_hash_root()[..]),
+            body_root: Hash256::from_slice(&self.body.tree_hash_root()[..]),
             signature: self.signature.clone(),
         }
     }
 
     /// Returns a "temporary" header, where the `state_root` is `spec.zero_hash`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn temporary_block_header(&self, spec: &ChainSpec) -> BeaconBlockHeader {
         BeaconBlockHeader {
             state_root: spec.zero_hash,
@@ -102,6 +102,9 @@ impl BeaconBlock {
 mod tests {
     use super::*;
 
-    ssz_tests!(BeaconBlock);
-    cached_tree_hash_tests!(BeaconBlock);
+    pub type FoundationBeaconBlock = BeaconBlock<MainnetEthSpec>;
+
+    ssz_tests!(FoundationBeaconBlock);
+
+    serde_tests!(FoundationBeaconBlock);
 }
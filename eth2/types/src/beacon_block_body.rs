@@ -1,43 +1,41 @@
-use crate::test_utils::{graffiti_from_hex_str, TestRandom};
+use crate::serde_utils::{graffiti_from_hex_str, graffiti_to_hex_str};
+use crate::test_utils::TestRandom;
 use crate::*;
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
+use ssz_types::VariableList;
 use test_random_derive::TestRandom;
-use tree_hash_derive::{CachedTreeHash, TreeHash};
+use tree_hash_derive::TreeHash;
 
 /// The body of a `BeaconChain` block, containing operations.
 ///
-/// Spec v0.6.3
-#[derive(
-    Debug,
-    PartialEq,
-    Clone,
-    Serialize,
-    Deserialize,
-    Encode,
-    Decode,
-    TreeHash,
-    CachedTreeHash,
-    TestRandom,
-)]
-pub struct BeaconBlockBody {
+/// Spec v0.8.1
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct BeaconBlockBody<T: EthSpec> {
     pub randao_reveal: Signature,
     pub eth1_data: Eth1Data,
-    #[serde(deserialize_with = "graffiti_from_hex_str")]
+    #[serde(
+        deserialize_with = "graffiti_from_hex_str",
+        serialize_with = "graffiti_to_hex_str"
+    )]
     pub graffiti: [u8; 32],
-    pub proposer_slashings: Vec<ProposerSlashing>,
-    pub attester_slashings: Vec<AttesterSlashing>,
-    pub attestations: Vec<Attestation>,
-    pub deposits: Vec<Deposit>,
-    pub voluntary_exits: Vec<VoluntaryExit>,
-    pub transfers: Vec<Transfer>,
+    pub proposer_slashings: VariableList<ProposerSlashing, T::MaxProposerSlashings>,
+    pub attester_slashings: VariableList<AttesterSlashing<T>, T::MaxAttesterSlashings>,
+    pub attestations: VariableList<Attestation<T>, T::MaxAttestations>,
+    pub deposits: VariableList<Deposit, T::MaxDeposits>,
+    pub voluntary_exits: VariableList<VoluntaryExit, T::MaxVoluntaryExits>,
+    pub transfers: VariableList<Transfer, T::MaxTransfers>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    ssz_tests!(BeaconBlockBody);
-    cached_tree_hash_tests!(BeaconBlockBody);
+    pub type FoundationBeaconBlockBody = BeaconBlockBody<MainnetEthSpec>;
+
+    ssz_tests!(FoundationBeaconBlockBody);
+
+    serde_tests!(FoundationBeaconBlockBody);
 }
@@ -10,7 +10,7 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 
 /// A header of a `BeaconBlock`.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -26,9 +26,9 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 )]
 pub struct BeaconBlockHeader {
     pub slot: Slot,
-    pub previous_block_root: Hash256,
+    pub parent_root: Hash256,
     pub state_root: Hash256,
-    pub block_body_root: Hash256,
+    pub body_root: Hash256,
     #[signed_root(skip_hashing)]
     pub signature: Signature,
 }
@@ -36,18 +36,18 @@ pub struct BeaconBlockHeader {
 impl BeaconBlockHeader {
     /// Returns the `tree_hash_root` of the header.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn canonical_root(&self) -> Hash256 {
         Hash256::from_slice(&self.signed_root()[..])
     }
 
     /// Given a `body`, consumes `self` and returns a complete `BeaconBlock`.
     ///
-    /// Spec v0.6.3
-    pub fn into_block(self, body: BeaconBlockBody) -> BeaconBlock {
+    /// Spec v0.8.1
+    pub fn into_block<T: EthSpec>(self, body: BeaconBlockBody<T>) -> BeaconBlock<T> {
         BeaconBlock {
             slot: self.slot,
-            previous_block_root: self.previous_block_root,
+            parent_root: self.parent_root,
             state_root: self.state_root,
             body,
             signature: self.signature,
@@ -60,5 +60,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(BeaconBlockHeader);
+
+    serde_tests!(BeaconBlockHeader);
     cached_tree_hash_tests!(BeaconBlockHeader);
 }
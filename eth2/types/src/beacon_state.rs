@@ -1,19 +1,22 @@
 use self::committee_cache::get_active_validator_indices;
 use self::exit_cache::ExitCache;
+use self::tree_hash_cache::BeaconTreeHashCache;
+use crate::serde_utils::{quoted_u64, quoted_u64_vec};
 use crate::test_utils::TestRandom;
 use crate::*;
-use cached_tree_hash::{Error as TreeHashCacheError, TreeHashCache};
+use cached_tree_hash::Error as TreeHashCacheError;
 use compare_fields_derive::CompareFields;
 use fixed_len_vec::{typenum::Unsigned, FixedLenVec};
 use hashing::hash;
 use int_to_bytes::{int_to_bytes32, int_to_bytes8};
 use pubkey_cache::PubkeyCache;
+use safe_arith::{ArithError, SafeArith};
 use serde_derive::{Deserialize, Serialize};
 use ssz::ssz_encode;
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
-use tree_hash_derive::{CachedTreeHash, TreeHash};
+use tree_hash_derive::TreeHash;
 
 pub use self::committee_cache::CommitteeCache;
 pub use beacon_state_types::*;
@@ -23,6 +26,7 @@ mod committee_cache;
 mod exit_cache;
 mod pubkey_cache;
 mod tests;
+mod tree_hash_cache;
 
 pub const CACHED_EPOCHS: usize = 3;
 const MAX_RANDOM_BYTE: u64 = (1 << 8) - 1;
@@ -58,12 +62,14 @@ pub enum Error {
     CurrentCommitteeCacheUninitialized,
     RelativeEpochError(RelativeEpochError),
     CommitteeCacheUninitialized(RelativeEpoch),
+    SszTypesError(ssz_types::Error),
     TreeHashCacheError(TreeHashCacheError),
+    ArithError(ArithError),
 }
 
 /// The state of the `BeaconChain` at some slot.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -74,81 +80,111 @@ pub enum Error {
     Encode,
     Decode,
     TreeHash,
-    CachedTreeHash,
     CompareFields,
 )]
+#[serde(bound = "T: EthSpec")]
 pub struct BeaconState<T>
 where
     T: EthSpec,
 {
-    // Misc
-    pub slot: Slot,
+    // Versioning
+    #[serde(with = "quoted_u64")]
     pub genesis_time: u64,
+    pub slot: Slot,
     pub fork: Fork,
 
-    // Validator registry
-    #[compare_fields(as_slice)]
-    pub validator_registry: Vec<Validator>,
+    // History
+    pub latest_block_header: BeaconBlockHeader,
+    #[compare_fields(as_iter)]
+    pub block_roots: FixedLenVec<Hash256, T::SlotsPerHistoricalRoot>,
+    #[compare_fields(as_iter)]
+    pub state_roots: FixedLenVec<Hash256, T::SlotsPerHistoricalRoot>,
     #[compare_fields(as_slice)]
-    pub balances: Vec<u64>,
+    pub historical_roots: VariableList<Hash256, T::HistoricalRootsLimit>,
 
-    // Randomness and committees
-    pub latest_randao_mixes: FixedLenVec<Hash256, T::LatestRandaoMixesLength>,
-    pub latest_start_shard: u64,
-
-    // Finality
-    pub previous_epoch_attestations: Vec<PendingAttestation>,
-    pub current_epoch_attestations: Vec<PendingAttestation>,
-    pub previous_justified_epoch: Epoch,
-    pub current_justified_epoch: Epoch,
-    pub previous_justified_root: Hash256,
-    pub current_justified_root: Hash256,
-    pub justification_bitfield: u64,
-    pub finalized_epoch: Epoch,
-    pub finalized_root: Hash256,
-
-    // Recent state
-    pub current_crosslinks: FixedLenVec<Crosslink, T::ShardCount>,
-    pub previous_crosslinks: FixedLenVec<Crosslink, T::ShardCount>,
-    pub latest_block_roots: FixedLenVec<Hash256, T::SlotsPerHistoricalRoot>,
+    // Ethereum 1.0 chain data
+    pub eth1_data: Eth1Data,
     #[compare_fields(as_slice)]
-    pub latest_state_roots: FixedLenVec<Hash256, T::SlotsPerHistoricalRoot>,
+    pub eth1_data_votes: VariableList<Eth1Data, T::SlotsPerEth1VotingPeriod>,
+    #[serde(with = "quoted_u64")]
+    pub eth1_deposit_index: u64,
+
+    // Registry
+    #[compare_fields(as_iter)]
+    pub validators: CowList<Validator, T::ValidatorRegistryLimit>,
+    #[compare_fields(as_iter)]
+    #[serde(with = "quoted_u64_vec")]
+    pub balances: CowList<u64, T::ValidatorRegistryLimit>,
+
+    // Shuffling
+    #[serde(with = "quoted_u64")]
+    pub start_shard: u64,
+    #[compare_fields(as_iter)]
+    pub randao_mixes: FixedLenVec<Hash256, T::EpochsPerHistoricalVector>,
+    #[compare_fields(as_iter)]
+    active_index_roots: FixedLenVec<Hash256, T::EpochsPerHistoricalVector>,
+    #[compare_fields(as_iter)]
+    compact_committees_roots: FixedLenVec<Hash256, T::EpochsPerHistoricalVector>,
+
+    // Slashings
+    #[compare_fields(as_iter)]
+    #[serde(with = "quoted_u64_vec")]
+    slashings: FixedLenVec<u64, T::EpochsPerSlashingsVector>,
+
+    // Attestations
     #[compare_fields(as_slice)]
-    latest_active_index_roots: FixedLenVec<Hash256, T::LatestActiveIndexRootsLength>,
-    latest_slashed_balances: FixedLenVec<u64, T::LatestSlashedExitLength>,
-    pub latest_block_header: BeaconBlockHeader,
-    pub historical_roots: Vec<Hash256>,
+    pub previous_epoch_attestations: VariableList<PendingAttestation<T>, T::MaxPendingAttestations>,
+    #[compare_fields(as_slice)]
+    pub current_epoch_attestations: VariableList<PendingAttestation<T>, T::MaxPendingAttestations>,
 
-    // Ethereum 1.0 chain data
-    pub latest_eth1_data: Eth1Data,
-    pub eth1_data_votes: Vec<Eth1Data>,
-    pub deposit_index: u64,
+    // Crosslinks
+    #[compare_fields(as_iter)]
+    pub previous_crosslinks: FixedLenVec<Crosslink, T::ShardCount>,
+    #[compare_fields(as_iter)]
+    pub current_crosslinks: FixedLenVec<Crosslink, T::ShardCount>,
+
+    // Finality
+    pub justification_bits: BitVector<T::JustificationBitsLength>,
+    pub previous_justified_checkpoint: Checkpoint,
+    pub current_justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
 
     // Caching (not in the spec)
-    #[serde(default)]
+    #[serde(skip_serializing, skip_deserializing)]
     #[ssz(skip_serializing)]
     #[ssz(skip_deserializing)]
     #[tree_hash(skip_hashing)]
     #[test_random(default)]
+    #[compare_fields(skip)]
     pub committee_caches: [CommitteeCache; CACHED_EPOCHS],
-    #[serde(default)]
+    #[serde(skip_serializing, skip_deserializing)]
     #[ssz(skip_serializing)]
     #[ssz(skip_deserializing)]
     #[tree_hash(skip_hashing)]
     #[test_random(default)]
+    #[compare_fields(skip)]
     pub pubkey_cache: PubkeyCache,
     #[serde(skip_serializing, skip_deserializing)]
     #[ssz(skip_serializing)]
     #[ssz(skip_deserializing)]
     #[tree_hash(skip_hashing)]
     #[test_random(default)]
-    pub tree_hash_cache: TreeHashCache,
+    #[compare_fields(skip)]
+    pub exit_cache: ExitCache,
     #[serde(skip_serializing, skip_deserializing)]
     #[ssz(skip_serializing)]
     #[ssz(skip_deserializing)]
     #[tree_hash(skip_hashing)]
     #[test_random(default)]
-    pub exit_cache: ExitCache,
+    #[compare_fields(skip)]
+    pub shuffling_cache: ShufflingCacheHandle,
+    #[serde(skip_serializing, skip_deserializing)]
+    #[ssz(skip_serializing)]
+    #[ssz(skip_deserializing)]
+    #[tree_hash(skip_hashing)]
+    #[test_random(default)]
+    #[compare_fields(skip)]
+    pub tree_hash_cache: BeaconTreeHashCache,
 }
 
 impl<T: EthSpec> BeaconState<T> {
@@ -157,92 +193,98 @@ impl<T: EthSpec> BeaconState<T> {
     /// This does not fully build a genesis beacon state, it omits processing of initial validator
     /// deposits. To obtain a full genesis beacon state, use the `BeaconStateBuilder`.
     ///
-    /// Spec v0.6.3
-    pub fn genesis(
-        genesis_time: u64,
-        latest_eth1_data: Eth1Data,
-        spec: &ChainSpec,
-    ) -> BeaconState<T> {
-        let initial_crosslink = Crosslink {
-            epoch: T::genesis_epoch(),
-            previous_crosslink_root: spec.zero_hash,
-            crosslink_data_root: spec.zero_hash,
-        };
-
+    /// Spec v0.8.1
+    pub fn genesis(genesis_time: u64, eth1_data: Eth1Data, spec: &ChainSpec) -> Self {
         BeaconState {
-            // Misc
-            slot: spec.genesis_slot,
+            // Versioning
             genesis_time,
+            slot: spec.genesis_slot,
             fork: Fork::genesis(T::genesis_epoch()),
 
+            // History
+            latest_block_header: BeaconBlock::<T>::empty(spec).temporary_block_header(spec),
+            block_roots: FixedLenVec::from(vec![
+                spec.zero_hash;
+                T::SlotsPerHistoricalRoot::to_usize()
+            ]),
+            state_roots: FixedLenVec::from(vec![
+                spec.zero_hash;
+                T::SlotsPerHistoricalRoot::to_usize()
+            ]),
+            historical_roots: VariableList::empty(),
+
+            // Eth1
+            eth1_data,
+            eth1_data_votes: VariableList::empty(),
+            eth1_deposit_index: 0,
+
             // Validator registry
-            validator_registry: vec![], // Set later in the function.
-            balances: vec![],           // Set later in the function.
+            validators: CowList::new(), // Set later in the function.
+            balances: CowList::new(),   // Set later in the function.
 
-            // Randomness and committees
-            latest_randao_mixes: FixedLenVec::from(vec![
+            // Shuffling
+            start_shard: 0,
+            randao_mixes: FixedLenVec::from(vec![
+                spec.zero_hash;
+                T::EpochsPerHistoricalVector::to_usize()
+            ]),
+            active_index_roots: FixedLenVec::from(vec![
+                spec.zero_hash;
+                T::EpochsPerHistoricalVector::to_usize()
+            ]),
+            compact_committees_roots: FixedLenVec::from(vec![
                 spec.zero_hash;
-                T::LatestRandaoMixesLength::to_usize()
+                T::EpochsPerHistoricalVector::to_usize(
+                )
+            ]),
+
+            // Slashings
+            slashings: FixedLenVec::from(vec![0; T::EpochsPerSlashingsVector::to_usize()]),
+
+            // Attestations
+            previous_epoch_attestations: VariableList::empty(),
+            current_epoch_attestations: VariableList::empty(),
+
+            // Crosslinks
+            previous_crosslinks: FixedLenVec::from(vec![
+                Crosslink::default();
+                T::ShardCount::to_usize()
+            ]),
+            current_crosslinks: FixedLenVec::from(vec![
+                Crosslink::default();
+                T::ShardCount::to_usize()
             ]),
-            latest_start_shard: 0,
 
             // Finality
-            previous_epoch_attestations: vec![],
-            current_epoch_attestations: vec![],
-            previous_justified_epoch: T::genesis_epoch(),
-            current_justified_epoch: T::genesis_epoch(),
-            previous_justified_root: spec.zero_hash,
-            current_justified_root: spec.zero_hash,
-            justification_bitfield: 0,
-            finalized_epoch: T::genesis_epoch(),
-            finalized_root: spec.zero_hash,
-
-            // Recent state
-            current_crosslinks: vec![initial_crosslink.clone(); T::ShardCount::to_usize()].into(),
-            previous_crosslinks: vec![initial_crosslink; T::ShardCount::to_usize()].into(),
-            latest_block_roots: vec![spec.zero_hash; T::SlotsPerHistoricalRoot::to_usize()].into(),
-            latest_state_roots: vec![spec.zero_hash; T::SlotsPerHistoricalRoot::to_usize()].into(),
-            latest_active_index_roots: vec![
-                spec.zero_hash;
-                T::LatestActiveIndexRootsLength::to_usize()
-            ]
-            .into(),
-            latest_slashed_balances: vec![0; T::LatestSlashedExitLength::to_usize()].into(),
-            latest_block_header: BeaconBlock::empty(spec).temporary_block_header(spec),
-            historical_roots: vec![],
-
-            /*
-             * PoW receipt root
-             */
-            latest_eth1_data,
-            eth1_data_votes: vec![],
-            deposit_index: 0,
-
-            /*
-             * Caching (not in spec)
-             */
+            justification_bits: BitVector::new(),
+            previous_justified_checkpoint: Checkpoint::default(),
+            current_justified_checkpoint: Checkpoint::default(),
+            finalized_checkpoint: Checkpoint::default(),
+
+            // Caching (not in spec)
             committee_caches: [
                 CommitteeCache::default(),
                 CommitteeCache::default(),
                 CommitteeCache::default(),
             ],
             pubkey_cache: PubkeyCache::default(),
-            tree_hash_cache: TreeHashCache::default(),
             exit_cache: ExitCache::default(),
+            shuffling_cache: ShufflingCacheHandle::default(),
+            tree_hash_cache: BeaconTreeHashCache::default(),
         }
     }
 
     /// Returns the `tree_hash_root` of the state.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn canonical_root(&self) -> Hash256 {
         Hash256::from_slice(&self.tree_hash_root()[..])
     }
 
     pub fn historical_batch(&self) -> HistoricalBatch<T> {
         HistoricalBatch {
-            block_roots: self.latest_block_roots.clone(),
-            state_roots: self.latest_state_roots.clone(),
+            block_roots: self.block_roots.clone(),
+            state_roots: self.state_roots.clone(),
         }
     }
 
@@ -251,19 +293,19 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Requires a fully up-to-date `pubkey_cache`, returns an error if this is not the case.
     pub fn get_validator_index(&self, pubkey: &PublicKey) -> Result<Option<usize>, Error> {
-        if self.pubkey_cache.len() == self.validator_registry.len() {
+        if self.pubkey_cache.len() == self.validators.len() {
             Ok(self.pubkey_cache.get(pubkey))
         } else {
             Err(Error::PubkeyCacheIncomplete {
                 cache_len: self.pubkey_cache.len(),
-                registry_len: self.validator_registry.len(),
+                registry_len: self.validators.len(),
             })
         }
     }
 
     /// The epoch corresponding to `self.slot`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn current_epoch(&self) -> Epoch {
         self.slot.epoch(T::slots_per_epoch())
     }
@@ -272,7 +314,7 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// If the current epoch is the genesis epoch, the genesis_epoch is returned.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn previous_epoch(&self) -> Epoch {
         let current_epoch = self.current_epoch();
         if current_epoch > T::genesis_epoch() {
@@ -284,12 +326,17 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// The epoch following `self.current_epoch()`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn next_epoch(&self) -> Epoch {
         self.current_epoch() + 1
     }
 
-    pub fn get_epoch_committee_count(&self, relative_epoch: RelativeEpoch) -> Result<u64, Error> {
+    /// Return the number of committees in the given `relative_epoch`.
+    ///
+    /// Note: Utilizes the cache and will fail if the appropriate cache is not initialized.
+    ///
+    /// Spec v0.8.1
+    pub fn get_committee_count(&self, relative_epoch: RelativeEpoch) -> Result<u64, Error> {
         let cache = self.cache(relative_epoch)?;
 
         Ok(cache.epoch_committee_count() as u64)
@@ -306,20 +353,25 @@ impl<T: EthSpec> BeaconState<T> {
         let active_validator_count = cache.active_validator_count();
         let shard_delta = T::get_shard_delta(active_validator_count, spec.target_committee_size);
 
-        Ok((self.latest_start_shard + shard_delta) % T::ShardCount::to_u64())
+        Ok((self.start_shard + shard_delta) % T::ShardCount::to_u64())
     }
 
     /// Get the slot of an attestation.
     ///
     /// Note: Utilizes the cache and will fail if the appropriate cache is not initialized.
     ///
-    /// Spec v0.6.3
-    pub fn get_attestation_slot(&self, attestation_data: &AttestationData) -> Result<Slot, Error> {
+    /// Spec v0.8.1
+    pub fn get_attestation_data_slot(
+        &self,
+        attestation_data: &AttestationData,
+    ) -> Result<Slot, Error> {
         let target_relative_epoch =
-            RelativeEpoch::from_epoch(self.current_epoch(), attestation_data.target_epoch)?;
+            RelativeEpoch::from_epoch(self.current_epoch(), attestation_data.target.epoch)?;
 
-        let cc =
-            self.get_crosslink_committee_for_shard(attestation_data.shard, target_relative_epoch)?;
+        let cc = self.get_crosslink_committee_for_shard(
+            attestation_data.crosslink.shard,
+            target_relative_epoch,
+        )?;
 
         Ok(cc.slot)
     }
@@ -342,9 +394,9 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Does not utilize the cache, performs a full iteration over the validator registry.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_active_validator_indices(&self, epoch: Epoch) -> Vec<usize> {
-        get_active_validator_indices(&self.validator_registry, epoch)
+        get_active_validator_indices(&self.validators, epoch)
     }
 
     /// Return the cached active validator indices at some epoch.
@@ -362,7 +414,7 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Note: Utilizes the cache and will fail if the appropriate cache is not initialized.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_crosslink_committees_at_slot(
         &self,
         slot: Slot,
@@ -379,7 +431,7 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Note: Utilizes the cache and will fail if the appropriate cache is not initialized.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_crosslink_committee_for_shard(
         &self,
         shard: u64,
@@ -396,7 +448,7 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Returns the beacon proposer index for the `slot` in the given `relative_epoch`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     // NOTE: be sure to test this bad boy.
     pub fn get_beacon_proposer_index(
         &self,
@@ -421,7 +473,7 @@ impl<T: EthSpec> BeaconState<T> {
                 let hash = hash(&preimage);
                 hash[i % 32]
             };
-            let effective_balance = self.validator_registry[candidate_index].effective_balance;
+            let effective_balance = self.validators[candidate_index].effective_balance;
             if (effective_balance * MAX_RANDOM_BYTE)
                 >= (spec.max_effective_balance * u64::from(random_byte))
             {
@@ -431,12 +483,12 @@ impl<T: EthSpec> BeaconState<T> {
         })
     }
 
-    /// Safely obtains the index for latest block roots, given some `slot`.
+    /// Safely obtains the index for `block_roots`, given some `slot`.
     ///
-    /// Spec v0.6.3
-    fn get_latest_block_roots_index(&self, slot: Slot) -> Result<usize, Error> {
-        if (slot < self.slot) && (self.slot <= slot + self.latest_block_roots.len() as u64) {
-            Ok(slot.as_usize() % self.latest_block_roots.len())
+    /// Spec v0.8.1
+    fn get_block_roots_index(&self, slot: Slot) -> Result<usize, Error> {
+        if (slot < self.slot) && (self.slot <= slot + self.block_roots.len() as u64) {
+            Ok(slot.as_usize() % self.block_roots.len())
         } else {
             Err(BeaconStateError::SlotOutOfBounds)
         }
@@ -444,15 +496,15 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Return the block root at a recent `slot`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_block_root(&self, slot: Slot) -> Result<&Hash256, BeaconStateError> {
-        let i = self.get_latest_block_roots_index(slot)?;
-        Ok(&self.latest_block_roots[i])
+        let i = self.get_block_roots_index(slot)?;
+        Ok(&self.block_roots[i])
     }
 
     /// Return the block root at a recent `epoch`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     // NOTE: the spec calls this get_block_root
     pub fn get_block_root_at_epoch(&self, epoch: Epoch) -> Result<&Hash256, BeaconStateError> {
         self.get_block_root(epoch.start_slot(T::slots_per_epoch()))
@@ -460,23 +512,23 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Sets the block root for some given slot.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn set_block_root(
         &mut self,
         slot: Slot,
         block_root: Hash256,
     ) -> Result<(), BeaconStateError> {
-        let i = self.get_latest_block_roots_index(slot)?;
-        self.latest_block_roots[i] = block_root;
+        let i = self.get_block_roots_index(slot)?;
+        self.block_roots[i] = block_root;
         Ok(())
     }
 
-    /// Safely obtains the index for `latest_randao_mixes`
+    /// Safely obtains the index for `randao_mixes`
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     fn get_randao_mix_index(&self, epoch: Epoch) -> Result<usize, Error> {
         let current_epoch = self.current_epoch();
-        let len = T::LatestRandaoMixesLength::to_u64();
+        let len = T::EpochsPerHistoricalVector::to_u64();
 
         if (epoch + len > current_epoch) & (epoch <= current_epoch) {
             Ok(epoch.as_usize() % len as usize)
@@ -491,45 +543,45 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// See `Self::get_randao_mix`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn update_randao_mix(&mut self, epoch: Epoch, signature: &Signature) -> Result<(), Error> {
-        let i = epoch.as_usize() % T::LatestRandaoMixesLength::to_usize();
+        let i = epoch.as_usize() % T::EpochsPerHistoricalVector::to_usize();
 
         let signature_hash = Hash256::from_slice(&hash(&ssz_encode(signature)));
 
-        self.latest_randao_mixes[i] = *self.get_randao_mix(epoch)? ^ signature_hash;
+        self.randao_mixes[i] = *self.get_randao_mix(epoch)? ^ signature_hash;
 
         Ok(())
     }
 
     /// Return the randao mix at a recent ``epoch``.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_randao_mix(&self, epoch: Epoch) -> Result<&Hash256, Error> {
         let i = self.get_randao_mix_index(epoch)?;
-        Ok(&self.latest_randao_mixes[i])
+        Ok(&self.randao_mixes[i])
     }
 
     /// Set the randao mix at a recent ``epoch``.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn set_randao_mix(&mut self, epoch: Epoch, mix: Hash256) -> Result<(), Error> {
         let i = self.get_randao_mix_index(epoch)?;
-        self.latest_randao_mixes[i] = mix;
+        self.randao_mixes[i] = mix;
         Ok(())
     }
 
-    /// Safely obtains the index for `latest_active_index_roots`, given some `epoch`.
+    /// Safely obtains the index for `active_index_roots`, given some `epoch`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     fn get_active_index_root_index(&self, epoch: Epoch, spec: &ChainSpec) -> Result<usize, Error> {
         let current_epoch = self.current_epoch();
 
         let lookahead = spec.activation_exit_delay;
-        let lookback = self.latest_active_index_roots.len() as u64 - lookahead;
+        let lookback = self.active_index_roots.len() as u64 - lookahead;
 
         if (epoch + lookback > current_epoch) && (current_epoch + lookahead >= epoch) {
-            Ok(epoch.as_usize() % self.latest_active_index_roots.len())
+            Ok(epoch.as_usize() % self.active_index_roots.len())
         } else {
             Err(Error::EpochOutOfBounds)
         }
@@ -537,15 +589,15 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Return the `active_index_root` at a recent `epoch`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_active_index_root(&self, epoch: Epoch, spec: &ChainSpec) -> Result<Hash256, Error> {
         let i = self.get_active_index_root_index(epoch, spec)?;
-        Ok(self.latest_active_index_roots[i])
+        Ok(self.active_index_roots[i])
     }
 
     /// Set the `active_index_root` at a recent `epoch`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn set_active_index_root(
         &mut self,
         epoch: Epoch,
@@ -553,24 +605,68 @@ impl<T: EthSpec> BeaconState<T> {
         spec: &ChainSpec,
     ) -> Result<(), Error> {
         let i = self.get_active_index_root_index(epoch, spec)?;
-        self.latest_active_index_roots[i] = index_root;
+        self.active_index_roots[i] = index_root;
         Ok(())
     }
 
     /// Replace `active_index_roots` with clones of `index_root`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn fill_active_index_roots_with(&mut self, index_root: Hash256) {
-        self.latest_active_index_roots =
-            vec![index_root; self.latest_active_index_roots.len()].into()
+        self.active_index_roots = vec![index_root; self.active_index_roots.len()].into()
     }
 
-    /// Safely obtains the index for latest state roots, given some `slot`.
+    /// Safely obtains the index for `compact_committees_roots`, given some `epoch`.
+    ///
+    /// The roots are set one epoch in advance, so the next epoch is also valid.
     ///
-    /// Spec v0.6.3
-    fn get_latest_state_roots_index(&self, slot: Slot) -> Result<usize, Error> {
-        if (slot < self.slot) && (self.slot <= slot + Slot::from(self.latest_state_roots.len())) {
-            Ok(slot.as_usize() % self.latest_state_roots.len())
+    /// Spec v0.8.1
+    fn get_compact_committee_root_index(&self, epoch: Epoch) -> Result<usize, Error> {
+        let next_epoch = self.next_epoch();
+        let len = self.compact_committees_roots.len() as u64;
+
+        if (epoch + len > next_epoch) && (epoch <= next_epoch) {
+            Ok(epoch.as_usize() % len as usize)
+        } else {
+            Err(Error::EpochOutOfBounds)
+        }
+    }
+
+    /// Return the `compact_committee_root` at a recent `epoch`.
+    ///
+    /// Spec v0.8.1
+    pub fn get_compact_committee_root(&self, epoch: Epoch) -> Result<Hash256, Error> {
+        let i = self.get_compact_committee_root_index(epoch)?;
+        Ok(self.compact_committees_roots[i])
+    }
+
+    /// Set the `compact_committee_root` at a recent `epoch`.
+    ///
+    /// Spec v0.8.1
+    pub fn set_compact_committee_root(
+        &mut self,
+        epoch: Epoch,
+        index_root: Hash256,
+    ) -> Result<(), Error> {
+        let i = self.get_compact_committee_root_index(epoch)?;
+        self.compact_committees_roots[i] = index_root;
+        Ok(())
+    }
+
+    /// Replace `compact_committees_roots` with clones of `committee_root`.
+    ///
+    /// Spec v0.8.1
+    pub fn fill_compact_committees_roots_with(&mut self, committee_root: Hash256) {
+        self.compact_committees_roots =
+            vec![committee_root; self.compact_committees_roots.len()].into()
+    }
+
+    /// Safely obtains the index for `state_roots`, given some `slot`.
+    ///
+    /// Spec v0.8.1
+    fn get_state_roots_index(&self, slot: Slot) -> Result<usize, Error> {
+        if (slot < self.slot) && (self.slot <= slot + Slot::from(self.state_roots.len())) {
+            Ok(slot.as_usize() % self.state_roots.len())
         } else {
             Err(BeaconStateError::SlotOutOfBounds)
         }
@@ -578,69 +674,75 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Gets the state root for some slot.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_state_root(&self, slot: Slot) -> Result<&Hash256, Error> {
-        let i = self.get_latest_state_roots_index(slot)?;
-        Ok(&self.latest_state_roots[i])
+        let i = self.get_state_roots_index(slot)?;
+        Ok(&self.state_roots[i])
     }
 
     /// Gets the oldest (earliest slot) state root.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_oldest_state_root(&self) -> Result<&Hash256, Error> {
-        let i = self
-            .get_latest_state_roots_index(self.slot - Slot::from(self.latest_state_roots.len()))?;
-        Ok(&self.latest_state_roots[i])
+        let i = self.get_state_roots_index(self.slot - Slot::from(self.state_roots.len()))?;
+        Ok(&self.state_roots[i])
     }
 
     /// Sets the latest state root for slot.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn set_state_root(&mut self, slot: Slot, state_root: Hash256) -> Result<(), Error> {
-        let i = self.get_latest_state_roots_index(slot)?;
-        self.latest_state_roots[i] = state_root;
+        let i = self.get_state_roots_index(slot)?;
+        self.state_roots[i] = state_root;
         Ok(())
     }
 
-    /// Safely obtains the index for `latest_slashed_balances`, given some `epoch`.
+    /// Safely obtain the index for `slashings`, given some `epoch`.
     ///
-    /// Spec v0.6.3
-    fn get_slashed_balance_index(&self, epoch: Epoch) -> Result<usize, Error> {
-        let i = epoch.as_usize() % self.latest_slashed_balances.len();
-
-        // NOTE: the validity of the epoch is not checked. It is not in the spec but it's probably
-        // useful to have.
-        if i < self.latest_slashed_balances.len() {
-            Ok(i)
+    /// Spec v0.8.1
+    fn get_slashings_index(&self, epoch: Epoch) -> Result<usize, Error> {
+        // We allow the slashings vector to be accessed at any cached epoch at or before
+        // the current epoch.
+        if epoch <= self.current_epoch()
+            && epoch + T::EpochsPerSlashingsVector::to_u64() >= self.current_epoch() + 1
+        {
+            Ok((epoch.as_u64() % T::EpochsPerSlashingsVector::to_u64()) as usize)
         } else {
-            Err(Error::InsufficientSlashedBalances)
+            Err(Error::EpochOutOfBounds)
         }
     }
 
-    /// Gets the total slashed balances for some epoch.
+    /// Get a reference to the entire `slashings` vector.
     ///
-    /// Spec v0.6.3
-    pub fn get_slashed_balance(&self, epoch: Epoch) -> Result<u64, Error> {
-        let i = self.get_slashed_balance_index(epoch)?;
-        Ok(self.latest_slashed_balances[i])
+    /// Spec v0.8.1
+    pub fn get_all_slashings(&self) -> &FixedLenVec<u64, T::EpochsPerSlashingsVector> {
+        &self.slashings
     }
 
-    /// Sets the total slashed balances for some epoch.
+    /// Get the total slashed balances for some epoch.
     ///
-    /// Spec v0.6.3
-    pub fn set_slashed_balance(&mut self, epoch: Epoch, balance: u64) -> Result<(), Error> {
-        let i = self.get_slashed_balance_index(epoch)?;
-        self.latest_slashed_balances[i] = balance;
+    /// Spec v0.8.1
+    pub fn get_slashings(&self, epoch: Epoch) -> Result<u64, Error> {
+        let i = self.get_slashings_index(epoch)?;
+        Ok(self.slashings[i])
+    }
+
+    /// Set the total slashed balances for some epoch.
+    ///
+    /// Spec v0.8.1
+    pub fn set_slashings(&mut self, epoch: Epoch, value: u64) -> Result<(), Error> {
+        let i = self.get_slashings_index(epoch)?;
+        self.slashings[i] = value;
         Ok(())
     }
 
     /// Get the attestations from the current or previous epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_matching_source_attestations(
         &self,
         epoch: Epoch,
-    ) -> Result<&[PendingAttestation], Error> {
+    ) -> Result<&[PendingAttestation<T>], Error> {
         if epoch == self.current_epoch() {
             Ok(&self.current_epoch_attestations)
         } else if epoch == self.previous_epoch() {
@@ -652,7 +754,7 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Get the current crosslink for a shard.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_current_crosslink(&self, shard: u64) -> Result<&Crosslink, Error> {
         self.current_crosslinks
             .get(shard as usize)
@@ -661,43 +763,27 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Get the previous crosslink for a shard.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_previous_crosslink(&self, shard: u64) -> Result<&Crosslink, Error> {
         self.previous_crosslinks
             .get(shard as usize)
             .ok_or(Error::ShardOutOfBounds)
     }
 
-    /// Transform an attestation into the crosslink that it reinforces.
-    ///
-    /// Spec v0.6.3
-    pub fn get_crosslink_from_attestation_data(
-        &self,
-        data: &AttestationData,
-        spec: &ChainSpec,
-    ) -> Result<Crosslink, Error> {
-        let current_crosslink_epoch = self.get_current_crosslink(data.shard)?.epoch;
-        Ok(Crosslink {
-            epoch: std::cmp::min(
-                data.target_epoch,
-                current_crosslink_epoch + spec.max_crosslink_epochs,
-            ),
-            previous_crosslink_root: data.previous_crosslink_root,
-            crosslink_data_root: data.crosslink_data_root,
-        })
-    }
-
     /// Generate a seed for the given `epoch`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn generate_seed(&self, epoch: Epoch, spec: &ChainSpec) -> Result<Hash256, Error> {
         // Bypass the safe getter for RANDAO so we can gracefully handle the scenario where `epoch
         // == 0`.
         let randao = {
-            let i = epoch + T::latest_randao_mixes_length() as u64 - spec.min_seed_lookahead;
-            self.latest_randao_mixes[i.as_usize() % self.latest_randao_mixes.len()]
+            let i = epoch + T::EpochsPerHistoricalVector::to_u64() - spec.min_seed_lookahead - 1;
+            self.randao_mixes[i.as_usize() % self.randao_mixes.len()]
+        };
+        let active_index_root = {
+            let i = epoch.as_usize() % self.active_index_roots.len();
+            self.active_index_roots[i]
         };
-        let active_index_root = self.get_active_index_root(epoch, spec)?;
         let epoch_bytes = int_to_bytes32(epoch.as_u64());
 
         let mut preimage = [0; 32 * 3];
@@ -710,13 +796,13 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Return the effective balance (also known as "balance at stake") for a validator with the given ``index``.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_effective_balance(
         &self,
         validator_index: usize,
         _spec: &ChainSpec,
     ) -> Result<u64, Error> {
-        self.validator_registry
+        self.validators
             .get(validator_index)
             .map(|v| v.effective_balance)
             .ok_or_else(|| Error::UnknownValidator)
@@ -724,7 +810,7 @@ impl<T: EthSpec> BeaconState<T> {
 
     ///  Return the epoch at which an activation or exit triggered in ``epoch`` takes effect.
     ///
-    ///  Spec v0.6.3
+    ///  Spec v0.8.1
     pub fn get_delayed_activation_exit_epoch(&self, epoch: Epoch, spec: &ChainSpec) -> Epoch {
         epoch + 1 + spec.activation_exit_delay
     }
@@ -733,12 +819,12 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Uses the epoch cache, and will error if it isn't initialized.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_churn_limit(&self, spec: &ChainSpec) -> Result<u64, Error> {
         Ok(std::cmp::max(
             spec.min_per_epoch_churn_limit,
-            self.cache(RelativeEpoch::Current)?.active_validator_count() as u64
-                / spec.churn_limit_quotient,
+            (self.cache(RelativeEpoch::Current)?.active_validator_count() as u64)
+                .safe_div(spec.churn_limit_quotient)?,
         ))
     }
 
@@ -747,7 +833,7 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Note: Utilizes the cache and will fail if the appropriate cache is not initialized.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_attestation_duties(
         &self,
         validator_index: usize,
@@ -760,16 +846,20 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Return the combined effective balance of an array of validators.
     ///
-    /// Spec v0.6.3
+    /// Returns a minimum of 1 Gwei, to avoid divisions by zero.
+    ///
+    /// Spec v0.8.1
     pub fn get_total_balance(
         &self,
         validator_indices: &[usize],
         spec: &ChainSpec,
     ) -> Result<u64, Error> {
-        validator_indices.iter().try_fold(0_u64, |acc, i| {
-            self.get_effective_balance(*i, spec)
-                .and_then(|bal| Ok(bal + acc))
-        })
+        let total = validator_indices.iter().try_fold(0_u64, |acc, i| {
+            let balance = self.get_effective_balance(*i, spec)?;
+            acc.safe_add(balance).map_err(Error::from)
+        })?;
+
+        Ok(std::cmp::max(total, 1))
     }
 
     /// Build all the caches, if they need to be built.
@@ -779,8 +869,7 @@ impl<T: EthSpec> BeaconState<T> {
         self.build_committee_cache(RelativeEpoch::Next, spec)?;
         self.update_pubkey_cache()?;
         self.update_tree_hash_cache()?;
-        self.exit_cache
-            .build_from_registry(&self.validator_registry, spec);
+        self.exit_cache.build_from_registry(&self.validators, spec);
 
         Ok(())
     }
@@ -796,6 +885,9 @@ impl<T: EthSpec> BeaconState<T> {
     }
 
     /// Build an epoch cache, unless it is has already been built.
+    ///
+    /// The shuffling is taken from the `shuffling_cache` if another state has already computed
+    /// it.
     pub fn build_committee_cache(
         &mut self,
         relative_epoch: RelativeEpoch,
@@ -867,11 +959,11 @@ impl<T: EthSpec> BeaconState<T> {
 
     /// Updates the pubkey cache, if required.
     ///
-    /// Adds all `pubkeys` from the `validator_registry` which are not already in the cache. Will
+    /// Adds all `pubkeys` from the `validators` which are not already in the cache. Will
     /// never re-add a pubkey.
     pub fn update_pubkey_cache(&mut self) -> Result<(), Error> {
         for (i, validator) in self
-            .validator_registry
+            .validators
             .iter()
             .enumerate()
             .skip(self.pubkey_cache.len())
@@ -895,37 +987,21 @@ impl<T: EthSpec> BeaconState<T> {
     /// Returns the `tree_hash_root` resulting from the update. This root can be considered the
     /// canonical root of `self`.
     pub fn update_tree_hash_cache(&mut self) -> Result<Hash256, Error> {
-        if self.tree_hash_cache.is_empty() {
-            self.tree_hash_cache = TreeHashCache::new(self)?;
-        } else {
-            // Move the cache outside of `self` to satisfy the borrow checker.
-            let mut cache = std::mem::replace(&mut self.tree_hash_cache, TreeHashCache::default());
-
-            cache.update(self)?;
+        // Move the cache outside of `self` to satisfy the borrow checker.
+        let mut cache =
+            std::mem::replace(&mut self.tree_hash_cache, BeaconTreeHashCache::default());
 
-            // Move the updated cache back into `self`.
-            self.tree_hash_cache = cache
-        }
+        let result = cache.recalculate_tree_hash_root(self);
 
-        self.cached_tree_hash_root()
-    }
+        // Move the updated cache back into `self`.
+        self.tree_hash_cache = cache;
 
-    /// Returns the tree hash root determined by the last execution of `self.update_tree_hash_cache(..)`.
-    ///
-    /// Note: does _not_ update the cache and may return an outdated root.
-    ///
-    /// Returns an error if the cache is not initialized or if an error is encountered during the
-    /// cache update.
-    pub fn cached_tree_hash_root(&self) -> Result<Hash256, Error> {
-        self.tree_hash_cache
-            .tree_hash_root()
-            .and_then(|b| Ok(Hash256::from_slice(b)))
-            .map_err(Into::into)
+        result
     }
 
     /// Completely drops the tree hash cache, replacing it with a new, empty cache.
     pub fn drop_tree_hash_cache(&mut self) {
-        self.tree_hash_cache = TreeHashCache::default()
+        self.tree_hash_cache = BeaconTreeHashCache::default()
     }
 }
 
@@ -940,3 +1016,15 @@ impl From<TreeHashCacheError> for Error {
         Error::TreeHashCacheError(e)
     }
 }
+
+impl From<ssz_types::Error> for Error {
+    fn from(e: ssz_types::Error) -> Error {
+        Error::SszTypesError(e)
+    }
+}
+
+impl From<ArithError> for Error {
+    fn from(e: ArithError) -> Error {
+        Error::ArithError(e)
+    }
+}
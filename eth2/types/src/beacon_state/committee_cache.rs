@@ -1,20 +1,18 @@
 use super::BeaconState;
 use crate::*;
-use core::num::NonZeroUsize;
-use serde_derive::{Deserialize, Serialize};
-use ssz_derive::{Decode, Encode};
 use std::ops::Range;
-use swap_or_not_shuffle::shuffle_list;
+use std::sync::Arc;
 
 mod tests;
 
 /// Computes and stores the shuffling for an epoch. Provides various getters to allow callers to
 /// read the committees for the given epoch.
-#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+///
+/// The shuffling itself is shared via a `ShufflingCache`, so cloning a `CommitteeCache` is cheap.
+#[derive(Debug, Default, PartialEq, Clone)]
 pub struct CommitteeCache {
     initialized_epoch: Option<Epoch>,
-    shuffling: Vec<usize>,
-    shuffling_positions: Vec<Option<NonZeroUsize>>,
+    shuffling: Arc<Shuffling>,
     shuffling_start_shard: u64,
     shard_count: u64,
     committee_count: usize,
@@ -22,13 +20,32 @@ pub struct CommitteeCache {
 }
 
 impl CommitteeCache {
-    /// Return a new, fully initialized cache.
+    /// Return a new, fully initialized cache, using the `ShufflingCache` attached to `state` (if
+    /// any).
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn initialized<T: EthSpec>(
         state: &BeaconState<T>,
         epoch: Epoch,
         spec: &ChainSpec,
+    ) -> Result<CommitteeCache, Error> {
+        match state.shuffling_cache.get() {
+            Some(shuffling_cache) => {
+                Self::initialized_with_cache(state, epoch, spec, shuffling_cache)
+            }
+            None => Self::initialized_with_cache(state, epoch, spec, &ShufflingCache::new(0)),
+        }
+    }
+
+    /// Return a new, fully initialized cache. The shuffling is only computed if it is not already
+    /// present in `shuffling_cache`.
+    ///
+    /// Spec v0.8.1
+    pub fn initialized_with_cache<T: EthSpec>(
+        state: &BeaconState<T>,
+        epoch: Epoch,
+        spec: &ChainSpec,
+        shuffling_cache: &ShufflingCache,
     ) -> Result<CommitteeCache, Error> {
         let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch)
             .map_err(|_| Error::EpochOutOfBounds)?;
@@ -38,49 +55,43 @@ impl CommitteeCache {
             return Err(Error::ZeroSlotsPerEpoch);
         }
 
-        let active_validator_indices =
-            get_active_validator_indices(&state.validator_registry, epoch);
+        let active_validator_count = get_active_validator_count(&state.validators, epoch);
 
-        if active_validator_indices.is_empty() {
+        if active_validator_count == 0 {
             return Err(Error::InsufficientValidators);
         }
 
-        let committee_count = T::get_epoch_committee_count(
-            active_validator_indices.len(),
-            spec.target_committee_size,
-        ) as usize;
+        let committee_count =
+            T::get_committee_count(active_validator_count, spec.target_committee_size) as usize;
 
         let shuffling_start_shard =
-            Self::compute_start_shard(state, relative_epoch, active_validator_indices.len(), spec);
+            Self::compute_start_shard(state, relative_epoch, active_validator_count, spec);
 
         let seed = state.generate_seed(epoch, spec)?;
 
-        let shuffling = shuffle_list(
-            active_validator_indices,
-            spec.shuffle_round_count,
-            &seed[..],
-            false,
-        )
-        .ok_or_else(|| Error::UnableToShuffle)?;
-
-        // The use of `NonZeroUsize` reduces the maximum number of possible validators by one.
-        if state.validator_registry.len() > usize::max_value() - 1 {
-            return Err(Error::TooManyValidators);
-        }
-
-        let mut shuffling_positions = vec![None; state.validator_registry.len()];
-        for (i, v) in shuffling.iter().enumerate() {
-            shuffling_positions[*v] = NonZeroUsize::new(i + 1);
-        }
+        let shuffling_id = ShufflingId {
+            epoch,
+            seed,
+            active_validator_count,
+            shuffle_round_count: spec.shuffle_round_count,
+        };
+
+        let shuffling = shuffling_cache.get_or_try_insert_with(shuffling_id, || {
+            Shuffling::new(
+                get_active_validator_indices(&state.validators, epoch),
+                state.validators.len(),
+                &seed[..],
+                spec,
+            )
+        })?;
 
         Ok(CommitteeCache {
             initialized_epoch: Some(epoch),
-            shuffling_start_shard,
             shuffling,
+            shuffling_start_shard,
             shard_count: T::shard_count() as u64,
             committee_count,
             slots_per_epoch: T::slots_per_epoch(),
-            shuffling_positions,
         })
     }
 
@@ -88,7 +99,7 @@ impl CommitteeCache {
     ///
     /// The `active_validator_count` must be the number of validators active at `relative_epoch`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn compute_start_shard<T: EthSpec>(
         state: &BeaconState<T>,
         relative_epoch: RelativeEpoch,
@@ -96,21 +107,21 @@ impl CommitteeCache {
         spec: &ChainSpec,
     ) -> u64 {
         match relative_epoch {
-            RelativeEpoch::Current => state.latest_start_shard,
+            RelativeEpoch::Current => state.start_shard,
             RelativeEpoch::Previous => {
                 let shard_delta =
                     T::get_shard_delta(active_validator_count, spec.target_committee_size);
 
-                (state.latest_start_shard + T::ShardCount::to_u64() - shard_delta)
+                (state.start_shard + T::ShardCount::to_u64() - shard_delta)
                     % T::ShardCount::to_u64()
             }
             RelativeEpoch::Next => {
                 let current_active_validators =
-                    get_active_validator_count(&state.validator_registry, state.current_epoch());
+                    get_active_validator_count(&state.validators, state.current_epoch());
                 let shard_delta =
                     T::get_shard_delta(current_active_validators, spec.target_committee_size);
 
-                (state.latest_start_shard + shard_delta) % T::ShardCount::to_u64()
+                (state.start_shard + shard_delta) % T::ShardCount::to_u64()
             }
         }
     }
@@ -128,18 +139,18 @@ impl CommitteeCache {
     ///
     /// Always returns `&[]` for a non-initialized epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn active_validator_indices(&self) -> &[usize] {
-        &self.shuffling
+        self.shuffling.as_slice()
     }
 
     /// Returns the shuffled list of active validator indices for the initialized epoch.
     ///
     /// Always returns `&[]` for a non-initialized epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn shuffling(&self) -> &[usize] {
-        &self.shuffling
+        self.shuffling.as_slice()
     }
 
     /// Return `Some(CrosslinkCommittee)` if the given shard has a committee during the given
@@ -147,7 +158,7 @@ impl CommitteeCache {
     ///
     /// Always returns `None` for a non-initialized epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_crosslink_committee_for_shard(&self, shard: Shard) -> Option<CrosslinkCommittee> {
         if shard >= self.shard_count || self.initialized_epoch.is_none() {
             return None;
@@ -201,7 +212,7 @@ impl CommitteeCache {
     ///
     /// Always returns `usize::default()` for a non-initialized epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn active_validator_count(&self) -> usize {
         self.shuffling.len()
     }
@@ -210,7 +221,7 @@ impl CommitteeCache {
     ///
     /// Always returns `usize::default()` for a non-initialized epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn epoch_committee_count(&self) -> usize {
         self.committee_count
     }
@@ -226,7 +237,7 @@ impl CommitteeCache {
     ///
     /// Returns `None` if `slot` is not in the initialized epoch, or if `Self` is not initialized.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_crosslink_committees_for_slot(&self, slot: Slot) -> Option<Vec<CrosslinkCommittee>> {
         let position = self
             .initialized_epoch?
@@ -258,7 +269,7 @@ impl CommitteeCache {
     ///
     /// Always returns `None` for a non-initialized epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn first_committee_at_slot(&self, slot: Slot) -> Option<&[usize]> {
         self.get_crosslink_committees_for_slot(slot)?
             .first()
@@ -267,16 +278,16 @@ impl CommitteeCache {
 
     /// Returns a slice of `self.shuffling` that represents the `index`'th committee in the epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     fn compute_committee(&self, index: usize) -> Option<&[usize]> {
-        Some(&self.shuffling[self.compute_committee_range(index)?])
+        Some(&self.shuffling.as_slice()[self.compute_committee_range(index)?])
     }
 
     /// Returns a range of `self.shuffling` that represents the `index`'th committee in the epoch.
     ///
     /// To avoid a divide-by-zero, returns `None` if `self.committee_count` is zero.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     fn compute_committee_range(&self, index: usize) -> Option<Range<usize>> {
         if self.committee_count == 0 {
             return None;
@@ -295,7 +306,7 @@ impl CommitteeCache {
     ///
     /// Always returns `None` for a non-initialized epoch.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     fn crosslink_slot_for_shard(&self, shard: u64) -> Option<Slot> {
         let offset = (shard + self.shard_count - self.shuffling_start_shard) % self.shard_count;
         Some(
@@ -308,17 +319,18 @@ impl CommitteeCache {
     ///
     /// Always returns `None` for a non-initialized epoch.
     fn shuffled_position(&self, validator_index: usize) -> Option<usize> {
-        self.shuffling_positions
-            .get(validator_index)?
-            .and_then(|p| Some(p.get() - 1))
+        self.shuffling.position(validator_index)
     }
 }
 
-/// Returns a list of all `validator_registry` indices where the validator is active at the given
+/// Returns a list of all `validators` indices where the validator is active at the given
 /// `epoch`.
 ///
-/// Spec v0.6.3
-pub fn get_active_validator_indices(validators: &[Validator], epoch: Epoch) -> Vec<usize> {
+/// Spec v0.8.1
+pub fn get_active_validator_indices<N: Unsigned>(
+    validators: &CowList<Validator, N>,
+    epoch: Epoch,
+) -> Vec<usize> {
     let mut active = Vec::with_capacity(validators.len());
 
     for (index, validator) in validators.iter().enumerate() {
@@ -332,10 +344,13 @@ pub fn get_active_validator_indices(validators: &[Validator], epoch: Epoch) -> V
     active
 }
 
-/// Returns the count of all `validator_registry` indices where the validator is active at the given
+/// Returns the count of all `validators` indices where the validator is active at the given
 /// `epoch`.
 ///
-/// Spec v0.6.3
-fn get_active_validator_count(validators: &[Validator], epoch: Epoch) -> usize {
+/// Spec v0.8.1
+fn get_active_validator_count<N: Unsigned>(
+    validators: &CowList<Validator, N>,
+    epoch: Epoch,
+) -> usize {
     validators.iter().filter(|v| v.is_active_at(epoch)).count()
 }
@@ -1,4 +1,4 @@
-use super::{ChainSpec, Epoch, Validator};
+use super::{ChainSpec, CowList, Epoch, Unsigned, Validator};
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -8,8 +8,12 @@ pub struct ExitCache(HashMap<Epoch, u64>);
 
 impl ExitCache {
     /// Add all validators with a non-trivial exit epoch to the cache.
-    pub fn build_from_registry(&mut self, validator_registry: &[Validator], spec: &ChainSpec) {
-        validator_registry
+    pub fn build_from_registry<N: Unsigned>(
+        &mut self,
+        validators: &CowList<Validator, N>,
+        spec: &ChainSpec,
+    ) {
+        validators
             .iter()
             .filter(|validator| validator.exit_epoch != spec.far_future_epoch)
             .for_each(|validator| self.record_validator_exit(validator.exit_epoch));
@@ -1,16 +1,19 @@
 use crate::*;
-use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 type ValidatorIndex = usize;
 
-#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+/// Maps validator pubkeys to their indices in the validator registry.
+///
+/// The map is shared between clones, it is copied by the first insert after a clone.
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct PubkeyCache {
     /// Maintain the number of keys added to the map. It is not sufficient to just use the HashMap
     /// len, as it does not increase when duplicate keys are added. Duplicate keys are used during
     /// testing.
     len: usize,
-    map: HashMap<PublicKey, ValidatorIndex>,
+    map: Arc<HashMap<PublicKey, ValidatorIndex>>,
 }
 
 impl PubkeyCache {
@@ -25,7 +28,7 @@ impl PubkeyCache {
     /// that an index is never skipped.
     pub fn insert(&mut self, pubkey: PublicKey, index: ValidatorIndex) -> bool {
         if index == self.len {
-            self.map.insert(pubkey, index);
+            Arc::make_mut(&mut self.map).insert(pubkey, index);
             self.len += 1;
             true
         } else {
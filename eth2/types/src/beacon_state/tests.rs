@@ -4,7 +4,8 @@ use crate::test_utils::*;
 use std::ops::RangeInclusive;
 
 ssz_tests!(FoundationBeaconState);
-cached_tree_hash_tests!(FoundationBeaconState);
+
+serde_tests!(FoundationBeaconState);
 
 fn test_beacon_proposer_index<T: EthSpec>() {
     let spec = T::default_spec();
@@ -44,7 +45,7 @@ fn test_beacon_proposer_index<T: EthSpec>() {
     // Test with two validators per slot, first validator has zero balance.
     let mut state = build_state(T::slots_per_epoch() as usize * 2);
     let shuffling = state.get_shuffling(relative_epoch).unwrap().to_vec();
-    state.validator_registry[shuffling[0]].effective_balance = 0;
+    state.validators[shuffling[0]].effective_balance = 0;
     test(&state, Slot::new(0), 1);
     for i in 1..T::slots_per_epoch() {
         test(&state, Slot::from(i), i as usize * 2);
@@ -58,13 +59,13 @@ fn beacon_proposer_index() {
 
 /// Should produce (note the set notation brackets):
 ///
-/// (current_epoch - LATEST_ACTIVE_INDEX_ROOTS_LENGTH + ACTIVATION_EXIT_DELAY, current_epoch +
+/// (current_epoch - EPOCHS_PER_HISTORICAL_VECTOR + ACTIVATION_EXIT_DELAY, current_epoch +
 /// ACTIVATION_EXIT_DELAY]
 fn active_index_range<T: EthSpec>(current_epoch: Epoch) -> RangeInclusive<Epoch> {
     let delay = T::default_spec().activation_exit_delay;
 
     let start: i32 =
-        current_epoch.as_u64() as i32 - T::latest_active_index_roots() as i32 + delay as i32;
+        current_epoch.as_u64() as i32 - T::epochs_per_historical_vector() as i32 + delay as i32;
     let end = current_epoch + delay;
 
     let start: Epoch = if start < 0 {
@@ -87,7 +88,7 @@ fn test_active_index<T: EthSpec>(state_slot: Slot) {
 
     let range = active_index_range::<T>(state.current_epoch());
 
-    let modulo = |epoch: Epoch| epoch.as_usize() % T::latest_active_index_roots();
+    let modulo = |epoch: Epoch| epoch.as_usize() % T::epochs_per_historical_vector();
 
     // Test the start and end of the range.
     assert_eq!(
@@ -117,7 +118,7 @@ fn test_active_index<T: EthSpec>(state_slot: Slot) {
 fn get_active_index_root_index() {
     test_active_index::<MainnetEthSpec>(Slot::new(0));
 
-    let epoch = Epoch::from(MainnetEthSpec::latest_active_index_roots() * 4);
+    let epoch = Epoch::from(MainnetEthSpec::epochs_per_historical_vector() * 4);
     let slot = epoch.start_slot(MainnetEthSpec::slots_per_epoch());
     test_active_index::<MainnetEthSpec>(slot);
 }
@@ -199,6 +200,73 @@ fn tree_hash_cache() {
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
 }
 
+#[test]
+fn tree_hash_cache_tracks_modifications() {
+    let spec = MinimalEthSpec::default_spec();
+    let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(80, &spec);
+    let (mut state, _keypairs) = builder.build();
+
+    let check = |state: &mut BeaconState<MinimalEthSpec>| {
+        assert_eq!(state.update_tree_hash_cache(), Ok(state.canonical_root()));
+    };
+
+    check(&mut state);
+    check(&mut state);
+
+    state.balances[3] += 1;
+    state.validators[70].slashed = true;
+    state.block_roots[0] = Hash256::from_low_u64_be(42);
+    state.slashings[1] = 7;
+    state.current_crosslinks[2].shard = 2;
+    state
+        .historical_roots
+        .push(Hash256::from_low_u64_be(1))
+        .unwrap();
+    check(&mut state);
+
+    let validator = state.validators[0].clone();
+    state.validators.push(validator).unwrap();
+    state.balances.push(1).unwrap();
+    check(&mut state);
+
+    // A clone shares the cache, which must not be fooled by modifications to either state.
+    let mut clone = state.clone();
+    clone.randao_mixes[5] = Hash256::from_low_u64_be(5);
+    clone.validators[1].effective_balance = 0;
+    check(&mut clone);
+    state.active_index_roots[5] = Hash256::from_low_u64_be(6);
+    check(&mut state);
+    assert_ne!(state.canonical_root(), clone.canonical_root());
+
+    state.drop_tree_hash_cache();
+    check(&mut state);
+}
+
+#[test]
+fn clone_is_copy_on_write() {
+    let spec = MinimalEthSpec::default_spec();
+    let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+    let (state, _keypairs) = builder.build();
+
+    let mut clone = state.clone();
+    assert!(clone.validators.ptr_eq(&state.validators));
+    assert!(clone.balances.ptr_eq(&state.balances));
+
+    clone.balances[3] += 1;
+    clone.validators[5].slashed = true;
+    clone.block_roots[0] = Hash256::from(42u64);
+
+    assert_eq!(clone.balances[3], state.balances[3] + 1);
+    assert!(!state.validators[5].slashed);
+    assert_eq!(state.block_roots[0], spec.zero_hash);
+
+    // The modified lists are no longer shared.
+    assert!(!clone.balances.ptr_eq(&state.balances));
+    assert_ne!(clone.tree_hash_root(), state.tree_hash_root());
+}
+
 /// Tests committee-specific components
 #[cfg(test)]
 mod committees {
@@ -244,7 +312,7 @@ mod committees {
             // of committees in an epoch.
             assert_eq!(
                 crosslink_committees.len() as u64,
-                state.get_epoch_committee_count(relative_epoch).unwrap() / T::slots_per_epoch()
+                state.get_committee_count(relative_epoch).unwrap() / T::slots_per_epoch()
             );
 
             for cc in crosslink_committees {
@@ -306,11 +374,11 @@ mod committees {
 
         let (mut state, _keypairs): (BeaconState<T>, _) = builder.build();
 
-        let distinct_hashes: Vec<Hash256> = (0..T::latest_randao_mixes_length())
+        let distinct_hashes: Vec<Hash256> = (0..T::epochs_per_historical_vector())
             .into_iter()
             .map(|i| Hash256::from(i as u64))
             .collect();
-        state.latest_randao_mixes = FixedLenVec::from(distinct_hashes);
+        state.randao_mixes = FixedLenVec::from(distinct_hashes);
 
         state
             .build_committee_cache(RelativeEpoch::Previous, spec)
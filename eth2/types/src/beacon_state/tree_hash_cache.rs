@@ -0,0 +1,153 @@
+use super::{BeaconState, Error};
+use crate::*;
+use cached_tree_hash::MerkleCache;
+use fixed_len_vec::{typenum::Unsigned, CowListTreeHashCache};
+use tree_hash::{mix_in_length, TreeHash, HASHSIZE};
+
+/// Caches the tree hash roots of the large lists and vectors of a `BeaconState`, so that its root
+/// may be re-computed after a slot or block by only hashing the parts of them which changed.
+///
+/// The remaining fields are small, or are replaced wholesale each epoch, so they are hashed in
+/// full each time.
+#[derive(Debug, Clone, Default)]
+pub struct BeaconTreeHashCache {
+    block_roots: CowListTreeHashCache<Hash256>,
+    state_roots: CowListTreeHashCache<Hash256>,
+    historical_roots: MerkleCache,
+    validators: CowListTreeHashCache<Validator>,
+    balances: CowListTreeHashCache<u64>,
+    randao_mixes: CowListTreeHashCache<Hash256>,
+    active_index_roots: CowListTreeHashCache<Hash256>,
+    compact_committees_roots: CowListTreeHashCache<Hash256>,
+    slashings: CowListTreeHashCache<u64>,
+    previous_crosslinks: CowListTreeHashCache<Crosslink>,
+    current_crosslinks: CowListTreeHashCache<Crosslink>,
+}
+
+/// The cache does not affect the value of a `BeaconState`, so states which differ only by the
+/// contents of their caches are equal.
+impl PartialEq for BeaconTreeHashCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl BeaconTreeHashCache {
+    /// Returns `state.tree_hash_root()`, updating the cache with the fields of `state`.
+    ///
+    /// The fields must be hashed in the order in which they are declared on `BeaconState`.
+    pub fn recalculate_tree_hash_root<T: EthSpec>(
+        &mut self,
+        state: &BeaconState<T>,
+    ) -> Result<Hash256, Error> {
+        let mut leaves = Vec::with_capacity(32 * HASHSIZE);
+
+        // Versioning
+        leaves.append(&mut state.genesis_time.tree_hash_root());
+        leaves.append(&mut state.slot.tree_hash_root());
+        leaves.append(&mut state.fork.tree_hash_root());
+
+        // History
+        leaves.append(&mut state.latest_block_header.tree_hash_root());
+        leaves.extend_from_slice(
+            state
+                .block_roots
+                .recalculate_tree_hash_root(&mut self.block_roots)?
+                .as_bytes(),
+        );
+        leaves.extend_from_slice(
+            state
+                .state_roots
+                .recalculate_tree_hash_root(&mut self.state_roots)?
+                .as_bytes(),
+        );
+        let historical_roots = self.historical_roots.recalculate_root(
+            state.historical_roots.to_vec(),
+            0,
+            depth_for(T::HistoricalRootsLimit::to_usize()),
+        )?;
+        leaves.append(&mut mix_in_length(
+            historical_roots.as_bytes(),
+            state.historical_roots.len(),
+        ));
+
+        // Ethereum 1.0 chain data
+        leaves.append(&mut state.eth1_data.tree_hash_root());
+        leaves.append(&mut state.eth1_data_votes.tree_hash_root());
+        leaves.append(&mut state.eth1_deposit_index.tree_hash_root());
+
+        // Registry
+        leaves.extend_from_slice(
+            state
+                .validators
+                .recalculate_tree_hash_root(&mut self.validators)?
+                .as_bytes(),
+        );
+        leaves.extend_from_slice(
+            state
+                .balances
+                .recalculate_tree_hash_root(&mut self.balances)?
+                .as_bytes(),
+        );
+
+        // Shuffling
+        leaves.append(&mut state.start_shard.tree_hash_root());
+        leaves.extend_from_slice(
+            state
+                .randao_mixes
+                .recalculate_tree_hash_root(&mut self.randao_mixes)?
+                .as_bytes(),
+        );
+        leaves.extend_from_slice(
+            state
+                .active_index_roots
+                .recalculate_tree_hash_root(&mut self.active_index_roots)?
+                .as_bytes(),
+        );
+        leaves.extend_from_slice(
+            state
+                .compact_committees_roots
+                .recalculate_tree_hash_root(&mut self.compact_committees_roots)?
+                .as_bytes(),
+        );
+
+        // Slashings
+        leaves.extend_from_slice(
+            state
+                .slashings
+                .recalculate_tree_hash_root(&mut self.slashings)?
+                .as_bytes(),
+        );
+
+        // Attestations
+        leaves.append(&mut state.previous_epoch_attestations.tree_hash_root());
+        leaves.append(&mut state.current_epoch_attestations.tree_hash_root());
+
+        // Crosslinks
+        leaves.extend_from_slice(
+            state
+                .previous_crosslinks
+                .recalculate_tree_hash_root(&mut self.previous_crosslinks)?
+                .as_bytes(),
+        );
+        leaves.extend_from_slice(
+            state
+                .current_crosslinks
+                .recalculate_tree_hash_root(&mut self.current_crosslinks)?
+                .as_bytes(),
+        );
+
+        // Finality
+        leaves.append(&mut state.justification_bits.tree_hash_root());
+        leaves.append(&mut state.previous_justified_checkpoint.tree_hash_root());
+        leaves.append(&mut state.current_justified_checkpoint.tree_hash_root());
+        leaves.append(&mut state.finalized_checkpoint.tree_hash_root());
+
+        Ok(Hash256::from_slice(&tree_hash::merkle_root(&leaves)))
+    }
+}
+
+/// Returns the depth of a tree with enough leaves for `max_leaves`.
+fn depth_for(max_leaves: usize) -> usize {
+    max_leaves.next_power_of_two().trailing_zeros() as usize
+}
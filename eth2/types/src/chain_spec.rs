@@ -1,11 +1,15 @@
+use crate::serde_utils::{u8_from_hex_str, u8_to_hex_str};
 use crate::*;
 use int_to_bytes::int_to_bytes4;
+use serde::de::Error as DeError;
+use serde::Deserializer;
 use serde_derive::{Deserialize, Serialize};
-use test_utils::{u8_from_hex_str, u8_to_hex_str};
+use std::fs::File;
+use std::path::Path;
 
 /// Each of the BLS signature domains.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 pub enum Domain {
     BeaconProposer,
     Randao,
@@ -17,24 +21,31 @@ pub enum Domain {
 
 /// Holds all the "constants" for a BeaconChain.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ChainSpec {
+    /*
+     * Constants
+     */
+    pub base_rewards_per_epoch: u64,
+    pub deposit_contract_tree_depth: u64,
+
     /*
      * Misc
      */
     pub target_committee_size: usize,
-    pub max_indices_per_attestation: u64,
+    pub max_validators_per_committee: u64,
     pub min_per_epoch_churn_limit: u64,
     pub churn_limit_quotient: u64,
-    pub base_rewards_per_epoch: u64,
     pub shuffle_round_count: u8,
+    pub min_genesis_active_validator_count: u64,
+    pub min_genesis_time: u64,
 
     /*
      *  Deposit contract
      */
-    pub deposit_contract_tree_depth: u64,
+    pub deposit_contract_address: Address,
 
     /*
      *  Gwei values
@@ -67,14 +78,14 @@ pub struct ChainSpec {
     pub slots_per_historical_root: usize,
     pub min_validator_withdrawability_delay: Epoch,
     pub persistent_committee_period: u64,
-    pub max_crosslink_epochs: u64,
+    pub max_epochs_per_crosslink: u64,
     pub min_epochs_to_inactivity_penalty: u64,
 
     /*
      * Reward and penalty quotients
      */
-    pub base_reward_quotient: u64,
-    pub whistleblowing_reward_quotient: u64,
+    pub base_reward_factor: u64,
+    pub whistleblower_reward_quotient: u64,
     pub proposer_reward_quotient: u64,
     pub inactivity_penalty_quotient: u64,
     pub min_slashing_penalty_quotient: u64,
@@ -106,13 +117,40 @@ pub struct ChainSpec {
 
     pub boot_nodes: Vec<String>,
     pub chain_id: u8,
+
+    /*
+     * Hard forks
+     *
+     * Must remain the final serialized field, so it is serialized after all plain values in
+     * TOML.
+     */
+    pub fork_schedule: Vec<ScheduledFork>,
+    #[serde(skip)]
+    pub(crate) resolved_forks: ResolvedForks,
 }
 
 impl ChainSpec {
     /// Get the domain number that represents the fork meta and signature domain.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_domain(&self, epoch: Epoch, domain: Domain, fork: &Fork) -> u64 {
+        self.compute_domain(domain, fork.get_fork_version(epoch))
+    }
+
+    /// Get the domain for a deposit signature.
+    ///
+    /// Deposits are valid across forks, thus the deposit domain is computed with the fork version
+    /// set to zero.
+    ///
+    /// Spec v0.8.1
+    pub fn get_deposit_domain(&self) -> u64 {
+        self.compute_domain(Domain::Deposit, [0; 4])
+    }
+
+    /// Return the domain for the `domain` type and `fork_version`.
+    ///
+    /// Spec v0.8.1
+    pub fn compute_domain(&self, domain: Domain, fork_version: [u8; 4]) -> u64 {
         let domain_constant = match domain {
             Domain::BeaconProposer => self.domain_beacon_proposer,
             Domain::Randao => self.domain_randao,
@@ -122,34 +160,110 @@ impl ChainSpec {
             Domain::Transfer => self.domain_transfer,
         };
 
-        let mut bytes: Vec<u8> = fork.get_fork_version(epoch).to_vec();
-        bytes.append(&mut int_to_bytes4(domain_constant));
+        let mut bytes: Vec<u8> = int_to_bytes4(domain_constant);
+        bytes.extend_from_slice(&fork_version);
+
+        let mut domain_and_fork = [0; 8];
+        domain_and_fork.copy_from_slice(&bytes);
+
+        u64::from_le_bytes(domain_and_fork)
+    }
+
+    /// Returns the latest fork in the `fork_schedule` which is active at `epoch`, if any.
+    pub fn scheduled_fork_at(&self, epoch: Epoch) -> Option<&ScheduledFork> {
+        self.fork_schedule
+            .iter()
+            .filter(|fork| fork.epoch <= epoch)
+            .max_by_key(|fork| fork.epoch)
+    }
+
+    /// Returns the fork in the `fork_schedule` which activates at the first slot of `epoch`, if
+    /// any.
+    pub fn fork_starting_at(&self, epoch: Epoch) -> Option<&ScheduledFork> {
+        self.fork_schedule.iter().find(|fork| fork.epoch == epoch)
+    }
 
-        let mut fork_and_domain = [0; 8];
-        fork_and_domain.copy_from_slice(&bytes);
+    /// Returns the fork version which is active at `epoch`, according to the `fork_schedule`.
+    pub fn fork_version_at(&self, epoch: Epoch) -> [u8; 4] {
+        self.scheduled_fork_at(epoch)
+            .map_or(GENESIS_FORK_VERSION, |fork| fork.version)
+    }
+
+    /// Returns the `Fork` which a `BeaconState` at `epoch` should hold, according to the
+    /// `fork_schedule`.
+    pub fn fork_at(&self, epoch: Epoch, genesis_epoch: Epoch) -> Fork {
+        match self.scheduled_fork_at(epoch) {
+            Some(fork) => Fork {
+                previous_version: self.fork_version_at(fork.epoch - 1),
+                current_version: fork.version,
+                epoch: fork.epoch,
+            },
+            None => Fork::genesis(genesis_epoch),
+        }
+    }
+
+    /// Returns the constants which apply at `epoch`, i.e., `self` with the `overrides` of every
+    /// fork active at `epoch` applied in order.
+    ///
+    /// The overrides are resolved once per fork (see `ResolvedForks`), so this is cheap to call
+    /// for every block and epoch.
+    pub fn at_epoch(&self, epoch: Epoch) -> SpecAtEpoch<'_> {
+        let has_overrides = self
+            .fork_schedule
+            .iter()
+            .any(|fork| fork.epoch <= epoch && !fork.overrides.is_empty());
+
+        if has_overrides {
+            SpecAtEpoch::Resolved(self.resolved_forks.at_epoch(self, epoch))
+        } else {
+            SpecAtEpoch::Unchanged(self)
+        }
+    }
 
-        u64::from_le_bytes(fork_and_domain)
+    /// Returns an error if the `fork_schedule` is not strictly increasing by epoch, or schedules
+    /// a fork at the genesis epoch.
+    pub fn verify_fork_schedule(&self, genesis_epoch: Epoch) -> Result<(), String> {
+        let mut previous_epoch = genesis_epoch;
+        for fork in &self.fork_schedule {
+            if fork.epoch <= previous_epoch {
+                return Err(format!(
+                    "Fork {} at epoch {} must be later than epoch {}",
+                    fork.name, fork.epoch, previous_epoch
+                ));
+            }
+            previous_epoch = fork.epoch;
+        }
+        Ok(())
     }
 
     /// Returns a `ChainSpec` compatible with the Ethereum Foundation specification.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn mainnet() -> Self {
         Self {
+            /*
+             * Constants
+             */
+            base_rewards_per_epoch: 5,
+            deposit_contract_tree_depth: 32,
+
             /*
              * Misc
              */
             target_committee_size: 128,
-            max_indices_per_attestation: 4096,
+            max_validators_per_committee: 4096,
             min_per_epoch_churn_limit: 4,
             churn_limit_quotient: 65_536,
-            base_rewards_per_epoch: 5,
             shuffle_round_count: 90,
+            min_genesis_active_validator_count: 65_536,
+            min_genesis_time: 1_578_009_600, // Jan 3, 2020
 
             /*
              *  Deposit contract
              */
-            deposit_contract_tree_depth: 32,
+            deposit_contract_address: "1234567890123456789012345678901234567890"
+                .parse()
+                .expect("chain spec deposit contract address"),
 
             /*
              *  Gwei values
@@ -172,21 +286,21 @@ impl ChainSpec {
              */
             genesis_time: u64::from(u32::max_value()),
             seconds_per_slot: 6,
-            min_attestation_inclusion_delay: 4,
+            min_attestation_inclusion_delay: 1,
             min_seed_lookahead: Epoch::new(1),
             activation_exit_delay: 4,
             slots_per_eth1_voting_period: 1_024,
             slots_per_historical_root: 8_192,
             min_validator_withdrawability_delay: Epoch::new(256),
             persistent_committee_period: 2_048,
-            max_crosslink_epochs: 64,
+            max_epochs_per_crosslink: 64,
             min_epochs_to_inactivity_penalty: 4,
 
             /*
              * Reward and penalty quotients
              */
-            base_reward_quotient: 32,
-            whistleblowing_reward_quotient: 512,
+            base_reward_factor: 64,
+            whistleblower_reward_quotient: 512,
             proposer_reward_quotient: 8,
             inactivity_penalty_quotient: 33_554_432,
             min_slashing_penalty_quotient: 32,
@@ -216,14 +330,20 @@ impl ChainSpec {
              */
             boot_nodes: vec![],
             chain_id: 1, // mainnet chain id
+
+            /*
+             * Hard forks
+             */
+            fork_schedule: vec![],
+            resolved_forks: ResolvedForks::default(),
         }
     }
 
     /// Ethereum Foundation minimal spec, as defined here:
     ///
-    /// https://github.com/ethereum/eth2.0-specs/blob/v0.6.3/configs/constant_presets/minimal.yaml
+    /// https://github.com/ethereum/eth2.0-specs/blob/v0.8.1/configs/constant_presets/minimal.yaml
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn minimal() -> Self {
         let genesis_slot = Slot::new(0);
 
@@ -233,8 +353,10 @@ impl ChainSpec {
         Self {
             target_committee_size: 4,
             shuffle_round_count: 10,
-            min_attestation_inclusion_delay: 2,
+            min_genesis_active_validator_count: 64,
             slots_per_eth1_voting_period: 16,
+            slots_per_historical_root: 64,
+            max_epochs_per_crosslink: 4,
             genesis_slot,
             chain_id: 2, // lighthouse testnet chain id
             boot_nodes,
@@ -249,6 +371,379 @@ impl Default for ChainSpec {
     }
 }
 
+/// The constants of a `ChainSpec` in the YAML config format of the Ethereum Foundation specs repo,
+/// as defined here:
+///
+/// https://github.com/ethereum/eth2.0-specs/blob/v0.8.1/configs/constant_presets/minimal.yaml
+///
+/// All keys are required and unknown keys are rejected.
+///
+/// Spec v0.8.1
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+pub struct YamlConfig {
+    /*
+     * Misc
+     */
+    pub shard_count: u64,
+    pub target_committee_size: u64,
+    pub max_validators_per_committee: u64,
+    pub min_per_epoch_churn_limit: u64,
+    pub churn_limit_quotient: u64,
+    pub shuffle_round_count: u8,
+    pub min_genesis_active_validator_count: u64,
+    pub min_genesis_time: u64,
+
+    /*
+     *  Deposit contract
+     */
+    pub deposit_contract_address: Address,
+
+    /*
+     *  Gwei values
+     */
+    pub min_deposit_amount: u64,
+    pub max_effective_balance: u64,
+    pub ejection_balance: u64,
+    pub effective_balance_increment: u64,
+
+    /*
+     * Initial Values
+     */
+    pub genesis_slot: u64,
+    #[serde(
+        deserialize_with = "u8_from_hex_or_int",
+        serialize_with = "u8_to_hex_str"
+    )]
+    pub bls_withdrawal_prefix: u8,
+
+    /*
+     * Time parameters
+     */
+    pub seconds_per_slot: u64,
+    pub min_attestation_inclusion_delay: u64,
+    pub slots_per_epoch: u64,
+    pub min_seed_lookahead: u64,
+    pub activation_exit_delay: u64,
+    pub slots_per_eth1_voting_period: u64,
+    pub slots_per_historical_root: u64,
+    pub min_validator_withdrawability_delay: u64,
+    pub persistent_committee_period: u64,
+    pub max_epochs_per_crosslink: u64,
+    pub min_epochs_to_inactivity_penalty: u64,
+
+    /*
+     * State list lengths
+     */
+    pub epochs_per_historical_vector: u64,
+    pub epochs_per_slashings_vector: u64,
+    pub historical_roots_limit: u64,
+    pub validator_registry_limit: u64,
+
+    /*
+     * Reward and penalty quotients
+     */
+    pub base_reward_factor: u64,
+    pub whistleblower_reward_quotient: u64,
+    pub proposer_reward_quotient: u64,
+    pub inactivity_penalty_quotient: u64,
+    pub min_slashing_penalty_quotient: u64,
+
+    /*
+     * Max operations per block
+     */
+    pub max_proposer_slashings: u64,
+    pub max_attester_slashings: u64,
+    pub max_attestations: u64,
+    pub max_deposits: u64,
+    pub max_voluntary_exits: u64,
+    pub max_transfers: u64,
+
+    /*
+     * Signature domains
+     */
+    pub domain_beacon_proposer: u32,
+    pub domain_randao: u32,
+    pub domain_attestation: u32,
+    pub domain_deposit: u32,
+    pub domain_voluntary_exit: u32,
+    pub domain_transfer: u32,
+}
+
+impl YamlConfig {
+    /// Returns the constants of `spec`, with the state list lengths taken from `T`.
+    pub fn from_spec<T: EthSpec>(spec: &ChainSpec) -> Self {
+        Self {
+            shard_count: T::shard_count() as u64,
+            target_committee_size: spec.target_committee_size as u64,
+            max_validators_per_committee: T::MaxValidatorsPerCommittee::to_u64(),
+            min_per_epoch_churn_limit: spec.min_per_epoch_churn_limit,
+            churn_limit_quotient: spec.churn_limit_quotient,
+            shuffle_round_count: spec.shuffle_round_count,
+            min_genesis_active_validator_count: spec.min_genesis_active_validator_count,
+            min_genesis_time: spec.min_genesis_time,
+            deposit_contract_address: spec.deposit_contract_address,
+            min_deposit_amount: spec.min_deposit_amount,
+            max_effective_balance: spec.max_effective_balance,
+            ejection_balance: spec.ejection_balance,
+            effective_balance_increment: spec.effective_balance_increment,
+            genesis_slot: spec.genesis_slot.as_u64(),
+            bls_withdrawal_prefix: spec.bls_withdrawal_prefix_byte,
+            seconds_per_slot: spec.seconds_per_slot,
+            min_attestation_inclusion_delay: spec.min_attestation_inclusion_delay,
+            slots_per_epoch: T::slots_per_epoch(),
+            min_seed_lookahead: spec.min_seed_lookahead.as_u64(),
+            activation_exit_delay: spec.activation_exit_delay,
+            slots_per_eth1_voting_period: T::slots_per_eth1_voting_period() as u64,
+            slots_per_historical_root: T::slots_per_historical_root() as u64,
+            min_validator_withdrawability_delay: spec.min_validator_withdrawability_delay.as_u64(),
+            persistent_committee_period: spec.persistent_committee_period,
+            max_epochs_per_crosslink: spec.max_epochs_per_crosslink,
+            min_epochs_to_inactivity_penalty: spec.min_epochs_to_inactivity_penalty,
+            epochs_per_historical_vector: T::epochs_per_historical_vector() as u64,
+            epochs_per_slashings_vector: T::epochs_per_slashings_vector() as u64,
+            historical_roots_limit: T::HistoricalRootsLimit::to_u64(),
+            validator_registry_limit: T::ValidatorRegistryLimit::to_u64(),
+            base_reward_factor: spec.base_reward_factor,
+            whistleblower_reward_quotient: spec.whistleblower_reward_quotient,
+            proposer_reward_quotient: spec.proposer_reward_quotient,
+            inactivity_penalty_quotient: spec.inactivity_penalty_quotient,
+            min_slashing_penalty_quotient: spec.min_slashing_penalty_quotient,
+            max_proposer_slashings: spec.max_proposer_slashings,
+            max_attester_slashings: spec.max_attester_slashings,
+            max_attestations: spec.max_attestations,
+            max_deposits: spec.max_deposits,
+            max_voluntary_exits: spec.max_voluntary_exits,
+            max_transfers: spec.max_transfers,
+            domain_beacon_proposer: spec.domain_beacon_proposer,
+            domain_randao: spec.domain_randao,
+            domain_attestation: spec.domain_attestation,
+            domain_deposit: spec.domain_deposit,
+            domain_voluntary_exit: spec.domain_voluntary_exit,
+            domain_transfer: spec.domain_transfer,
+        }
+    }
+
+    /// Returns `spec` with its constants replaced by those in `self`.
+    ///
+    /// Values which are not part of the config (e.g., `genesis_time`, `boot_nodes`) are taken from
+    /// `spec`.
+    ///
+    /// Returns an error if `self` contains a value which is fixed at compile-time by `T` (e.g.,
+    /// `SHARD_COUNT`), but differs from `T`, or a maximum number of operations per block which
+    /// exceeds the length of the corresponding `BeaconBlockBody` list in `T`.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, spec: &ChainSpec) -> Result<ChainSpec, String> {
+        let compile_time_values = [
+            ("SHARD_COUNT", self.shard_count, T::shard_count() as u64),
+            (
+                "MAX_VALIDATORS_PER_COMMITTEE",
+                self.max_validators_per_committee,
+                T::MaxValidatorsPerCommittee::to_u64(),
+            ),
+            (
+                "SLOTS_PER_EPOCH",
+                self.slots_per_epoch,
+                T::slots_per_epoch(),
+            ),
+            (
+                "SLOTS_PER_ETH1_VOTING_PERIOD",
+                self.slots_per_eth1_voting_period,
+                T::slots_per_eth1_voting_period() as u64,
+            ),
+            (
+                "SLOTS_PER_HISTORICAL_ROOT",
+                self.slots_per_historical_root,
+                T::slots_per_historical_root() as u64,
+            ),
+            (
+                "EPOCHS_PER_HISTORICAL_VECTOR",
+                self.epochs_per_historical_vector,
+                T::epochs_per_historical_vector() as u64,
+            ),
+            (
+                "EPOCHS_PER_SLASHINGS_VECTOR",
+                self.epochs_per_slashings_vector,
+                T::epochs_per_slashings_vector() as u64,
+            ),
+            (
+                "HISTORICAL_ROOTS_LIMIT",
+                self.historical_roots_limit,
+                T::HistoricalRootsLimit::to_u64(),
+            ),
+            (
+                "VALIDATOR_REGISTRY_LIMIT",
+                self.validator_registry_limit,
+                T::ValidatorRegistryLimit::to_u64(),
+            ),
+            (
+                "GENESIS_SLOT",
+                self.genesis_slot,
+                T::genesis_epoch().start_slot(T::slots_per_epoch()).as_u64(),
+            ),
+        ];
+
+        for (name, config_value, compile_time_value) in compile_time_values.iter() {
+            if config_value != compile_time_value {
+                return Err(format!(
+                    "{} is {} in the config, but must be {} for these spec constants",
+                    name, config_value, compile_time_value
+                ));
+            }
+        }
+
+        let max_operations = [
+            (
+                "MAX_PROPOSER_SLASHINGS",
+                self.max_proposer_slashings,
+                T::MaxProposerSlashings::to_u64(),
+            ),
+            (
+                "MAX_ATTESTER_SLASHINGS",
+                self.max_attester_slashings,
+                T::MaxAttesterSlashings::to_u64(),
+            ),
+            (
+                "MAX_ATTESTATIONS",
+                self.max_attestations,
+                T::MaxAttestations::to_u64(),
+            ),
+            ("MAX_DEPOSITS", self.max_deposits, T::MaxDeposits::to_u64()),
+            (
+                "MAX_VOLUNTARY_EXITS",
+                self.max_voluntary_exits,
+                T::MaxVoluntaryExits::to_u64(),
+            ),
+            (
+                "MAX_TRANSFERS",
+                self.max_transfers,
+                T::MaxTransfers::to_u64(),
+            ),
+        ];
+
+        for (name, config_value, compile_time_limit) in max_operations.iter() {
+            if config_value > compile_time_limit {
+                return Err(format!(
+                    "{} is {} in the config, but may not exceed {} for these spec constants",
+                    name, config_value, compile_time_limit
+                ));
+            }
+        }
+
+        Ok(ChainSpec {
+            target_committee_size: self.target_committee_size as usize,
+            max_validators_per_committee: self.max_validators_per_committee,
+            min_per_epoch_churn_limit: self.min_per_epoch_churn_limit,
+            churn_limit_quotient: self.churn_limit_quotient,
+            shuffle_round_count: self.shuffle_round_count,
+            min_genesis_active_validator_count: self.min_genesis_active_validator_count,
+            min_genesis_time: self.min_genesis_time,
+            deposit_contract_address: self.deposit_contract_address,
+            min_deposit_amount: self.min_deposit_amount,
+            max_effective_balance: self.max_effective_balance,
+            ejection_balance: self.ejection_balance,
+            effective_balance_increment: self.effective_balance_increment,
+            genesis_slot: Slot::new(self.genesis_slot),
+            bls_withdrawal_prefix_byte: self.bls_withdrawal_prefix,
+            seconds_per_slot: self.seconds_per_slot,
+            min_attestation_inclusion_delay: self.min_attestation_inclusion_delay,
+            min_seed_lookahead: Epoch::new(self.min_seed_lookahead),
+            activation_exit_delay: self.activation_exit_delay,
+            slots_per_eth1_voting_period: self.slots_per_eth1_voting_period,
+            slots_per_historical_root: self.slots_per_historical_root as usize,
+            min_validator_withdrawability_delay: Epoch::new(
+                self.min_validator_withdrawability_delay,
+            ),
+            persistent_committee_period: self.persistent_committee_period,
+            max_epochs_per_crosslink: self.max_epochs_per_crosslink,
+            min_epochs_to_inactivity_penalty: self.min_epochs_to_inactivity_penalty,
+            base_reward_factor: self.base_reward_factor,
+            whistleblower_reward_quotient: self.whistleblower_reward_quotient,
+            proposer_reward_quotient: self.proposer_reward_quotient,
+            inactivity_penalty_quotient: self.inactivity_penalty_quotient,
+            min_slashing_penalty_quotient: self.min_slashing_penalty_quotient,
+            max_proposer_slashings: self.max_proposer_slashings,
+            max_attester_slashings: self.max_attester_slashings,
+            max_attestations: self.max_attestations,
+            max_deposits: self.max_deposits,
+            max_voluntary_exits: self.max_voluntary_exits,
+            max_transfers: self.max_transfers,
+            domain_beacon_proposer: self.domain_beacon_proposer,
+            domain_randao: self.domain_randao,
+            domain_attestation: self.domain_attestation,
+            domain_deposit: self.domain_deposit,
+            domain_voluntary_exit: self.domain_voluntary_exit,
+            domain_transfer: self.domain_transfer,
+            ..spec.clone()
+        })
+    }
+
+    /// Parse a config from a YAML string.
+    pub fn from_yaml_str(yaml: &str) -> Result<Self, String> {
+        serde_yaml::from_str(yaml).map_err(|e| format!("Unable to parse YAML config: {}", e))
+    }
+
+    /// Load a config from a YAML file.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+        serde_yaml::from_reader(file).map_err(|e| format!("Unable to parse {:?}: {}", path, e))
+    }
+
+    pub fn to_yaml_string(&self) -> Result<String, String> {
+        serde_yaml::to_string(self).map_err(|e| format!("Unable to encode YAML config: {}", e))
+    }
+
+    /// Returns the keys of the values which differ between `self` and `other`.
+    pub fn differences(&self, other: &YamlConfig) -> Vec<String> {
+        let to_mapping = |config: &YamlConfig| match serde_yaml::to_value(config) {
+            Ok(serde_yaml::Value::Mapping(mapping)) => mapping,
+            _ => serde_yaml::Mapping::new(),
+        };
+        let ours = to_mapping(self);
+        let theirs = to_mapping(other);
+
+        ours.iter()
+            .filter(|(key, value)| theirs.get(key) != Some(value))
+            .filter_map(|(key, _)| key.as_str().map(String::from))
+            .collect()
+    }
+}
+
+/// Some YAML value which is hex-encoded in the specs repo.
+///
+/// Unquoted hex values (e.g., `0x00`) are parsed as integers by YAML, so both are accepted.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HexOrInt {
+    Int(u64),
+    Hex(String),
+}
+
+fn u64_from_hex_or_int<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match <HexOrInt as serde::Deserialize>::deserialize(deserializer)? {
+        HexOrInt::Int(int) => Ok(int),
+        HexOrInt::Hex(hex) => {
+            if !hex.starts_with("0x") {
+                return Err(D::Error::custom(format!("{} is not 0x-prefixed hex", hex)));
+            }
+            u64::from_str_radix(&hex[2..], 16).map_err(D::Error::custom)
+        }
+    }
+}
+
+fn u8_from_hex_or_int<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let int = u64_from_hex_or_int(deserializer)?;
+    if int > u64::from(u8::max_value()) {
+        return Err(D::Error::custom(format!("{} is larger than one byte", int)));
+    }
+    Ok(int as u8)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,8 +760,8 @@ mod tests {
 
         let domain = spec.get_domain(epoch, domain_type, &fork);
 
-        let mut expected = fork.get_fork_version(epoch).to_vec();
-        expected.append(&mut int_to_bytes4(raw_domain));
+        let mut expected = int_to_bytes4(raw_domain);
+        expected.append(&mut fork.get_fork_version(epoch).to_vec());
 
         assert_eq!(int_to_bytes8(domain), expected);
     }
@@ -282,4 +777,197 @@ mod tests {
         test_domain(Domain::VoluntaryExit, spec.domain_voluntary_exit, &spec);
         test_domain(Domain::Transfer, spec.domain_transfer, &spec);
     }
+
+    fn toy_fork_spec() -> ChainSpec {
+        ChainSpec {
+            fork_schedule: vec![
+                ScheduledFork {
+                    name: "toy_a".to_string(),
+                    epoch: Epoch::new(4),
+                    version: [0, 0, 0, 1],
+                    overrides: SpecOverrides {
+                        max_transfers: Some(16),
+                        ..SpecOverrides::default()
+                    },
+                },
+                ScheduledFork {
+                    name: "toy_b".to_string(),
+                    epoch: Epoch::new(8),
+                    version: [0, 0, 0, 2],
+                    overrides: SpecOverrides::default(),
+                },
+            ],
+            ..ChainSpec::mainnet()
+        }
+    }
+
+    #[test]
+    fn test_fork_schedule() {
+        let spec = toy_fork_spec();
+        let genesis_epoch = Epoch::new(0);
+
+        assert!(spec.verify_fork_schedule(genesis_epoch).is_ok());
+
+        assert_eq!(spec.fork_version_at(Epoch::new(3)), GENESIS_FORK_VERSION);
+        assert_eq!(spec.fork_version_at(Epoch::new(4)), [0, 0, 0, 1]);
+        assert_eq!(spec.fork_version_at(Epoch::new(100)), [0, 0, 0, 2]);
+
+        assert!(spec.fork_starting_at(Epoch::new(4)).is_some());
+        assert!(spec.fork_starting_at(Epoch::new(5)).is_none());
+
+        assert_eq!(
+            spec.fork_at(Epoch::new(0), genesis_epoch),
+            Fork::genesis(genesis_epoch)
+        );
+        assert_eq!(
+            spec.fork_at(Epoch::new(9), genesis_epoch),
+            Fork {
+                previous_version: [0, 0, 0, 1],
+                current_version: [0, 0, 0, 2],
+                epoch: Epoch::new(8),
+            }
+        );
+    }
+
+    #[test]
+    fn test_spec_at_epoch() {
+        let spec = toy_fork_spec();
+
+        assert_eq!(spec.at_epoch(Epoch::new(3)).max_transfers, 0);
+        assert_eq!(spec.at_epoch(Epoch::new(4)).max_transfers, 16);
+        // Overrides persist through later forks.
+        assert_eq!(spec.at_epoch(Epoch::new(8)).max_transfers, 16);
+    }
+
+    #[test]
+    fn test_invalid_fork_schedule() {
+        let mut spec = toy_fork_spec();
+        spec.fork_schedule.reverse();
+
+        assert!(spec.verify_fork_schedule(Epoch::new(0)).is_err());
+    }
+
+    /// The minimal preset from the specs repo, with hex values unquoted as they are upstream.
+    const MINIMAL_YAML: &str = "
+SHARD_COUNT: 8
+TARGET_COMMITTEE_SIZE: 4
+MAX_VALIDATORS_PER_COMMITTEE: 4096
+MIN_PER_EPOCH_CHURN_LIMIT: 4
+CHURN_LIMIT_QUOTIENT: 65536
+SHUFFLE_ROUND_COUNT: 10
+MIN_GENESIS_ACTIVE_VALIDATOR_COUNT: 64
+MIN_GENESIS_TIME: 1578009600
+DEPOSIT_CONTRACT_ADDRESS: 0x1234567890123456789012345678901234567890
+MIN_DEPOSIT_AMOUNT: 1000000000
+MAX_EFFECTIVE_BALANCE: 32000000000
+EJECTION_BALANCE: 16000000000
+EFFECTIVE_BALANCE_INCREMENT: 1000000000
+GENESIS_SLOT: 0
+BLS_WITHDRAWAL_PREFIX: 0x00
+SECONDS_PER_SLOT: 6
+MIN_ATTESTATION_INCLUSION_DELAY: 1
+SLOTS_PER_EPOCH: 8
+MIN_SEED_LOOKAHEAD: 1
+ACTIVATION_EXIT_DELAY: 4
+SLOTS_PER_ETH1_VOTING_PERIOD: 16
+SLOTS_PER_HISTORICAL_ROOT: 64
+MIN_VALIDATOR_WITHDRAWABILITY_DELAY: 256
+PERSISTENT_COMMITTEE_PERIOD: 2048
+MAX_EPOCHS_PER_CROSSLINK: 4
+MIN_EPOCHS_TO_INACTIVITY_PENALTY: 4
+EPOCHS_PER_HISTORICAL_VECTOR: 64
+EPOCHS_PER_SLASHINGS_VECTOR: 64
+HISTORICAL_ROOTS_LIMIT: 16777216
+VALIDATOR_REGISTRY_LIMIT: 1099511627776
+BASE_REWARD_FACTOR: 64
+WHISTLEBLOWER_REWARD_QUOTIENT: 512
+PROPOSER_REWARD_QUOTIENT: 8
+INACTIVITY_PENALTY_QUOTIENT: 33554432
+MIN_SLASHING_PENALTY_QUOTIENT: 32
+MAX_PROPOSER_SLASHINGS: 16
+MAX_ATTESTER_SLASHINGS: 1
+MAX_ATTESTATIONS: 128
+MAX_DEPOSITS: 16
+MAX_VOLUNTARY_EXITS: 16
+MAX_TRANSFERS: 0
+DOMAIN_BEACON_PROPOSER: 0
+DOMAIN_RANDAO: 1
+DOMAIN_ATTESTATION: 2
+DOMAIN_DEPOSIT: 3
+DOMAIN_VOLUNTARY_EXIT: 4
+DOMAIN_TRANSFER: 5
+";
+
+    #[test]
+    fn test_yaml_config_minimal_preset() {
+        let config = YamlConfig::from_yaml_str(MINIMAL_YAML).unwrap();
+        assert_eq!(
+            config,
+            YamlConfig::from_spec::<MinimalEthSpec>(&ChainSpec::minimal())
+        );
+
+        let spec = config
+            .apply_to_chain_spec::<MinimalEthSpec>(&ChainSpec::minimal())
+            .unwrap();
+        assert_eq!(spec, ChainSpec::minimal());
+    }
+
+    #[test]
+    fn test_yaml_config_round_trip() {
+        let config = YamlConfig::from_spec::<MainnetEthSpec>(&ChainSpec::mainnet());
+        let yaml = config.to_yaml_string().unwrap();
+        let decoded = YamlConfig::from_yaml_str(&yaml).unwrap();
+
+        assert_eq!(decoded, config);
+        assert!(decoded.differences(&config).is_empty());
+    }
+
+    #[test]
+    fn test_yaml_config_unknown_key() {
+        let yaml = format!("{}UNKNOWN_CONSTANT: 1\n", MINIMAL_YAML);
+        assert!(YamlConfig::from_yaml_str(&yaml).is_err());
+    }
+
+    #[test]
+    fn test_yaml_config_missing_key() {
+        let yaml = MINIMAL_YAML.replace("MAX_TRANSFERS: 0\n", "");
+        assert!(YamlConfig::from_yaml_str(&yaml).is_err());
+    }
+
+    #[test]
+    fn test_yaml_config_typenum_mismatch() {
+        let yaml = MINIMAL_YAML.replace("SHARD_COUNT: 8", "SHARD_COUNT: 16");
+        let config = YamlConfig::from_yaml_str(&yaml).unwrap();
+
+        assert!(config
+            .apply_to_chain_spec::<MinimalEthSpec>(&ChainSpec::minimal())
+            .is_err());
+        assert_eq!(
+            config.differences(&YamlConfig::from_spec::<MinimalEthSpec>(
+                &ChainSpec::minimal()
+            )),
+            vec!["SHARD_COUNT".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_yaml_config_max_operations_exceed_typenum() {
+        let yaml = MINIMAL_YAML.replace("MAX_ATTESTATIONS: 128", "MAX_ATTESTATIONS: 129");
+        let config = YamlConfig::from_yaml_str(&yaml).unwrap();
+
+        assert!(config
+            .apply_to_chain_spec::<MinimalEthSpec>(&ChainSpec::minimal())
+            .is_err());
+
+        let yaml = MINIMAL_YAML.replace("MAX_ATTESTATIONS: 128", "MAX_ATTESTATIONS: 64");
+        let config = YamlConfig::from_yaml_str(&yaml).unwrap();
+
+        assert_eq!(
+            config
+                .apply_to_chain_spec::<MinimalEthSpec>(&ChainSpec::minimal())
+                .unwrap()
+                .max_attestations,
+            64
+        );
+    }
 }
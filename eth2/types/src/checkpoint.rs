@@ -0,0 +1,40 @@
+use crate::test_utils::TestRandom;
+use crate::{Epoch, Hash256};
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::{CachedTreeHash, TreeHash};
+
+/// Casper FFG checkpoint, used in attestations.
+///
+/// Spec v0.8.1
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Default,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    TreeHash,
+    CachedTreeHash,
+    TestRandom,
+)]
+pub struct Checkpoint {
+    pub epoch: Epoch,
+    pub root: Hash256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_tests!(Checkpoint);
+
+    serde_tests!(Checkpoint);
+    cached_tree_hash_tests!(Checkpoint);
+}
@@ -0,0 +1,38 @@
+use crate::serde_utils::quoted_u64_vec;
+use crate::test_utils::TestRandom;
+use crate::{EthSpec, PublicKey};
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::VariableList;
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// Spec v0.8.1
+#[derive(Clone, Debug, PartialEq, TreeHash, Encode, Decode, Serialize, Deserialize, TestRandom)]
+pub struct CompactCommittee<T: EthSpec> {
+    pub pubkeys: VariableList<PublicKey, T::MaxValidatorsPerCommittee>,
+    #[serde(with = "quoted_u64_vec")]
+    pub compact_validators: VariableList<u64, T::MaxValidatorsPerCommittee>,
+}
+
+impl<T: EthSpec> Default for CompactCommittee<T> {
+    fn default() -> Self {
+        Self {
+            pubkeys: VariableList::default(),
+            compact_validators: VariableList::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainnetEthSpec;
+
+    pub type FoundationCompactCommittee = CompactCommittee<MainnetEthSpec>;
+
+    ssz_tests!(FoundationCompactCommittee);
+
+    serde_tests!(FoundationCompactCommittee);
+}
@@ -1,3 +1,4 @@
+use crate::serde_utils::quoted_u64;
 use crate::test_utils::TestRandom;
 use crate::{Epoch, Hash256};
 
@@ -8,7 +9,7 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
 
 /// Specifies the block hash for a shard at an epoch.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     Clone,
@@ -25,9 +26,13 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
     TestRandom,
 )]
 pub struct Crosslink {
-    pub epoch: Epoch,
-    pub previous_crosslink_root: Hash256,
-    pub crosslink_data_root: Hash256,
+    #[serde(with = "quoted_u64")]
+    pub shard: u64,
+    pub parent_root: Hash256,
+    // Crosslinking data
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+    pub data_root: Hash256,
 }
 
 #[cfg(test)]
@@ -35,5 +40,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(Crosslink);
+
+    serde_tests!(Crosslink);
     cached_tree_hash_tests!(Crosslink);
 }
@@ -1,6 +1,6 @@
 use crate::test_utils::TestRandom;
 use crate::*;
-use fixed_len_vec::typenum::U32;
+use fixed_len_vec::typenum::U33;
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
@@ -9,7 +9,7 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
 
 /// A deposit to potentially become a beacon chain validator.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -23,8 +23,9 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
     TestRandom,
 )]
 pub struct Deposit {
-    pub proof: FixedLenVec<Hash256, U32>,
-    pub index: u64,
+    /// A Merkle branch of `DEPOSIT_CONTRACT_TREE_DEPTH + 1` nodes, the final node being the mixed
+    /// in deposit count.
+    pub proof: FixedLenVec<Hash256, U33>,
     pub data: DepositData,
 }
 
@@ -33,5 +34,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(Deposit);
+
+    serde_tests!(Deposit);
     cached_tree_hash_tests!(Deposit);
 }
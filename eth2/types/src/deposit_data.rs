@@ -1,3 +1,4 @@
+use crate::serde_utils::quoted_u64;
 use crate::test_utils::TestRandom;
 use crate::*;
 use bls::{PublicKey, Signature};
@@ -10,7 +11,7 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 
 /// The data supplied by the user to the deposit contract.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -27,6 +28,7 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 pub struct DepositData {
     pub pubkey: PublicKey,
     pub withdrawal_credentials: Hash256,
+    #[serde(with = "quoted_u64")]
     pub amount: u64,
     #[signed_root(skip_hashing)]
     pub signature: Signature,
@@ -35,16 +37,10 @@ pub struct DepositData {
 impl DepositData {
     /// Generate the signature for a given DepositData details.
     ///
-    /// Spec v0.6.3
-    pub fn create_signature(
-        &self,
-        secret_key: &SecretKey,
-        epoch: Epoch,
-        fork: &Fork,
-        spec: &ChainSpec,
-    ) -> Signature {
+    /// Spec v0.8.1
+    pub fn create_signature(&self, secret_key: &SecretKey, spec: &ChainSpec) -> Signature {
         let msg = self.signed_root();
-        let domain = spec.get_domain(epoch, Domain::Deposit, fork);
+        let domain = spec.get_deposit_domain();
 
         Signature::new(msg.as_slice(), domain, secret_key)
     }
@@ -55,5 +51,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(DepositData);
+
+    serde_tests!(DepositData);
     cached_tree_hash_tests!(DepositData);
 }
@@ -1,4 +1,5 @@
 use super::Hash256;
+use crate::serde_utils::quoted_u64;
 use crate::test_utils::TestRandom;
 
 use serde_derive::{Deserialize, Serialize};
@@ -8,7 +9,7 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
 
 /// Contains data obtained from the Eth1 chain.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -24,6 +25,7 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
 )]
 pub struct Eth1Data {
     pub deposit_root: Hash256,
+    #[serde(with = "quoted_u64")]
     pub deposit_count: u64,
     pub block_hash: Hash256,
 }
@@ -33,5 +35,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(Eth1Data);
+
+    serde_tests!(Eth1Data);
     cached_tree_hash_tests!(Eth1Data);
 }
@@ -1,6 +1,7 @@
 use crate::{
-    test_utils::{fork_from_hex_str, TestRandom},
-    Epoch,
+    serde_utils::{fork_from_hex_str, fork_to_hex_str},
+    test_utils::TestRandom,
+    Epoch, GENESIS_FORK_VERSION,
 };
 
 use serde_derive::{Deserialize, Serialize};
@@ -10,7 +11,7 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
 
 /// Specifies a fork of the `BeaconChain`, to prevent replay attacks.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     Clone,
@@ -25,9 +26,15 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
     TestRandom,
 )]
 pub struct Fork {
-    #[serde(deserialize_with = "fork_from_hex_str")]
+    #[serde(
+        deserialize_with = "fork_from_hex_str",
+        serialize_with = "fork_to_hex_str"
+    )]
     pub previous_version: [u8; 4],
-    #[serde(deserialize_with = "fork_from_hex_str")]
+    #[serde(
+        deserialize_with = "fork_from_hex_str",
+        serialize_with = "fork_to_hex_str"
+    )]
     pub current_version: [u8; 4],
     pub epoch: Epoch,
 }
@@ -35,18 +42,18 @@ pub struct Fork {
 impl Fork {
     /// Initialize the `Fork` from the genesis parameters in the `spec`.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn genesis(genesis_epoch: Epoch) -> Self {
         Self {
-            previous_version: [0; 4],
-            current_version: [0; 4],
+            previous_version: GENESIS_FORK_VERSION,
+            current_version: GENESIS_FORK_VERSION,
             epoch: genesis_epoch,
         }
     }
 
     /// Return the fork version of the given ``epoch``.
     ///
-    /// Spec v0.6.3
+    /// Spec v0.8.1
     pub fn get_fork_version(&self, epoch: Epoch) -> [u8; 4] {
         if epoch < self.epoch {
             return self.previous_version;
@@ -60,6 +67,8 @@ mod tests {
     use super::*;
 
     ssz_tests!(Fork);
+
+    serde_tests!(Fork);
     cached_tree_hash_tests!(Fork);
 
     fn test_genesis(epoch: Epoch) {
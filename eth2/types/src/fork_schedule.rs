@@ -0,0 +1,259 @@
+use crate::{
+    serde_utils::{fork_from_hex_str, fork_to_hex_str},
+    ChainSpec, Epoch,
+};
+use parking_lot::RwLock;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// The fork version of the chain prior to any `ScheduledFork`.
+pub const GENESIS_FORK_VERSION: [u8; 4] = [0; 4];
+
+/// A hard fork, activated at the first slot of `epoch`.
+///
+/// At the fork `BeaconState.fork` is updated to `version`, and the `overrides` are applied to the
+/// `ChainSpec` for all subsequent epochs (see `ChainSpec::at_epoch`).
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledFork {
+    pub name: String,
+    pub epoch: Epoch,
+    #[serde(
+        deserialize_with = "fork_from_hex_str",
+        serialize_with = "fork_to_hex_str"
+    )]
+    pub version: [u8; 4],
+    #[serde(default)]
+    pub overrides: SpecOverrides,
+}
+
+/// `ChainSpec` constants which may be changed by a `ScheduledFork`.
+///
+/// Only constants which can safely change at an epoch boundary (i.e., those not determining the
+/// size or shape of the `BeaconState`) are included. `None` values are left unchanged.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpecOverrides {
+    /*
+     * Reward and penalty quotients
+     */
+    pub base_reward_factor: Option<u64>,
+    pub whistleblower_reward_quotient: Option<u64>,
+    pub proposer_reward_quotient: Option<u64>,
+    pub inactivity_penalty_quotient: Option<u64>,
+    pub min_slashing_penalty_quotient: Option<u64>,
+
+    /*
+     * Max operations per block
+     */
+    pub max_proposer_slashings: Option<u64>,
+    pub max_attester_slashings: Option<u64>,
+    pub max_attestations: Option<u64>,
+    pub max_deposits: Option<u64>,
+    pub max_voluntary_exits: Option<u64>,
+    pub max_transfers: Option<u64>,
+}
+
+impl SpecOverrides {
+    /// Returns `true` if no constants are overridden.
+    pub fn is_empty(&self) -> bool {
+        *self == SpecOverrides::default()
+    }
+
+    /// Replace the constants in `spec` with any overridden values.
+    pub fn apply(&self, spec: &mut ChainSpec) {
+        macro_rules! apply {
+            ($($field: ident),*) => {
+                $(
+                    if let Some(value) = self.$field {
+                        spec.$field = value;
+                    }
+                )*
+            };
+        }
+
+        apply!(
+            base_reward_factor,
+            whistleblower_reward_quotient,
+            proposer_reward_quotient,
+            inactivity_penalty_quotient,
+            min_slashing_penalty_quotient,
+            max_proposer_slashings,
+            max_attester_slashings,
+            max_attestations,
+            max_deposits,
+            max_voluntary_exits,
+            max_transfers
+        );
+    }
+}
+
+/// The constants which apply at some epoch, as returned by `ChainSpec::at_epoch`.
+pub enum SpecAtEpoch<'a> {
+    /// No fork with overrides is active, so the constants are those of the `ChainSpec` itself.
+    Unchanged(&'a ChainSpec),
+    /// The constants with the overrides of every active fork applied.
+    Resolved(Arc<ChainSpec>),
+}
+
+impl<'a> Deref for SpecAtEpoch<'a> {
+    type Target = ChainSpec;
+
+    fn deref(&self) -> &ChainSpec {
+        match self {
+            SpecAtEpoch::Unchanged(spec) => spec,
+            SpecAtEpoch::Resolved(spec) => spec,
+        }
+    }
+}
+
+/// A cache of the constants in effect from each `ScheduledFork` with overrides.
+///
+/// Resolving the overrides requires a clone of the `ChainSpec`, so it is done once per fork and
+/// shared by every later call to `ChainSpec::at_epoch`. The cache is only used whilst the
+/// `ChainSpec` holding it is equal to the one it was computed from, so modifying the `ChainSpec`
+/// (e.g., its `fork_schedule`) never yields stale constants.
+///
+/// The cache is ignored when comparing `ChainSpec`s, and not serialized.
+#[derive(Default)]
+pub struct ResolvedForks(RwLock<Option<Arc<Resolved>>>);
+
+struct Resolved {
+    /// The `ChainSpec` from which `specs` were computed.
+    base: ChainSpec,
+    /// The constants in effect from each fork with overrides, ordered by epoch.
+    specs: Vec<(Epoch, Arc<ChainSpec>)>,
+}
+
+impl ResolvedForks {
+    /// Returns the constants of `spec` at `epoch`, which must be at or after the epoch of some
+    /// fork with overrides.
+    pub fn at_epoch(&self, spec: &ChainSpec, epoch: Epoch) -> Arc<ChainSpec> {
+        let resolved = self.get_or_resolve(spec);
+
+        resolved
+            .specs
+            .iter()
+            .rev()
+            .find(|(fork_epoch, _)| *fork_epoch <= epoch)
+            .map(|(_, spec)| spec.clone())
+            .unwrap_or_else(|| Arc::new(resolved.base.clone()))
+    }
+
+    fn get_or_resolve(&self, spec: &ChainSpec) -> Arc<Resolved> {
+        if let Some(resolved) = self.0.read().as_ref() {
+            if resolved.base == *spec {
+                return resolved.clone();
+            }
+        }
+
+        let mut base = spec.clone();
+        base.resolved_forks = ResolvedForks::default();
+        let mut forks: Vec<&ScheduledFork> = base
+            .fork_schedule
+            .iter()
+            .filter(|fork| !fork.overrides.is_empty())
+            .collect();
+        forks.sort_by_key(|fork| fork.epoch);
+
+        let mut current = base.clone();
+        let specs = forks
+            .into_iter()
+            .map(|fork| {
+                fork.overrides.apply(&mut current);
+                (fork.epoch, Arc::new(current.clone()))
+            })
+            .collect();
+
+        let resolved = Arc::new(Resolved { base, specs });
+        *self.0.write() = Some(resolved.clone());
+        resolved
+    }
+}
+
+impl Clone for ResolvedForks {
+    fn clone(&self) -> Self {
+        ResolvedForks(RwLock::new(self.0.read().clone()))
+    }
+}
+
+impl PartialEq for ResolvedForks {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for ResolvedForks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ResolvedForks")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_only_replace_set_values() {
+        let mut spec = ChainSpec::mainnet();
+        let overrides = SpecOverrides {
+            max_transfers: Some(16),
+            ..SpecOverrides::default()
+        };
+        assert!(!overrides.is_empty());
+
+        overrides.apply(&mut spec);
+
+        let mut expected = ChainSpec::mainnet();
+        expected.max_transfers = 16;
+        assert_eq!(spec, expected);
+    }
+
+    fn spec_with_fork_at_epoch_4() -> ChainSpec {
+        let mut spec = ChainSpec::mainnet();
+        spec.fork_schedule = vec![ScheduledFork {
+            name: "toy".to_string(),
+            epoch: Epoch::new(4),
+            version: [0, 0, 0, 1],
+            overrides: SpecOverrides {
+                max_transfers: Some(16),
+                ..SpecOverrides::default()
+            },
+        }];
+        spec
+    }
+
+    #[test]
+    fn overrides_are_resolved_once_per_fork() {
+        let spec = spec_with_fork_at_epoch_4();
+
+        let first = spec.at_epoch(Epoch::new(4));
+        let second = spec.at_epoch(Epoch::new(5));
+        match (&first, &second) {
+            (SpecAtEpoch::Resolved(first), SpecAtEpoch::Resolved(second)) => {
+                assert!(Arc::ptr_eq(first, second))
+            }
+            _ => panic!("overrides should apply"),
+        }
+        assert_eq!(second.max_transfers, 16);
+
+        match spec.at_epoch(Epoch::new(3)) {
+            SpecAtEpoch::Unchanged(unchanged) => assert!(std::ptr::eq(unchanged, &spec)),
+            SpecAtEpoch::Resolved(_) => panic!("no overrides should apply"),
+        }
+    }
+
+    #[test]
+    fn resolved_overrides_follow_changes_to_the_spec() {
+        let mut spec = spec_with_fork_at_epoch_4();
+        assert_eq!(spec.at_epoch(Epoch::new(4)).max_attestations, 128);
+
+        spec.max_attestations = 1;
+        spec.fork_schedule[0].overrides.max_transfers = Some(8);
+
+        let at_fork = spec.at_epoch(Epoch::new(4));
+        assert_eq!(at_fork.max_attestations, 1);
+        assert_eq!(at_fork.max_transfers, 8);
+    }
+}
@@ -1,15 +1,16 @@
-use crate::{test_utils::TestRandom, AggregateSignature, AttestationData};
+use crate::serde_utils::quoted_u64_vec;
+use crate::{test_utils::TestRandom, AggregateSignature, AttestationData, EthSpec, VariableList};
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
-use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
+use tree_hash_derive::{SignedRoot, TreeHash};
 
 /// Details an attestation that can be slashable.
 ///
 /// To be included in an `AttesterSlashing`.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -19,33 +20,35 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
     Encode,
     Decode,
     TreeHash,
-    CachedTreeHash,
     TestRandom,
     SignedRoot,
 )]
-pub struct IndexedAttestation {
+#[serde(bound = "T: EthSpec")]
+pub struct IndexedAttestation<T: EthSpec> {
     /// Lists validator registry indices, not committee indices.
-    pub custody_bit_0_indices: Vec<u64>,
-    pub custody_bit_1_indices: Vec<u64>,
+    #[serde(with = "quoted_u64_vec")]
+    pub custody_bit_0_indices: VariableList<u64, T::MaxValidatorsPerCommittee>,
+    #[serde(with = "quoted_u64_vec")]
+    pub custody_bit_1_indices: VariableList<u64, T::MaxValidatorsPerCommittee>,
     pub data: AttestationData,
     #[signed_root(skip_hashing)]
     pub signature: AggregateSignature,
 }
 
-impl IndexedAttestation {
+impl<T: EthSpec> IndexedAttestation<T> {
     /// Check if ``attestation_data_1`` and ``attestation_data_2`` have the same target.
     ///
-    /// Spec v0.6.3
-    pub fn is_double_vote(&self, other: &IndexedAttestation) -> bool {
-        self.data.target_epoch == other.data.target_epoch && self.data != other.data
+    /// Spec v0.8.1
+    pub fn is_double_vote(&self, other: &Self) -> bool {
+        self.data.target.epoch == other.data.target.epoch && self.data != other.data
     }
 
     /// Check if ``attestation_data_1`` surrounds ``attestation_data_2``.
     ///
-    /// Spec v0.6.3
-    pub fn is_surround_vote(&self, other: &IndexedAttestation) -> bool {
-        self.data.source_epoch < other.data.source_epoch
-            && other.data.target_epoch < self.data.target_epoch
+    /// Spec v0.8.1
+    pub fn is_surround_vote(&self, other: &Self) -> bool {
+        self.data.source.epoch < other.data.source.epoch
+            && other.data.target.epoch < self.data.target.epoch
     }
 }
 
@@ -54,6 +57,9 @@ mod tests {
     use super::*;
     use crate::slot_epoch::Epoch;
     use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use crate::MainnetEthSpec;
+
+    pub type FoundationIndexedAttestation = IndexedAttestation<MainnetEthSpec>;
 
     #[test]
     pub fn test_is_double_vote_true() {
@@ -121,15 +127,19 @@ mod tests {
         );
     }
 
-    ssz_tests!(IndexedAttestation);
-    cached_tree_hash_tests!(IndexedAttestation);
+    ssz_tests!(FoundationIndexedAttestation);
+
+    serde_tests!(FoundationIndexedAttestation);
 
-    fn create_indexed_attestation(target_epoch: u64, source_epoch: u64) -> IndexedAttestation {
+    fn create_indexed_attestation(
+        target_epoch: u64,
+        source_epoch: u64,
+    ) -> FoundationIndexedAttestation {
         let mut rng = XorShiftRng::from_seed([42; 16]);
-        let mut indexed_vote = IndexedAttestation::random_for_test(&mut rng);
+        let mut indexed_vote = FoundationIndexedAttestation::random_for_test(&mut rng);
 
-        indexed_vote.data.source_epoch = Epoch::new(source_epoch);
-        indexed_vote.data.target_epoch = Epoch::new(target_epoch);
+        indexed_vote.data.source.epoch = Epoch::new(source_epoch);
+        indexed_vote.data.target.epoch = Epoch::new(target_epoch);
         indexed_vote
     }
 }
@@ -13,17 +13,22 @@ pub mod beacon_block_body;
 pub mod beacon_block_header;
 pub mod beacon_state;
 pub mod chain_spec;
+pub mod checkpoint;
+pub mod compact_committee;
 pub mod crosslink;
 pub mod crosslink_committee;
 pub mod deposit;
 pub mod deposit_data;
 pub mod eth1_data;
 pub mod fork;
+pub mod fork_schedule;
 pub mod free_attestation;
 pub mod historical_batch;
 pub mod indexed_attestation;
 pub mod pending_attestation;
 pub mod proposer_slashing;
+pub mod serde_utils;
+pub mod shuffling_cache;
 pub mod transfer;
 pub mod voluntary_exit;
 #[macro_use]
@@ -45,19 +50,27 @@ pub use crate::beacon_block::BeaconBlock;
 pub use crate::beacon_block_body::BeaconBlockBody;
 pub use crate::beacon_block_header::BeaconBlockHeader;
 pub use crate::beacon_state::{Error as BeaconStateError, *};
-pub use crate::chain_spec::{ChainSpec, Domain};
+pub use crate::chain_spec::{ChainSpec, Domain, YamlConfig};
+pub use crate::checkpoint::Checkpoint;
+pub use crate::compact_committee::CompactCommittee;
 pub use crate::crosslink::Crosslink;
 pub use crate::crosslink_committee::{CrosslinkCommittee, OwnedCrosslinkCommittee};
 pub use crate::deposit::Deposit;
 pub use crate::deposit_data::DepositData;
 pub use crate::eth1_data::Eth1Data;
 pub use crate::fork::Fork;
+pub use crate::fork_schedule::{
+    ResolvedForks, ScheduledFork, SpecAtEpoch, SpecOverrides, GENESIS_FORK_VERSION,
+};
 pub use crate::free_attestation::FreeAttestation;
 pub use crate::historical_batch::HistoricalBatch;
 pub use crate::indexed_attestation::IndexedAttestation;
 pub use crate::pending_attestation::PendingAttestation;
 pub use crate::proposer_slashing::ProposerSlashing;
 pub use crate::relative_epoch::{Error as RelativeEpochError, RelativeEpoch};
+pub use crate::shuffling_cache::{
+    Shuffling, ShufflingCache, ShufflingCacheHandle, ShufflingId, SHUFFLING_CACHE_SIZE,
+};
 pub use crate::slot_epoch::{Epoch, Slot};
 pub use crate::slot_height::SlotHeight;
 pub use crate::transfer::Transfer;
@@ -71,8 +84,6 @@ pub type CrosslinkCommittees = Vec<(Committee, u64)>;
 pub type Hash256 = H256;
 pub type Address = H160;
 pub type EthBalance = U256;
-pub type Bitfield = boolean_bitfield::BooleanBitfield;
-pub type BitfieldError = boolean_bitfield::Error;
 
 /// Maps a (slot, shard_id) to attestation_indices.
 pub type AttesterMap = HashMap<(u64, u64), Vec<usize>>;
@@ -81,4 +92,6 @@ pub type AttesterMap = HashMap<(u64, u64), Vec<usize>>;
 pub type ProposerMap = HashMap<u64, usize>;
 
 pub use bls::{AggregatePublicKey, AggregateSignature, Keypair, PublicKey, SecretKey, Signature};
+pub use cow_list::CowList;
 pub use fixed_len_vec::{typenum, typenum::Unsigned, FixedLenVec};
+pub use ssz_types::{BitList, BitVector, VariableList};
@@ -1,37 +1,34 @@
+use crate::serde_utils::quoted_u64;
 use crate::test_utils::TestRandom;
-use crate::{AttestationData, Bitfield};
+use crate::{AttestationData, BitList, EthSpec};
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
-use tree_hash_derive::{CachedTreeHash, TreeHash};
+use tree_hash_derive::TreeHash;
 
 /// An attestation that has been included in the state but not yet fully processed.
 ///
-/// Spec v0.6.3
-#[derive(
-    Debug,
-    Clone,
-    PartialEq,
-    Serialize,
-    Deserialize,
-    Encode,
-    Decode,
-    TreeHash,
-    CachedTreeHash,
-    TestRandom,
-)]
-pub struct PendingAttestation {
-    pub aggregation_bitfield: Bitfield,
+/// Spec v0.8.1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct PendingAttestation<T: EthSpec> {
+    pub aggregation_bits: BitList<T::MaxValidatorsPerCommittee>,
     pub data: AttestationData,
+    #[serde(with = "quoted_u64")]
     pub inclusion_delay: u64,
+    #[serde(with = "quoted_u64")]
     pub proposer_index: u64,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::MainnetEthSpec;
 
-    ssz_tests!(PendingAttestation);
-    cached_tree_hash_tests!(PendingAttestation);
+    pub type FoundationPendingAttestation = PendingAttestation<MainnetEthSpec>;
+
+    ssz_tests!(FoundationPendingAttestation);
+
+    serde_tests!(FoundationPendingAttestation);
 }
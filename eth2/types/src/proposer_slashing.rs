@@ -1,4 +1,5 @@
 use super::BeaconBlockHeader;
+use crate::serde_utils::quoted_u64;
 use crate::test_utils::TestRandom;
 
 use serde_derive::{Deserialize, Serialize};
@@ -8,7 +9,7 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
 
 /// Two conflicting proposals from the same proposer (validator).
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -22,6 +23,7 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
     TestRandom,
 )]
 pub struct ProposerSlashing {
+    #[serde(with = "quoted_u64")]
     pub proposer_index: u64,
     pub header_1: BeaconBlockHeader,
     pub header_2: BeaconBlockHeader,
@@ -32,5 +34,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(ProposerSlashing);
+
+    serde_tests!(ProposerSlashing);
     cached_tree_hash_tests!(ProposerSlashing);
 }
@@ -0,0 +1,292 @@
+//! Helpers for the canonical (spec-conformant) JSON and YAML representation of the consensus
+//! types:
+//!
+//! - Byte arrays are `0x`-prefixed hex strings.
+//! - Integers are quoted decimal strings (e.g., `"42"`), as a JSON number cannot safely hold a
+//!   `u64`. Unquoted integers are also accepted, as used by the YAML test vectors.
+//! - Bitfields, hashes, public keys and signatures are `0x`-prefixed hex strings of their SSZ
+//!   encoding (implemented on the types themselves).
+use serde::de::{self, Error, Visitor};
+use serde::{Deserialize, Deserializer, Serializer};
+use serde_hex::{encode, PrefixedHexVisitor};
+use std::fmt;
+
+pub const FORK_BYTES_LEN: usize = 4;
+pub const GRAFFITI_BYTES_LEN: usize = 32;
+
+pub fn u8_from_hex_str<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: String = Deserialize::deserialize(deserializer)?;
+
+    if !s.starts_with("0x") {
+        return Err(D::Error::custom(format!("{} is not 0x-prefixed hex", s)));
+    }
+
+    u8::from_str_radix(&s[2..], 16).map_err(D::Error::custom)
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // Serde requires the `byte` to be a ref.
+pub fn u8_to_hex_str<S>(byte: &u8, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&encode([*byte]))
+}
+
+pub fn fork_from_hex_str<'de, D>(deserializer: D) -> Result<[u8; FORK_BYTES_LEN], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut array = [0; FORK_BYTES_LEN];
+    array.copy_from_slice(&fixed_len_bytes_from_hex_str(deserializer, FORK_BYTES_LEN)?);
+    Ok(array)
+}
+
+pub fn fork_to_hex_str<S>(bytes: &[u8; FORK_BYTES_LEN], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&encode(bytes))
+}
+
+pub fn graffiti_from_hex_str<'de, D>(deserializer: D) -> Result<[u8; GRAFFITI_BYTES_LEN], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut array = [0; GRAFFITI_BYTES_LEN];
+    array.copy_from_slice(&fixed_len_bytes_from_hex_str(
+        deserializer,
+        GRAFFITI_BYTES_LEN,
+    )?);
+    Ok(array)
+}
+
+pub fn graffiti_to_hex_str<S>(
+    bytes: &[u8; GRAFFITI_BYTES_LEN],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&encode(bytes))
+}
+
+/// Deserializes a `0x`-prefixed hex string, which must decode to exactly `len` bytes.
+fn fixed_len_bytes_from_hex_str<'de, D>(deserializer: D, len: usize) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let bytes = deserializer.deserialize_str(PrefixedHexVisitor)?;
+
+    if bytes.len() == len {
+        Ok(bytes)
+    } else {
+        Err(D::Error::custom(format!(
+            "expected {} bytes, got {}",
+            len,
+            bytes.len()
+        )))
+    }
+}
+
+/// Visits a `u64` which is either a quoted decimal string (e.g., `"42"`) or an integer.
+pub struct QuotedU64Visitor;
+
+impl<'de> Visitor<'de> for QuotedU64Visitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a quoted or unquoted u64")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        Ok(value)
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        if value >= 0 {
+            Ok(value as u64)
+        } else {
+            Err(E::custom(format!("negative u64 ({})", value)))
+        }
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        value
+            .parse()
+            .map_err(|e| E::custom(format!("invalid u64 {:?} ({:?})", value, e)))
+    }
+}
+
+/// Serializes a `u64` as a quoted decimal string.
+///
+/// Use with `#[serde(with = "quoted_u64")]`.
+pub mod quoted_u64 {
+    use super::*;
+
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Serde requires the `value` to be a ref.
+    pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(QuotedU64Visitor)
+    }
+}
+
+/// Serializes a list of `u64` (e.g., a `Vec`, `VariableList`, `FixedLenVec` or `CowList`) as a list
+/// of quoted decimal strings.
+///
+/// Use with `#[serde(with = "quoted_u64_vec")]`.
+pub mod quoted_u64_vec {
+    use super::*;
+
+    struct QuotedU64(u64);
+
+    impl<'de> Deserialize<'de> for QuotedU64 {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            quoted_u64::deserialize(deserializer).map(QuotedU64)
+        }
+    }
+
+    pub fn serialize<S, T>(values: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        for<'a> &'a T: IntoIterator<Item = &'a u64>,
+    {
+        serializer.collect_seq(values.into_iter().map(u64::to_string))
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: From<Vec<u64>>,
+    {
+        let values: Vec<QuotedU64> = Deserialize::deserialize(deserializer)?;
+
+        Ok(values.into_iter().map(|v| v.0).collect::<Vec<_>>().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Quoted {
+        #[serde(with = "quoted_u64")]
+        value: u64,
+        #[serde(with = "quoted_u64_vec")]
+        values: Vec<u64>,
+    }
+
+    #[test]
+    fn quoted_u64_json() {
+        let quoted = Quoted {
+            value: u64::max_value(),
+            values: vec![0, 42],
+        };
+        let json = json!({
+            "value": "18446744073709551615",
+            "values": ["0", "42"],
+        });
+
+        assert_eq!(serde_json::to_value(&quoted).unwrap(), json);
+        assert_eq!(serde_json::from_value::<Quoted>(json).unwrap(), quoted);
+    }
+
+    #[test]
+    fn quoted_u64_accepts_unquoted() {
+        let yaml = "value: 1\nvalues: [2, \"3\"]";
+
+        assert_eq!(
+            serde_yaml::from_str::<Quoted>(yaml).unwrap(),
+            Quoted {
+                value: 1,
+                values: vec![2, 3],
+            }
+        );
+    }
+
+    #[test]
+    fn quoted_u64_rejects_invalid() {
+        assert!(serde_json::from_str::<Quoted>(r#"{"value": "-1", "values": []}"#).is_err());
+        assert!(serde_json::from_str::<Quoted>(r#"{"value": -1, "values": []}"#).is_err());
+        assert!(serde_json::from_str::<Quoted>(r#"{"value": "0x01", "values": []}"#).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fork {
+        #[serde(
+            deserialize_with = "fork_from_hex_str",
+            serialize_with = "fork_to_hex_str"
+        )]
+        version: [u8; FORK_BYTES_LEN],
+    }
+
+    #[test]
+    fn fork_hex() {
+        let fork = Fork {
+            version: [0, 1, 2, 255],
+        };
+        let json = json!({ "version": "0x000102ff" });
+
+        assert_eq!(serde_json::to_value(&fork).unwrap(), json);
+        assert_eq!(serde_json::from_value::<Fork>(json).unwrap(), fork);
+    }
+
+    #[test]
+    fn fork_hex_rejects_incorrect_length() {
+        assert!(serde_json::from_str::<Fork>(r#"{"version": "0x0001"}"#).is_err());
+        assert!(serde_json::from_str::<Fork>(r#"{"version": "0x0001020304"}"#).is_err());
+        assert!(serde_json::from_str::<Fork>(r#"{"version": "00010203"}"#).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Byte {
+        #[serde(deserialize_with = "u8_from_hex_str", serialize_with = "u8_to_hex_str")]
+        value: u8,
+    }
+
+    #[test]
+    fn u8_hex() {
+        let byte = Byte { value: 255 };
+        let json = json!({ "value": "0xff" });
+
+        assert_eq!(serde_json::to_value(&byte).unwrap(), json);
+        assert_eq!(serde_json::from_value::<Byte>(json).unwrap(), byte);
+    }
+
+    #[test]
+    fn u8_hex_rejects_invalid() {
+        for value in &["", "0", "ff", "0x", "0x100", "é", "0xé", "éé"] {
+            assert!(
+                serde_json::from_value::<Byte>(json!({ "value": value })).is_err(),
+                "should reject {:?}",
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::*;
+use core::num::NonZeroUsize;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use swap_or_not_shuffle::shuffle_list;
+
+/// The default number of shufflings held by a chain's `ShufflingCache`.
+///
+/// Each shuffling holds two `usize` per validator, so this is a few hundred megabytes at worst
+/// for a registry of a million validators.
+pub const SHUFFLING_CACHE_SIZE: usize = 16;
+
+/// Uniquely identifies the shuffling of the active validators at some epoch.
+///
+/// The seed commits to the active validator indices (via the active index root), so two states
+/// with equal ids have identical shufflings, even if they are on different forks. The
+/// `shuffle_round_count` is the only `ChainSpec` constant used to compute a shuffling from its
+/// seed, so states of chains with different specs never share a shuffling.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ShufflingId {
+    pub epoch: Epoch,
+    pub seed: Hash256,
+    pub active_validator_count: usize,
+    pub shuffle_round_count: u8,
+}
+
+/// The active validator indices for some epoch, in shuffled order.
+#[derive(Debug, Default, PartialEq)]
+pub struct Shuffling {
+    shuffling: Vec<usize>,
+    /// Maps a validator index to its position in `shuffling`, plus one.
+    positions: Vec<Option<NonZeroUsize>>,
+}
+
+impl Shuffling {
+    /// Shuffles `active_validator_indices` with `seed`.
+    ///
+    /// The `validator_count` must be the length of the validator registry.
+    ///
+    /// Spec v0.8.1
+    pub fn new(
+        active_validator_indices: Vec<usize>,
+        validator_count: usize,
+        seed: &[u8],
+        spec: &ChainSpec,
+    ) -> Result<Self, BeaconStateError> {
+        // The use of `NonZeroUsize` reduces the maximum number of possible validators by one.
+        if validator_count > usize::max_value() - 1 {
+            return Err(BeaconStateError::TooManyValidators);
+        }
+
+        let shuffling = shuffle_list(
+            active_validator_indices,
+            spec.shuffle_round_count,
+            seed,
+            false,
+        )
+        .ok_or_else(|| BeaconStateError::UnableToShuffle)?;
+
+        let mut positions = vec![None; validator_count];
+        for (i, v) in shuffling.iter().enumerate() {
+            positions[*v] = NonZeroUsize::new(i + 1);
+        }
+
+        Ok(Self {
+            shuffling,
+            positions,
+        })
+    }
+
+    /// Returns the shuffled active validator indices.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.shuffling
+    }
+
+    pub fn len(&self) -> usize {
+        self.shuffling.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shuffling.is_empty()
+    }
+
+    /// Returns the index of some validator in the shuffling, if it is active.
+    pub fn position(&self, validator_index: usize) -> Option<usize> {
+        self.positions
+            .get(validator_index)?
+            .and_then(|p| Some(p.get() - 1))
+    }
+}
+
+/// Holds recently computed shufflings so they may be shared between `BeaconState`s (e.g., clones
+/// of a state, states on different forks or states freshly loaded from the database).
+///
+/// A cache is owned by a `BeaconChain` and attached to its states via a `ShufflingCacheHandle`.
+///
+/// When full, the shuffling with the lowest epoch is evicted.
+pub struct ShufflingCache {
+    capacity: usize,
+    shufflings: RwLock<HashMap<ShufflingId, Arc<Shuffling>>>,
+}
+
+impl ShufflingCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            shufflings: RwLock::new(HashMap::with_capacity(capacity)),
+        }
+    }
+
+    pub fn get(&self, id: &ShufflingId) -> Option<Arc<Shuffling>> {
+        self.shufflings.read().get(id).cloned()
+    }
+
+    /// Adds `shuffling` to the cache, evicting the lowest-epoch shuffling if the cache is full.
+    ///
+    /// If a shuffling with the same `id` is already present, it is returned instead.
+    pub fn insert(&self, id: ShufflingId, shuffling: Arc<Shuffling>) -> Arc<Shuffling> {
+        let mut shufflings = self.shufflings.write();
+
+        if let Some(existing) = shufflings.get(&id) {
+            return existing.clone();
+        }
+
+        if shufflings.len() >= self.capacity {
+            let lowest = shufflings.keys().min_by_key(|id| id.epoch).cloned();
+            if let Some(lowest) = lowest {
+                shufflings.remove(&lowest);
+            }
+        }
+
+        if self.capacity > 0 {
+            shufflings.insert(id, shuffling.clone());
+        }
+
+        shuffling
+    }
+
+    /// Returns the shuffling for `id`, computing it with `f` (without holding any lock) and adding
+    /// it to the cache if it is not present.
+    pub fn get_or_try_insert_with<F, E>(&self, id: ShufflingId, f: F) -> Result<Arc<Shuffling>, E>
+    where
+        F: FnOnce() -> Result<Shuffling, E>,
+    {
+        if let Some(shuffling) = self.get(&id) {
+            return Ok(shuffling);
+        }
+
+        Ok(self.insert(id, Arc::new(f()?)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.shufflings.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The `ShufflingCache` used when building the committee caches of a `BeaconState`, if any.
+///
+/// States without a cache compute each shuffling themselves. The handle is ignored when comparing
+/// states.
+#[derive(Clone, Default)]
+pub struct ShufflingCacheHandle(Option<Arc<ShufflingCache>>);
+
+impl ShufflingCacheHandle {
+    pub fn new(cache: Arc<ShufflingCache>) -> Self {
+        ShufflingCacheHandle(Some(cache))
+    }
+
+    pub fn get(&self) -> Option<&ShufflingCache> {
+        self.0.as_ref().map(Arc::as_ref)
+    }
+}
+
+impl PartialEq for ShufflingCacheHandle {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for ShufflingCacheHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ShufflingCacheHandle")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(epoch: u64) -> ShufflingId {
+        ShufflingId {
+            epoch: Epoch::new(epoch),
+            seed: Hash256::from(epoch),
+            active_validator_count: 8,
+            shuffle_round_count: 10,
+        }
+    }
+
+    fn shuffling(epoch: u64) -> Shuffling {
+        let spec = ChainSpec::minimal();
+        Shuffling::new((0..8).collect(), 8, &Hash256::from(epoch)[..], &spec).unwrap()
+    }
+
+    #[test]
+    fn positions_are_accurate() {
+        let shuffling = shuffling(42);
+
+        assert_eq!(shuffling.len(), 8);
+        for (i, v) in shuffling.as_slice().iter().enumerate() {
+            assert_eq!(shuffling.position(*v), Some(i));
+        }
+        assert_eq!(shuffling.position(8), None);
+    }
+
+    #[test]
+    fn computes_once() {
+        let cache = ShufflingCache::new(4);
+
+        let a = cache
+            .get_or_try_insert_with(id(1), || Ok::<_, ()>(shuffling(1)))
+            .unwrap();
+        let b = cache
+            .get_or_try_insert_with(id(1), || -> Result<Shuffling, ()> {
+                panic!("shuffling should be cached")
+            })
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let cache = ShufflingCache::new(4);
+
+        assert_eq!(cache.get_or_try_insert_with(id(1), || Err(())), Err(()));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn evicts_lowest_epoch() {
+        let cache = ShufflingCache::new(2);
+
+        cache.insert(id(2), Arc::new(shuffling(2)));
+        cache.insert(id(1), Arc::new(shuffling(1)));
+        cache.insert(id(3), Arc::new(shuffling(3)));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&id(1)).is_none());
+        assert!(cache.get(&id(2)).is_some());
+        assert!(cache.get(&id(3)).is_some());
+    }
+}
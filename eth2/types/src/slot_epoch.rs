@@ -4,7 +4,8 @@
 //! `Slot` and `Epoch` have implementations which permit conversion, comparison and math operations
 //! between each and `u64`, however specifically not between each other.
 //!
-//! All math operations on `Slot` and `Epoch` are saturating, they never wrap.
+//! All math operations on `Slot` and `Epoch` are saturating, they never wrap. The `SafeArith`
+//! operations are also available, for when saturation would hide an error.
 //!
 //! It would be easy to define `PartialOrd` and other traits generically across all types which
 //! implement `Into<u64>`, however this would allow operations between `Slots` and `Epochs` which
@@ -13,7 +14,7 @@
 use crate::slot_height::SlotHeight;
 use crate::test_utils::TestRandom;
 use rand::RngCore;
-use serde_derive::{Deserialize, Serialize};
+use safe_arith::SafeArith;
 use slog;
 use ssz::{ssz_encode, Decode, DecodeError, Encode};
 use std::cmp::{Ord, Ordering};
@@ -22,15 +23,16 @@ use std::hash::{Hash, Hasher};
 use std::iter::Iterator;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, Sub, SubAssign};
 
-#[derive(Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
-#[serde(transparent)]
+#[derive(Eq, Debug, Clone, Copy, Default)]
 pub struct Slot(u64);
 
-#[derive(Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(Eq, Debug, Clone, Copy, Default)]
 pub struct Epoch(u64);
 
 impl_common!(Slot);
 impl_common!(Epoch);
+impl_serde!(Slot);
+impl_serde!(Epoch);
 
 impl Slot {
     pub fn new(slot: u64) -> Slot {
@@ -135,6 +135,32 @@ macro_rules! impl_math_between {
     };
 }
 
+macro_rules! impl_safe_arith {
+    ($main: ident, $other: ident) => {
+        impl SafeArith<$other> for $main {
+            fn safe_add(&self, other: $other) -> safe_arith::Result<$main> {
+                self.0.safe_add(other.into()).map($main::from)
+            }
+
+            fn safe_sub(&self, other: $other) -> safe_arith::Result<$main> {
+                self.0.safe_sub(other.into()).map($main::from)
+            }
+
+            fn safe_mul(&self, other: $other) -> safe_arith::Result<$main> {
+                self.0.safe_mul(other.into()).map($main::from)
+            }
+
+            fn safe_div(&self, other: $other) -> safe_arith::Result<$main> {
+                self.0.safe_div(other.into()).map($main::from)
+            }
+
+            fn safe_rem(&self, other: $other) -> safe_arith::Result<$main> {
+                self.0.safe_rem(other.into()).map($main::from)
+            }
+        }
+    };
+}
+
 macro_rules! impl_math {
     ($type: ident) => {
         impl $type {
@@ -266,6 +292,23 @@ macro_rules! impl_ssz {
     };
 }
 
+macro_rules! impl_serde {
+    ($type: ident) => {
+        /// Serializes as a quoted decimal string, see `crate::serde_utils`.
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                crate::serde_utils::quoted_u64::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                crate::serde_utils::quoted_u64::deserialize(deserializer).map($type)
+            }
+        }
+    };
+}
+
 macro_rules! impl_hash {
     ($type: ident) => {
         // Implemented to stop clippy lint:
@@ -284,6 +327,8 @@ macro_rules! impl_common {
         impl_from_into_usize!($type);
         impl_math_between!($type, $type);
         impl_math_between!($type, u64);
+        impl_safe_arith!($type, $type);
+        impl_safe_arith!($type, u64);
         impl_math!($type);
         impl_display!($type);
         impl_ssz!($type);
@@ -475,6 +520,39 @@ macro_rules! math_between_tests {
             assert_rem(10, 100, 10);
             assert_rem(302042, 3293, 2379);
         }
+
+        #[test]
+        fn safe_arith() {
+            let other = |n: u64| -> $other { $type(n).into() };
+
+            assert_eq!($type(1).safe_add(other(2)), Ok($type(3)));
+            assert_eq!($type(3).safe_sub(other(2)), Ok($type(1)));
+            assert_eq!($type(3).safe_mul(other(2)), Ok($type(6)));
+            assert_eq!($type(7).safe_div(other(2)), Ok($type(3)));
+            assert_eq!($type(7).safe_rem(other(2)), Ok($type(1)));
+
+            // Unlike the operators, safe arithmetic does not saturate.
+            assert_eq!(
+                $type(u64::max_value()).safe_add(other(1)),
+                Err(safe_arith::ArithError::Overflow)
+            );
+            assert_eq!(
+                $type(0).safe_sub(other(1)),
+                Err(safe_arith::ArithError::Overflow)
+            );
+            assert_eq!(
+                $type(u64::max_value()).safe_mul(other(2)),
+                Err(safe_arith::ArithError::Overflow)
+            );
+            assert_eq!(
+                $type(1).safe_div(other(0)),
+                Err(safe_arith::ArithError::DivisionByZero)
+            );
+            assert_eq!(
+                $type(1).safe_rem(other(0)),
+                Err(safe_arith::ArithError::DivisionByZero)
+            );
+        }
     };
 }
 
@@ -581,6 +659,14 @@ macro_rules! all_tests {
         ssz_tests!($type);
         cached_tree_hash_tests!($type);
 
+        #[test]
+        pub fn serde_quoted() {
+            let json = serde_json::to_string(&$type(42)).unwrap();
+            assert_eq!(json, "\"42\"");
+            assert_eq!(serde_json::from_str::<$type>(&json).unwrap(), $type(42));
+            assert_eq!(serde_yaml::from_str::<$type>("42").unwrap(), $type(42));
+        }
+
         mod u64_tests {
             use super::*;
 
@@ -2,7 +2,7 @@ use crate::slot_epoch::{Epoch, Slot};
 use crate::test_utils::TestRandom;
 
 use rand::RngCore;
-use serde_derive::Serialize;
+use safe_arith::SafeArith;
 use ssz::{ssz_encode, Decode, DecodeError, Encode};
 use std::cmp::{Ord, Ordering};
 use std::fmt;
@@ -10,10 +10,11 @@ use std::hash::{Hash, Hasher};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, Sub, SubAssign};
 
 /// Beacon block height, effectively `Slot/GENESIS_START_BLOCK`.
-#[derive(Eq, Debug, Clone, Copy, Default, Serialize)]
+#[derive(Eq, Debug, Clone, Copy, Default)]
 pub struct SlotHeight(u64);
 
 impl_common!(SlotHeight);
+impl_serde!(SlotHeight);
 
 impl SlotHeight {
     pub fn new(slot: u64) -> SlotHeight {
@@ -5,14 +5,14 @@ use tree_hash::TreeHash;
 /// Builds an attestation to be used for testing purposes.
 ///
 /// This struct should **never be used for production purposes.**
-pub struct TestingAttestationBuilder {
+pub struct TestingAttestationBuilder<T: EthSpec> {
     committee: Vec<usize>,
-    attestation: Attestation,
+    attestation: Attestation<T>,
 }
 
-impl TestingAttestationBuilder {
+impl<T: EthSpec> TestingAttestationBuilder<T> {
     /// Create a new attestation builder.
-    pub fn new<T: EthSpec>(
+    pub fn new(
         state: &BeaconState<T>,
         committee: &[usize],
         slot: Slot,
@@ -21,18 +21,15 @@ impl TestingAttestationBuilder {
     ) -> Self {
         let data_builder = TestingAttestationDataBuilder::new(state, shard, slot, spec);
 
-        let mut aggregation_bitfield = Bitfield::new();
-        let mut custody_bitfield = Bitfield::new();
-
-        for (i, _) in committee.iter().enumerate() {
-            custody_bitfield.set(i, false);
-            aggregation_bitfield.set(i, false);
-        }
+        let aggregation_bits =
+            BitList::with_capacity(committee.len()).expect("committee should fit in a bitfield");
+        let custody_bits =
+            BitList::with_capacity(committee.len()).expect("committee should fit in a bitfield");
 
         let attestation = Attestation {
-            aggregation_bitfield,
+            aggregation_bits,
             data: data_builder.build(),
-            custody_bitfield,
+            custody_bits,
             signature: AggregateSignature::new(),
         };
 
@@ -67,8 +64,9 @@ impl TestingAttestationBuilder {
                 .expect("Signing validator not in attestation committee");
 
             self.attestation
-                .aggregation_bitfield
-                .set(committee_index, true);
+                .aggregation_bits
+                .set(committee_index, true)
+                .expect("committee index should be within the bitfield");
 
             let message = AttestationDataAndCustodyBit {
                 data: self.attestation.data.clone(),
@@ -77,7 +75,7 @@ impl TestingAttestationBuilder {
             .tree_hash_root();
 
             let domain = spec.get_domain(
-                self.attestation.data.target_epoch,
+                self.attestation.data.target.epoch,
                 Domain::Attestation,
                 fork,
             );
@@ -88,7 +86,7 @@ impl TestingAttestationBuilder {
     }
 
     /// Consume the builder and return the attestation.
-    pub fn build(self) -> Attestation {
+    pub fn build(self) -> Attestation<T> {
         self.attestation
     }
 }
@@ -100,7 +100,7 @@ impl<T: EthSpec> TestingBeaconStateBuilder<T> {
             "Building {} Validator objects from keypairs...",
             validator_count
         );
-        let validators = keypairs
+        let validators: Vec<Validator> = keypairs
             .par_iter()
             .map(|keypair| {
                 let withdrawal_credentials = Hash256::from_slice(&get_withdrawal_credentials(
@@ -111,13 +111,13 @@ impl<T: EthSpec> TestingBeaconStateBuilder<T> {
                 Validator {
                     pubkey: keypair.pk.clone(),
                     withdrawal_credentials,
+                    effective_balance: starting_balance,
+                    slashed: false,
                     // All validators start active.
                     activation_eligibility_epoch: T::genesis_epoch(),
                     activation_epoch: T::genesis_epoch(),
                     exit_epoch: spec.far_future_epoch,
                     withdrawable_epoch: spec.far_future_epoch,
-                    slashed: false,
-                    effective_balance: starting_balance,
                 }
             })
             .collect();
@@ -135,8 +135,8 @@ impl<T: EthSpec> TestingBeaconStateBuilder<T> {
         let balances = vec![starting_balance; validator_count];
 
         debug!("Importing {} existing validators...", validator_count);
-        state.validator_registry = validators;
-        state.balances = balances;
+        state.validators = validators.into();
+        state.balances = balances.into();
 
         debug!("BeaconState initialized.");
 
@@ -177,11 +177,11 @@ impl<T: EthSpec> TestingBeaconStateBuilder<T> {
 
         // NOTE: we could update the latest start shard here
 
-        state.previous_justified_epoch = epoch - 3;
-        state.current_justified_epoch = epoch - 2;
-        state.justification_bitfield = u64::max_value();
+        state.previous_justified_checkpoint.epoch = epoch - 3;
+        state.current_justified_checkpoint.epoch = epoch - 2;
+        state.justification_bits = BitVector::from_bytes(vec![0b0000_1111]).unwrap();
 
-        state.finalized_epoch = epoch - 3;
+        state.finalized_checkpoint.epoch = epoch - 3;
     }
 
     /// Creates a full set of attestations for the `BeaconState`. Each attestation has full
@@ -228,10 +228,10 @@ impl<T: EthSpec> TestingBeaconStateBuilder<T> {
                 builder.add_committee_participation(signers);
                 let attestation = builder.build();
 
-                if attestation.data.target_epoch < state.current_epoch() {
-                    state.previous_epoch_attestations.push(attestation)
+                if attestation.data.target.epoch < state.current_epoch() {
+                    state.previous_epoch_attestations.push(attestation).unwrap()
                 } else {
-                    state.current_epoch_attestations.push(attestation)
+                    state.current_epoch_attestations.push(attestation).unwrap()
                 }
             }
         }
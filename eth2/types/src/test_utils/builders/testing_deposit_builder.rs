@@ -13,7 +13,6 @@ impl TestingDepositBuilder {
     pub fn new(pubkey: PublicKey, amount: u64) -> Self {
         let deposit = Deposit {
             proof: vec![].into(),
-            index: 0,
             data: DepositData {
                 pubkey,
                 withdrawal_credentials: Hash256::zero(),
@@ -25,17 +24,12 @@ impl TestingDepositBuilder {
         Self { deposit }
     }
 
-    /// Set the `deposit.index` value.
-    pub fn set_index(&mut self, index: u64) {
-        self.deposit.index = index;
-    }
-
     /// Signs the deposit, also setting the following values:
     ///
     /// - `pubkey` to the signing pubkey.
     /// - `withdrawal_credentials` to the signing pubkey.
     /// - `proof_of_possesssion`
-    pub fn sign(&mut self, keypair: &Keypair, epoch: Epoch, fork: &Fork, spec: &ChainSpec) {
+    pub fn sign(&mut self, keypair: &Keypair, spec: &ChainSpec) {
         let withdrawal_credentials = Hash256::from_slice(
             &get_withdrawal_credentials(&keypair.pk, spec.bls_withdrawal_prefix_byte)[..],
         );
@@ -43,10 +37,7 @@ impl TestingDepositBuilder {
         self.deposit.data.pubkey = keypair.pk.clone();
         self.deposit.data.withdrawal_credentials = withdrawal_credentials;
 
-        self.deposit.data.signature =
-            self.deposit
-                .data
-                .create_signature(&keypair.sk, epoch, fork, spec);
+        self.deposit.data.signature = self.deposit.data.create_signature(&keypair.sk, spec);
     }
 
     /// Builds the deposit, consuming the builder.
@@ -4,23 +4,18 @@ use crate::*;
 /// Builds an `AttesterSlashing` to be used for testing purposes.
 ///
 /// This struct should **never be used for production purposes.**
-pub struct TestingPendingAttestationBuilder {
-    pending_attestation: PendingAttestation,
+pub struct TestingPendingAttestationBuilder<T: EthSpec> {
+    pending_attestation: PendingAttestation<T>,
 }
 
-impl TestingPendingAttestationBuilder {
+impl<T: EthSpec> TestingPendingAttestationBuilder<T> {
     /// Create a new valid* `PendingAttestation` for the given parameters.
     ///
     /// The `inclusion_delay` will be set to `MIN_ATTESTATION_INCLUSION_DELAY`.
     ///
     /// * The aggregation and custody bitfields will all be empty, they need to be set with
     /// `Self::add_committee_participation`.
-    pub fn new<T: EthSpec>(
-        state: &BeaconState<T>,
-        shard: u64,
-        slot: Slot,
-        spec: &ChainSpec,
-    ) -> Self {
+    pub fn new(state: &BeaconState<T>, shard: u64, slot: Slot, spec: &ChainSpec) -> Self {
         let data_builder = TestingAttestationDataBuilder::new(state, shard, slot, spec);
 
         let relative_epoch =
@@ -31,7 +26,7 @@ impl TestingPendingAttestationBuilder {
             .unwrap() as u64;
 
         let pending_attestation = PendingAttestation {
-            aggregation_bitfield: Bitfield::new(),
+            aggregation_bits: BitList::with_capacity(0).expect("empty bitfield is valid"),
             data: data_builder.build(),
             inclusion_delay: spec.min_attestation_inclusion_delay,
             proposer_index,
@@ -47,17 +42,20 @@ impl TestingPendingAttestationBuilder {
     /// The `PendingAttestation` will appear to be signed by each committee member who's value in
     /// `signers` is true.
     pub fn add_committee_participation(&mut self, signers: Vec<bool>) {
-        let mut aggregation_bitfield = Bitfield::new();
+        let mut aggregation_bits =
+            BitList::with_capacity(signers.len()).expect("committee should fit in a bitfield");
 
         for (i, signed) in signers.iter().enumerate() {
-            aggregation_bitfield.set(i, *signed);
+            aggregation_bits
+                .set(i, *signed)
+                .expect("signer index should be within the bitfield");
         }
 
-        self.pending_attestation.aggregation_bitfield = aggregation_bitfield;
+        self.pending_attestation.aggregation_bits = aggregation_bits;
     }
 
     /// Returns the `PendingAttestation`, consuming the builder.
-    pub fn build(self) -> PendingAttestation {
+    pub fn build(self) -> PendingAttestation<T> {
         self.pending_attestation
     }
 }
@@ -14,6 +14,10 @@ macro_rules! ssz_tests {
             let decoded = $type::from_ssz_bytes(&bytes).unwrap();
 
             assert_eq!(original, decoded);
+
+            let streamed = $type::from_ssz_reader(&mut &bytes[..], bytes.len()).unwrap();
+
+            assert_eq!(original, streamed);
         }
 
         #[test]
@@ -33,6 +37,45 @@ macro_rules! ssz_tests {
     };
 }
 
+#[cfg(test)]
+#[macro_export]
+macro_rules! serde_tests {
+    ($type: ident) => {
+        #[test]
+        pub fn test_serde_json_round_trip() {
+            use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+
+            let mut rng = XorShiftRng::from_seed([42; 16]);
+            let original = $type::random_for_test(&mut rng);
+
+            let json = serde_json::to_string(&original).unwrap();
+            let decoded: $type = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(original, decoded);
+        }
+
+        #[test]
+        pub fn test_serde_json_has_no_numbers() {
+            use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+            use serde_json::Value;
+
+            fn assert_no_numbers(value: &Value) {
+                match value {
+                    Value::Number(n) => panic!("Unquoted number in JSON: {}", n),
+                    Value::Array(values) => values.iter().for_each(assert_no_numbers),
+                    Value::Object(map) => map.values().for_each(assert_no_numbers),
+                    _ => (),
+                }
+            }
+
+            let mut rng = XorShiftRng::from_seed([42; 16]);
+            let original = $type::random_for_test(&mut rng);
+
+            assert_no_numbers(&serde_json::to_value(&original).unwrap());
+        }
+    };
+}
+
 #[cfg(test)]
 #[macro_export]
 macro_rules! cached_tree_hash_tests {
@@ -53,6 +53,32 @@ where
     }
 }
 
+impl<U, N: Unsigned> TestRandom for CowList<U, N>
+where
+    U: TestRandom,
+{
+    fn random_for_test(rng: &mut impl RngCore) -> Self {
+        Vec::random_for_test(rng).into()
+    }
+}
+
+impl<U, N: Unsigned> TestRandom for VariableList<U, N>
+where
+    U: TestRandom,
+{
+    fn random_for_test(rng: &mut impl RngCore) -> Self {
+        let mut output = vec![];
+
+        if N::to_usize() != 0 {
+            for _ in 0..(usize::random_for_test(rng) % std::cmp::min(4, N::to_usize())) {
+                output.push(<U>::random_for_test(rng));
+            }
+        }
+
+        output.into()
+    }
+}
+
 impl<T, N: Unsigned> TestRandom for FixedLenVec<T, N>
 where
     T: TestRandom + Default,
@@ -60,8 +86,10 @@ where
     fn random_for_test(rng: &mut impl RngCore) -> Self {
         let mut output = vec![];
 
-        for _ in 0..(usize::random_for_test(rng) % std::cmp::min(4, N::to_usize())) {
-            output.push(<T>::random_for_test(rng));
+        if N::to_usize() != 0 {
+            for _ in 0..(usize::random_for_test(rng) % std::cmp::min(4, N::to_usize())) {
+                output.push(<T>::random_for_test(rng));
+            }
         }
 
         output.into()
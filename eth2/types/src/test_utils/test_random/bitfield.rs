@@ -1,10 +1,27 @@
 use super::*;
-use crate::Bitfield;
+use crate::{BitList, BitVector};
 
-impl TestRandom for Bitfield {
+impl<N: Unsigned + Clone> TestRandom for BitList<N> {
     fn random_for_test(rng: &mut impl RngCore) -> Self {
-        let mut raw_bytes = vec![0; 32];
-        rng.fill_bytes(&mut raw_bytes);
-        Bitfield::from_bytes(&raw_bytes)
+        let len = usize::random_for_test(rng) % (N::to_usize() + 1);
+        let mut bitfield = Self::with_capacity(len).expect("len is within the max length");
+        for i in 0..len {
+            bitfield
+                .set(i, bool::random_for_test(rng))
+                .expect("bit is within the length");
+        }
+        bitfield
+    }
+}
+
+impl<N: Unsigned + Clone> TestRandom for BitVector<N> {
+    fn random_for_test(rng: &mut impl RngCore) -> Self {
+        let mut bitfield = Self::new();
+        for i in 0..N::to_usize() {
+            bitfield
+                .set(i, bool::random_for_test(rng))
+                .expect("bit is within the fixed length");
+        }
+        bitfield
     }
 }
@@ -1,4 +1,5 @@
 use super::Slot;
+use crate::serde_utils::quoted_u64;
 use crate::test_utils::TestRandom;
 use bls::{PublicKey, Signature};
 use derivative::Derivative;
@@ -11,7 +12,7 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 
 /// The data submitted to the deposit contract.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     Clone,
@@ -27,9 +28,13 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 )]
 #[derivative(PartialEq, Eq, Hash)]
 pub struct Transfer {
+    #[serde(with = "quoted_u64")]
     pub sender: u64,
+    #[serde(with = "quoted_u64")]
     pub recipient: u64,
+    #[serde(with = "quoted_u64")]
     pub amount: u64,
+    #[serde(with = "quoted_u64")]
     pub fee: u64,
     pub slot: Slot,
     pub pubkey: PublicKey,
@@ -43,5 +48,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(Transfer);
+
+    serde_tests!(Transfer);
     cached_tree_hash_tests!(Transfer);
 }
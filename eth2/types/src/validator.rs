@@ -1,3 +1,4 @@
+use crate::serde_utils::quoted_u64;
 use crate::{test_utils::TestRandom, Epoch, Hash256, PublicKey};
 
 use serde_derive::{Deserialize, Serialize};
@@ -7,7 +8,7 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
 
 /// Information about a `BeaconChain` validator.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     Clone,
@@ -23,12 +24,13 @@ use tree_hash_derive::{CachedTreeHash, TreeHash};
 pub struct Validator {
     pub pubkey: PublicKey,
     pub withdrawal_credentials: Hash256,
+    #[serde(with = "quoted_u64")]
+    pub effective_balance: u64,
+    pub slashed: bool,
     pub activation_eligibility_epoch: Epoch,
     pub activation_epoch: Epoch,
     pub exit_epoch: Epoch,
     pub withdrawable_epoch: Epoch,
-    pub slashed: bool,
-    pub effective_balance: u64,
 }
 
 impl Validator {
@@ -59,12 +61,12 @@ impl Default for Validator {
         Self {
             pubkey: PublicKey::default(),
             withdrawal_credentials: Hash256::default(),
+            effective_balance: std::u64::MAX,
+            slashed: false,
             activation_eligibility_epoch: Epoch::from(std::u64::MAX),
             activation_epoch: Epoch::from(std::u64::MAX),
             exit_epoch: Epoch::from(std::u64::MAX),
             withdrawable_epoch: Epoch::from(std::u64::MAX),
-            slashed: false,
-            effective_balance: std::u64::MAX,
         }
     }
 }
@@ -128,5 +130,7 @@ mod tests {
     }
 
     ssz_tests!(Validator);
+
+    serde_tests!(Validator);
     cached_tree_hash_tests!(Validator);
 }
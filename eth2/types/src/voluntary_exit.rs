@@ -1,3 +1,4 @@
+use crate::serde_utils::quoted_u64;
 use crate::{test_utils::TestRandom, Epoch};
 use bls::Signature;
 
@@ -9,7 +10,7 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 
 /// An exit voluntarily submitted a validator who wishes to withdraw.
 ///
-/// Spec v0.6.3
+/// Spec v0.8.1
 #[derive(
     Debug,
     PartialEq,
@@ -25,6 +26,7 @@ use tree_hash_derive::{CachedTreeHash, SignedRoot, TreeHash};
 )]
 pub struct VoluntaryExit {
     pub epoch: Epoch,
+    #[serde(with = "quoted_u64")]
     pub validator_index: u64,
     #[signed_root(skip_hashing)]
     pub signature: Signature,
@@ -35,5 +37,7 @@ mod tests {
     use super::*;
 
     ssz_tests!(VoluntaryExit);
+
+    serde_tests!(VoluntaryExit);
     cached_tree_hash_tests!(VoluntaryExit);
 }
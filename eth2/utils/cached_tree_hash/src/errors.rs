@@ -16,4 +16,5 @@ pub enum Error {
     NoBytesForChunk(usize),
     NoSchemaForIndex(usize),
     NotLeafNode(usize),
+    TooManyLeaves { leaves: usize, depth: usize },
 }
@@ -40,6 +40,7 @@ use tree_hash::{TreeHash, TreeHashType, BYTES_PER_CHUNK, HASHSIZE};
 mod btree_overlay;
 mod errors;
 mod impls;
+mod merkle_cache;
 pub mod merkleize;
 mod resize;
 mod tree_hash_cache;
@@ -47,6 +48,7 @@ mod tree_hash_cache;
 pub use btree_overlay::{BTreeOverlay, BTreeSchema};
 pub use errors::Error;
 pub use impls::vec;
+pub use merkle_cache::MerkleCache;
 pub use tree_hash_cache::TreeHashCache;
 
 pub trait CachedTreeHash: TreeHash {
@@ -0,0 +1,180 @@
+use super::Error;
+use ethereum_types::H256 as Hash256;
+use hashing::hash;
+use tree_hash::get_zero_hash;
+
+/// Stores every node of a Merkle tree, so that re-computing the root after some of its leaves have
+/// changed only re-hashes the branches above those leaves.
+///
+/// Unlike a `TreeHashCache`, the tree may be padded out to a depth far greater than its number of
+/// leaves (e.g., an SSZ list with a large maximum length). Padding is never stored, instead it is
+/// substituted with the cached zero hashes from `tree_hash`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MerkleCache {
+    /// `layers[0]` holds the leaves, `layers[i + 1]` holds the parents of the nodes in
+    /// `layers[i]`.
+    layers: Vec<Vec<Hash256>>,
+}
+
+impl MerkleCache {
+    /// Replaces the leaves of the tree with `leaves`, returning the root of a tree with `2^depth`
+    /// leaves (the first of which are `leaves`, the remainder being zero).
+    ///
+    /// Each leaf may itself be the root of a subtree of height `leaf_height` (e.g., a chunk of a
+    /// larger list), in which case the padding is the root of a zero subtree of that height.
+    ///
+    /// A parent node is only re-hashed if either of its children differ from the previous call.
+    pub fn recalculate_root(
+        &mut self,
+        leaves: Vec<Hash256>,
+        leaf_height: usize,
+        depth: usize,
+    ) -> Result<Hash256, Error> {
+        if depth >= std::mem::size_of::<usize>() * 8 || leaves.len() > 1 << depth {
+            return Err(Error::TooManyLeaves {
+                leaves: leaves.len(),
+                depth,
+            });
+        }
+
+        let old_layers = std::mem::replace(&mut self.layers, Vec::with_capacity(depth + 1));
+        let mut layer = leaves;
+
+        for height in 0..depth {
+            let parents = {
+                let old_children = old_layers.get(height);
+                let old_parents = old_layers.get(height + 1);
+                let zero_hash = Hash256::from_slice(get_zero_hash(leaf_height + height));
+
+                (0..(layer.len() + 1) / 2)
+                    .map(|i| {
+                        let left = layer[i * 2];
+                        let right = layer.get(i * 2 + 1);
+
+                        match (old_children, old_parents) {
+                            (Some(old_children), Some(old_parents))
+                                if i < old_parents.len()
+                                    && old_children.get(i * 2) == Some(&left)
+                                    && old_children.get(i * 2 + 1) == right =>
+                            {
+                                old_parents[i]
+                            }
+                            _ => hash_concat(&left, right.unwrap_or(&zero_hash)),
+                        }
+                    })
+                    .collect()
+            };
+
+            self.layers.push(std::mem::replace(&mut layer, parents));
+        }
+
+        let root = layer
+            .first()
+            .cloned()
+            .unwrap_or_else(|| Hash256::from_slice(get_zero_hash(leaf_height + depth)));
+        self.layers.push(layer);
+
+        Ok(root)
+    }
+}
+
+fn hash_concat(left: &Hash256, right: &Hash256) -> Hash256 {
+    let mut preimage = Vec::with_capacity(64);
+    preimage.extend_from_slice(left.as_bytes());
+    preimage.extend_from_slice(right.as_bytes());
+    Hash256::from_slice(&hash(&preimage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_hash::merkleize_padded;
+
+    fn leaves(n: u64) -> Vec<Hash256> {
+        (0..n).map(Hash256::from_low_u64_be).collect()
+    }
+
+    fn reference_root(leaves: &[Hash256], depth: usize) -> Hash256 {
+        let bytes = leaves
+            .iter()
+            .flat_map(|leaf| leaf.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        Hash256::from_slice(&merkleize_padded(&bytes, 1 << depth))
+    }
+
+    #[test]
+    fn matches_merkleize_padded() {
+        for depth in 0..6 {
+            for n in 0..=(1 << depth) {
+                let leaves = leaves(n);
+                assert_eq!(
+                    MerkleCache::default().recalculate_root(leaves.clone(), 0, depth),
+                    Ok(reference_root(&leaves, depth)),
+                    "depth: {}, n: {}",
+                    depth,
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn modifications_grow_and_shrink() {
+        let depth = 10;
+        let mut cache = MerkleCache::default();
+        let mut leaves = leaves(37);
+
+        let mut check = |leaves: &Vec<Hash256>| {
+            assert_eq!(
+                cache.recalculate_root(leaves.clone(), 0, depth),
+                Ok(reference_root(leaves, depth))
+            );
+        };
+
+        check(&leaves);
+        check(&leaves);
+
+        leaves[0] = Hash256::repeat_byte(1);
+        leaves[36] = Hash256::repeat_byte(2);
+        check(&leaves);
+
+        leaves.push(Hash256::repeat_byte(3));
+        leaves.push(Hash256::repeat_byte(4));
+        check(&leaves);
+
+        leaves.truncate(5);
+        check(&leaves);
+
+        leaves.clear();
+        check(&leaves);
+
+        leaves.push(Hash256::repeat_byte(5));
+        check(&leaves);
+    }
+
+    #[test]
+    fn subtree_leaves() {
+        // Three subtrees of height 2, i.e., 12 of the 32 leaves of a tree of depth 5.
+        let all_leaves = leaves(12);
+        let subtree_roots = all_leaves
+            .chunks(4)
+            .map(|subtree| reference_root(subtree, 2))
+            .collect();
+
+        assert_eq!(
+            MerkleCache::default().recalculate_root(subtree_roots, 2, 3),
+            Ok(reference_root(&all_leaves, 5))
+        );
+    }
+
+    #[test]
+    fn too_many_leaves() {
+        assert_eq!(
+            MerkleCache::default().recalculate_root(leaves(5), 0, 2),
+            Err(Error::TooManyLeaves {
+                leaves: 5,
+                depth: 2
+            })
+        );
+    }
+}
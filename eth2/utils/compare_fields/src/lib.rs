@@ -5,6 +5,11 @@
 //!
 //! Note: `compare_fields_derive` requires `PartialEq` and `Debug` implementations.
 //!
+//! Fields marked `#[compare_fields(as_slice)]` are compared element-wise and fields marked
+//! `#[compare_fields(skip)]` are omitted from the comparison entirely. List types which do not
+//! dereference to a slice may be compared element-wise with `#[compare_fields(as_iter)]`, provided
+//! a reference to the field implements `IntoIterator`.
+//!
 //! ## Example
 //!
 //! ```rust
@@ -125,6 +130,18 @@ impl Comparison {
         Self::parent(field_name, a == b, children)
     }
 
+    /// As per `from_slice`, for lists which are not backed by a slice.
+    pub fn from_iter<'a, T, I>(field_name: String, a: I, b: I) -> Self
+    where
+        T: Debug + PartialEq<T> + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let a: Vec<&T> = a.into_iter().collect();
+        let b: Vec<&T> = b.into_iter().collect();
+
+        Self::from_slice(field_name, &a, &b)
+    }
+
     pub fn retain_children<F>(&mut self, f: F)
     where
         F: FnMut(&FieldComparison) -> bool,
@@ -6,8 +6,20 @@ use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
 fn is_slice(field: &syn::Field) -> bool {
+    has_attr(field, "( as_slice )")
+}
+
+fn is_iter(field: &syn::Field) -> bool {
+    has_attr(field, "( as_iter )")
+}
+
+fn is_skipped(field: &syn::Field) -> bool {
+    has_attr(field, "( skip )")
+}
+
+fn has_attr(field: &syn::Field, tts: &str) -> bool {
     for attr in &field.attrs {
-        if attr.tts.to_string() == "( as_slice )" {
+        if attr.tts.to_string() == tts {
             return true;
         }
     }
@@ -37,7 +49,9 @@ pub fn compare_fields_derive(input: TokenStream) -> TokenStream {
         let field_name = format!("{:}", ident_a);
         let ident_b = ident_a.clone();
 
-        let quote = if is_slice(field) {
+        let quote = if is_skipped(field) {
+            continue;
+        } else if is_slice(field) {
             quote! {
                 comparisons.push(compare_fields::Comparison::from_slice(
                         #field_name.to_string(),
@@ -45,6 +59,14 @@ pub fn compare_fields_derive(input: TokenStream) -> TokenStream {
                         &b.#ident_b)
                 );
             }
+        } else if is_iter(field) {
+            quote! {
+                comparisons.push(compare_fields::Comparison::from_iter(
+                        #field_name.to_string(),
+                        &self.#ident_a,
+                        &b.#ident_b)
+                );
+            }
         } else {
             quote! {
                 comparisons.push(
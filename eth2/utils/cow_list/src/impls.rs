@@ -0,0 +1,161 @@
+use super::*;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+impl<T: Serialize, N: Unsigned> Serialize for CowList<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>, N: Unsigned> Deserialize<'de> for CowList<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let vec = Vec::deserialize(deserializer)?;
+
+        if vec.len() <= Self::max_len() {
+            Ok(vec.into())
+        } else {
+            Err(D::Error::custom(format!(
+                "CowList of length {} exceeds the maximum of {}",
+                vec.len(),
+                Self::max_len()
+            )))
+        }
+    }
+}
+
+impl<T, N: Unsigned> tree_hash::TreeHash for CowList<T, N>
+where
+    T: tree_hash::TreeHash,
+{
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Vec<u8> {
+        let root = tree_hash::impls::padded_iter_tree_hash_root(self.iter(), Self::max_len());
+
+        tree_hash::mix_in_length(&root, self.len())
+    }
+}
+
+impl<T, N: Unsigned> ssz::Encode for CowList<T, N>
+where
+    T: ssz::Encode,
+{
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        if T::is_ssz_fixed_len() {
+            buf.reserve(T::ssz_fixed_len() * self.len());
+
+            for item in self {
+                item.ssz_append(buf);
+            }
+        } else {
+            let mut encoder = ssz::SszEncoder::list(buf, self.len() * ssz::BYTES_PER_LENGTH_OFFSET);
+
+            for item in self {
+                encoder.append(item);
+            }
+
+            encoder.finalize();
+        }
+    }
+}
+
+impl<T, N: Unsigned> ssz::Decode for CowList<T, N>
+where
+    T: ssz::Decode,
+{
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        let vec = Vec::from_ssz_bytes(bytes)?;
+
+        if vec.len() <= Self::max_len() {
+            Ok(vec.into())
+        } else {
+            Err(ssz::DecodeError::BytesInvalid(format!(
+                "CowList {:?}",
+                Error::OutOfBounds {
+                    i: vec.len(),
+                    len: Self::max_len(),
+                }
+            )))
+        }
+    }
+
+    fn from_ssz_reader<R: std::io::Read + ?Sized>(
+        reader: &mut R,
+        len: usize,
+    ) -> Result<Self, ssz::DecodeError> {
+        ssz::decode_list_from_reader(reader, len, Self::max_len()).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz::{Decode, Encode};
+    use ssz_types::VariableList;
+    use tree_hash::TreeHash;
+    use typenum::{U1024, U4};
+
+    fn vec_and_list(len: usize) -> (VariableList<u64, U1024>, CowList<u64, U1024>) {
+        let vec: Vec<u64> = (0..len as u64).collect();
+        let list = CowList::from(vec.clone());
+        (vec.into(), list)
+    }
+
+    #[test]
+    fn ssz_matches_variable_list() {
+        for len in &[0, 1, CHUNK_LEN + 1] {
+            let (vec, list) = vec_and_list(*len);
+
+            let bytes = list.as_ssz_bytes();
+            assert_eq!(bytes, vec.as_ssz_bytes());
+            assert_eq!(CowList::from_ssz_bytes(&bytes), Ok(list));
+        }
+    }
+
+    #[test]
+    fn ssz_rejects_too_many_items() {
+        let bytes = vec![0_u64; 5].as_ssz_bytes();
+
+        assert!(CowList::<u64, U4>::from_ssz_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn tree_hash_matches_variable_list() {
+        for len in &[0, 1, CHUNK_LEN + 1] {
+            let (vec, list) = vec_and_list(*len);
+
+            assert_eq!(list.tree_hash_root(), vec.tree_hash_root());
+        }
+    }
+
+    #[test]
+    fn serde_matches_variable_list() {
+        let (vec, list) = vec_and_list(CHUNK_LEN + 1);
+
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, serde_json::to_string(&vec).unwrap());
+        assert_eq!(
+            serde_json::from_str::<CowList<u64, U1024>>(&json).unwrap(),
+            list
+        );
+        assert!(serde_json::from_str::<CowList<u64, U4>>(&json).is_err());
+    }
+}
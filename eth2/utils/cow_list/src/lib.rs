@@ -0,0 +1,380 @@
+//! A list which is cheap to clone, for the large lists in a `BeaconState`.
+//!
+//! Items are stored in fixed-size chunks behind reference-counted pointers. Cloning a `CowList`
+//! only increments a reference count, whilst mutating an item copies just the chunk holding it
+//! (and the list of chunk pointers) if they are shared with another `CowList`.
+//!
+//! Like an `ssz_types::VariableList`, a `CowList` may hold no more than `N` items and its SSZ, tree
+//! hash and serde representations are identical to those of a `VariableList`.
+use rayon::prelude::*;
+use ssz_types::Error;
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::slice;
+use std::sync::Arc;
+use typenum::Unsigned;
+
+mod impls;
+mod tree_hash_cache;
+
+pub use tree_hash_cache::CowListTreeHashCache;
+
+/// The number of items in each chunk.
+///
+/// Smaller chunks make mutations cheaper, larger chunks make clones (of the chunk pointers, after
+/// a clone is mutated) and iteration cheaper.
+pub const CHUNK_LEN: usize = 64;
+
+/// A copy-on-write list, see the crate docs.
+///
+/// All chunks hold `CHUNK_LEN` items, except the last which may hold fewer (but never zero).
+pub struct CowList<T, N> {
+    chunks: Arc<Vec<Arc<Vec<T>>>>,
+    len: usize,
+    _phantom: PhantomData<N>,
+}
+
+impl<T, N: Unsigned> CowList<T, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the type-level maximum length.
+    pub fn max_len() -> usize {
+        N::to_usize()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i < self.len {
+            Some(&self.chunks[i / CHUNK_LEN][i % CHUNK_LEN])
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> Iter<T> {
+        Iter {
+            chunks: self.chunks.iter(),
+            chunk: [].iter(),
+            remaining: self.len,
+        }
+    }
+
+    /// Iterates the items in parallel, using `rayon`.
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = &T>
+    where
+        T: Send + Sync,
+        N: Sync,
+    {
+        (0..self.len).into_par_iter().map(move |i| &self[i])
+    }
+
+    /// Returns `true` if `self` and `other` share all of their chunks, i.e., `other` is an
+    /// unmodified clone of `self` (or vice versa).
+    ///
+    /// A `false` result does not imply the lists are unequal.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.chunks, &other.chunks)
+    }
+}
+
+impl<T: Clone, N: Unsigned> CowList<T, N> {
+    /// Returns a mutable reference to the item at `i`, copying its chunk if it is shared.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i < self.len {
+            let chunk = &mut Arc::make_mut(&mut self.chunks)[i / CHUNK_LEN];
+            Some(&mut Arc::make_mut(chunk)[i % CHUNK_LEN])
+        } else {
+            None
+        }
+    }
+
+    /// Appends `item` to the back of `self`.
+    ///
+    /// Returns an error if appending `item` would exceed the maximum length.
+    pub fn push(&mut self, item: T) -> Result<(), Error> {
+        if self.len >= Self::max_len() {
+            return Err(Error::OutOfBounds {
+                i: self.len + 1,
+                len: Self::max_len(),
+            });
+        }
+
+        let chunks = Arc::make_mut(&mut self.chunks);
+
+        if self.len % CHUNK_LEN == 0 {
+            let mut chunk = Vec::with_capacity(CHUNK_LEN);
+            chunk.push(item);
+            chunks.push(Arc::new(chunk));
+        } else {
+            let last = chunks.last_mut().expect("a partial chunk exists");
+            Arc::make_mut(last).push(item);
+        }
+
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Iterates mutably across all items, copying every shared chunk.
+    ///
+    /// Prefer `get_mut` where only some items will be modified.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        Arc::make_mut(&mut self.chunks)
+            .iter_mut()
+            .flat_map(|chunk| Arc::make_mut(chunk).iter_mut())
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.len);
+        for chunk in self.chunks.iter() {
+            vec.extend_from_slice(chunk);
+        }
+        vec
+    }
+}
+
+impl<T, N> Default for CowList<T, N> {
+    fn default() -> Self {
+        Self {
+            chunks: Arc::new(vec![]),
+            len: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, N> Clone for CowList<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug, N: Unsigned> fmt::Debug for CowList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, N: Unsigned> PartialEq for CowList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || (self.len == other.len && self.iter().eq(other.iter()))
+    }
+}
+
+impl<T: Eq, N: Unsigned> Eq for CowList<T, N> {}
+
+/// Items past the maximum length are dropped, as per `VariableList::from`.
+impl<T, N: Unsigned> From<Vec<T>> for CowList<T, N> {
+    fn from(vec: Vec<T>) -> Self {
+        vec.into_iter().collect()
+    }
+}
+
+impl<T: Clone, N: Unsigned> Into<Vec<T>> for CowList<T, N> {
+    fn into(self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+/// Items past the maximum length are dropped, as per `VariableList::from`.
+impl<T, N: Unsigned> FromIterator<T> for CowList<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut chunks = vec![];
+        let mut chunk = Vec::with_capacity(CHUNK_LEN);
+        let mut len = 0;
+
+        for item in iter.into_iter().take(N::to_usize()) {
+            chunk.push(item);
+            len += 1;
+
+            if chunk.len() == CHUNK_LEN {
+                let full = std::mem::replace(&mut chunk, Vec::with_capacity(CHUNK_LEN));
+                chunks.push(Arc::new(full));
+            }
+        }
+
+        if !chunk.is_empty() {
+            chunks.push(Arc::new(chunk));
+        }
+
+        Self {
+            chunks: Arc::new(chunks),
+            len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, N: Unsigned> Index<usize> for CowList<T, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, i: usize) -> &T {
+        match self.get(i) {
+            Some(item) => item,
+            None => panic!("index {} out of bounds for CowList of len {}", i, self.len),
+        }
+    }
+}
+
+impl<T: Clone, N: Unsigned> IndexMut<usize> for CowList<T, N> {
+    #[inline]
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let len = self.len;
+        match self.get_mut(i) {
+            Some(item) => item,
+            None => panic!("index {} out of bounds for CowList of len {}", i, len),
+        }
+    }
+}
+
+impl<'a, T, N: Unsigned> IntoIterator for &'a CowList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An iterator across the items of a `CowList`.
+pub struct Iter<'a, T> {
+    chunks: slice::Iter<'a, Arc<Vec<T>>>,
+    chunk: slice::Iter<'a, T>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(item) = self.chunk.next() {
+                self.remaining -= 1;
+                return Some(item);
+            }
+            self.chunk = self.chunks.next()?.iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typenum::U1024;
+
+    fn list(len: usize) -> CowList<u64, U1024> {
+        (0..len as u64).collect()
+    }
+
+    #[test]
+    fn from_vec_and_back() {
+        for len in &[0, 1, CHUNK_LEN - 1, CHUNK_LEN, CHUNK_LEN + 1, CHUNK_LEN * 3] {
+            let vec: Vec<u64> = (0..*len as u64).collect();
+            let list: CowList<_, U1024> = CowList::from(vec.clone());
+
+            assert_eq!(list.len(), *len);
+            assert_eq!(list.iter().len(), *len);
+            assert_eq!(list.to_vec(), vec);
+            assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec);
+            assert_eq!(list.par_iter().cloned().collect::<Vec<_>>(), vec);
+            assert_eq!(list.get(*len), None);
+        }
+    }
+
+    #[test]
+    fn push() {
+        let mut list = CowList::new();
+        for i in 0..(CHUNK_LEN * 2 + 1) as u64 {
+            list.push(i).unwrap();
+        }
+        assert_eq!(list, self::list(CHUNK_LEN * 2 + 1));
+    }
+
+    #[test]
+    fn push_past_max_len() {
+        let mut list = self::list(1023);
+
+        assert_eq!(list.push(1023), Ok(()));
+        assert_eq!(
+            list.push(1024),
+            Err(Error::OutOfBounds { i: 1025, len: 1024 })
+        );
+        assert_eq!(list.len(), 1024);
+    }
+
+    #[test]
+    fn from_vec_truncates() {
+        assert_eq!(self::list(1025), self::list(1024));
+    }
+
+    #[test]
+    fn mutations_only_copy_touched_chunks() {
+        let a = list(CHUNK_LEN * 4);
+        let mut b = a.clone();
+        assert!(a.ptr_eq(&b));
+
+        b[CHUNK_LEN + 1] = 42;
+
+        assert_eq!(a[CHUNK_LEN + 1], CHUNK_LEN as u64 + 1);
+        assert_eq!(b[CHUNK_LEN + 1], 42);
+        assert!(!a.ptr_eq(&b));
+        assert_ne!(a, b);
+
+        for i in 0..a.chunks.len() {
+            assert_eq!(Arc::ptr_eq(&a.chunks[i], &b.chunks[i]), i != 1);
+        }
+    }
+
+    #[test]
+    fn push_does_not_modify_clone() {
+        let mut a = list(CHUNK_LEN + 1);
+        let b = a.clone();
+
+        a.push(7).unwrap();
+
+        assert_eq!(a.len(), CHUNK_LEN + 2);
+        assert_eq!(b, list(CHUNK_LEN + 1));
+    }
+
+    #[test]
+    fn iter_mut() {
+        let a = list(CHUNK_LEN * 2);
+        let mut b = a.clone();
+
+        b.iter_mut().for_each(|item| *item *= 2);
+
+        assert_eq!(a, list(CHUNK_LEN * 2));
+        assert_eq!(
+            b.to_vec(),
+            (0..CHUNK_LEN as u64 * 2).map(|i| i * 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let _ = list(3)[3];
+    }
+}
@@ -0,0 +1,167 @@
+use super::*;
+use cached_tree_hash::{Error as TreeHashCacheError, MerkleCache};
+use ethereum_types::H256 as Hash256;
+use std::sync::Weak;
+use tree_hash::{impls::padded_iter_tree_hash_root, TreeHash, TreeHashType};
+
+/// Caches the tree hash root of each chunk of a `CowList`, along with the tree above those roots.
+///
+/// A chunk is only re-hashed if it has been copied (i.e., modified) since the cache was last
+/// updated, which is detected by comparing chunk pointers. Only weak references to the chunks are
+/// held, so the cache neither keeps them alive nor forces a copy when they are next modified.
+#[derive(Debug, Clone)]
+pub struct CowListTreeHashCache<T> {
+    chunks: Vec<(Weak<Vec<T>>, Hash256)>,
+    tree: MerkleCache,
+}
+
+impl<T> Default for CowListTreeHashCache<T> {
+    fn default() -> Self {
+        Self {
+            chunks: vec![],
+            tree: MerkleCache::default(),
+        }
+    }
+}
+
+impl<T, N> CowList<T, N>
+where
+    T: TreeHash + Send + Sync,
+    N: Unsigned,
+{
+    /// Returns `self.tree_hash_root()`, only re-hashing the chunks which have changed since
+    /// `cache` was last updated.
+    pub fn recalculate_tree_hash_root(
+        &self,
+        cache: &mut CowListTreeHashCache<T>,
+    ) -> Result<Hash256, TreeHashCacheError> {
+        let root = self.recalculate_padded_tree_hash_root(cache)?;
+
+        Ok(Hash256::from_slice(&tree_hash::mix_in_length(
+            root.as_bytes(),
+            self.len(),
+        )))
+    }
+
+    /// As per `recalculate_tree_hash_root`, but without mixing in the length (i.e., the root of
+    /// a tree with enough leaves for `N` items).
+    pub fn recalculate_padded_tree_hash_root(
+        &self,
+        cache: &mut CowListTreeHashCache<T>,
+    ) -> Result<Hash256, TreeHashCacheError> {
+        let items_per_leaf = match T::tree_hash_type() {
+            TreeHashType::Basic => T::tree_hash_packing_factor(),
+            TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => 1,
+        };
+        let leaves_per_chunk = CHUNK_LEN / items_per_leaf;
+        let max_leaves = (Self::max_len() + items_per_leaf - 1) / items_per_leaf;
+
+        let depth = max_leaves.next_power_of_two().trailing_zeros() as usize;
+        let chunk_depth = leaves_per_chunk.next_power_of_two().trailing_zeros() as usize;
+
+        // A chunk would be padded deeper than the whole list, so there is nothing to cache.
+        if depth < chunk_depth {
+            return Ok(Hash256::from_slice(&padded_iter_tree_hash_root(
+                self.iter(),
+                Self::max_len(),
+            )));
+        }
+
+        let old_chunks = std::mem::replace(&mut cache.chunks, vec![]);
+
+        cache.chunks = self
+            .chunks
+            .par_iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let root = match old_chunks.get(i) {
+                    Some((old_chunk, root))
+                        if old_chunk
+                            .upgrade()
+                            .map_or(false, |old_chunk| Arc::ptr_eq(&old_chunk, chunk)) =>
+                    {
+                        *root
+                    }
+                    _ => Hash256::from_slice(&padded_iter_tree_hash_root(chunk.iter(), CHUNK_LEN)),
+                };
+
+                (Arc::downgrade(chunk), root)
+            })
+            .collect();
+
+        let leaves = cache.chunks.iter().map(|(_, root)| *root).collect();
+
+        cache
+            .tree
+            .recalculate_root(leaves, chunk_depth, depth - chunk_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typenum::{U1024, U16};
+
+    fn check<T, N>(list: &CowList<T, N>, cache: &mut CowListTreeHashCache<T>)
+    where
+        T: TreeHash + Send + Sync,
+        N: Unsigned,
+    {
+        assert_eq!(
+            list.recalculate_tree_hash_root(cache),
+            Ok(Hash256::from_slice(&list.tree_hash_root()))
+        );
+    }
+
+    #[test]
+    fn basic_items() {
+        let mut cache = CowListTreeHashCache::default();
+        let mut list: CowList<u64, U1024> = (0..CHUNK_LEN as u64 * 3 + 7).collect();
+        check(&list, &mut cache);
+        check(&list, &mut cache);
+
+        let clone = list.clone();
+        list[CHUNK_LEN + 3] = 42;
+        check(&list, &mut cache);
+        check(&clone, &mut cache);
+
+        list.push(7).unwrap();
+        check(&list, &mut cache);
+
+        check(&CowList::<u64, U1024>::new(), &mut cache);
+    }
+
+    #[test]
+    fn container_items() {
+        let mut cache = CowListTreeHashCache::default();
+        let mut list: CowList<Hash256, U1024> = (0..CHUNK_LEN as u64 * 2)
+            .map(Hash256::from_low_u64_be)
+            .collect();
+        check(&list, &mut cache);
+
+        list.iter_mut()
+            .for_each(|item| *item = Hash256::repeat_byte(1));
+        check(&list, &mut cache);
+
+        list.push(Hash256::repeat_byte(2)).unwrap();
+        check(&list, &mut cache);
+    }
+
+    #[test]
+    fn list_shorter_than_a_chunk() {
+        let mut cache = CowListTreeHashCache::default();
+        let list: CowList<Hash256, U16> = (0..3).map(Hash256::from_low_u64_be).collect();
+        check(&list, &mut cache);
+    }
+
+    #[test]
+    fn dropped_chunks_are_rehashed() {
+        let mut cache = CowListTreeHashCache::default();
+        let list: CowList<u64, U1024> = (0..CHUNK_LEN as u64).collect();
+        check(&list, &mut cache);
+        drop(list);
+
+        let list: CowList<u64, U1024> = (1..CHUNK_LEN as u64 + 1).collect();
+        check(&list, &mut cache);
+    }
+}
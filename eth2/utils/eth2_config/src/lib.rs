@@ -2,9 +2,9 @@ use clap::ArgMatches;
 use serde_derive::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
-use types::ChainSpec;
+use types::{ChainSpec, MainnetEthSpec, MinimalEthSpec, YamlConfig};
 
 /// The core configuration of a Lighthouse beacon node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,13 +44,47 @@ impl Eth2Config {
     ///
     /// Returns an error if arguments are obviously invalid. May succeed even if some values are
     /// invalid.
-    pub fn apply_cli_args(&mut self, args: &ArgMatches) -> Result<(), &'static str> {
+    pub fn apply_cli_args(&mut self, args: &ArgMatches) -> Result<(), String> {
+        if let Some(path) = args.value_of("spec-config") {
+            self.apply_yaml_config_file(Path::new(path))?;
+        }
+
         if args.is_present("recent-genesis") {
             self.spec.genesis_time = recent_genesis_time()
         }
 
         Ok(())
     }
+
+    /// Replace the constants in `self.spec` with those in the YAML config file at `path` (see
+    /// `types::YamlConfig`).
+    ///
+    /// Returns an error if the file cannot be read, contains unknown or missing keys, or contains
+    /// values which are incompatible with `self.spec_constants`.
+    pub fn apply_yaml_config_file(&mut self, path: &Path) -> Result<(), String> {
+        let config = YamlConfig::from_file(path)?;
+
+        self.spec = match self.spec_constants.as_str() {
+            "mainnet" => config.apply_to_chain_spec::<MainnetEthSpec>(&self.spec),
+            "minimal" => config.apply_to_chain_spec::<MinimalEthSpec>(&self.spec),
+            other => Err(format!("Unknown spec constants: {}", other)),
+        }
+        .map_err(|e| format!("Invalid spec config {:?}: {}", path, e))?;
+
+        Ok(())
+    }
+
+    /// Returns `self.spec` as a YAML config, with the compile-time values for
+    /// `self.spec_constants`.
+    ///
+    /// Returns `None` if `self.spec_constants` is unknown.
+    pub fn yaml_config(&self) -> Option<YamlConfig> {
+        match self.spec_constants.as_str() {
+            "mainnet" => Some(YamlConfig::from_spec::<MainnetEthSpec>(&self.spec)),
+            "minimal" => Some(YamlConfig::from_spec::<MinimalEthSpec>(&self.spec)),
+            _ => None,
+        }
+    }
 }
 
 /// Returns the system time, mod 30 minutes.
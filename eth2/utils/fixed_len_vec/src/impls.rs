@@ -17,32 +17,32 @@ where
     }
 
     fn tree_hash_root(&self) -> Vec<u8> {
-        tree_hash::impls::vec_tree_hash_root(&self.vec)
+        tree_hash::impls::iter_tree_hash_root(self.iter())
     }
 }
 
 impl<T, N: Unsigned> cached_tree_hash::CachedTreeHash for FixedLenVec<T, N>
 where
-    T: cached_tree_hash::CachedTreeHash + tree_hash::TreeHash,
+    T: cached_tree_hash::CachedTreeHash + tree_hash::TreeHash + Clone,
 {
     fn new_tree_hash_cache(
         &self,
         depth: usize,
     ) -> Result<cached_tree_hash::TreeHashCache, cached_tree_hash::Error> {
-        let (cache, _overlay) = cached_tree_hash::vec::new_tree_hash_cache(&self.vec, depth)?;
+        let (cache, _overlay) = cached_tree_hash::vec::new_tree_hash_cache(&self.to_vec(), depth)?;
 
         Ok(cache)
     }
 
     fn tree_hash_cache_schema(&self, depth: usize) -> cached_tree_hash::BTreeSchema {
-        cached_tree_hash::vec::produce_schema(&self.vec, depth)
+        cached_tree_hash::vec::produce_schema(&self.to_vec(), depth)
     }
 
     fn update_tree_hash_cache(
         &self,
         cache: &mut cached_tree_hash::TreeHashCache,
     ) -> Result<(), cached_tree_hash::Error> {
-        cached_tree_hash::vec::update_tree_hash_cache(&self.vec, cache)?;
+        cached_tree_hash::vec::update_tree_hash_cache(&self.to_vec(), cache)?;
 
         Ok(())
     }
@@ -68,13 +68,13 @@ where
         if T::is_ssz_fixed_len() {
             buf.reserve(T::ssz_fixed_len() * self.len());
 
-            for item in &self.vec {
+            for item in self.iter() {
                 item.ssz_append(buf);
             }
         } else {
             let mut encoder = ssz::SszEncoder::list(buf, self.len() * ssz::BYTES_PER_LENGTH_OFFSET);
 
-            for item in &self.vec {
+            for item in self.iter() {
                 encoder.append(item);
             }
 
@@ -112,6 +112,13 @@ where
             ssz::decode_list_of_variable_length_items(bytes).and_then(|vec| Ok(vec.into()))
         }
     }
+
+    fn from_ssz_reader<R: std::io::Read + ?Sized>(
+        reader: &mut R,
+        len: usize,
+    ) -> Result<Self, ssz::DecodeError> {
+        ssz::decode_list_from_reader(reader, len, N::to_usize()).map(Into::into)
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +136,10 @@ mod ssz_tests {
 
     fn round_trip<T: Encode + Decode + std::fmt::Debug + PartialEq>(item: T) {
         let encoded = &item.as_ssz_bytes();
+        assert_eq!(
+            T::from_ssz_reader(&mut &encoded[..], encoded.len()).as_ref(),
+            Ok(&item)
+        );
         assert_eq!(T::from_ssz_bytes(&encoded), Ok(item));
     }
 
@@ -1,23 +1,26 @@
-use serde_derive::{Deserialize, Serialize};
-use std::marker::PhantomData;
-use std::ops::{Deref, Index, IndexMut};
-use std::slice::SliceIndex;
+use cow_list::CowList;
+use ethereum_types::H256 as Hash256;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::{Index, IndexMut};
 use typenum::Unsigned;
 
+pub use cow_list::{CowListTreeHashCache, Iter, CHUNK_LEN};
 pub use typenum;
 
 mod impls;
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(transparent)]
+/// A vector with a length fixed by `N`.
+///
+/// The items are held in reference-counted chunks (see `cow_list::CowList`), so clones are cheap.
+/// Mutating an item of a clone copies only the chunk holding that item.
 pub struct FixedLenVec<T, N> {
-    vec: Vec<T>,
-    _phantom: PhantomData<N>,
+    items: CowList<T, N>,
 }
 
 impl<T, N: Unsigned> FixedLenVec<T, N> {
     pub fn len(&self) -> usize {
-        self.vec.len()
+        self.items.len()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -27,55 +30,131 @@ impl<T, N: Unsigned> FixedLenVec<T, N> {
     pub fn capacity() -> usize {
         N::to_usize()
     }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.items.get(i)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Returns `true` if `self` and `other` share all of their chunks, i.e., `other` is an
+    /// unmodified clone of `self` (or vice versa).
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.items.ptr_eq(&other.items)
+    }
+}
+
+impl<T: Clone, N: Unsigned> FixedLenVec<T, N> {
+    /// Returns a mutable reference to the item at `i`, copying its chunk if it is shared.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        self.items.get_mut(i)
+    }
+
+    /// Iterates mutably across all items, copying every shared chunk.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.items.iter_mut()
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.items.to_vec()
+    }
+}
+
+impl<T, N> FixedLenVec<T, N>
+where
+    T: tree_hash::TreeHash + Send + Sync,
+    N: Unsigned,
+{
+    /// Returns `self.tree_hash_root()`, only re-hashing the chunks which have changed since
+    /// `cache` was last updated.
+    pub fn recalculate_tree_hash_root(
+        &self,
+        cache: &mut CowListTreeHashCache<T>,
+    ) -> Result<Hash256, cached_tree_hash::Error> {
+        self.items.recalculate_padded_tree_hash_root(cache)
+    }
+}
+
+impl<T, N> Clone for FixedLenVec<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, N: Unsigned> fmt::Debug for FixedLenVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.items.fmt(f)
+    }
+}
+
+impl<T: PartialEq, N: Unsigned> PartialEq for FixedLenVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
 }
 
 impl<T: Default, N: Unsigned> From<Vec<T>> for FixedLenVec<T, N> {
     fn from(mut vec: Vec<T>) -> Self {
         vec.resize_with(Self::capacity(), Default::default);
 
-        Self {
-            vec,
-            _phantom: PhantomData,
-        }
+        Self { items: vec.into() }
     }
 }
 
-impl<T, N: Unsigned> Into<Vec<T>> for FixedLenVec<T, N> {
+impl<T: Clone, N: Unsigned> Into<Vec<T>> for FixedLenVec<T, N> {
     fn into(self) -> Vec<T> {
-        self.vec
+        self.to_vec()
     }
 }
 
-impl<T, N: Unsigned> Default for FixedLenVec<T, N> {
+impl<T, N> Default for FixedLenVec<T, N> {
     fn default() -> Self {
         Self {
-            vec: Vec::default(),
-            _phantom: PhantomData,
+            items: CowList::default(),
         }
     }
 }
 
-impl<T, N: Unsigned, I: SliceIndex<[T]>> Index<I> for FixedLenVec<T, N> {
-    type Output = I::Output;
+impl<T, N: Unsigned> Index<usize> for FixedLenVec<T, N> {
+    type Output = T;
 
     #[inline]
-    fn index(&self, index: I) -> &Self::Output {
-        Index::index(&self.vec, index)
+    fn index(&self, i: usize) -> &T {
+        &self.items[i]
     }
 }
 
-impl<T, N: Unsigned, I: SliceIndex<[T]>> IndexMut<I> for FixedLenVec<T, N> {
+impl<T: Clone, N: Unsigned> IndexMut<usize> for FixedLenVec<T, N> {
     #[inline]
-    fn index_mut(&mut self, index: I) -> &mut Self::Output {
-        IndexMut::index_mut(&mut self.vec, index)
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.items[i]
+    }
+}
+
+impl<'a, T, N: Unsigned> IntoIterator for &'a FixedLenVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-impl<T, N: Unsigned> Deref for FixedLenVec<T, N> {
-    type Target = [T];
+impl<T: Serialize, N: Unsigned> Serialize for FixedLenVec<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
 
-    fn deref(&self) -> &[T] {
-        &self.vec[..]
+impl<'de, T: Deserialize<'de>, N: Unsigned> Deserialize<'de> for FixedLenVec<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self {
+            items: Vec::deserialize(deserializer)?.into(),
+        })
     }
 }
 
@@ -91,8 +170,7 @@ mod test {
         let mut fixed: FixedLenVec<u64, U8192> = vec.clone().into();
 
         assert_eq!(fixed[0], 1);
-        assert_eq!(&fixed[0..1], &vec[0..1]);
-        assert_eq!((&fixed[..]).len(), 8192);
+        assert_eq!(fixed.iter().len(), 8192);
 
         fixed[1] = 3;
         assert_eq!(fixed[1], 3);
@@ -102,20 +180,33 @@ mod test {
     fn length() {
         let vec = vec![42; 5];
         let fixed: FixedLenVec<u64, U4> = FixedLenVec::from(vec.clone());
-        assert_eq!(&fixed[..], &vec[0..4]);
+        assert_eq!(fixed.to_vec(), vec[0..4].to_vec());
 
         let vec = vec![42; 3];
         let fixed: FixedLenVec<u64, U4> = FixedLenVec::from(vec.clone());
-        assert_eq!(&fixed[0..3], &vec[..]);
-        assert_eq!(&fixed[..], &vec![42, 42, 42, 0][..]);
+        assert_eq!(fixed.to_vec(), vec![42, 42, 42, 0]);
 
         let vec = vec![];
         let fixed: FixedLenVec<u64, U4> = FixedLenVec::from(vec.clone());
-        assert_eq!(&fixed[..], &vec![0, 0, 0, 0][..]);
+        assert_eq!(fixed.to_vec(), vec![0, 0, 0, 0]);
     }
 
     #[test]
-    fn deref() {
+    fn clone_on_write() {
+        let a: FixedLenVec<u64, U4> = FixedLenVec::from(vec![1, 2, 3, 4]);
+        let mut b = a.clone();
+
+        assert!(a.ptr_eq(&b));
+
+        b[0] = 42;
+
+        assert!(!a.ptr_eq(&b));
+        assert_eq!(a.to_vec(), vec![1, 2, 3, 4]);
+        assert_eq!(b.to_vec(), vec![42, 2, 3, 4]);
+    }
+
+    #[test]
+    fn get() {
         let vec = vec![0, 2, 4, 6];
         let fixed: FixedLenVec<u64, U4> = FixedLenVec::from(vec);
 
@@ -123,6 +214,26 @@ mod test {
         assert_eq!(fixed.get(3), Some(&6));
         assert_eq!(fixed.get(4), None);
     }
+
+    #[test]
+    fn recalculate_tree_hash_root() {
+        use tree_hash::TreeHash;
+
+        let mut cache = CowListTreeHashCache::default();
+        let mut check = |fixed: &FixedLenVec<Hash256, U8192>| {
+            assert_eq!(
+                fixed.recalculate_tree_hash_root(&mut cache),
+                Ok(Hash256::from_slice(&fixed.tree_hash_root()))
+            );
+        };
+
+        let mut fixed = FixedLenVec::from(vec![]);
+        check(&fixed);
+
+        fixed[0] = Hash256::repeat_byte(1);
+        fixed[8191] = Hash256::repeat_byte(2);
+        check(&fixed);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,120 @@
+//! Arithmetic which returns an `Err` instead of panicking (debug builds) or wrapping (release
+//! builds) on overflow, underflow and division by zero.
+//!
+//! Intended for use where operands may be influenced by untrusted input, such as the balances
+//! in a `BeaconState` received from a peer or the constants in a user-supplied `ChainSpec`.
+
+/// An arithmetic operation could not be completed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ArithError {
+    /// The result does not fit in the type (including underflow of an unsigned type).
+    Overflow,
+    DivisionByZero,
+}
+
+pub type Result<T> = std::result::Result<T, ArithError>;
+
+/// Checked arithmetic between `Self` and `Rhs`.
+pub trait SafeArith<Rhs = Self>: Sized + Copy {
+    fn safe_add(&self, other: Rhs) -> Result<Self>;
+    fn safe_sub(&self, other: Rhs) -> Result<Self>;
+    fn safe_mul(&self, other: Rhs) -> Result<Self>;
+    fn safe_div(&self, other: Rhs) -> Result<Self>;
+    fn safe_rem(&self, other: Rhs) -> Result<Self>;
+
+    fn safe_add_assign(&mut self, other: Rhs) -> Result<()> {
+        *self = self.safe_add(other)?;
+        Ok(())
+    }
+
+    fn safe_sub_assign(&mut self, other: Rhs) -> Result<()> {
+        *self = self.safe_sub(other)?;
+        Ok(())
+    }
+
+    fn safe_mul_assign(&mut self, other: Rhs) -> Result<()> {
+        *self = self.safe_mul(other)?;
+        Ok(())
+    }
+
+    fn safe_div_assign(&mut self, other: Rhs) -> Result<()> {
+        *self = self.safe_div(other)?;
+        Ok(())
+    }
+
+    fn safe_rem_assign(&mut self, other: Rhs) -> Result<()> {
+        *self = self.safe_rem(other)?;
+        Ok(())
+    }
+}
+
+macro_rules! impl_safe_arith {
+    ($type: ty) => {
+        impl SafeArith for $type {
+            fn safe_add(&self, other: Self) -> Result<Self> {
+                self.checked_add(other).ok_or(ArithError::Overflow)
+            }
+
+            fn safe_sub(&self, other: Self) -> Result<Self> {
+                self.checked_sub(other).ok_or(ArithError::Overflow)
+            }
+
+            fn safe_mul(&self, other: Self) -> Result<Self> {
+                self.checked_mul(other).ok_or(ArithError::Overflow)
+            }
+
+            fn safe_div(&self, other: Self) -> Result<Self> {
+                self.checked_div(other).ok_or(ArithError::DivisionByZero)
+            }
+
+            fn safe_rem(&self, other: Self) -> Result<Self> {
+                self.checked_rem(other).ok_or(ArithError::DivisionByZero)
+            }
+        }
+    };
+}
+
+impl_safe_arith!(u8);
+impl_safe_arith!(u16);
+impl_safe_arith!(u32);
+impl_safe_arith!(u64);
+impl_safe_arith!(usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let x = 10u64;
+        let y = 5u64;
+        assert_eq!(x.safe_add(y), Ok(15));
+        assert_eq!(x.safe_sub(y), Ok(5));
+        assert_eq!(x.safe_mul(y), Ok(50));
+        assert_eq!(x.safe_div(y), Ok(2));
+        assert_eq!(x.safe_rem(y), Ok(0));
+    }
+
+    #[test]
+    fn overflow_and_underflow() {
+        assert_eq!(u64::max_value().safe_add(1), Err(ArithError::Overflow));
+        assert_eq!(0u64.safe_sub(1), Err(ArithError::Overflow));
+        assert_eq!(u64::max_value().safe_mul(2), Err(ArithError::Overflow));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(1u64.safe_div(0), Err(ArithError::DivisionByZero));
+        assert_eq!(1u64.safe_rem(0), Err(ArithError::DivisionByZero));
+    }
+
+    #[test]
+    fn assign_is_unchanged_on_error() {
+        let mut x = u64::max_value();
+        assert_eq!(x.safe_add_assign(1), Err(ArithError::Overflow));
+        assert_eq!(x, u64::max_value());
+
+        x.safe_sub_assign(1).unwrap();
+        assert_eq!(x, u64::max_value() - 1);
+    }
+}
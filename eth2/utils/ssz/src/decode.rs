@@ -1,6 +1,8 @@
 use super::*;
+use std::io::{self, Read};
 
 pub mod impls;
+pub mod stream;
 
 /// Returned when SSZ decoding fails.
 #[derive(Debug, PartialEq)]
@@ -20,6 +22,14 @@ pub enum DecodeError {
     OutOfBoundsByte { i: usize },
     /// The given bytes were invalid for some application-level reason.
     BytesInvalid(String),
+    /// The reader failed to supply the bytes (e.g., it reached the end of its input early).
+    ReadError(io::ErrorKind),
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(e: io::Error) -> DecodeError {
+        DecodeError::ReadError(e.kind())
+    }
 }
 
 /// Provides SSZ decoding (de-serialization) via the `from_ssz_bytes(&bytes)` method.
@@ -46,6 +56,23 @@ pub trait Decode: Sized {
     /// The supplied bytes must be the exact length required to decode `Self`, excess bytes will
     /// result in an error.
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Attempts to decode `Self` from exactly `len` bytes read from `reader`, returning a
+    /// `DecodeError` on failure.
+    ///
+    /// The default implementation reads all `len` bytes into a buffer and passes them to
+    /// `from_ssz_bytes`, which suits small objects. Containers (via `#[derive(Decode)]`) and lists
+    /// override it to decode each item as it is read, so the full encoding is never held in
+    /// memory.
+    ///
+    /// Never reads more than `len` bytes. Reads are small, so `reader` should be buffered (e.g.,
+    /// with `std::io::BufReader`).
+    fn from_ssz_reader<R: Read + ?Sized>(reader: &mut R, len: usize) -> Result<Self, DecodeError> {
+        let mut bytes = vec![0; len];
+        reader.read_exact(&mut bytes)?;
+
+        Self::from_ssz_bytes(&bytes)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -235,6 +262,14 @@ fn read_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
     })?)
 }
 
+/// Reads a `BYTES_PER_LENGTH_OFFSET`-byte length from `reader`.
+fn read_offset_from_reader<R: Read + ?Sized>(reader: &mut R) -> Result<usize, DecodeError> {
+    let mut bytes = [0; BYTES_PER_LENGTH_OFFSET];
+    reader.read_exact(&mut bytes)?;
+
+    decode_offset(&bytes)
+}
+
 /// Decode bytes as a little-endian usize, returning an `Err` if `bytes.len() !=
 /// BYTES_PER_LENGTH_OFFSET`.
 fn decode_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
@@ -2,6 +2,26 @@ use super::*;
 use core::num::NonZeroUsize;
 use ethereum_types::{H256, U128, U256};
 
+/// Reads a fixed-length item of `len` bytes from `reader` into `buf`, which must be exactly the
+/// size of the item.
+///
+/// Used by fixed-length types to decode from a reader into a stack buffer, rather than the heap
+/// buffer of the default `Decode::from_ssz_reader`.
+fn read_fixed_len<R: Read + ?Sized>(
+    reader: &mut R,
+    len: usize,
+    buf: &mut [u8],
+) -> Result<(), DecodeError> {
+    let expected = buf.len();
+
+    if len != expected {
+        Err(DecodeError::InvalidByteLength { len, expected })
+    } else {
+        reader.read_exact(buf)?;
+        Ok(())
+    }
+}
+
 macro_rules! impl_decodable_for_uint {
     ($type: ident, $bit_size: expr) => {
         impl Decode for $type {
@@ -26,6 +46,16 @@ macro_rules! impl_decodable_for_uint {
                     Ok(Self::from_le_bytes(array))
                 }
             }
+
+            fn from_ssz_reader<R: Read + ?Sized>(
+                reader: &mut R,
+                len: usize,
+            ) -> Result<Self, DecodeError> {
+                let mut array = [0; $bit_size / 8];
+                read_fixed_len(reader, len, &mut array)?;
+
+                Ok(Self::from_le_bytes(array))
+            }
         }
     };
 }
@@ -213,6 +243,13 @@ impl Decode for bool {
             }
         }
     }
+
+    fn from_ssz_reader<R: Read + ?Sized>(reader: &mut R, len: usize) -> Result<Self, DecodeError> {
+        let mut byte = [0; 1];
+        read_fixed_len(reader, len, &mut byte)?;
+
+        Self::from_ssz_bytes(&byte)
+    }
 }
 
 impl Decode for NonZeroUsize {
@@ -288,6 +325,13 @@ impl Decode for H256 {
             Ok(H256::from_slice(bytes))
         }
     }
+
+    fn from_ssz_reader<R: Read + ?Sized>(reader: &mut R, len: usize) -> Result<Self, DecodeError> {
+        let mut array = [0; 32];
+        read_fixed_len(reader, len, &mut array)?;
+
+        Ok(H256::from_slice(&array))
+    }
 }
 
 impl Decode for U256 {
@@ -309,6 +353,13 @@ impl Decode for U256 {
             Ok(U256::from_little_endian(bytes))
         }
     }
+
+    fn from_ssz_reader<R: Read + ?Sized>(reader: &mut R, len: usize) -> Result<Self, DecodeError> {
+        let mut array = [0; 32];
+        read_fixed_len(reader, len, &mut array)?;
+
+        Ok(U256::from_little_endian(&array))
+    }
 }
 
 impl Decode for U128 {
@@ -330,6 +381,13 @@ impl Decode for U128 {
             Ok(U128::from_little_endian(bytes))
         }
     }
+
+    fn from_ssz_reader<R: Read + ?Sized>(reader: &mut R, len: usize) -> Result<Self, DecodeError> {
+        let mut array = [0; 16];
+        read_fixed_len(reader, len, &mut array)?;
+
+        Ok(U128::from_little_endian(&array))
+    }
 }
 
 macro_rules! impl_decodable_for_u8_array {
@@ -356,6 +414,16 @@ macro_rules! impl_decodable_for_u8_array {
                     Ok(array)
                 }
             }
+
+            fn from_ssz_reader<R: Read + ?Sized>(
+                reader: &mut R,
+                len: usize,
+            ) -> Result<Self, DecodeError> {
+                let mut array: [u8; $len] = [0; $len];
+                read_fixed_len(reader, len, &mut array)?;
+
+                Ok(array)
+            }
         }
     };
 }
@@ -380,6 +448,10 @@ impl<T: Decode> Decode for Vec<T> {
             decode_list_of_variable_length_items(bytes)
         }
     }
+
+    fn from_ssz_reader<R: Read + ?Sized>(reader: &mut R, len: usize) -> Result<Self, DecodeError> {
+        decode_list_from_reader(reader, len, usize::max_value())
+    }
 }
 
 /// Decodes `bytes` as if it were a list of variable-length items.
@@ -433,6 +505,94 @@ pub fn decode_list_of_variable_length_items<T: Decode>(
     Ok(values)
 }
 
+/// Decodes a list of items from exactly `len` bytes read from `reader`, decoding each item as it
+/// is read.
+///
+/// Returns an error if the list contains more than `max_len` items. The number of items is known
+/// before any are decoded, so an oversized list is rejected without being read.
+pub fn decode_list_from_reader<T: Decode, R: Read + ?Sized>(
+    reader: &mut R,
+    len: usize,
+    max_len: usize,
+) -> Result<Vec<T>, DecodeError> {
+    if len == 0 {
+        return Ok(vec![]);
+    }
+
+    let check_num_items = |num_items: usize| {
+        if num_items > max_len {
+            Err(DecodeError::BytesInvalid(format!(
+                "List of {} items exceeds maximum of {}",
+                num_items, max_len
+            )))
+        } else {
+            Ok(())
+        }
+    };
+
+    if T::is_ssz_fixed_len() {
+        let item_len = T::ssz_fixed_len();
+
+        // The final item must not be truncated.
+        if len % item_len != 0 {
+            return Err(DecodeError::InvalidByteLength {
+                len: len % item_len,
+                expected: item_len,
+            });
+        }
+
+        let num_items = len / item_len;
+        check_num_items(num_items)?;
+
+        let mut values = Vec::with_capacity(num_items);
+        for _ in 0..num_items {
+            values.push(T::from_ssz_reader(reader, item_len)?);
+        }
+
+        Ok(values)
+    } else {
+        let first_offset = read_offset_from_reader(reader)?;
+
+        // The value of the first offset must not point back into the same bytes that defined
+        // it, nor beyond the end of the list.
+        if first_offset < BYTES_PER_LENGTH_OFFSET || first_offset > len {
+            return Err(DecodeError::OutOfBoundsByte { i: first_offset });
+        }
+
+        let num_items = first_offset / BYTES_PER_LENGTH_OFFSET;
+
+        // The fixed-length section must be a clean multiple of `BYTES_PER_LENGTH_OFFSET`.
+        if first_offset != num_items * BYTES_PER_LENGTH_OFFSET {
+            return Err(DecodeError::InvalidByteLength {
+                len: first_offset,
+                expected: num_items * BYTES_PER_LENGTH_OFFSET,
+            });
+        }
+
+        check_num_items(num_items)?;
+
+        let mut offsets = Vec::with_capacity(num_items + 1);
+        offsets.push(first_offset);
+        for _ in 1..num_items {
+            let offset = read_offset_from_reader(reader)?;
+
+            if offset < offsets[offsets.len() - 1] || offset > len {
+                return Err(DecodeError::OutOfBoundsByte { i: offset });
+            }
+
+            offsets.push(offset);
+        }
+        offsets.push(len);
+
+        let mut values = Vec::with_capacity(num_items);
+        for pair in offsets.windows(2) {
+            values.push(T::from_ssz_reader(reader, pair[1] - pair[0])?);
+        }
+
+        Ok(values)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,184 @@
+use super::*;
+
+/// An item returned by `SszStreamDecoder::read_next`.
+///
+/// Fixed-length items are decoded as soon as they are read. Variable-length items are only
+/// represented by an offset in the fixed-length portion of the container, so they remain
+/// `Pending` until passed to `SszStreamDecoder::decode_next`.
+pub enum SszStreamItem<T> {
+    Ready(T),
+    Pending,
+}
+
+/// Decodes the items of an SSZ container from a `Read`, without holding the full encoding in
+/// memory.
+///
+/// Items are read in two passes, matching the layout of the encoding:
+///
+/// 1. `read_next` is called for every item, in order. Fixed-length items are decoded directly
+///    from the reader, whilst the offsets of variable-length items are validated and stored.
+/// 2. `decode_next` is called for every item, in order, decoding the variable-length items from
+///    the reader.
+///
+/// `finish` then ensures that exactly the expected number of bytes were read.
+///
+/// ## Example
+///
+/// ```rust
+/// use ssz_derive::{Encode, Decode};
+/// use ssz::{Decode, Encode, SszStreamDecoder};
+///
+/// #[derive(PartialEq, Debug, Encode, Decode)]
+/// struct Foo {
+///     a: u64,
+///     b: Vec<u16>,
+/// }
+///
+/// fn main() {
+///     let foo = Foo {
+///         a: 42,
+///         b: vec![1, 3, 3, 7]
+///     };
+///
+///     let bytes = foo.as_ssz_bytes();
+///     let mut reader = &bytes[..];
+///
+///     let fixed_len = <u64 as Decode>::ssz_fixed_len() + <Vec<u16> as Decode>::ssz_fixed_len();
+///     let mut decoder = SszStreamDecoder::new(&mut reader, bytes.len(), fixed_len).unwrap();
+///
+///     let a = decoder.read_next::<u64>().unwrap();
+///     let b = decoder.read_next::<Vec<u16>>().unwrap();
+///
+///     let decoded_foo = Foo {
+///         a: decoder.decode_next(a).unwrap(),
+///         b: decoder.decode_next(b).unwrap(),
+///     };
+///
+///     decoder.finish().unwrap();
+///
+///     assert_eq!(foo, decoded_foo);
+/// }
+///
+/// ```
+pub struct SszStreamDecoder<'a, R: Read + ?Sized> {
+    reader: &'a mut R,
+    /// The total length of the container.
+    len: usize,
+    /// The length of the fixed-length portion of the container.
+    fixed_len: usize,
+    /// The number of bytes read so far.
+    position: usize,
+    /// The offsets of the variable-length items, in order.
+    offsets: Vec<usize>,
+    /// The index in `offsets` of the next variable-length item to be decoded.
+    offsets_index: usize,
+}
+
+impl<'a, R: Read + ?Sized> SszStreamDecoder<'a, R> {
+    /// Instantiate a new decoder for a container which occupies the next `len` bytes of
+    /// `reader`, the first `fixed_len` bytes of which are its fixed-length portion.
+    pub fn new(reader: &'a mut R, len: usize, fixed_len: usize) -> Result<Self, DecodeError> {
+        if fixed_len > len {
+            return Err(DecodeError::InvalidByteLength {
+                len,
+                expected: fixed_len,
+            });
+        }
+
+        Ok(Self {
+            reader,
+            len,
+            fixed_len,
+            position: 0,
+            offsets: vec![],
+            offsets_index: 0,
+        })
+    }
+
+    /// Reads the next item from the fixed-length portion of the container.
+    ///
+    /// Fixed-length items are decoded immediately. For variable-length items, the offset is
+    /// checked against the fixed-length portion, the previous offset and the container length.
+    pub fn read_next<T: Decode>(&mut self) -> Result<SszStreamItem<T>, DecodeError> {
+        let item_len = if T::is_ssz_fixed_len() {
+            T::ssz_fixed_len()
+        } else {
+            BYTES_PER_LENGTH_OFFSET
+        };
+
+        if self.position + item_len > self.fixed_len {
+            return Err(DecodeError::InvalidByteLength {
+                len: self.fixed_len,
+                expected: self.position + item_len,
+            });
+        }
+
+        let item = if T::is_ssz_fixed_len() {
+            SszStreamItem::Ready(T::from_ssz_reader(self.reader, item_len)?)
+        } else {
+            let offset = read_offset_from_reader(self.reader)?;
+
+            // The first offset must point to the byte immediately following the fixed-length
+            // bytes, and each subsequent offset must not point backwards.
+            let previous_offset = self.offsets.last().cloned().unwrap_or(self.fixed_len);
+
+            if (self.offsets.is_empty() && offset != self.fixed_len)
+                || (previous_offset > offset)
+                || (offset > self.len)
+            {
+                return Err(DecodeError::OutOfBoundsByte { i: offset });
+            }
+
+            self.offsets.push(offset);
+
+            SszStreamItem::Pending
+        };
+
+        self.position += item_len;
+
+        Ok(item)
+    }
+
+    /// Decodes the value of an item returned by `read_next`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when attempting to decode more variable-length items than were read.
+    pub fn decode_next<T: Decode>(&mut self, item: SszStreamItem<T>) -> Result<T, DecodeError> {
+        match item {
+            SszStreamItem::Ready(value) => Ok(value),
+            SszStreamItem::Pending => {
+                let start = self.offsets[self.offsets_index];
+                let end = self
+                    .offsets
+                    .get(self.offsets_index + 1)
+                    .cloned()
+                    .unwrap_or(self.len);
+
+                // Variable-length items must be decoded in order, after the fixed-length portion.
+                if self.position != start {
+                    return Err(DecodeError::OutOfBoundsByte { i: start });
+                }
+
+                let value = T::from_ssz_reader(self.reader, end - start)?;
+
+                self.offsets_index += 1;
+                self.position = end;
+
+                Ok(value)
+            }
+        }
+    }
+
+    /// Ensures that the entire container has been read.
+    pub fn finish(self) -> Result<(), DecodeError> {
+        if self.position != self.len {
+            return Err(DecodeError::InvalidByteLength {
+                len: self.len,
+                expected: self.position,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -39,7 +39,9 @@ mod encode;
 mod macros;
 
 pub use decode::{
-    impls::decode_list_of_variable_length_items, Decode, DecodeError, SszDecoder, SszDecoderBuilder,
+    impls::{decode_list_from_reader, decode_list_of_variable_length_items},
+    stream::{SszStreamDecoder, SszStreamItem},
+    Decode, DecodeError, SszDecoder, SszDecoderBuilder,
 };
 pub use encode::{Encode, SszEncoder};
 
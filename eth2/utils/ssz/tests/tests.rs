@@ -8,6 +8,10 @@ mod round_trip {
     fn round_trip<T: Encode + Decode + std::fmt::Debug + PartialEq>(items: Vec<T>) {
         for item in items {
             let encoded = &item.as_ssz_bytes();
+            assert_eq!(
+                T::from_ssz_reader(&mut &encoded[..], encoded.len()).as_ref(),
+                Ok(&item)
+            );
             assert_eq!(T::from_ssz_bytes(&encoded), Ok(item));
         }
     }
@@ -120,6 +124,13 @@ mod round_trip {
                 expected: 14,
             })
         );
+        assert_eq!(
+            FixedLen::from_ssz_reader(&mut &bytes[..], bytes.len()),
+            Err(DecodeError::InvalidByteLength {
+                len: 15,
+                expected: 14,
+            })
+        );
     }
 
     #[test]
@@ -152,6 +163,10 @@ mod round_trip {
             VariableLen::from_ssz_bytes(&bytes),
             Err(DecodeError::OutOfBoundsByte { i: 9 })
         );
+        assert_eq!(
+            VariableLen::from_ssz_reader(&mut &bytes[..], bytes.len()),
+            Err(DecodeError::OutOfBoundsByte { i: 9 })
+        );
     }
 
     #[test]
@@ -168,6 +183,7 @@ mod round_trip {
         // The error message triggered is not so helpful, it's caught by a side-effect. Just
         // checking there is _some_ error is fine.
         assert!(VariableLen::from_ssz_bytes(&bytes).is_err());
+        assert!(VariableLen::from_ssz_reader(&mut &bytes[..], bytes.len()).is_err());
     }
 
     #[test]
@@ -182,6 +198,10 @@ mod round_trip {
             VariableLen::from_ssz_bytes(&bytes),
             Err(DecodeError::OutOfBoundsByte { i: 11 })
         );
+        assert_eq!(
+            VariableLen::from_ssz_reader(&mut &bytes[..], bytes.len()),
+            Err(DecodeError::OutOfBoundsByte { i: 11 })
+        );
     }
 
     #[test]
@@ -284,6 +304,10 @@ mod round_trip {
             ThreeVariableLen::from_ssz_bytes(&bytes),
             Err(DecodeError::OutOfBoundsByte { i: 14 })
         );
+        assert_eq!(
+            ThreeVariableLen::from_ssz_reader(&mut &bytes[..], bytes.len()),
+            Err(DecodeError::OutOfBoundsByte { i: 14 })
+        );
     }
 
     #[derive(Debug, PartialEq, Encode, Decode)]
@@ -377,3 +401,123 @@ mod round_trip {
         round_trip(vec);
     }
 }
+
+mod from_reader {
+    use super::*;
+    use std::io::{ErrorKind, Read};
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Outer {
+        a: u16,
+        b: Vec<Inner>,
+        c: Vec<u8>,
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Inner {
+        a: Vec<u16>,
+        b: u64,
+    }
+
+    fn outer() -> Outer {
+        Outer {
+            a: 42,
+            b: vec![
+                Inner { a: vec![], b: 1 },
+                Inner {
+                    a: vec![1, 3, 3, 7],
+                    b: 2,
+                },
+            ],
+            c: vec![0, 1, 2],
+        }
+    }
+
+    #[test]
+    fn stops_at_len() {
+        let mut bytes = outer().as_ssz_bytes();
+        let len = bytes.len();
+        bytes.append(&mut vec![42; 3]);
+
+        let mut reader = &bytes[..];
+
+        assert_eq!(Outer::from_ssz_reader(&mut reader, len), Ok(outer()));
+        assert_eq!(reader, &[42; 3]);
+    }
+
+    #[test]
+    fn reader_too_short() {
+        let bytes = outer().as_ssz_bytes();
+
+        assert_eq!(
+            Outer::from_ssz_reader(&mut &bytes[..bytes.len() - 1], bytes.len()),
+            Err(DecodeError::ReadError(ErrorKind::UnexpectedEof))
+        );
+    }
+
+    #[test]
+    fn fixed_len_item_with_wrong_len_is_not_read() {
+        let bytes = 42_u64.as_ssz_bytes();
+        let mut reader = &bytes[..];
+
+        assert_eq!(
+            u64::from_ssz_reader(&mut reader, 4),
+            Err(DecodeError::InvalidByteLength {
+                len: 4,
+                expected: 8
+            })
+        );
+        assert_eq!(
+            H256::from_ssz_reader(&mut reader, 8),
+            Err(DecodeError::InvalidByteLength {
+                len: 8,
+                expected: 32
+            })
+        );
+        assert_eq!(reader, &bytes[..]);
+    }
+
+    #[test]
+    fn offset_beyond_len() {
+        let mut bytes = outer().as_ssz_bytes();
+        // Point the offset of `c` beyond the end of the container.
+        bytes[6..10].copy_from_slice(&1_000_u32.to_le_bytes());
+
+        assert_eq!(
+            Outer::from_ssz_reader(&mut &bytes[..], bytes.len()),
+            Err(DecodeError::OutOfBoundsByte { i: 1_000 })
+        );
+    }
+
+    #[test]
+    fn invalid_offset_is_rejected_before_reading_variable_bytes() {
+        let mut bytes = outer().as_ssz_bytes();
+        // Point the offset of `b` backwards into the fixed-length bytes.
+        bytes[2] = 0;
+
+        let mut reader = &bytes[..];
+
+        assert_eq!(
+            Outer::from_ssz_reader(&mut reader, bytes.len()),
+            Err(DecodeError::OutOfBoundsByte { i: 0 })
+        );
+        // Only the fixed-length bytes up to and including the bad offset were read.
+        assert_eq!(reader.bytes().count(), bytes.len() - 6);
+    }
+
+    #[test]
+    fn list_exceeding_max_len() {
+        let fixed = vec![1_u16, 2, 3].as_ssz_bytes();
+        let variable = vec![vec![1_u16], vec![2], vec![3]].as_ssz_bytes();
+
+        assert_eq!(
+            ssz::decode_list_from_reader::<u16, _>(&mut &fixed[..], fixed.len(), 3),
+            Ok(vec![1, 2, 3])
+        );
+        assert!(ssz::decode_list_from_reader::<u16, _>(&mut &fixed[..], fixed.len(), 2).is_err());
+        assert!(
+            ssz::decode_list_from_reader::<Vec<u16>, _>(&mut &variable[..], variable.len(), 2)
+                .is_err()
+        );
+    }
+}
@@ -138,7 +138,8 @@ fn should_skip_deserializing(field: &syn::Field) -> bool {
 
 /// Implements `ssz::Decode` for some `struct`.
 ///
-/// Fields are decoded in the order they are defined.
+/// Fields are decoded in the order they are defined. Both `from_ssz_bytes` and `from_ssz_reader`
+/// are implemented; the latter decodes each field directly from the reader.
 ///
 /// ## Field attributes
 ///
@@ -161,10 +162,13 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     let mut decodes = vec![];
     let mut is_fixed_lens = vec![];
     let mut fixed_lens = vec![];
+    let mut stream_reads = vec![];
+    let mut stream_decodes = vec![];
+    let mut stream_fields = vec![];
 
     // Build quotes for fields that should be deserialized and those that should be built from
     // `Default`.
-    for field in &struct_data.fields {
+    for (i, field) in struct_data.fields.iter().enumerate() {
         match &field.ident {
             Some(ref ident) => {
                 if should_skip_deserializing(field) {
@@ -172,8 +176,25 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
                     decodes.push(quote! {
                         #ident: <_>::default()
                     });
+
+                    stream_fields.push(quote! {
+                        #ident: <_>::default()
+                    });
                 } else {
                     let ty = &field.ty;
+                    let item = syn::Ident::new(&format!("__ssz_item_{}", i), ident.span());
+
+                    stream_reads.push(quote! {
+                        let #item = decoder.read_next::<#ty>()?;
+                    });
+
+                    stream_decodes.push(quote! {
+                        let #item = decoder.decode_next(#item)?;
+                    });
+
+                    stream_fields.push(quote! {
+                        #ident: #item
+                    });
 
                     register_types.push(quote! {
                         builder.register_type::<#ty>()?;
@@ -196,6 +217,8 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
         };
     }
 
+    let fixed_lens_b = fixed_lens.clone();
+
     let output = quote! {
         impl #impl_generics ssz::Decode for #name #ty_generics #where_clause {
             fn is_ssz_fixed_len() -> bool {
@@ -216,7 +239,7 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
                 }
             }
 
-            fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+            fn from_ssz_bytes(bytes: &[u8]) -> std::result::Result<Self, ssz::DecodeError> {
                 let mut builder = ssz::SszDecoderBuilder::new(bytes);
 
                 #(
@@ -225,12 +248,40 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
 
                 let mut decoder = builder.build()?;
 
-                Ok(Self {
+                std::result::Result::Ok(Self {
                     #(
                         #decodes,
                     )*
                 })
             }
+
+            fn from_ssz_reader<SszReader: std::io::Read + ?Sized>(
+                reader: &mut SszReader,
+                len: usize,
+            ) -> std::result::Result<Self, ssz::DecodeError> {
+                let fixed_len = #(
+                        #fixed_lens_b +
+                    )*
+                        0;
+
+                let mut decoder = ssz::SszStreamDecoder::new(reader, len, fixed_len)?;
+
+                #(
+                    #stream_reads
+                )*
+
+                #(
+                    #stream_decodes
+                )*
+
+                decoder.finish()?;
+
+                std::result::Result::Ok(Self {
+                    #(
+                        #stream_fields,
+                    )*
+                })
+            }
         }
     };
     output.into()
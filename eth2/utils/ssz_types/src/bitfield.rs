@@ -82,9 +82,12 @@ pub type BitVector<N> = Bitfield<Fixed<N>>;
 ///
 /// ## Note
 ///
-/// The internal representation of the bitfield is the same as that required by SSZ. The highest
-/// byte (by `Vec` index) stores the lowest bit-indices and the right-most bit stores the lowest
-/// bit-index. E.g., `vec![0b0000_0010, 0b0000_0001]` has bits `0, 9` set.
+/// In the internal representation of the bitfield the highest byte (by `Vec` index) stores the
+/// lowest bit-indices and the right-most bit stores the lowest bit-index. E.g., `vec![0b0000_0010,
+/// 0b0000_0001]` has bits `0, 9` set.
+///
+/// SSZ stores the lowest bit-indices in the _lowest_ byte, so the byte order is reversed by
+/// `into_bytes` and `from_bytes`.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Bitfield<T> {
     bytes: Vec<u8>,
@@ -146,14 +149,18 @@ impl<N: Unsigned + Clone> Bitfield<Variable<N>> {
             .expect("Bitfield capacity has been confirmed earlier.");
         bitfield.set(len, true).expect("Bitfield index must exist.");
 
-        bitfield.bytes
+        let mut bytes = bitfield.bytes;
+        bytes.reverse();
+        bytes
     }
 
     /// Instantiates a new instance from `bytes`. Consumes the same format that `self.into_bytes()`
     /// produces (SSZ).
     ///
     /// Returns `None` if `bytes` are not a valid encoding.
-    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+    pub fn from_bytes(mut bytes: Vec<u8>) -> Result<Self, Error> {
+        bytes.reverse();
+
         let mut initial_bitfield: Bitfield<Variable<N>> = {
             let num_bits = bytes.len() * 8;
             Bitfield::from_raw_bytes(bytes, num_bits)
@@ -215,14 +222,17 @@ impl<N: Unsigned + Clone> Bitfield<Fixed<N>> {
     /// assert_eq!(BitVector4::new().into_bytes(), vec![0b0000_0000]);
     /// ```
     pub fn into_bytes(self) -> Vec<u8> {
-        self.into_raw_bytes()
+        let mut bytes = self.into_raw_bytes();
+        bytes.reverse();
+        bytes
     }
 
     /// Instantiates a new instance from `bytes`. Consumes the same format that `self.into_bytes()`
     /// produces (SSZ).
     ///
     /// Returns `None` if `bytes` are not a valid encoding.
-    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+    pub fn from_bytes(mut bytes: Vec<u8>) -> Result<Self, Error> {
+        bytes.reverse();
         Self::from_raw_bytes(bytes, Self::capacity())
     }
 }
@@ -362,6 +372,14 @@ impl<T: BitfieldBehaviour> Bitfield<T> {
         self.bytes.iter().all(|byte| *byte == 0)
     }
 
+    /// Returns the number of bits that are set to `true`.
+    pub fn num_set_bits(&self) -> usize {
+        self.bytes
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+
     /// Compute the intersection (binary-and) of this bitfield with another.
     ///
     /// Returns `None` if `self.is_comparable(other) == false`.
@@ -451,6 +469,11 @@ fn bytes_for_bit_len(bit_len: usize) -> usize {
     std::cmp::max(1, (bit_len + 7) / 8)
 }
 
+/// Returns the number of 32-byte tree hash chunks required to store `bit_len` bits.
+fn chunks_for_bit_len(bit_len: usize) -> usize {
+    (bit_len + 255) / 256
+}
+
 /// An iterator over the bits in a `Bitfield`.
 pub struct BitIter<'a, T> {
     bitfield: &'a Bitfield<T>,
@@ -487,6 +510,25 @@ impl<N: Unsigned + Clone> Decode for Bitfield<Variable<N>> {
             ssz::DecodeError::BytesInvalid(format!("BitList failed to decode: {:?}", e))
         })
     }
+    fn from_ssz_reader<R: std::io::Read + ?Sized>(
+        reader: &mut R,
+        len: usize,
+    ) -> Result<Self, ssz::DecodeError> {
+        // Reject oversized inputs before buffering them.
+        let max_len = bytes_for_bit_len(N::to_usize() + 1);
+
+        if len > max_len {
+            return Err(ssz::DecodeError::BytesInvalid(format!(
+                "BitList failed to decode: {} bytes exceeds maximum of {}",
+                len, max_len
+            )));
+        }
+
+        let mut bytes = vec![0; len];
+        reader.read_exact(&mut bytes)?;
+
+        Self::from_ssz_bytes(&bytes)
+    }
 }
 
 impl<N: Unsigned + Clone> Encode for Bitfield<Fixed<N>> {
@@ -505,7 +547,11 @@ impl<N: Unsigned + Clone> Encode for Bitfield<Fixed<N>> {
 
 impl<N: Unsigned + Clone> Decode for Bitfield<Fixed<N>> {
     fn is_ssz_fixed_len() -> bool {
-        false
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        bytes_for_bit_len(N::to_usize())
     }
 
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
@@ -573,75 +619,33 @@ impl<N: Unsigned + Clone> tree_hash::TreeHash for Bitfield<Variable<N>> {
     }
 
     fn tree_hash_root(&self) -> Vec<u8> {
-        // TODO: pad this out to max length.
-        self.as_ssz_bytes().tree_hash_root()
+        // The root of the bits (without the length bit), padded to the maximum length.
+        let mut bytes = self.as_slice().to_vec();
+        bytes.reverse();
+        let root = tree_hash::merkleize_padded(&bytes, chunks_for_bit_len(N::to_usize()));
+
+        tree_hash::mix_in_length(&root, self.len())
     }
 }
 
 impl<N: Unsigned + Clone> tree_hash::TreeHash for Bitfield<Fixed<N>> {
     fn tree_hash_type() -> tree_hash::TreeHashType {
-        // TODO: move this to be a vector.
-        tree_hash::TreeHashType::List
+        tree_hash::TreeHashType::Vector
     }
 
     fn tree_hash_packed_encoding(&self) -> Vec<u8> {
-        // TODO: move this to be a vector.
         unreachable!("Vector should never be packed.")
     }
 
     fn tree_hash_packing_factor() -> usize {
-        // TODO: move this to be a vector.
         unreachable!("Vector should never be packed.")
     }
 
     fn tree_hash_root(&self) -> Vec<u8> {
-        self.as_ssz_bytes().tree_hash_root()
-    }
-}
-
-impl<N: Unsigned + Clone> cached_tree_hash::CachedTreeHash for Bitfield<Variable<N>> {
-    fn new_tree_hash_cache(
-        &self,
-        depth: usize,
-    ) -> Result<cached_tree_hash::TreeHashCache, cached_tree_hash::Error> {
-        let bytes = self.clone().into_bytes();
-
-        let (mut cache, schema) = cached_tree_hash::vec::new_tree_hash_cache(&bytes, depth)?;
-
-        cache.add_length_nodes(schema.into_overlay(0).chunk_range(), bytes.len())?;
-
-        Ok(cache)
-    }
-
-    fn num_tree_hash_cache_chunks(&self) -> usize {
-        // Add two extra nodes to cater for the node before and after to allow mixing-in length.
-        cached_tree_hash::BTreeOverlay::new(self, 0, 0).num_chunks() + 2
-    }
-
-    fn tree_hash_cache_schema(&self, depth: usize) -> cached_tree_hash::BTreeSchema {
-        let bytes = self.clone().into_bytes();
-        cached_tree_hash::vec::produce_schema(&bytes, depth)
-    }
-
-    fn update_tree_hash_cache(
-        &self,
-        cache: &mut cached_tree_hash::TreeHashCache,
-    ) -> Result<(), cached_tree_hash::Error> {
-        let bytes = self.clone().into_bytes();
-
-        // Skip the length-mixed-in root node.
-        cache.chunk_index += 1;
-
-        // Update the cache, returning the new overlay.
-        let new_overlay = cached_tree_hash::vec::update_tree_hash_cache(&bytes, cache)?;
-
-        // Mix in length
-        cache.mix_in_length(new_overlay.chunk_range(), bytes.len())?;
-
-        // Skip an extra node to clear the length node.
-        cache.chunk_index += 1;
-
-        Ok(())
+        tree_hash::merkleize_padded(
+            &self.clone().into_bytes(),
+            chunks_for_bit_len(N::to_usize()),
+        )
     }
 }
 
@@ -806,7 +810,7 @@ mod bitlist {
 
         assert_eq!(
             BitList8::with_capacity(8).unwrap().as_ssz_bytes(),
-            vec![0b0000_0001, 0b0000_0000],
+            vec![0b0000_0000, 0b0000_0001],
         );
 
         assert_eq!(
@@ -818,17 +822,17 @@ mod bitlist {
         for i in 0..8 {
             b.set(i, true).unwrap();
         }
-        assert_eq!(b.as_ssz_bytes(), vec![0b0000_0001, 255]);
+        assert_eq!(b.as_ssz_bytes(), vec![255, 0b0000_0001]);
 
         let mut b = BitList8::with_capacity(8).unwrap();
         for i in 0..4 {
             b.set(i, true).unwrap();
         }
-        assert_eq!(b.as_ssz_bytes(), vec![0b0000_0001, 0b0000_1111]);
+        assert_eq!(b.as_ssz_bytes(), vec![0b0000_1111, 0b0000_0001]);
 
         assert_eq!(
             BitList16::with_capacity(16).unwrap().as_ssz_bytes(),
-            vec![0b0000_0001, 0b0000_0000, 0b0000_0000]
+            vec![0b0000_0000, 0b0000_0000, 0b0000_0001]
         );
     }
 
@@ -848,8 +852,11 @@ mod bitlist {
 
         assert!(BitList8::from_ssz_bytes(&[0b0000_0001]).is_ok());
         assert!(BitList8::from_ssz_bytes(&[0b0000_0010]).is_ok());
-        assert!(BitList8::from_ssz_bytes(&[0b0000_0001, 0b0000_0100]).is_ok());
-        assert!(BitList8::from_ssz_bytes(&[0b0000_0010, 0b0000_0100]).is_err());
+        assert!(BitList8::from_ssz_bytes(&[0b0000_0100, 0b0000_0001]).is_ok());
+        assert!(BitList8::from_ssz_bytes(&[0b0000_0100, 0b0000_0010]).is_err());
+
+        let oversized = [0b0000_0000, 0b0000_0000, 0b0000_0001];
+        assert!(BitList8::from_ssz_reader(&mut &oversized[..], oversized.len()).is_err());
     }
 
     #[test]
@@ -904,7 +911,9 @@ mod bitlist {
     }
 
     fn assert_round_trip<T: Encode + Decode + PartialEq + std::fmt::Debug>(t: T) {
-        assert_eq!(T::from_ssz_bytes(&t.as_ssz_bytes()).unwrap(), t);
+        let bytes = t.as_ssz_bytes();
+        assert_eq!(T::from_ssz_reader(&mut &bytes[..], bytes.len()).unwrap(), t);
+        assert_eq!(T::from_ssz_bytes(&bytes).unwrap(), t);
     }
 
     #[test]
@@ -1126,6 +1135,16 @@ mod bitlist {
         assert!(a.difference(&a).unwrap().is_zero());
     }
 
+    #[test]
+    fn num_set_bits() {
+        let a = BitList1024::from_raw_bytes(vec![0b1100, 0b0001], 16).unwrap();
+        let b = BitList1024::from_raw_bytes(vec![0b1011, 0b1001], 16).unwrap();
+
+        assert_eq!(a.num_set_bits(), 3);
+        assert_eq!(b.num_set_bits(), 5);
+        assert_eq!(BitList1024::with_capacity(16).unwrap().num_set_bits(), 0);
+    }
+
     #[test]
     fn iter() {
         let mut bitfield = BitList1024::with_capacity(9).unwrap();
@@ -1137,4 +1156,20 @@ mod bitlist {
             vec![false, false, true, false, false, false, false, false, true]
         );
     }
+
+    #[test]
+    fn tree_hash() {
+        use tree_hash::TreeHash;
+
+        let mut b = BitList1024::with_capacity(9).unwrap();
+        b.set(0, true).unwrap();
+        b.set(8, true).unwrap();
+
+        // Bits are packed little-endian and the tree is padded to hold 1,024 bits (4 chunks).
+        let mut chunk = vec![0b0000_0001, 0b0000_0001];
+        chunk.resize(32, 0);
+        let root = tree_hash::merkleize_padded(&chunk, 4);
+
+        assert_eq!(b.tree_hash_root(), tree_hash::mix_in_length(&root, 9));
+    }
 }
@@ -212,7 +212,7 @@ where
     }
 
     fn tree_hash_root(&self) -> Vec<u8> {
-        tree_hash::impls::vec_tree_hash_root(&self.vec)
+        tree_hash::impls::padded_iter_tree_hash_root(self.vec.iter(), N::to_usize())
     }
 }
 
@@ -307,6 +307,13 @@ where
             ssz::decode_list_of_variable_length_items(bytes).and_then(|vec| Ok(vec.into()))
         }
     }
+
+    fn from_ssz_reader<R: std::io::Read + ?Sized>(
+        reader: &mut R,
+        len: usize,
+    ) -> Result<Self, ssz::DecodeError> {
+        ssz::decode_list_from_reader(reader, len, N::to_usize()).map(Into::into)
+    }
 }
 
 #[cfg(test)]
@@ -324,6 +331,10 @@ mod ssz_tests {
 
     fn round_trip<T: Encode + Decode + std::fmt::Debug + PartialEq>(item: T) {
         let encoded = &item.as_ssz_bytes();
+        assert_eq!(
+            T::from_ssz_reader(&mut &encoded[..], encoded.len()).as_ref(),
+            Ok(&item)
+        );
         assert_eq!(T::from_ssz_bytes(&encoded), Ok(item));
     }
 
@@ -1,7 +1,8 @@
 use crate::Error;
 use serde_derive::{Deserialize, Serialize};
+use std::iter::FromIterator;
 use std::marker::PhantomData;
-use std::ops::{Deref, Index, IndexMut};
+use std::ops::{Deref, DerefMut, Index, IndexMut};
 use std::slice::SliceIndex;
 use typenum::Unsigned;
 
@@ -68,6 +69,14 @@ impl<T, N: Unsigned> VariableList<T, N> {
         }
     }
 
+    /// Create an empty list.
+    pub fn empty() -> Self {
+        Self {
+            vec: vec![],
+            _phantom: PhantomData,
+        }
+    }
+
     /// Returns the number of values presently in `self`.
     pub fn len(&self) -> usize {
         self.vec.len()
@@ -99,7 +108,7 @@ impl<T, N: Unsigned> VariableList<T, N> {
     }
 }
 
-impl<T: Default, N: Unsigned> From<Vec<T>> for VariableList<T, N> {
+impl<T, N: Unsigned> From<Vec<T>> for VariableList<T, N> {
     fn from(mut vec: Vec<T>) -> Self {
         vec.truncate(N::to_usize());
 
@@ -110,6 +119,16 @@ impl<T: Default, N: Unsigned> From<Vec<T>> for VariableList<T, N> {
     }
 }
 
+/// Items past the maximum length are dropped, as per `VariableList::from`.
+impl<T, N: Unsigned> FromIterator<T> for VariableList<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            vec: iter.into_iter().take(N::to_usize()).collect(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
 impl<T, N: Unsigned> Into<Vec<T>> for VariableList<T, N> {
     fn into(self) -> Vec<T> {
         self.vec
@@ -149,6 +168,21 @@ impl<T, N: Unsigned> Deref for VariableList<T, N> {
     }
 }
 
+impl<T, N: Unsigned> DerefMut for VariableList<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.vec[..]
+    }
+}
+
+impl<'a, T, N: Unsigned> IntoIterator for &'a VariableList<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -215,46 +249,21 @@ where
     T: tree_hash::TreeHash,
 {
     fn tree_hash_type() -> tree_hash::TreeHashType {
-        tree_hash::TreeHashType::Vector
+        tree_hash::TreeHashType::List
     }
 
     fn tree_hash_packed_encoding(&self) -> Vec<u8> {
-        unreachable!("Vector should never be packed.")
+        unreachable!("List should never be packed.")
     }
 
     fn tree_hash_packing_factor() -> usize {
-        unreachable!("Vector should never be packed.")
+        unreachable!("List should never be packed.")
     }
 
     fn tree_hash_root(&self) -> Vec<u8> {
-        tree_hash::impls::vec_tree_hash_root(&self.vec)
-    }
-}
-
-impl<T, N: Unsigned> cached_tree_hash::CachedTreeHash for VariableList<T, N>
-where
-    T: cached_tree_hash::CachedTreeHash + tree_hash::TreeHash,
-{
-    fn new_tree_hash_cache(
-        &self,
-        depth: usize,
-    ) -> Result<cached_tree_hash::TreeHashCache, cached_tree_hash::Error> {
-        let (cache, _overlay) = cached_tree_hash::vec::new_tree_hash_cache(&self.vec, depth)?;
-
-        Ok(cache)
-    }
+        let root = tree_hash::impls::padded_iter_tree_hash_root(self.vec.iter(), N::to_usize());
 
-    fn tree_hash_cache_schema(&self, depth: usize) -> cached_tree_hash::BTreeSchema {
-        cached_tree_hash::vec::produce_schema(&self.vec, depth)
-    }
-
-    fn update_tree_hash_cache(
-        &self,
-        cache: &mut cached_tree_hash::TreeHashCache,
-    ) -> Result<(), cached_tree_hash::Error> {
-        cached_tree_hash::vec::update_tree_hash_cache(&self.vec, cache)?;
-
-        Ok(())
+        tree_hash::mix_in_length(&root, self.len())
     }
 }
 
@@ -277,7 +286,7 @@ where
 
 impl<T, N: Unsigned> ssz::Decode for VariableList<T, N>
 where
-    T: ssz::Decode + Default,
+    T: ssz::Decode,
 {
     fn is_ssz_fixed_len() -> bool {
         <Vec<T>>::is_ssz_fixed_len()
@@ -292,6 +301,15 @@ where
 
         Self::new(vec).map_err(|e| ssz::DecodeError::BytesInvalid(format!("VariableList {:?}", e)))
     }
+
+    fn from_ssz_reader<R: std::io::Read + ?Sized>(
+        reader: &mut R,
+        len: usize,
+    ) -> Result<Self, ssz::DecodeError> {
+        let vec = ssz::decode_list_from_reader(reader, len, N::to_usize())?;
+
+        Self::new(vec).map_err(|e| ssz::DecodeError::BytesInvalid(format!("VariableList {:?}", e)))
+    }
 }
 
 #[cfg(test)]
@@ -309,6 +327,10 @@ mod tests {
 
     fn round_trip<T: Encode + Decode + std::fmt::Debug + PartialEq>(item: T) {
         let encoded = &item.as_ssz_bytes();
+        assert_eq!(
+            T::from_ssz_reader(&mut &encoded[..], encoded.len()).as_ref(),
+            Ok(&item)
+        );
         assert_eq!(T::from_ssz_bytes(&encoded), Ok(item));
     }
 
@@ -317,4 +339,21 @@ mod tests {
         round_trip::<VariableList<u16, U8>>(vec![42; 8].into());
         round_trip::<VariableList<u16, U8>>(vec![0; 8].into());
     }
+
+    #[test]
+    fn tree_hash_root() {
+        use tree_hash::TreeHash;
+
+        let list: VariableList<u64, U8> = vec![1, 2, 3].into();
+
+        // Eight `u64` values are packed into two chunks, only the first of which is used.
+        let mut leaves: Vec<u8> = [1_u64, 2, 3]
+            .iter()
+            .flat_map(|i| i.to_le_bytes().to_vec())
+            .collect();
+        leaves.resize(64, 0);
+        let root = tree_hash::merkle_root(&leaves);
+
+        assert_eq!(list.tree_hash_root(), tree_hash::mix_in_length(&root, 3));
+    }
 }
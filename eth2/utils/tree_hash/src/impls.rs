@@ -1,7 +1,6 @@
 use super::*;
-use crate::merkle_root;
+use crate::{merkle_root, merkleize_padded, mix_in_length};
 use ethereum_types::H256;
-use hashing::hash;
 use int_to_bytes::int_to_bytes32;
 
 macro_rules! impl_for_bitsize {
@@ -113,11 +112,7 @@ macro_rules! impl_for_list {
             }
 
             fn tree_hash_root(&self) -> Vec<u8> {
-                let mut root_and_len = Vec::with_capacity(HASHSIZE * 2);
-                root_and_len.append(&mut vec_tree_hash_root(self));
-                root_and_len.append(&mut int_to_bytes32(self.len() as u64));
-
-                hash(&root_and_len)
+                mix_in_length(&vec_tree_hash_root(self), self.len())
             }
         }
     };
@@ -130,29 +125,66 @@ pub fn vec_tree_hash_root<T>(vec: &[T]) -> Vec<u8>
 where
     T: TreeHash,
 {
-    let leaves = match T::tree_hash_type() {
+    iter_tree_hash_root(vec.iter())
+}
+
+/// As per `vec_tree_hash_root`, for list types which are not backed by a slice.
+pub fn iter_tree_hash_root<'a, T, I>(iter: I) -> Vec<u8>
+where
+    T: TreeHash + 'a,
+    I: ExactSizeIterator<Item = &'a T>,
+{
+    merkle_root(&iter_tree_hash_leaves(iter))
+}
+
+/// Returns the root of a tree with enough leaves to hold `max_len` items, only the first of which
+/// are supplied by `iter`.
+///
+/// This is the root of an SSZ `List[T, max_len]` _before_ its length is mixed in, or the root of
+/// a `Vector[T, max_len]`.
+pub fn padded_iter_tree_hash_root<'a, T, I>(iter: I, max_len: usize) -> Vec<u8>
+where
+    T: TreeHash + 'a,
+    I: ExactSizeIterator<Item = &'a T>,
+{
+    let min_leaves = match T::tree_hash_type() {
+        TreeHashType::Basic => {
+            let packing_factor = T::tree_hash_packing_factor();
+            (max_len + packing_factor - 1) / packing_factor
+        }
+        TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => max_len,
+    };
+
+    merkleize_padded(&iter_tree_hash_leaves(iter), min_leaves)
+}
+
+/// Returns the concatenated leaf chunks for the items in `iter`, packing basic types.
+fn iter_tree_hash_leaves<'a, T, I>(iter: I) -> Vec<u8>
+where
+    T: TreeHash + 'a,
+    I: ExactSizeIterator<Item = &'a T>,
+{
+    match T::tree_hash_type() {
         TreeHashType::Basic => {
             let mut leaves =
-                Vec::with_capacity((HASHSIZE / T::tree_hash_packing_factor()) * vec.len());
+                Vec::with_capacity((HASHSIZE / T::tree_hash_packing_factor()) * iter.len());
 
-            for item in vec {
+            for item in iter {
                 leaves.append(&mut item.tree_hash_packed_encoding());
             }
 
             leaves
         }
         TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => {
-            let mut leaves = Vec::with_capacity(vec.len() * HASHSIZE);
+            let mut leaves = Vec::with_capacity(iter.len() * HASHSIZE);
 
-            for item in vec {
+            for item in iter {
                 leaves.append(&mut item.tree_hash_root())
             }
 
             leaves
         }
-    };
-
-    merkle_root(&leaves)
+    }
 }
 
 #[cfg(test)]
@@ -170,4 +202,21 @@ mod test {
         assert_eq!(false.tree_hash_root(), false_bytes);
     }
 
+    #[test]
+    fn padded_list_root() {
+        let list: Vec<u64> = vec![1, 2, 3];
+
+        // Four `u64` fit in a single chunk, so a limit of four does not add any padding.
+        assert_eq!(
+            padded_iter_tree_hash_root(list.iter(), 4),
+            vec_tree_hash_root(&list)
+        );
+
+        let mut padded = vec_tree_hash_root(&list);
+        padded.append(&mut vec![0; HASHSIZE]);
+        assert_eq!(
+            padded_iter_tree_hash_root(list.iter(), 8),
+            hashing::hash(&padded)
+        );
+    }
 }
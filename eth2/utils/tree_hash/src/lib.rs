@@ -5,7 +5,7 @@ pub mod impls;
 mod merkleize_padded;
 mod merkleize_standard;
 
-pub use merkleize_padded::merkleize_padded;
+pub use merkleize_padded::{get_zero_hash, merkleize_padded};
 pub use merkleize_standard::merkleize_standard;
 
 /// Alias to `merkleize_padded(&bytes, 0)`
@@ -13,6 +13,15 @@ pub fn merkle_root(bytes: &[u8]) -> Vec<u8> {
     merkleize_padded(&bytes, 0)
 }
 
+/// Returns the node created by hashing `root` and `length`, as is done for SSZ lists.
+pub fn mix_in_length(root: &[u8], length: usize) -> Vec<u8> {
+    let mut root_and_len = Vec::with_capacity(HASHSIZE * 2);
+    root_and_len.extend_from_slice(root);
+    root_and_len.append(&mut int_to_bytes::int_to_bytes32(length as u64));
+
+    hashing::hash(&root_and_len)
+}
+
 pub const BYTES_PER_CHUNK: usize = 32;
 pub const HASHSIZE: usize = 32;
 pub const MERKLE_HASH_CHUNK: usize = 2 * BYTES_PER_CHUNK;
@@ -227,8 +227,9 @@ impl ChunkStore {
     }
 }
 
-/// Returns a cached padding node for a given height.
-fn get_zero_hash(height: usize) -> &'static [u8] {
+/// Returns a cached padding node for a given height, i.e., the root of a tree with `2^height` zero
+/// leaves.
+pub fn get_zero_hash(height: usize) -> &'static [u8] {
     if height <= MAX_TREE_DEPTH {
         &ZERO_HASHES[height]
     } else {
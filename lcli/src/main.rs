@@ -0,0 +1,83 @@
+mod transition;
+
+use clap::{App, Arg, SubCommand};
+
+fn main() {
+    let matches = App::new("Lighthouse CLI Tool")
+        .version("0.1.0")
+        .author("Sigma Prime <contact@sigmaprime.io>")
+        .about("Performs various Lighthouse developer tasks offline, against files on disk.")
+        .subcommand(
+            SubCommand::with_name("transition")
+                .about("Applies blocks and/or empty slots to a pre-state, producing a post-state.")
+                .version("0.1.0")
+                .author("Sigma Prime <contact@sigmaprime.io>")
+                .arg(
+                    Arg::with_name("spec")
+                        .long("spec")
+                        .short("s")
+                        .value_name("SPEC")
+                        .help("The EthSpec of the states and blocks.")
+                        .takes_value(true)
+                        .possible_values(&["minimal", "mainnet"])
+                        .default_value("minimal"),
+                )
+                .arg(
+                    Arg::with_name("pre-state")
+                        .long("pre-state")
+                        .value_name("FILE")
+                        .help("The pre-state, as SSZ (if the file ends in `.ssz`) or YAML.")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("block")
+                        .long("block")
+                        .short("b")
+                        .value_name("FILE")
+                        .help("A block to apply, as SSZ or YAML. May be supplied multiple times.")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("slots")
+                        .long("slots")
+                        .short("n")
+                        .value_name("N")
+                        .help("The number of empty slots to process after applying the blocks.")
+                        .takes_value(true)
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::with_name("post-state")
+                        .long("post-state")
+                        .value_name("FILE")
+                        .help("Where to write the post-state, as SSZ or YAML.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("trace")
+                        .long("trace")
+                        .value_name("FILE")
+                        .help("Write a YAML report of the fields changed by each processing step.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("no-signature-verification")
+                        .long("no-signature-verification")
+                        .help("Do not verify the block proposer signatures."),
+                ),
+        )
+        .get_matches();
+
+    let result = match matches.subcommand() {
+        ("transition", Some(matches)) => transition::run(matches),
+        _ => Err("A subcommand must be supplied. See --help for more information.".to_string()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
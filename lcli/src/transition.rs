@@ -0,0 +1,158 @@
+use clap::ArgMatches;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use ssz::{Decode, Encode};
+use state_processing::{
+    per_block_processing_with_tracer, per_slot_processing_with_tracer, NoRewards, NoTracer,
+    StateDiffTracer, StateTracer,
+};
+use std::fs;
+use std::path::Path;
+use types::*;
+
+/// Runs the `transition` subcommand, with the `EthSpec` given by the `spec` argument.
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    match matches.value_of("spec") {
+        Some("minimal") => run_with_spec::<MinimalEthSpec>(matches),
+        Some("mainnet") => run_with_spec::<MainnetEthSpec>(matches),
+        other => Err(format!("Unknown spec: {:?}", other)),
+    }
+}
+
+fn run_with_spec<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let spec = T::default_spec();
+
+    let pre_state_path = matches
+        .value_of("pre-state")
+        .ok_or_else(|| "A pre-state is required".to_string())?;
+    let mut state: BeaconState<T> = load(Path::new(pre_state_path))?;
+
+    let blocks: Vec<BeaconBlock<T>> = matches
+        .values_of("block")
+        .map(|paths| paths.map(|path| load(Path::new(path))).collect())
+        .unwrap_or_else(|| Ok(vec![]))?;
+
+    let slots: u64 = matches
+        .value_of("slots")
+        .unwrap_or("0")
+        .parse()
+        .map_err(|e| format!("Invalid number of slots: {:?}", e))?;
+
+    let verify_block_signatures = !matches.is_present("no-signature-verification");
+
+    let result = match matches.value_of("trace") {
+        Some(trace_path) => {
+            let mut tracer = StateDiffTracer::new(&state);
+            let result = transition(
+                &mut state,
+                &blocks,
+                slots,
+                verify_block_signatures,
+                &spec,
+                &mut tracer,
+            );
+
+            // The report is written regardless of the result, since it shows the steps leading up
+            // to any failure.
+            let yaml = serde_yaml::to_string(tracer.report())
+                .map_err(|e| format!("Unable to encode trace report: {}", e))?;
+            fs::write(trace_path, yaml)
+                .map_err(|e| format!("Unable to write {}: {}", trace_path, e))?;
+
+            result
+        }
+        None => transition(
+            &mut state,
+            &blocks,
+            slots,
+            verify_block_signatures,
+            &spec,
+            &mut NoTracer,
+        ),
+    };
+    result?;
+
+    println!("slot: {}", state.slot);
+    println!("state_root: {:?}", state.canonical_root());
+
+    if let Some(post_state_path) = matches.value_of("post-state") {
+        store(Path::new(post_state_path), &state)?;
+    }
+
+    Ok(())
+}
+
+/// Applies each of the `blocks` to `state` (processing empty slots as required to reach the slot
+/// of each block), then processes `slots` empty slots.
+fn transition<T: EthSpec, U: StateTracer<T>>(
+    state: &mut BeaconState<T>,
+    blocks: &[BeaconBlock<T>],
+    slots: u64,
+    verify_block_signatures: bool,
+    spec: &ChainSpec,
+    tracer: &mut U,
+) -> Result<(), String> {
+    for (i, block) in blocks.iter().enumerate() {
+        if block.slot < state.slot {
+            return Err(format!(
+                "Block {} is at slot {}, which is prior to the state slot {}",
+                i, block.slot, state.slot
+            ));
+        }
+
+        while state.slot < block.slot {
+            process_slot(state, spec, tracer)?;
+        }
+
+        per_block_processing_with_tracer(state, block, verify_block_signatures, spec, tracer)
+            .map_err(|e| format!("Block {} at slot {} is invalid: {:?}", i, block.slot, e))?;
+
+        let state_root = state.canonical_root();
+        if block.state_root != state_root {
+            eprintln!(
+                "Warning: block {} has state root {:?}, but the post-state root is {:?}",
+                i, block.state_root, state_root
+            );
+        }
+    }
+
+    for _ in 0..slots {
+        process_slot(state, spec, tracer)?;
+    }
+
+    Ok(())
+}
+
+fn process_slot<T: EthSpec, U: StateTracer<T>>(
+    state: &mut BeaconState<T>,
+    spec: &ChainSpec,
+    tracer: &mut U,
+) -> Result<(), String> {
+    per_slot_processing_with_tracer(state, spec, tracer, &mut NoRewards)
+        .map_err(|e| format!("Slot processing failed at slot {}: {:?}", state.slot, e))
+}
+
+/// Returns `true` if `path` should be read or written as SSZ, rather than YAML.
+fn is_ssz(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "ssz")
+}
+
+fn load<T: Decode + DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Unable to read {:?}: {}", path, e))?;
+
+    if is_ssz(path) {
+        T::from_ssz_bytes(&bytes).map_err(|e| format!("Unable to decode {:?}: {:?}", path, e))
+    } else {
+        serde_yaml::from_slice(&bytes).map_err(|e| format!("Unable to decode {:?}: {}", path, e))
+    }
+}
+
+fn store<T: Encode + Serialize>(path: &Path, item: &T) -> Result<(), String> {
+    let bytes = if is_ssz(path) {
+        item.as_ssz_bytes()
+    } else {
+        serde_yaml::to_vec(item).map_err(|e| format!("Unable to encode {:?}: {}", path, e))?
+    };
+
+    fs::write(path, bytes).map_err(|e| format!("Unable to write {:?}: {}", path, e))
+}
@@ -1,6 +1,7 @@
 use super::*;
 use compare_fields::{CompareFields, Comparison, FieldComparison};
 use std::fmt::Debug;
+use std::path::{Path, PathBuf};
 use types::BeaconState;
 
 pub const MAX_VALUE_STRING_LEN: usize = 500;
@@ -8,14 +9,21 @@ pub const MAX_VALUE_STRING_LEN: usize = 500;
 #[derive(Debug, PartialEq, Clone)]
 pub struct CaseResult {
     pub case_index: usize,
+    pub path: PathBuf,
     pub desc: String,
     pub result: Result<(), Error>,
 }
 
 impl CaseResult {
-    pub fn new(case_index: usize, case: &impl Case, result: Result<(), Error>) -> Self {
+    pub fn new(
+        case_index: usize,
+        path: &Path,
+        case: &impl Case,
+        result: Result<(), Error>,
+    ) -> Self {
         CaseResult {
             case_index,
+            path: path.to_path_buf(),
             desc: case.description(),
             result,
         }
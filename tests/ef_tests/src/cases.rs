@@ -1,5 +1,9 @@
 use super::*;
+use crate::bls_setting::BlsSetting;
+use crate::decode::{ssz_decode_file, yaml_decode_file};
+use serde_derive::Deserialize;
 use std::fmt::Debug;
+use std::path::{Path, PathBuf};
 
 mod bls_aggregate_pubkeys;
 mod bls_aggregate_sigs;
@@ -8,7 +12,12 @@ mod bls_g2_uncompressed;
 mod bls_priv_to_pub;
 mod bls_sign_msg;
 mod epoch_processing_crosslinks;
+mod epoch_processing_final_updates;
+mod epoch_processing_justification_and_finalization;
 mod epoch_processing_registry_updates;
+mod epoch_processing_slashings;
+mod genesis_initialization;
+mod genesis_validity;
 mod operations_attestation;
 mod operations_attester_slashing;
 mod operations_block_header;
@@ -29,7 +38,12 @@ pub use bls_g2_uncompressed::*;
 pub use bls_priv_to_pub::*;
 pub use bls_sign_msg::*;
 pub use epoch_processing_crosslinks::*;
+pub use epoch_processing_final_updates::*;
+pub use epoch_processing_justification_and_finalization::*;
 pub use epoch_processing_registry_updates::*;
+pub use epoch_processing_slashings::*;
+pub use genesis_initialization::*;
+pub use genesis_validity::*;
 pub use operations_attestation::*;
 pub use operations_attester_slashing::*;
 pub use operations_block_header::*;
@@ -58,9 +72,43 @@ pub trait Case: Debug {
     fn result(&self, case_index: usize) -> Result<(), Error>;
 }
 
+/// Loads a test case from its directory of the test vectors.
+pub trait LoadCase: Sized {
+    fn load_from_dir(path: &Path) -> Result<Self, Error>;
+}
+
+/// The optional `meta.yaml` file of a test case.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Metadata {
+    pub description: Option<String>,
+    pub bls_setting: Option<BlsSetting>,
+}
+
+impl Metadata {
+    /// Loads the `meta.yaml` file in `path`, if it exists.
+    pub fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        let meta_path = path.join("meta.yaml");
+
+        if meta_path.exists() {
+            yaml_decode_file(&meta_path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+}
+
+/// Loads the SSZ file at `path`, if it exists.
+pub fn ssz_decode_optional_file<T: ssz::Decode>(path: &Path) -> Result<Option<T>, Error> {
+    if path.exists() {
+        ssz_decode_file(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
 #[derive(Debug)]
 pub struct Cases<T> {
-    pub test_cases: Vec<T>,
+    pub test_cases: Vec<(PathBuf, T)>,
 }
 
 impl<T> EfTest for Cases<T>
@@ -71,40 +119,7 @@ where
         self.test_cases
             .iter()
             .enumerate()
-            .map(|(i, tc)| CaseResult::new(i, tc, tc.result(i)))
+            .map(|(i, (path, tc))| CaseResult::new(i, path, tc, tc.result(i)))
             .collect()
     }
 }
-
-impl<T: YamlDecode> YamlDecode for Cases<T> {
-    /// Decodes a YAML list of test cases
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        let mut p = 0;
-        let mut elems: Vec<&str> = yaml
-            .match_indices("\n- ")
-            // Skip the `\n` used for matching a new line
-            .map(|(i, _)| i + 1)
-            .map(|i| {
-                let yaml_element = &yaml[p..i];
-                p = i;
-
-                yaml_element
-            })
-            .collect();
-
-        elems.push(&yaml[p..]);
-
-        let test_cases = elems
-            .iter()
-            .map(|s| {
-                // Remove the `- ` prefix.
-                let s = &s[2..];
-                // Remove a single level of indenting.
-                s.replace("\n  ", "\n")
-            })
-            .map(|s| T::yaml_decode(&s.to_string()).unwrap())
-            .collect();
-
-        Ok(Self { test_cases })
-    }
-}
@@ -2,6 +2,7 @@ use super::*;
 use crate::case_result::compare_result;
 use bls::{AggregatePublicKey, PublicKey};
 use serde_derive::Deserialize;
+use std::path::Path;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlsAggregatePubkeys {
@@ -9,9 +10,9 @@ pub struct BlsAggregatePubkeys {
     pub output: String,
 }
 
-impl YamlDecode for BlsAggregatePubkeys {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        Ok(serde_yaml::from_str(yaml).unwrap())
+impl LoadCase for BlsAggregatePubkeys {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        yaml_decode_file(&path.join("data.yaml"))
     }
 }
 
@@ -2,6 +2,7 @@ use super::*;
 use crate::case_result::compare_result;
 use bls::{AggregateSignature, Signature};
 use serde_derive::Deserialize;
+use std::path::Path;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlsAggregateSigs {
@@ -9,9 +10,9 @@ pub struct BlsAggregateSigs {
     pub output: String,
 }
 
-impl YamlDecode for BlsAggregateSigs {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        Ok(serde_yaml::from_str(yaml).unwrap())
+impl LoadCase for BlsAggregateSigs {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        yaml_decode_file(&path.join("data.yaml"))
     }
 }
 
@@ -2,6 +2,7 @@ use super::*;
 use crate::case_result::compare_result;
 use bls::{compress_g2, hash_on_g2};
 use serde_derive::Deserialize;
+use std::path::Path;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlsG2CompressedInput {
@@ -15,20 +16,14 @@ pub struct BlsG2Compressed {
     pub output: Vec<String>,
 }
 
-impl YamlDecode for BlsG2Compressed {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        Ok(serde_yaml::from_str(yaml).unwrap())
+impl LoadCase for BlsG2Compressed {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        yaml_decode_file(&path.join("data.yaml"))
     }
 }
 
 impl Case for BlsG2Compressed {
     fn result(&self, _case_index: usize) -> Result<(), Error> {
-        // FIXME: re-enable in v0.7
-        // https://github.com/ethereum/eth2.0-spec-tests/issues/3
-        if _case_index == 4 {
-            return Err(Error::SkippedKnownFailure);
-        }
-
         // Convert message and domain to required types
         let msg = hex::decode(&self.input.message[2..])
             .map_err(|e| Error::FailedToParseTest(format!("{:?}", e)))?;
@@ -2,6 +2,7 @@ use super::*;
 use crate::case_result::compare_result;
 use bls::hash_on_g2;
 use serde_derive::Deserialize;
+use std::path::Path;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlsG2UncompressedInput {
@@ -15,9 +16,9 @@ pub struct BlsG2Uncompressed {
     pub output: Vec<Vec<String>>,
 }
 
-impl YamlDecode for BlsG2Uncompressed {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        Ok(serde_yaml::from_str(yaml).unwrap())
+impl LoadCase for BlsG2Uncompressed {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        yaml_decode_file(&path.join("data.yaml"))
     }
 }
 
@@ -0,0 +1,41 @@
+use super::*;
+use crate::case_result::compare_beacon_state_results_without_caches;
+use state_processing::per_epoch_processing::process_final_updates;
+use std::path::Path;
+use types::{BeaconState, EthSpec};
+
+#[derive(Debug, Clone)]
+pub struct EpochProcessingFinalUpdates<E: EthSpec> {
+    pub metadata: Metadata,
+    pub pre: BeaconState<E>,
+    pub post: Option<BeaconState<E>>,
+}
+
+impl<E: EthSpec> LoadCase for EpochProcessingFinalUpdates<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            metadata: Metadata::load_from_dir(path)?,
+            pre: ssz_decode_file(&path.join("pre.ssz"))?,
+            post: ssz_decode_optional_file(&path.join("post.ssz"))?,
+        })
+    }
+}
+
+impl<E: EthSpec> Case for EpochProcessingFinalUpdates<E> {
+    fn description(&self) -> String {
+        self.metadata.description.clone().unwrap_or_default()
+    }
+
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        let mut state = self.pre.clone();
+        let mut expected = self.post.clone();
+        let spec = &E::default_spec();
+
+        // Processing requires the epoch cache.
+        state.build_all_caches(spec).unwrap();
+
+        let mut result = process_final_updates(&mut state, spec).map(|_| state);
+
+        compare_beacon_state_results_without_caches(&mut result, &mut expected)
+    }
+}
@@ -0,0 +1,48 @@
+use super::*;
+use crate::case_result::compare_beacon_state_results_without_caches;
+use state_processing::per_epoch_processing::{
+    process_justification_and_finalization, validator_statuses::ValidatorStatuses,
+};
+use std::path::Path;
+use types::{BeaconState, EthSpec};
+
+#[derive(Debug, Clone)]
+pub struct EpochProcessingJustificationAndFinalization<E: EthSpec> {
+    pub metadata: Metadata,
+    pub pre: BeaconState<E>,
+    pub post: Option<BeaconState<E>>,
+}
+
+impl<E: EthSpec> LoadCase for EpochProcessingJustificationAndFinalization<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            metadata: Metadata::load_from_dir(path)?,
+            pre: ssz_decode_file(&path.join("pre.ssz"))?,
+            post: ssz_decode_optional_file(&path.join("post.ssz"))?,
+        })
+    }
+}
+
+impl<E: EthSpec> Case for EpochProcessingJustificationAndFinalization<E> {
+    fn description(&self) -> String {
+        self.metadata.description.clone().unwrap_or_default()
+    }
+
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        let mut state = self.pre.clone();
+        let mut expected = self.post.clone();
+        let spec = &E::default_spec();
+
+        // Processing requires the epoch cache.
+        state.build_all_caches(spec).unwrap();
+
+        let mut result = (|| {
+            let mut validator_statuses = ValidatorStatuses::new(&state, spec)?;
+            validator_statuses.process_attestations(&state, spec)?;
+            process_justification_and_finalization(&mut state, &validator_statuses.total_balances)
+                .map(|_| state)
+        })();
+
+        compare_beacon_state_results_without_caches(&mut result, &mut expected)
+    }
+}
@@ -1,27 +1,29 @@
 use super::*;
 use crate::case_result::compare_beacon_state_results_without_caches;
-use serde_derive::Deserialize;
 use state_processing::per_epoch_processing::registry_updates::process_registry_updates;
+use std::path::Path;
 use types::{BeaconState, EthSpec};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct EpochProcessingRegistryUpdates<E: EthSpec> {
-    pub description: String,
-    #[serde(bound = "E: EthSpec")]
+    pub metadata: Metadata,
     pub pre: BeaconState<E>,
-    #[serde(bound = "E: EthSpec")]
     pub post: Option<BeaconState<E>>,
 }
 
-impl<E: EthSpec> YamlDecode for EpochProcessingRegistryUpdates<E> {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        Ok(serde_yaml::from_str(yaml).unwrap())
+impl<E: EthSpec> LoadCase for EpochProcessingRegistryUpdates<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            metadata: Metadata::load_from_dir(path)?,
+            pre: ssz_decode_file(&path.join("pre.ssz"))?,
+            post: ssz_decode_optional_file(&path.join("post.ssz"))?,
+        })
     }
 }
 
 impl<E: EthSpec> Case for EpochProcessingRegistryUpdates<E> {
     fn description(&self) -> String {
-        self.description.clone()
+        self.metadata.description.clone().unwrap_or_default()
     }
 
     fn result(&self, _case_index: usize) -> Result<(), Error> {
@@ -0,0 +1,62 @@
+use super::*;
+use crate::case_result::compare_beacon_state_results_without_caches;
+use serde_derive::Deserialize;
+use state_processing::initialize_beacon_state_from_eth1;
+use std::path::Path;
+use types::{BeaconState, Deposit, EthSpec, Hash256};
+
+/// The `meta.yaml` fields specific to the `genesis/initialization` tests.
+#[derive(Debug, Clone, Deserialize)]
+struct InitializationMetadata {
+    deposits_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GenesisInitialization<E: EthSpec> {
+    pub metadata: Metadata,
+    pub eth1_block_hash: Hash256,
+    pub eth1_timestamp: u64,
+    pub deposits: Vec<Deposit>,
+    pub state: Option<BeaconState<E>>,
+}
+
+impl<E: EthSpec> LoadCase for GenesisInitialization<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        let metadata = Metadata::load_from_dir(path)?;
+        let initialization_metadata: InitializationMetadata =
+            yaml_decode_file(&path.join("meta.yaml"))?;
+        let deposits = (0..initialization_metadata.deposits_count)
+            .map(|i| ssz_decode_file(&path.join(format!("deposits_{}.ssz", i))))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            metadata,
+            eth1_block_hash: yaml_decode_file(&path.join("eth1_block_hash.yaml"))?,
+            eth1_timestamp: yaml_decode_file(&path.join("eth1_timestamp.yaml"))?,
+            deposits,
+            state: ssz_decode_optional_file(&path.join("state.ssz"))?,
+        })
+    }
+}
+
+impl<E: EthSpec> Case for GenesisInitialization<E> {
+    fn description(&self) -> String {
+        self.metadata.description.clone().unwrap_or_default()
+    }
+
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        self.metadata.bls_setting.unwrap_or_default().check()?;
+
+        let spec = &E::default_spec();
+        let mut expected = self.state.clone();
+
+        let mut result = initialize_beacon_state_from_eth1(
+            self.eth1_block_hash,
+            self.eth1_timestamp,
+            self.deposits.clone(),
+            spec,
+        );
+
+        compare_beacon_state_results_without_caches(&mut result, &mut expected)
+    }
+}
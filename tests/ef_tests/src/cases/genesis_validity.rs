@@ -0,0 +1,36 @@
+use super::*;
+use crate::case_result::compare_result;
+use state_processing::is_valid_genesis_state;
+use std::path::Path;
+use types::{BeaconState, EthSpec};
+
+#[derive(Debug, Clone)]
+pub struct GenesisValidity<E: EthSpec> {
+    pub metadata: Metadata,
+    pub genesis: BeaconState<E>,
+    pub is_valid: bool,
+}
+
+impl<E: EthSpec> LoadCase for GenesisValidity<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            metadata: Metadata::load_from_dir(path)?,
+            genesis: ssz_decode_file(&path.join("genesis.ssz"))?,
+            is_valid: yaml_decode_file(&path.join("is_valid.yaml"))?,
+        })
+    }
+}
+
+impl<E: EthSpec> Case for GenesisValidity<E> {
+    fn description(&self) -> String {
+        self.metadata.description.clone().unwrap_or_default()
+    }
+
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        let spec = &E::default_spec();
+
+        let is_valid = is_valid_genesis_state(&self.genesis, spec);
+
+        compare_result::<bool, Error>(&Ok(is_valid), &Some(self.is_valid))
+    }
+}
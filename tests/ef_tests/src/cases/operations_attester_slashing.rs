@@ -1,34 +1,35 @@
 use super::*;
-use crate::bls_setting::BlsSetting;
 use crate::case_result::compare_beacon_state_results_without_caches;
-use serde_derive::Deserialize;
 use state_processing::per_block_processing::process_attester_slashings;
+use std::path::Path;
 use types::{AttesterSlashing, BeaconState, EthSpec};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct OperationsAttesterSlashing<E: EthSpec> {
-    pub description: String,
-    pub bls_setting: Option<BlsSetting>,
-    #[serde(bound = "E: EthSpec")]
+    pub metadata: Metadata,
     pub pre: BeaconState<E>,
-    pub attester_slashing: AttesterSlashing,
-    #[serde(bound = "E: EthSpec")]
+    pub attester_slashing: AttesterSlashing<E>,
     pub post: Option<BeaconState<E>>,
 }
 
-impl<E: EthSpec> YamlDecode for OperationsAttesterSlashing<E> {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        Ok(serde_yaml::from_str(yaml).unwrap())
+impl<E: EthSpec> LoadCase for OperationsAttesterSlashing<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            metadata: Metadata::load_from_dir(path)?,
+            pre: ssz_decode_file(&path.join("pre.ssz"))?,
+            attester_slashing: ssz_decode_file(&path.join("attester_slashing.ssz"))?,
+            post: ssz_decode_optional_file(&path.join("post.ssz"))?,
+        })
     }
 }
 
 impl<E: EthSpec> Case for OperationsAttesterSlashing<E> {
     fn description(&self) -> String {
-        self.description.clone()
+        self.metadata.description.clone().unwrap_or_default()
     }
 
     fn result(&self, _case_index: usize) -> Result<(), Error> {
-        self.bls_setting.unwrap_or_default().check()?;
+        self.metadata.bls_setting.unwrap_or_default().check()?;
 
         let mut state = self.pre.clone();
         let attester_slashing = self.attester_slashing.clone();
@@ -1,36 +1,37 @@
 use super::*;
-use crate::bls_setting::BlsSetting;
 use crate::case_result::compare_beacon_state_results_without_caches;
-use serde_derive::Deserialize;
 use state_processing::per_block_processing::process_block_header;
+use std::path::Path;
 use types::{BeaconBlock, BeaconState, EthSpec};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct OperationsBlockHeader<E: EthSpec> {
-    pub description: String,
-    pub bls_setting: Option<BlsSetting>,
-    #[serde(bound = "E: EthSpec")]
+    pub metadata: Metadata,
     pub pre: BeaconState<E>,
-    pub block: BeaconBlock,
-    #[serde(bound = "E: EthSpec")]
+    pub block: BeaconBlock<E>,
     pub post: Option<BeaconState<E>>,
 }
 
-impl<E: EthSpec> YamlDecode for OperationsBlockHeader<E> {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        Ok(serde_yaml::from_str(yaml).unwrap())
+impl<E: EthSpec> LoadCase for OperationsBlockHeader<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            metadata: Metadata::load_from_dir(path)?,
+            pre: ssz_decode_file(&path.join("pre.ssz"))?,
+            block: ssz_decode_file(&path.join("block.ssz"))?,
+            post: ssz_decode_optional_file(&path.join("post.ssz"))?,
+        })
     }
 }
 
 impl<E: EthSpec> Case for OperationsBlockHeader<E> {
     fn description(&self) -> String {
-        self.description.clone()
+        self.metadata.description.clone().unwrap_or_default()
     }
 
     fn result(&self, _case_index: usize) -> Result<(), Error> {
         let spec = &E::default_spec();
 
-        self.bls_setting.unwrap_or_default().check()?;
+        self.metadata.bls_setting.unwrap_or_default().check()?;
 
         let mut state = self.pre.clone();
         let mut expected = self.post.clone();
@@ -1,44 +1,48 @@
 use super::*;
-use crate::bls_setting::BlsSetting;
 use crate::case_result::compare_beacon_state_results_without_caches;
 use serde_derive::Deserialize;
 use state_processing::{per_block_processing, per_slot_processing};
+use std::path::Path;
 use types::{BeaconBlock, BeaconState, EthSpec, RelativeEpoch};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct SanityBlocks<E: EthSpec> {
-    pub description: String,
-    pub bls_setting: Option<BlsSetting>,
-    #[serde(bound = "E: EthSpec")]
+    pub metadata: Metadata,
     pub pre: BeaconState<E>,
-    pub blocks: Vec<BeaconBlock>,
-    #[serde(bound = "E: EthSpec")]
+    pub blocks: Vec<BeaconBlock<E>>,
     pub post: Option<BeaconState<E>>,
 }
 
-impl<E: EthSpec> YamlDecode for SanityBlocks<E> {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        Ok(serde_yaml::from_str(yaml).unwrap())
+/// The `meta.yaml` fields specific to the `sanity/blocks` tests.
+#[derive(Debug, Clone, Deserialize)]
+struct BlocksMetadata {
+    blocks_count: usize,
+}
+
+impl<E: EthSpec> LoadCase for SanityBlocks<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        let metadata = Metadata::load_from_dir(path)?;
+        let blocks_metadata: BlocksMetadata = yaml_decode_file(&path.join("meta.yaml"))?;
+        let blocks = (0..blocks_metadata.blocks_count)
+            .map(|i| ssz_decode_file(&path.join(format!("blocks_{}.ssz", i))))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            metadata,
+            pre: ssz_decode_file(&path.join("pre.ssz"))?,
+            blocks,
+            post: ssz_decode_optional_file(&path.join("post.ssz"))?,
+        })
     }
 }
 
 impl<E: EthSpec> Case for SanityBlocks<E> {
     fn description(&self) -> String {
-        self.description.clone()
+        self.metadata.description.clone().unwrap_or_default()
     }
 
-    fn result(&self, case_index: usize) -> Result<(), Error> {
-        self.bls_setting.unwrap_or_default().check()?;
-
-        // FIXME: re-enable these tests in v0.7
-        let known_failures = vec![
-            0,  // attestation: https://github.com/ethereum/eth2.0-spec-tests/issues/6
-            10, // transfer: https://github.com/ethereum/eth2.0-spec-tests/issues/7
-            11, // voluntary exit: signature is invalid, don't know why
-        ];
-        if known_failures.contains(&case_index) {
-            return Err(Error::SkippedKnownFailure);
-        }
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        self.metadata.bls_setting.unwrap_or_default().check()?;
 
         let mut state = self.pre.clone();
         let mut expected = self.post.clone();
@@ -1,138 +1,205 @@
 use super::*;
 use crate::case_result::compare_result;
 use cached_tree_hash::{CachedTreeHash, TreeHashCache};
+use serde::de::DeserializeOwned;
 use serde_derive::Deserialize;
 use ssz::{Decode, Encode};
 use std::fmt::Debug;
-use std::marker::PhantomData;
-use tree_hash::TreeHash;
+use std::fs;
+use std::path::Path;
+use tree_hash::{SignedRoot, TreeHash};
 use types::{
     test_utils::{SeedableRng, TestRandom, XorShiftRng},
-    Attestation, AttestationData, AttestationDataAndCustodyBit, AttesterSlashing, BeaconBlock,
-    BeaconBlockBody, BeaconBlockHeader, BeaconState, Crosslink, Deposit, DepositData, Eth1Data,
-    EthSpec, Fork, Hash256, HistoricalBatch, IndexedAttestation, PendingAttestation,
-    ProposerSlashing, Transfer, Validator, VoluntaryExit,
+    Hash256,
 };
 
-// Enum variant names are used by Serde when deserializing the test YAML
-#[allow(clippy::large_enum_variant)]
+/// The `roots.yaml` file of a test case.
 #[derive(Debug, Clone, Deserialize)]
-pub enum SszStatic<E>
-where
-    E: EthSpec,
-{
-    Fork(SszStaticInner<Fork, E>),
-    Crosslink(SszStaticInner<Crosslink, E>),
-    Eth1Data(SszStaticInner<Eth1Data, E>),
-    AttestationData(SszStaticInner<AttestationData, E>),
-    AttestationDataAndCustodyBit(SszStaticInner<AttestationDataAndCustodyBit, E>),
-    IndexedAttestation(SszStaticInner<IndexedAttestation, E>),
-    DepositData(SszStaticInner<DepositData, E>),
-    BeaconBlockHeader(SszStaticInner<BeaconBlockHeader, E>),
-    Validator(SszStaticInner<Validator, E>),
-    PendingAttestation(SszStaticInner<PendingAttestation, E>),
-    HistoricalBatch(SszStaticInner<HistoricalBatch<E>, E>),
-    ProposerSlashing(SszStaticInner<ProposerSlashing, E>),
-    AttesterSlashing(SszStaticInner<AttesterSlashing, E>),
-    Attestation(SszStaticInner<Attestation, E>),
-    Deposit(SszStaticInner<Deposit, E>),
-    VoluntaryExit(SszStaticInner<VoluntaryExit, E>),
-    Transfer(SszStaticInner<Transfer, E>),
-    BeaconBlockBody(SszStaticInner<BeaconBlockBody, E>),
-    BeaconBlock(SszStaticInner<BeaconBlock, E>),
-    BeaconState(SszStaticInner<BeaconState<E>, E>),
+struct SszStaticRoots {
+    root: String,
+    signing_root: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct SszStaticInner<T, E>
-where
-    E: EthSpec,
-{
-    pub value: T,
-    pub serialized: String,
-    pub root: String,
-    #[serde(skip, default)]
-    _phantom: PhantomData<E>,
+/// Verifies the SSZ encoding, canonical JSON/YAML representation and `TreeHash` root of a `T`.
+#[derive(Debug, Clone)]
+pub struct SszStatic<T> {
+    roots: SszStaticRoots,
+    serialized: Vec<u8>,
+    value: T,
+}
+
+/// As per `SszStatic`, but also verifies the `CachedTreeHash` root.
+///
+/// Note: the `cached_tree_hash` crate does not yet support length-limited lists, so types
+/// containing them are tested with `SszStatic`.
+#[derive(Debug, Clone)]
+pub struct SszStaticTHC<T> {
+    roots: SszStaticRoots,
+    serialized: Vec<u8>,
+    value: T,
 }
 
-impl<E: EthSpec + serde::de::DeserializeOwned> YamlDecode for SszStatic<E> {
-    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
-        serde_yaml::from_str(yaml).map_err(|e| Error::FailedToParseTest(format!("{:?}", e)))
+/// As per `SszStatic`, but also verifies the `SignedRoot` of the value.
+#[derive(Debug, Clone)]
+pub struct SszStaticSR<T> {
+    roots: SszStaticRoots,
+    serialized: Vec<u8>,
+    value: T,
+}
+
+fn load_from_dir<T: DeserializeOwned>(path: &Path) -> Result<(SszStaticRoots, Vec<u8>, T), Error> {
+    let roots = yaml_decode_file(&path.join("roots.yaml"))?;
+    let serialized = fs::read(&path.join("serialized.ssz"))
+        .map_err(|e| Error::FailedToParseTest(format!("{:?}", e)))?;
+    let value = yaml_decode_file(&path.join("value.yaml"))?;
+
+    Ok((roots, serialized, value))
+}
+
+impl<T: DeserializeOwned> LoadCase for SszStatic<T> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        load_from_dir(path).map(|(roots, serialized, value)| Self {
+            roots,
+            serialized,
+            value,
+        })
     }
 }
 
-impl<E: EthSpec> Case for SszStatic<E> {
+impl<T: DeserializeOwned> LoadCase for SszStaticTHC<T> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        load_from_dir(path).map(|(roots, serialized, value)| Self {
+            roots,
+            serialized,
+            value,
+        })
+    }
+}
+
+impl<T: DeserializeOwned> LoadCase for SszStaticSR<T> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        load_from_dir(path).map(|(roots, serialized, value)| Self {
+            roots,
+            serialized,
+            value,
+        })
+    }
+}
+
+impl<T> Case for SszStatic<T>
+where
+    T: Clone
+        + Decode
+        + Debug
+        + Encode
+        + PartialEq<T>
+        + DeserializeOwned
+        + serde::Serialize
+        + TreeHash,
+{
     fn result(&self, _case_index: usize) -> Result<(), Error> {
-        use self::SszStatic::*;
-
-        match *self {
-            Fork(ref val) => ssz_static_test(val),
-            Crosslink(ref val) => ssz_static_test(val),
-            Eth1Data(ref val) => ssz_static_test(val),
-            AttestationData(ref val) => ssz_static_test(val),
-            AttestationDataAndCustodyBit(ref val) => ssz_static_test(val),
-            IndexedAttestation(ref val) => ssz_static_test(val),
-            DepositData(ref val) => ssz_static_test(val),
-            BeaconBlockHeader(ref val) => ssz_static_test(val),
-            Validator(ref val) => ssz_static_test(val),
-            PendingAttestation(ref val) => ssz_static_test(val),
-            HistoricalBatch(ref val) => ssz_static_test(val),
-            ProposerSlashing(ref val) => ssz_static_test(val),
-            AttesterSlashing(ref val) => ssz_static_test(val),
-            Attestation(ref val) => ssz_static_test(val),
-            Deposit(ref val) => ssz_static_test(val),
-            VoluntaryExit(ref val) => ssz_static_test(val),
-            Transfer(ref val) => ssz_static_test(val),
-            BeaconBlockBody(ref val) => ssz_static_test(val),
-            BeaconBlock(ref val) => ssz_static_test(val),
-            BeaconState(ref val) => ssz_static_test(val),
-        }
+        ssz_static_test(&self.roots, &self.serialized, &self.value)
     }
 }
 
-fn ssz_static_test<T, E: EthSpec>(tc: &SszStaticInner<T, E>) -> Result<(), Error>
+impl<T> Case for SszStaticTHC<T>
 where
     T: Clone
         + Decode
         + Debug
         + Encode
         + PartialEq<T>
-        + serde::de::DeserializeOwned
+        + DeserializeOwned
+        + serde::Serialize
         + TreeHash
         + CachedTreeHash
         + TestRandom,
+{
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        ssz_static_test(&self.roots, &self.serialized, &self.value)?;
+
+        let expected_root = decode_root(&self.roots.root)?;
+
+        // Verify a _new_ CachedTreeHash root of the decoded struct matches the test.
+        let cache = TreeHashCache::new(&self.value).unwrap();
+        let cached_tree_hash_root = Hash256::from_slice(cache.tree_hash_root().unwrap());
+        compare_result::<Hash256, Error>(&Ok(cached_tree_hash_root), &Some(expected_root))?;
+
+        // Verify the root after an update from a random CachedTreeHash to the decoded struct.
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let random_instance = T::random_for_test(&mut rng);
+        let mut cache = TreeHashCache::new(&random_instance).unwrap();
+        cache.update(&self.value).unwrap();
+        let updated_root = Hash256::from_slice(cache.tree_hash_root().unwrap());
+        compare_result::<Hash256, Error>(&Ok(updated_root), &Some(expected_root))
+    }
+}
+
+impl<T> Case for SszStaticSR<T>
+where
+    T: Clone
+        + Decode
+        + Debug
+        + Encode
+        + PartialEq<T>
+        + DeserializeOwned
+        + serde::Serialize
+        + TreeHash
+        + SignedRoot,
+{
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        ssz_static_test(&self.roots, &self.serialized, &self.value)?;
+
+        let expected_signing_root = self
+            .roots
+            .signing_root
+            .as_ref()
+            .ok_or_else(|| Error::FailedToParseTest("Missing signing_root".to_string()))
+            .and_then(|root| decode_root(root))?;
+        let signing_root = Hash256::from_slice(&self.value.signed_root());
+        compare_result::<Hash256, Error>(&Ok(signing_root), &Some(expected_signing_root))
+    }
+}
+
+/// Execute a `ssz_static` test case.
+fn ssz_static_test<T>(roots: &SszStaticRoots, serialized: &[u8], value: &T) -> Result<(), Error>
+where
+    T: Clone
+        + Decode
+        + Debug
+        + Encode
+        + PartialEq<T>
+        + DeserializeOwned
+        + serde::Serialize
+        + TreeHash,
 {
     // Verify we can decode SSZ in the same way we can decode YAML.
-    let ssz = hex::decode(&tc.serialized[2..])
-        .map_err(|e| Error::FailedToParseTest(format!("{:?}", e)))?;
-    let expected = tc.value.clone();
-    let decode_result = T::from_ssz_bytes(&ssz);
-    compare_result(&decode_result, &Some(expected))?;
+    let decode_result = T::from_ssz_bytes(serialized);
+    compare_result(&decode_result, &Some(value.clone()))?;
 
     // Verify we can encode the result back into original ssz bytes
     let decoded = decode_result.unwrap();
     let encoded_result = decoded.as_ssz_bytes();
-    compare_result::<Vec<u8>, Error>(&Ok(encoded_result), &Some(ssz))?;
+    compare_result::<Vec<u8>, Error>(&Ok(encoded_result), &Some(serialized.to_vec()))?;
+
+    // Verify the value round-trips through our canonical JSON and YAML representations.
+    let json_result =
+        serde_json::to_string(&decoded).and_then(|json| serde_json::from_str::<T>(&json));
+    compare_result(&json_result, &Some(decoded.clone()))?;
+
+    let yaml_result =
+        serde_yaml::to_string(&decoded).and_then(|yaml| serde_yaml::from_str::<T>(&yaml));
+    compare_result(&yaml_result, &Some(decoded.clone()))?;
 
     // Verify the TreeHash root of the decoded struct matches the test.
-    let expected_root =
-        &hex::decode(&tc.root[2..]).map_err(|e| Error::FailedToParseTest(format!("{:?}", e)))?;
-    let expected_root = Hash256::from_slice(&expected_root);
+    let expected_root = decode_root(&roots.root)?;
     let tree_hash_root = Hash256::from_slice(&decoded.tree_hash_root());
-    compare_result::<Hash256, Error>(&Ok(tree_hash_root), &Some(expected_root))?;
-
-    // Verify a _new_ CachedTreeHash root of the decoded struct matches the test.
-    let cache = TreeHashCache::new(&decoded).unwrap();
-    let cached_tree_hash_root = Hash256::from_slice(cache.tree_hash_root().unwrap());
-    compare_result::<Hash256, Error>(&Ok(cached_tree_hash_root), &Some(expected_root))?;
-
-    // Verify the root after an update from a random CachedTreeHash to the decoded struct.
-    let mut rng = XorShiftRng::from_seed([42; 16]);
-    let random_instance = T::random_for_test(&mut rng);
-    let mut cache = TreeHashCache::new(&random_instance).unwrap();
-    cache.update(&decoded).unwrap();
-    let updated_root = Hash256::from_slice(cache.tree_hash_root().unwrap());
-    compare_result::<Hash256, Error>(&Ok(updated_root), &Some(expected_root))?;
-
-    Ok(())
+    compare_result::<Hash256, Error>(&Ok(tree_hash_root), &Some(expected_root))
+}
+
+/// Decodes a `0x`-prefixed hex root from `roots.yaml`.
+fn decode_root(root: &str) -> Result<Hash256, Error> {
+    let bytes =
+        hex::decode(&root[2..]).map_err(|e| Error::FailedToParseTest(format!("{:?}", e)))?;
+    Ok(Hash256::from_slice(&bytes))
 }
@@ -0,0 +1,153 @@
+use crate::case_result::CaseResult;
+use crate::cases::{Case, Cases, LoadCase};
+use crate::error::Error;
+use crate::EfTest;
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The fork of the test vectors run by this crate.
+pub const FORK_NAME: &str = "phase0";
+
+/// Loads and runs every test case in the
+/// `tests/<config_name>/phase0/<runner_name>/<handler_name>/<suite>/<case>` directories of the
+/// test vectors, panicking if any of them fail.
+pub fn run_handler<T>(config_name: &str, runner_name: &str, handler_name: &str)
+where
+    T: Case + LoadCase + Send + Sync,
+{
+    let handler_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("eth2.0-spec-tests")
+        .join("tests")
+        .join(config_name)
+        .join(FORK_NAME)
+        .join(runner_name)
+        .join(handler_name);
+
+    assert!(
+        handler_path.exists(),
+        "Unable to locate {:?}. Did you init git submodules?",
+        handler_path
+    );
+
+    let test_case_paths: Vec<PathBuf> = subdirectories(&handler_path)
+        .into_iter()
+        .flat_map(|suite_path| subdirectories(&suite_path))
+        .collect();
+
+    let test_cases = test_case_paths
+        .into_par_iter()
+        .map(|path| {
+            let case = T::load_from_dir(&path)
+                .unwrap_or_else(|e| panic!("Unable to load {:?}: {:?}", path, e));
+            (path, case)
+        })
+        .collect();
+
+    let results = Cases { test_cases }.test_results();
+
+    let name = format!("{}/{}/{}", config_name, runner_name, handler_name);
+    assert_tests_pass(&name, &handler_path, &results);
+}
+
+/// Returns the paths of all directories directly within `path`, in lexicographical order.
+fn subdirectories(path: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(path)
+        .unwrap_or_else(|e| panic!("Unable to read {:?}: {:?}", path, e))
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+
+    paths.sort();
+    paths
+}
+
+pub fn assert_tests_pass(name: &str, path: &Path, results: &[CaseResult]) {
+    let (failed, skipped_bls, skipped_known_failures) = categorize_results(results);
+
+    if failed.len() + skipped_known_failures.len() > 0 {
+        print_results(
+            name,
+            &failed,
+            &skipped_bls,
+            &skipped_known_failures,
+            &results,
+        );
+        if !failed.is_empty() {
+            panic!("Tests failed (see above)");
+        }
+    } else {
+        println!("Passed {} tests in {:?}", results.len(), path);
+    }
+}
+
+pub fn categorize_results(
+    results: &[CaseResult],
+) -> (Vec<&CaseResult>, Vec<&CaseResult>, Vec<&CaseResult>) {
+    let mut failed = vec![];
+    let mut skipped_bls = vec![];
+    let mut skipped_known_failures = vec![];
+
+    for case in results {
+        match case.result.as_ref().err() {
+            Some(Error::SkippedBls) => skipped_bls.push(case),
+            Some(Error::SkippedKnownFailure) => skipped_known_failures.push(case),
+            Some(_) => failed.push(case),
+            None => (),
+        }
+    }
+
+    (failed, skipped_bls, skipped_known_failures)
+}
+
+pub fn print_results(
+    name: &str,
+    failed: &[&CaseResult],
+    skipped_bls: &[&CaseResult],
+    skipped_known_failures: &[&CaseResult],
+    results: &[CaseResult],
+) {
+    println!("--------------------------------------------------");
+    println!(
+        "Test {}",
+        if failed.is_empty() {
+            "Result"
+        } else {
+            "Failure"
+        }
+    );
+    println!("Title: {}", name);
+    println!();
+    println!(
+        "{} tests, {} failed, {} skipped (known failure), {} skipped (bls), {} passed.",
+        results.len(),
+        failed.len(),
+        skipped_known_failures.len(),
+        skipped_bls.len(),
+        results.len() - skipped_bls.len() - skipped_known_failures.len() - failed.len()
+    );
+    println!();
+
+    for case in skipped_known_failures {
+        println!("-------");
+        println!(
+            "case[{}] ({}) at {:?} skipped because it's a known failure",
+            case.case_index, case.desc, case.path,
+        );
+    }
+    for failure in failed {
+        let error = failure.result.clone().unwrap_err();
+
+        println!("-------");
+        println!(
+            "case[{}] ({}) at {:?} failed with {}:",
+            failure.case_index,
+            failure.desc,
+            failure.path,
+            error.name()
+        );
+        println!("{}", error.message());
+    }
+    println!();
+}
@@ -1,17 +1,17 @@
 use types::EthSpec;
 
 pub use case_result::CaseResult;
-pub use cases::Case;
-pub use doc::Doc;
+pub use cases::*;
 pub use error::Error;
+pub use handler::run_handler;
 pub use yaml_decode::YamlDecode;
 
 mod bls_setting;
 mod case_result;
 mod cases;
-mod doc;
-mod doc_header;
+mod decode;
 mod error;
+mod handler;
 mod yaml_decode;
 
 /// Defined where an object can return the results of some test(s) adhering to the Ethereum
@@ -2,10 +2,6 @@ use super::*;
 use ethereum_types::{U128, U256};
 use types::Fork;
 
-mod utils;
-
-pub use utils::*;
-
 pub trait YamlDecode: Sized {
     /// Decode an object from the test specification YAML.
     fn yaml_decode(string: &str) -> Result<Self, Error>;
@@ -1,6 +1,6 @@
 //TODO: generalise these enums to the crate
 use crate::block_producer::{BeaconNodeError, PublishOutcome};
-use types::{Attestation, AttestationData, Slot};
+use types::{Attestation, AttestationData, EthSpec, Slot};
 
 /// Defines the methods required to produce and publish attestations on a Beacon Node. Abstracts the
 /// actual beacon node.
@@ -16,8 +16,8 @@ pub trait BeaconNodeAttestation: Send + Sync {
     /// Request that the node publishes a attestation.
     ///
     /// Returns `true` if the publish was successful.
-    fn publish_attestation(
+    fn publish_attestation<T: EthSpec>(
         &self,
-        attestation: Attestation,
+        attestation: Attestation<T>,
     ) -> Result<PublishOutcome, BeaconNodeError>;
 }
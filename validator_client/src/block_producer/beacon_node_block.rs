@@ -1,4 +1,4 @@
-use types::{BeaconBlock, Signature, Slot};
+use types::{BeaconBlock, EthSpec, Signature, Slot};
 #[derive(Debug, PartialEq, Clone)]
 pub enum BeaconNodeError {
     RemoteFailure(String),
@@ -18,14 +18,17 @@ pub trait BeaconNodeBlock: Send + Sync {
     /// Request that the node produces a block.
     ///
     /// Returns Ok(None) if the Beacon Node is unable to produce at the given slot.
-    fn produce_beacon_block(
+    fn produce_beacon_block<T: EthSpec>(
         &self,
         slot: Slot,
         randao_reveal: &Signature,
-    ) -> Result<Option<BeaconBlock>, BeaconNodeError>;
+    ) -> Result<Option<BeaconBlock<T>>, BeaconNodeError>;
 
     /// Request that the node publishes a block.
     ///
     /// Returns `true` if the publish was successful.
-    fn publish_beacon_block(&self, block: BeaconBlock) -> Result<PublishOutcome, BeaconNodeError>;
+    fn publish_beacon_block<T: EthSpec>(
+        &self,
+        block: BeaconBlock<T>,
+    ) -> Result<PublishOutcome, BeaconNodeError>;
 }
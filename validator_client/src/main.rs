@@ -73,6 +73,13 @@ fn main() {
                 .possible_values(&["mainnet", "minimal"])
                 .default_value("minimal"),
         )
+        .arg(
+            Arg::with_name("spec-config")
+                .long("spec-config")
+                .value_name("YAML_FILE")
+                .help("Path to a spec config file (in the eth2.0-specs YAML format) to override the spec constants.")
+                .takes_value(true),
+        )
         .get_matches();
 
     let data_dir = match matches
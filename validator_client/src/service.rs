@@ -25,6 +25,7 @@ use protos::services_grpc::{
 };
 use slog::{error, info, warn};
 use slot_clock::{SlotClock, SystemTimeSlotClock};
+use std::marker::PhantomData;
 use std::sync::Arc;
 use std::sync::RwLock;
 use std::time::{Duration, Instant, SystemTime};
@@ -32,7 +33,7 @@ use tokio::prelude::*;
 use tokio::runtime::Builder;
 use tokio::timer::Interval;
 use tokio_timer::clock::Clock;
-use types::{ChainSpec, Epoch, EthSpec, Fork, Slot};
+use types::{ChainSpec, Epoch, EthSpec, Fork, Slot, YamlConfig};
 
 /// A fixed amount of time after a slot to perform operations. This gives the node time to complete
 /// per-slot processes.
@@ -112,6 +113,29 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static> Service<B, S> {
                         );
                         return Err(format!("Beacon node has the wrong chain id. Expected chain id: {}, node's chain id: {}", eth2_config.spec.chain_id, info.chain_id).into());
                     }
+                    // verify the node's spec constants
+                    if info.get_spec_config().is_empty() {
+                        warn!(
+                            log,
+                            "Beacon node did not provide its spec config, unable to verify constants"
+                        );
+                    } else {
+                        let node_config = YamlConfig::from_yaml_str(info.get_spec_config())?;
+                        let differences =
+                            YamlConfig::from_spec::<T>(&eth2_config.spec).differences(&node_config);
+                        if !differences.is_empty() {
+                            error!(
+                                log,
+                                "Beacon node has different spec constants. Exiting";
+                                "constants" => differences.join(", ")
+                            );
+                            return Err(format!(
+                                "Beacon node has different spec constants: {}",
+                                differences.join(", ")
+                            )
+                            .into());
+                        }
+                    }
                     break info;
                 }
             };
@@ -261,7 +285,7 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static> Service<B, S> {
                     // wait for node to process
                     std::thread::sleep(TIME_DELAY_FROM_SLOT);
                     // if a non-fatal error occurs, proceed to the next slot.
-                    let _ignore_error = service.per_slot_execution();
+                    let _ignore_error = service.per_slot_execution::<T>();
                     // completed a slot process
                     Ok(())
                 })
@@ -273,7 +297,7 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static> Service<B, S> {
 
     /// The execution logic that runs every slot.
     // Errors are logged to output, and core execution continues unless fatal errors occur.
-    fn per_slot_execution(&mut self) -> error_chain::Result<()> {
+    fn per_slot_execution<T: EthSpec>(&mut self) -> error_chain::Result<()> {
         /* get the new current slot and epoch */
         self.update_current_slot()?;
 
@@ -281,7 +305,7 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static> Service<B, S> {
         self.check_for_duties();
 
         /* process any required duties for validators */
-        self.process_duties();
+        self.process_duties::<T>();
 
         Ok(())
     }
@@ -328,7 +352,7 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static> Service<B, S> {
     }
 
     /// If there are any duties to process, spawn a separate thread and perform required actions.
-    fn process_duties(&mut self) {
+    fn process_duties<T: EthSpec>(&mut self) {
         if let Some(work) = self.duties_manager.get_current_work(self.current_slot) {
             for (signer_index, work_type) in work {
                 if work_type.produce_block {
@@ -344,13 +368,14 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static> Service<B, S> {
                     std::thread::spawn(move || {
                         info!(log, "Producing a block"; "Validator"=> format!("{}", signers[signer_index]));
                         let signer = &signers[signer_index];
-                        let mut block_producer = BlockProducer {
+                        let mut block_producer = BlockProducer::<_, _, T> {
                             fork,
                             slot,
                             spec,
                             beacon_node,
                             signer,
                             slots_per_epoch,
+                            _phantom: PhantomData,
                         };
                         block_producer.handle_produce_block(log);
                     });
@@ -367,13 +392,14 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static> Service<B, S> {
                     std::thread::spawn(move || {
                         info!(log, "Producing an attestation"; "Validator"=> format!("{}", signers[signer_index]));
                         let signer = &signers[signer_index];
-                        let mut attestation_producer = AttestationProducer {
+                        let mut attestation_producer = AttestationProducer::<_, _, T> {
                             fork,
                             duty: work_type.attestation_duty.expect("Should never be none"),
                             spec,
                             beacon_node,
                             signer,
                             slots_per_epoch,
+                            _phantom: PhantomData,
                         };
                         attestation_producer.handle_produce_attestation(log);
                     });